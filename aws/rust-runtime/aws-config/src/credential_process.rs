@@ -54,6 +54,48 @@ where
     }
 }
 
+/// Runs `command` with the system shell and returns its standard output
+///
+/// The command must exit with status 0. `retrieving` names what the output is, e.g.
+/// `"credentials"`, and is used in error messages.
+pub(crate) fn run_command<T>(
+    command: &CommandWithSensitiveArgs<T>,
+    retrieving: &str,
+) -> Result<Vec<u8>, CredentialsError>
+where
+    T: AsRef<str>,
+{
+    let mut process = if cfg!(windows) {
+        let mut process = Command::new("cmd.exe");
+        process.args(["/C", command.unredacted()]);
+        process
+    } else {
+        let mut process = Command::new("sh");
+        process.args(["-c", command.unredacted()]);
+        process
+    };
+
+    let output = process.output().map_err(|e| {
+        CredentialsError::provider_error(format!(
+            "Error retrieving {} from external process: {}",
+            retrieving, e
+        ))
+    })?;
+
+    // Security: command arguments can be logged at trace level
+    tracing::trace!(command = ?process, status = ?output.status, "executed command (unredacted)");
+
+    if !output.status.success() {
+        let reason =
+            std::str::from_utf8(&output.stderr).unwrap_or("could not decode stderr as UTF-8");
+        return Err(CredentialsError::provider_error(format!(
+            "Error retrieving {}: external process exited with code {}. Stderr: {}",
+            retrieving, output.status, reason
+        )));
+    }
+    Ok(output.stdout)
+}
+
 /// External process credentials provider
 ///
 /// This credentials provider runs a configured external process and parses
@@ -112,37 +154,9 @@ impl CredentialProcessProvider {
     async fn credentials(&self) -> provider::Result {
         // Security: command arguments must be redacted at debug level
         tracing::debug!(command = %self.command, "loading credentials from external process");
+        let output = run_command(&self.command, "credentials")?;
 
-        let mut command = if cfg!(windows) {
-            let mut command = Command::new("cmd.exe");
-            command.args(["/C", self.command.unredacted()]);
-            command
-        } else {
-            let mut command = Command::new("sh");
-            command.args(["-c", self.command.unredacted()]);
-            command
-        };
-
-        let output = command.output().map_err(|e| {
-            CredentialsError::provider_error(format!(
-                "Error retrieving credentials from external process: {}",
-                e
-            ))
-        })?;
-
-        // Security: command arguments can be logged at trace level
-        tracing::trace!(command = ?command, status = ?output.status, "executed command (unredacted)");
-
-        if !output.status.success() {
-            let reason =
-                std::str::from_utf8(&output.stderr).unwrap_or("could not decode stderr as UTF-8");
-            return Err(CredentialsError::provider_error(format!(
-                "Error retrieving credentials: external process exited with code {}. Stderr: {}",
-                output.status, reason
-            )));
-        }
-
-        let output = std::str::from_utf8(&output.stdout).map_err(|e| {
+        let output = std::str::from_utf8(&output).map_err(|e| {
             CredentialsError::provider_error(format!(
                 "Error retrieving credentials from external process: could not decode output as UTF-8: {}",
                 e
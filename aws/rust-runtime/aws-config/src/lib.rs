@@ -122,6 +122,7 @@ pub mod meta;
 pub mod profile;
 pub mod provider_config;
 pub mod retry;
+pub(crate) mod service_config;
 #[cfg(feature = "credentials-sso")]
 pub mod sso;
 pub(crate) mod standard_property;
@@ -164,12 +165,14 @@ mod loader {
     use aws_types::SdkConfig;
 
     use crate::connector::default_connector;
+    use crate::default_provider::ignore_configured_endpoint_urls::ignore_configured_endpoint_urls_provider;
     use crate::default_provider::use_dual_stack::use_dual_stack_provider;
     use crate::default_provider::use_fips::use_fips_provider;
     use crate::default_provider::{app_name, credentials, region, retry_config, timeout_config};
     use crate::meta::region::ProvideRegion;
     use crate::profile::profile_file::ProfileFiles;
     use crate::provider_config::ProviderConfig;
+    use crate::service_config::EnvServiceConfig;
 
     /// Load a cross-service [`SdkConfig`](aws_types::SdkConfig) from the environment
     ///
@@ -449,6 +452,11 @@ mod loader {
         /// When this method is used, the [`Region`](aws_types::region::Region) is only used for
         /// signing; it is not used to route the request.
         ///
+        /// If this method isn't used, endpoint URLs are loaded for each service from the
+        /// `AWS_ENDPOINT_URL_<SERVICE>` and `AWS_ENDPOINT_URL` environment variables, then from the
+        /// `[services]` section referenced by the profile and the profile's `endpoint_url`. Setting
+        /// `AWS_IGNORE_CONFIGURED_ENDPOINT_URLS=true` ignores those sources.
+        ///
         /// # Examples
         ///
         /// Use a static endpoint for all services
@@ -590,6 +598,15 @@ mod loader {
                 use_dual_stack_provider(&conf).await
             };
 
+            let ignore_configured_endpoint_urls = ignore_configured_endpoint_urls_provider(&conf)
+                .await
+                .unwrap_or(false);
+            let service_config = if ignore_configured_endpoint_urls {
+                None
+            } else {
+                Some(EnvServiceConfig::load(&conf).await)
+            };
+
             let credentials_provider = if let Some(provider) = self.credentials_provider {
                 provider
             } else {
@@ -614,6 +631,9 @@ mod loader {
             builder.set_endpoint_url(self.endpoint_url);
             builder.set_use_fips(use_fips);
             builder.set_use_dual_stack(use_dual_stack);
+            if let Some(service_config) = service_config {
+                builder = builder.service_config(service_config);
+            }
             builder.build()
         }
     }
@@ -626,6 +646,7 @@ mod loader {
         use aws_smithy_client::never::NeverConnector;
         use aws_types::app_name::AppName;
         use aws_types::os_shim_internal::{Env, Fs};
+        use aws_types::service_config::ServiceConfigKey;
         use tracing_test::traced_test;
 
         use crate::profile::profile_file::{ProfileFileKind, ProfileFiles};
@@ -713,6 +734,40 @@ mod loader {
             assert_eq!(None, conf.use_dual_stack());
         }
 
+        #[tokio::test]
+        async fn service_config_endpoint_urls() {
+            let key = ServiceConfigKey::new("S3", "AWS_ENDPOINT_URL", "endpoint_url");
+            let env = Env::from_slice(&[("AWS_ENDPOINT_URL_S3", "http://localhost:9000")]);
+            let conf = from_env()
+                .configure(
+                    ProviderConfig::empty()
+                        .with_sleep(InstantSleep)
+                        .with_http_connector(no_traffic_connector())
+                        .with_env(env),
+                )
+                .load()
+                .await;
+            assert_eq!(
+                Some("http://localhost:9000".to_string()),
+                conf.service_config().unwrap().load_config(key)
+            );
+
+            let env = Env::from_slice(&[
+                ("AWS_ENDPOINT_URL_S3", "http://localhost:9000"),
+                ("AWS_IGNORE_CONFIGURED_ENDPOINT_URLS", "true"),
+            ]);
+            let conf = from_env()
+                .configure(
+                    ProviderConfig::empty()
+                        .with_sleep(InstantSleep)
+                        .with_http_connector(no_traffic_connector())
+                        .with_env(env),
+                )
+                .load()
+                .await;
+            assert!(conf.service_config().is_none());
+        }
+
         #[tokio::test]
         async fn app_name() {
             let app_name = AppName::new("my-app-name").unwrap();
@@ -0,0 +1,760 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Edit AWS profile files in place
+//!
+//! [`ProfileFileEditor`] makes targeted changes to `~/.aws/config` and `~/.aws/credentials`. Lines
+//! that aren't changed are written back exactly as they were read, so comments, ordering and
+//! formatting are preserved. Edited and inserted lines use the file's line endings, which are
+//! detected from its first line.
+//!
+//! # Examples
+//! ```no_run
+//! # async fn docs() -> Result<(), Box<dyn std::error::Error>> {
+//! use aws_config::profile::editor::{ProfileFileEditor, Section};
+//! use aws_config::profile::profile_file::ProfileFileKind;
+//! use aws_types::os_shim_internal::{Env, Fs};
+//!
+//! let mut config = ProfileFileEditor::load(Fs::real(), &Env::real(), ProfileFileKind::Config).await?;
+//! config.set(&Section::profile("dev"), "region", "us-west-2")?;
+//! config.set_sub_property(&Section::services("local"), "s3", "endpoint_url", "http://localhost:9000")?;
+//! config.save().await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::profile::parser::default_profile_file_path;
+use crate::profile::profile_file::ProfileFileKind;
+use aws_credential_types::Credentials;
+use aws_types::os_shim_internal::{Env, Fs};
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::io;
+use std::path::{Path, PathBuf};
+
+const WHITESPACE: &[char] = &[' ', '\t'];
+const COMMENT: &[char] = &['#', ';'];
+const DEFAULT: &str = "default";
+const PROFILE_PREFIX: &str = "profile";
+const SSO_SESSION_PREFIX: &str = "sso-session";
+const SERVICES_PREFIX: &str = "services";
+const SUB_PROPERTY_INDENT: &str = "  ";
+
+/// A section of a profile file
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Section {
+    /// A profile, written as `[profile <name>]` in the config file and `[<name>]` in the credentials file
+    Profile(String),
+    /// An SSO session, written as `[sso-session <name>]`. Only valid in the config file.
+    SsoSession(String),
+    /// Service-specific settings, written as `[services <name>]`. Only valid in the config file.
+    Services(String),
+}
+
+impl Section {
+    /// A profile section
+    pub fn profile(name: impl Into<String>) -> Self {
+        Section::Profile(name.into())
+    }
+
+    /// An `sso-session` section
+    pub fn sso_session(name: impl Into<String>) -> Self {
+        Section::SsoSession(name.into())
+    }
+
+    /// A `services` section
+    pub fn services(name: impl Into<String>) -> Self {
+        Section::Services(name.into())
+    }
+
+    /// The header for this section in a new file of the given kind
+    fn header(&self, kind: ProfileFileKind) -> String {
+        match (self, kind) {
+            (Section::Profile(name), ProfileFileKind::Config) if name == DEFAULT => {
+                format!("[{}]", DEFAULT)
+            }
+            (Section::Profile(name), ProfileFileKind::Config) => {
+                format!("[{} {}]", PROFILE_PREFIX, name)
+            }
+            (Section::Profile(name), ProfileFileKind::Credentials) => format!("[{}]", name),
+            (Section::SsoSession(name), _) => format!("[{} {}]", SSO_SESSION_PREFIX, name),
+            (Section::Services(name), _) => format!("[{} {}]", SERVICES_PREFIX, name),
+        }
+    }
+
+    /// Returns true if a header line with the given (prefix, name) refers to this section
+    fn matches(&self, kind: ProfileFileKind, prefix: Option<&str>, header_name: &str) -> bool {
+        match (self, kind, prefix) {
+            (Section::Profile(name), ProfileFileKind::Config, Some(PROFILE_PREFIX)) => {
+                name == header_name
+            }
+            (Section::Profile(name), ProfileFileKind::Config, None) => {
+                name == DEFAULT && header_name == DEFAULT
+            }
+            (Section::Profile(name), ProfileFileKind::Credentials, None) => name == header_name,
+            (Section::SsoSession(name), ProfileFileKind::Config, Some(SSO_SESSION_PREFIX))
+            | (Section::Services(name), ProfileFileKind::Config, Some(SERVICES_PREFIX)) => {
+                name == header_name
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Display for Section {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Section::Profile(name) => write!(f, "profile `{}`", name),
+            Section::SsoSession(name) => write!(f, "sso-session `{}`", name),
+            Section::Services(name) => write!(f, "services `{}`", name),
+        }
+    }
+}
+
+/// An edit to a profile file was invalid
+#[derive(Debug)]
+pub struct InvalidProfileEdit {
+    message: String,
+}
+
+impl InvalidProfileEdit {
+    fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+        }
+    }
+}
+
+impl Display for InvalidProfileEdit {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid profile file edit: {}", self.message)
+    }
+}
+
+impl Error for InvalidProfileEdit {}
+
+/// Edits a single profile file while preserving its comments and formatting
+///
+/// Changes are made in memory and written back to the file system with [`save`](Self::save).
+#[derive(Debug)]
+pub struct ProfileFileEditor {
+    fs: Fs,
+    kind: ProfileFileKind,
+    path: PathBuf,
+    lines: Vec<String>,
+    line_ending: &'static str,
+}
+
+impl ProfileFileEditor {
+    /// Loads the default config or credentials file
+    ///
+    /// The file's location follows the same rules as loading profiles: `AWS_CONFIG_FILE` and
+    /// `AWS_SHARED_CREDENTIALS_FILE` override the default `~/.aws/config` and `~/.aws/credentials`.
+    /// A file that doesn't exist yet is treated as empty.
+    pub async fn load(fs: Fs, env: &Env, kind: ProfileFileKind) -> io::Result<Self> {
+        let path = default_profile_file_path(kind, env);
+        Self::load_path(fs, kind, path).await
+    }
+
+    /// Loads the profile file at `path`
+    ///
+    /// A file that doesn't exist yet is treated as empty.
+    pub async fn load_path(
+        fs: Fs,
+        kind: ProfileFileKind,
+        path: impl Into<PathBuf>,
+    ) -> io::Result<Self> {
+        let path = path.into();
+        let contents = match fs.read_to_end(&path).await {
+            Ok(contents) => String::from_utf8(contents)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(err) => return Err(err),
+        };
+        Ok(Self::from_contents(fs, kind, path, &contents))
+    }
+
+    fn from_contents(fs: Fs, kind: ProfileFileKind, path: PathBuf, contents: &str) -> Self {
+        let line_ending = match contents.find('\n') {
+            Some(idx) if contents[..idx].ends_with('\r') => "\r\n",
+            _ => "\n",
+        };
+        let lines = if contents.is_empty() {
+            Vec::new()
+        } else {
+            contents
+                .split('\n')
+                .map(|line| match line_ending {
+                    "\r\n" => line.strip_suffix('\r').unwrap_or(line),
+                    _ => line,
+                })
+                .map(ToOwned::to_owned)
+                .collect()
+        };
+        Self {
+            fs,
+            kind,
+            path,
+            lines,
+            line_ending,
+        }
+    }
+
+    /// The path this editor reads from and saves to
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The edited file contents
+    pub fn contents(&self) -> String {
+        self.lines.join(self.line_ending)
+    }
+
+    /// Atomically writes the edited contents back to the file
+    pub async fn save(&self) -> io::Result<()> {
+        self.fs.write(&self.path, self.contents()).await
+    }
+
+    /// Returns the value of `key` in `section`
+    ///
+    /// For a property with sub-properties, the sub-properties are returned one per line.
+    pub fn get(&self, section: &Section, key: &str) -> Option<String> {
+        let body = self.find_section(section)?.1;
+        let (line, end) = self.find_property(body, key)?;
+        let mut value = property_value(&self.lines[line]).to_string();
+        for continuation in &self.lines[line + 1..end] {
+            if !is_blank(continuation) {
+                value.push('\n');
+                value.push_str(continuation.trim_matches(WHITESPACE));
+            }
+        }
+        Some(value)
+    }
+
+    /// Sets `key` to `value` in `section`, creating the section if it doesn't exist
+    ///
+    /// An existing property keeps its position and the formatting around its `=` sign.
+    pub fn set(
+        &mut self,
+        section: &Section,
+        key: &str,
+        value: &str,
+    ) -> Result<(), InvalidProfileEdit> {
+        validate_key(key)?;
+        validate_value(value)?;
+        let body = self.find_or_create_section(section)?;
+        match self.find_property(body.clone(), key) {
+            Some((line, end)) => {
+                let prefix = property_prefix(&self.lines[line]).to_string();
+                self.lines
+                    .splice(line..end, [format!("{}{}", prefix, value)]);
+            }
+            None => {
+                let at = self.insertion_point(body);
+                self.lines.insert(at, format!("{} = {}", key, value));
+            }
+        }
+        Ok(())
+    }
+
+    /// Sets the sub-property `sub_key` of `key` in `section`, creating the property and section as needed
+    ///
+    /// ```ini
+    /// [services local]
+    /// s3 =
+    ///   endpoint_url = http://localhost:9000
+    /// ```
+    pub fn set_sub_property(
+        &mut self,
+        section: &Section,
+        key: &str,
+        sub_key: &str,
+        value: &str,
+    ) -> Result<(), InvalidProfileEdit> {
+        validate_key(key)?;
+        validate_key(sub_key)?;
+        validate_value(value)?;
+        let body = self.find_or_create_section(section)?;
+        let sub_property = format!("{}{} = {}", SUB_PROPERTY_INDENT, sub_key, value);
+        match self.find_property(body.clone(), key) {
+            // A property with a plain value is replaced with sub-properties
+            Some((line, end)) if !property_value(&self.lines[line]).is_empty() => {
+                let prefix = property_prefix(&self.lines[line]).trim_end().to_string();
+                self.lines.splice(line..end, [prefix, sub_property]);
+            }
+            Some((line, end)) => {
+                let existing = (line + 1..end).find(|idx| {
+                    let continuation = self.lines[*idx].trim_matches(WHITESPACE);
+                    continuation
+                        .split_once('=')
+                        .map(|(k, _)| k.trim_matches(WHITESPACE) == sub_key)
+                        .unwrap_or(false)
+                });
+                match existing {
+                    Some(idx) => {
+                        let indent_len = self.lines[idx].len()
+                            - self.lines[idx].trim_start_matches(WHITESPACE).len();
+                        let indent = self.lines[idx][..indent_len].to_string();
+                        let prefix =
+                            property_prefix(self.lines[idx].trim_start_matches(WHITESPACE))
+                                .to_string();
+                        self.lines[idx] = format!("{}{}{}", indent, prefix, value);
+                    }
+                    None => {
+                        let at = (line + 1..end)
+                            .rev()
+                            .find(|idx| !is_blank(&self.lines[*idx]))
+                            .unwrap_or(line)
+                            + 1;
+                        self.lines.insert(at, sub_property);
+                    }
+                }
+            }
+            None => {
+                let at = self.insertion_point(body);
+                self.lines
+                    .splice(at..at, [format!("{} =", key), sub_property]);
+            }
+        }
+        Ok(())
+    }
+
+    /// Removes `key` from `section`
+    ///
+    /// Returns true if the property existed.
+    pub fn unset(&mut self, section: &Section, key: &str) -> bool {
+        let removed = self
+            .find_section(section)
+            .and_then(|(_, body)| self.find_property(body, key));
+        match removed {
+            Some((line, end)) => {
+                self.lines.drain(line..end);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Removes `section` along with its properties and any comments within it
+    ///
+    /// Returns true if the section existed.
+    pub fn remove_section(&mut self, section: &Section) -> bool {
+        match self.find_section(section) {
+            Some((header, body)) => {
+                self.lines.drain(header..body.end);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Replaces the static credentials of `profile`, e.g. after rotating an access key
+    ///
+    /// The session token is removed if `credentials` doesn't have one.
+    pub fn set_credentials(
+        &mut self,
+        profile: &str,
+        credentials: &Credentials,
+    ) -> Result<(), InvalidProfileEdit> {
+        let section = Section::profile(profile);
+        self.set(&section, "aws_access_key_id", credentials.access_key_id())?;
+        self.set(
+            &section,
+            "aws_secret_access_key",
+            credentials.secret_access_key(),
+        )?;
+        match credentials.session_token() {
+            Some(token) => self.set(&section, "aws_session_token", token)?,
+            None => {
+                self.unset(&section, "aws_session_token");
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the index of the header line and the range of lines in the body of `section`
+    fn find_section(&self, section: &Section) -> Option<(usize, std::ops::Range<usize>)> {
+        let headers = self
+            .lines
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, line)| parse_header(line).map(|header| (idx, header)))
+            .filter(|(_, (prefix, name))| section.matches(self.kind, *prefix, name))
+            .collect::<Vec<_>>();
+        // `[profile default]` takes priority over `[default]`, matching how profiles are loaded
+        let (header, _) = headers
+            .iter()
+            .find(|(_, (prefix, _))| prefix.is_some())
+            .or_else(|| headers.first())?;
+        let end = (header + 1..self.lines.len())
+            .find(|idx| is_header(&self.lines[*idx]))
+            .unwrap_or(self.lines.len());
+        Some((*header, header + 1..end))
+    }
+
+    fn find_or_create_section(
+        &mut self,
+        section: &Section,
+    ) -> Result<std::ops::Range<usize>, InvalidProfileEdit> {
+        if let (Section::SsoSession(_) | Section::Services(_), ProfileFileKind::Credentials) =
+            (section, self.kind)
+        {
+            return Err(InvalidProfileEdit::new(format!(
+                "{} can only be written to the config file",
+                section
+            )));
+        }
+        let name = match section {
+            Section::Profile(name) | Section::SsoSession(name) | Section::Services(name) => name,
+        };
+        validate_key(name)?;
+        if let Some((_, body)) = self.find_section(section) {
+            return Ok(body);
+        }
+
+        // Keep a trailing newline at the end of the file
+        let had_trailing_newline = self.lines.last().map(String::is_empty).unwrap_or(false);
+        if had_trailing_newline {
+            self.lines.pop();
+        }
+        if self
+            .lines
+            .last()
+            .map(|line| !is_blank(line))
+            .unwrap_or(false)
+        {
+            self.lines.push(String::new());
+        }
+        self.lines.push(section.header(self.kind));
+        let body = self.lines.len()..self.lines.len();
+        self.lines.push(String::new());
+        Ok(body)
+    }
+
+    /// Returns the range of lines making up `key` within `body`, including its continuation lines
+    fn find_property(&self, body: std::ops::Range<usize>, key: &str) -> Option<(usize, usize)> {
+        let line = body.clone().find(|idx| {
+            let line = &self.lines[*idx];
+            !line.starts_with(WHITESPACE)
+                && !is_comment(line)
+                && line
+                    .split_once('=')
+                    .map(|(k, _)| k.trim_matches(WHITESPACE) == key)
+                    .unwrap_or(false)
+        })?;
+        // Continuation lines may be separated by blank lines, but trailing blank lines belong to the section
+        let end = (line + 1..body.end)
+            .take_while(|idx| {
+                let line = &self.lines[*idx];
+                line.starts_with(WHITESPACE) || line.is_empty()
+            })
+            .filter(|idx| !is_blank(&self.lines[*idx]))
+            .last()
+            .map(|idx| idx + 1)
+            .unwrap_or(line + 1);
+        Some((line, end))
+    }
+
+    /// Where a new property is inserted: after the last property in the section, so that comments
+    /// at the end of a section, which usually describe the next section, stay in place
+    fn insertion_point(&self, body: std::ops::Range<usize>) -> usize {
+        body.clone()
+            .rev()
+            .find(|idx| !is_blank(&self.lines[*idx]) && !is_comment(&self.lines[*idx]))
+            .map(|idx| idx + 1)
+            .unwrap_or(body.start)
+    }
+}
+
+fn is_blank(line: &str) -> bool {
+    line.trim_matches(WHITESPACE)
+        .trim_end_matches('\r')
+        .is_empty()
+}
+
+fn is_comment(line: &str) -> bool {
+    line.starts_with(COMMENT)
+}
+
+fn is_header(line: &str) -> bool {
+    line.starts_with('[')
+}
+
+/// Parses a `[prefix name]` header line into its optional prefix and name
+fn parse_header(line: &str) -> Option<(Option<&str>, &str)> {
+    let inner = line.strip_prefix('[')?.split(']').next()?;
+    let inner = inner.trim_matches(WHITESPACE);
+    for prefix in [PROFILE_PREFIX, SSO_SESSION_PREFIX, SERVICES_PREFIX] {
+        if let Some(name) = inner.strip_prefix(prefix) {
+            if name.starts_with(WHITESPACE) {
+                return Some((Some(prefix), name.trim_matches(WHITESPACE)));
+            }
+        }
+    }
+    Some((None, inner))
+}
+
+/// The text of a property line up to and including the `=` sign and any whitespace after it
+fn property_prefix(line: &str) -> &str {
+    let equals = line.find('=').expect("property lines contain `=`");
+    let value_start = line[equals + 1..]
+        .find(|c| !WHITESPACE.contains(&c))
+        .map(|offset| equals + 1 + offset)
+        .unwrap_or(line.len());
+    &line[..value_start]
+}
+
+fn property_value(line: &str) -> &str {
+    line[property_prefix(line).len()..]
+        .trim_matches(WHITESPACE)
+        .trim_end_matches('\r')
+}
+
+/// Keys and section names must be valid identifiers to be read back
+fn validate_key(key: &str) -> Result<(), InvalidProfileEdit> {
+    let valid = !key.is_empty()
+        && key.chars().all(|ch| {
+            ch.is_ascii_alphanumeric() || ['_', '-', '/', '.', '%', '@', ':', '+'].contains(&ch)
+        });
+    if valid {
+        Ok(())
+    } else {
+        Err(InvalidProfileEdit::new(format!(
+            "`{}` is not a valid identifier",
+            key
+        )))
+    }
+}
+
+fn validate_value(value: &str) -> Result<(), InvalidProfileEdit> {
+    if value.contains(['\n', '\r']) {
+        Err(InvalidProfileEdit::new("values may not contain newlines"))
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ProfileFileEditor, Section};
+    use crate::profile::profile_file::{ProfileFileKind, ProfileFiles};
+    use crate::profile::ProfileSet;
+    use aws_credential_types::Credentials;
+    use aws_types::os_shim_internal::{Env, Fs};
+
+    fn editor(kind: ProfileFileKind, contents: &str) -> ProfileFileEditor {
+        ProfileFileEditor::from_contents(Fs::from_slice(&[]), kind, "test".into(), contents)
+    }
+
+    #[test]
+    fn set_preserves_comments_and_formatting() {
+        let mut editor = editor(
+            ProfileFileKind::Config,
+            "# my config\n[default]\nregion=us-east-1 \n; output format\noutput = json\n\n# dev profile\n[profile dev]\nregion = us-west-2\n",
+        );
+        editor
+            .set(&Section::profile("default"), "region", "eu-west-1")
+            .unwrap();
+        editor
+            .set(&Section::profile("default"), "retry_mode", "standard")
+            .unwrap();
+        assert_eq!(
+            "# my config\n[default]\nregion=eu-west-1\n; output format\noutput = json\nretry_mode = standard\n\n# dev profile\n[profile dev]\nregion = us-west-2\n",
+            editor.contents()
+        );
+    }
+
+    #[test]
+    fn create_sections() {
+        let mut editor = editor(ProfileFileKind::Config, "[default]\nregion = us-east-1\n");
+        editor
+            .set(&Section::profile("dev"), "sso_session", "my-sso")
+            .unwrap();
+        editor
+            .set(&Section::sso_session("my-sso"), "sso_region", "us-east-1")
+            .unwrap();
+        editor
+            .set_sub_property(
+                &Section::services("local"),
+                "s3",
+                "endpoint_url",
+                "http://localhost:9000",
+            )
+            .unwrap();
+        assert_eq!(
+            "[default]\nregion = us-east-1\n\n[profile dev]\nsso_session = my-sso\n\n[sso-session my-sso]\nsso_region = us-east-1\n\n[services local]\ns3 =\n  endpoint_url = http://localhost:9000\n",
+            editor.contents()
+        );
+    }
+
+    #[test]
+    fn sub_properties() {
+        let mut editor = editor(
+            ProfileFileKind::Config,
+            "[services local]\ns3 =\n    endpoint_url = http://old\ndynamodb = not-a-sub-property\n",
+        );
+        let section = Section::services("local");
+        editor
+            .set_sub_property(&section, "s3", "endpoint_url", "http://new")
+            .unwrap();
+        editor
+            .set_sub_property(&section, "s3", "addressing_style", "path")
+            .unwrap();
+        editor
+            .set_sub_property(
+                &section,
+                "dynamodb",
+                "endpoint_url",
+                "http://localhost:8000",
+            )
+            .unwrap();
+        assert_eq!(
+            "[services local]\ns3 =\n    endpoint_url = http://new\n  addressing_style = path\ndynamodb =\n  endpoint_url = http://localhost:8000\n",
+            editor.contents()
+        );
+        assert_eq!(
+            Some("\nendpoint_url = http://new\naddressing_style = path".to_string()),
+            editor.get(&section, "s3")
+        );
+    }
+
+    #[test]
+    fn unset_and_remove_section() {
+        let mut editor = editor(
+            ProfileFileKind::Credentials,
+            "[default]\naws_access_key_id = a\naws_session_token =\n  continued\n\n[other]\naws_access_key_id = b\n",
+        );
+        assert!(editor.unset(&Section::profile("default"), "aws_session_token"));
+        assert!(!editor.unset(&Section::profile("default"), "aws_session_token"));
+        assert!(editor.remove_section(&Section::profile("other")));
+        assert_eq!("[default]\naws_access_key_id = a\n", editor.contents());
+    }
+
+    #[test]
+    fn config_only_sections_are_rejected_in_credentials_file() {
+        let mut editor = editor(ProfileFileKind::Credentials, "");
+        let err = editor
+            .set(&Section::services("local"), "s3", "x")
+            .expect_err("services sections are config only");
+        assert!(format!("{}", err).contains("can only be written to the config file"));
+        editor
+            .set(&Section::profile("bad name"), "k", "v")
+            .expect_err("invalid identifier");
+    }
+
+    #[test]
+    fn profile_default_takes_priority() {
+        let mut editor = editor(
+            ProfileFileKind::Config,
+            "[default]\nregion = a\n[profile default]\nregion = b\n",
+        );
+        editor
+            .set(&Section::profile("default"), "region", "c")
+            .unwrap();
+        assert_eq!(
+            "[default]\nregion = a\n[profile default]\nregion = c\n",
+            editor.contents()
+        );
+    }
+
+    #[test]
+    fn crlf_line_endings_are_preserved() {
+        let mut editor = editor(
+            ProfileFileKind::Config,
+            "# my config\r\n[default]\r\nregion = us-east-1\r\n",
+        );
+        assert_eq!(
+            Some("us-east-1".to_string()),
+            editor.get(&Section::profile("default"), "region")
+        );
+        editor
+            .set(&Section::profile("default"), "region", "eu-west-1")
+            .unwrap();
+        editor
+            .set(&Section::profile("default"), "output", "json")
+            .unwrap();
+        editor
+            .set_sub_property(
+                &Section::services("local"),
+                "s3",
+                "endpoint_url",
+                "http://localhost:9000",
+            )
+            .unwrap();
+        assert_eq!(
+            "# my config\r\n[default]\r\nregion = eu-west-1\r\noutput = json\r\n\r\n[services local]\r\ns3 =\r\n  endpoint_url = http://localhost:9000\r\n",
+            editor.contents()
+        );
+    }
+
+    #[tokio::test]
+    async fn rotate_credentials_and_save() {
+        let fs = Fs::from_slice(&[(
+            "/home/.aws/credentials",
+            "# rotated weekly\n[default]\naws_access_key_id = old-akid\naws_secret_access_key = old-secret\naws_session_token = old-token\n",
+        )]);
+        let env = Env::from_slice(&[("HOME", "/home")]);
+        let mut editor = ProfileFileEditor::load(fs.clone(), &env, ProfileFileKind::Credentials)
+            .await
+            .unwrap();
+        editor
+            .set_credentials(
+                "default",
+                &Credentials::new("new-akid", "new-secret", None, None, "test"),
+            )
+            .unwrap();
+        editor.save().await.unwrap();
+
+        assert_eq!(
+            "# rotated weekly\n[default]\naws_access_key_id = new-akid\naws_secret_access_key = new-secret\n",
+            String::from_utf8(fs.read_to_end("/home/.aws/credentials").await.unwrap()).unwrap()
+        );
+        let profiles = crate::profile::load(&fs, &env, &ProfileFiles::default(), None)
+            .await
+            .unwrap();
+        assert_eq!(Some("new-akid"), profiles.get("aws_access_key_id"));
+        assert_eq!(None, profiles.get("aws_session_token"));
+    }
+
+    #[tokio::test]
+    async fn edits_round_trip_through_the_parser() {
+        let fs = Fs::from_slice(&[]);
+        let mut editor =
+            ProfileFileEditor::load_path(fs.clone(), ProfileFileKind::Config, "config")
+                .await
+                .unwrap();
+        editor
+            .set(&Section::profile("default"), "services", "local")
+            .unwrap();
+        editor
+            .set_sub_property(
+                &Section::services("local"),
+                "s3",
+                "endpoint_url",
+                "http://localhost:9000",
+            )
+            .unwrap();
+        editor.save().await.unwrap();
+
+        let profiles: ProfileSet = crate::profile::load(
+            &fs,
+            &Env::from_slice(&[]),
+            &ProfileFiles::builder()
+                .with_file(ProfileFileKind::Config, "config")
+                .build(),
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            Some("http://localhost:9000"),
+            profiles
+                .get_services_section("local")
+                .unwrap()
+                .get_sub_property("s3", "endpoint_url")
+        );
+    }
+}
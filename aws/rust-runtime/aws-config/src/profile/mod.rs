@@ -20,6 +20,7 @@ pub use parser::{load, Profile, ProfileFileLoadError, ProfileSet, Property};
 
 pub mod app_name;
 pub mod credentials;
+pub mod editor;
 pub mod profile_file;
 pub mod region;
 
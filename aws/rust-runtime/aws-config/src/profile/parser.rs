@@ -15,6 +15,7 @@ use std::path::PathBuf;
 use std::sync::Arc;
 
 pub use self::parse::ProfileParseError;
+pub(crate) use self::source::default_profile_file_path;
 
 mod normalize;
 mod parse;
@@ -73,6 +74,7 @@ pub async fn load(
 #[derive(Debug, Eq, Clone, PartialEq)]
 pub struct ProfileSet {
     profiles: HashMap<String, Profile>,
+    services_sections: HashMap<String, Profile>,
     selected_profile: Cow<'static, str>,
 }
 
@@ -114,6 +116,22 @@ impl ProfileSet {
         self.profiles.get(profile_name)
     }
 
+    /// Retrieves a `[services <name>]` section from the profile set
+    ///
+    /// Services sections hold service-specific settings as sub-properties keyed by service, and
+    /// are referenced from a profile with the `services` property:
+    /// ```ini
+    /// [profile dev]
+    /// services = local
+    ///
+    /// [services local]
+    /// s3 =
+    ///   endpoint_url = http://localhost:9000
+    /// ```
+    pub fn get_services_section(&self, name: &str) -> Option<&Profile> {
+        self.services_sections.get(name)
+    }
+
     /// Returns the name of the currently selected profile
     pub fn selected_profile(&self) -> &str {
         self.selected_profile.as_ref()
@@ -142,6 +160,7 @@ impl ProfileSet {
     fn empty() -> Self {
         Self {
             profiles: Default::default(),
+            services_sections: Default::default(),
             selected_profile: "default".into(),
         }
     }
@@ -171,6 +190,20 @@ impl Profile {
     pub fn get(&self, name: &str) -> Option<&str> {
         self.properties.get(name).map(|prop| prop.value())
     }
+
+    /// Returns the sub-property named `sub_property` nested within the property named `name`
+    ///
+    /// ```ini
+    /// [profile example]
+    /// name =
+    ///   sub_property = value
+    /// ```
+    pub fn get_sub_property(&self, name: &str, sub_property: &str) -> Option<&str> {
+        self.get(name)?.lines().find_map(|line| {
+            let (key, value) = line.split_once('=')?;
+            (key.trim() == sub_property).then(|| value.trim())
+        })
+    }
 }
 
 /// Key-Value property pair
@@ -11,6 +11,7 @@ use std::collections::HashMap;
 
 const DEFAULT: &str = "default";
 const PROFILE_PREFIX: &str = "profile";
+const SERVICES_PREFIX: &str = "services";
 
 #[derive(Eq, PartialEq, Hash, Debug)]
 struct ProfileName<'a> {
@@ -60,6 +61,15 @@ impl ProfileName<'_> {
     }
 }
 
+/// Returns the name of a `[services <name>]` section, or `None` if `input` names a profile
+fn parse_services_section_name(input: &str) -> Option<&str> {
+    input
+        .trim_matches(WHITESPACE)
+        .strip_prefix(SERVICES_PREFIX)
+        .filter(|stripped| stripped.starts_with(WHITESPACE))
+        .map(|stripped| stripped.trim_matches(WHITESPACE))
+}
+
 /// Normalize a raw profile into a `MergedProfile`
 ///
 /// This function follows the following rules, codified in the tests & the reference Java implementation
@@ -67,11 +77,37 @@ impl ProfileName<'_> {
 /// - Profile names are validated (see `validate_profile_name`)
 /// - A profile named `profile default` takes priority over a profile named `default`.
 /// - Profiles with identical names are merged
+/// - In config files, `[services <name>]` sections are kept separately from profiles
 pub(super) fn merge_in(
     base: &mut ProfileSet,
-    raw_profile_set: RawProfileSet<'_>,
+    mut raw_profile_set: RawProfileSet<'_>,
     kind: ProfileFileKind,
 ) {
+    if matches!(kind, ProfileFileKind::Config) {
+        let services_sections = raw_profile_set
+            .keys()
+            .filter(|name| parse_services_section_name(name).is_some())
+            .copied()
+            .collect::<Vec<_>>();
+        for raw_name in services_sections {
+            let raw_section = raw_profile_set.remove(raw_name).expect("key exists");
+            let name = parse_services_section_name(raw_name).expect("checked above");
+            if validate_identifier(name).is_err() {
+                tracing::warn!(
+                    "services section `{}` ignored because `{}` was not a valid identifier",
+                    name,
+                    name
+                );
+                continue;
+            }
+            let section = base
+                .services_sections
+                .entry(name.to_string())
+                .or_insert_with(|| Profile::new(name.to_string(), Default::default()));
+            merge_into_base(section, raw_section)
+        }
+    }
+
     // parse / validate profile names
     let validated_profiles = raw_profile_set
         .into_iter()
@@ -233,6 +269,37 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn services_sections_are_kept_separately() {
+        let mut profile: RawProfileSet<'_> = HashMap::new();
+        profile.insert("services   dev ", {
+            let mut out = HashMap::new();
+            out.insert("s3", "\nendpoint_url = http://localhost:9000".into());
+            out
+        });
+        profile.insert("default", HashMap::new());
+        let mut base = ProfileSet::empty();
+        merge_in(&mut base, profile, ProfileFileKind::Config);
+        assert_eq!(vec!["default"], base.profiles().collect::<Vec<_>>());
+        assert_eq!(
+            Some("http://localhost:9000"),
+            base.get_services_section("dev")
+                .expect("contains services section")
+                .get_sub_property("s3", "endpoint_url")
+        );
+    }
+
+    #[test]
+    #[traced_test]
+    fn services_sections_are_not_allowed_in_credentials_files() {
+        let mut profile: RawProfileSet<'_> = HashMap::new();
+        profile.insert("services dev", HashMap::new());
+        let mut base = ProfileSet::empty();
+        merge_in(&mut base, profile, ProfileFileKind::Credentials);
+        assert!(base.get_services_section("dev").is_none());
+        assert!(logs_contain("profile `services dev` ignored"));
+    }
+
     #[test]
     #[traced_test]
     fn invalid_profile_generates_warning() {
@@ -63,6 +63,26 @@ pub(super) async fn load(
     })
 }
 
+/// Resolves the path of a default profile file
+///
+/// The path can be overridden with `AWS_CONFIG_FILE` / `AWS_SHARED_CREDENTIALS_FILE` and may
+/// begin with `~`, which is expanded to the user's home directory.
+pub(crate) fn default_profile_file_path(
+    kind: ProfileFileKind,
+    environment: &os_shim_internal::Env,
+) -> PathBuf {
+    let (path_is_default, path) = environment
+        .get(kind.override_environment_variable())
+        .map(|p| (false, Cow::Owned(p)))
+        .ok()
+        .unwrap_or_else(|| (true, kind.default_path().into()));
+    expand_home(
+        path.as_ref(),
+        path_is_default,
+        &home_dir(environment, Os::real()),
+    )
+}
+
 fn file_contents_to_string(path: &Path, contents: Vec<u8>) -> String {
     // if the file is not valid utf-8, log a warning and use an empty file instead
     match String::from_utf8(contents) {
@@ -0,0 +1,157 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Service-specific configuration loaded from the environment and the AWS config file
+
+use crate::profile::ProfileSet;
+use crate::provider_config::ProviderConfig;
+use aws_types::os_shim_internal::Env;
+use aws_types::service_config::{LoadServiceConfig, ServiceConfigKey};
+
+/// Profile property naming the `[services <name>]` section used by a profile
+const SERVICES: &str = "services";
+
+/// Loads service-specific configuration from the environment and the AWS config file
+///
+/// For a key such as `AWS_ENDPOINT_URL` / `endpoint_url` requested by DynamoDB, this checks the
+/// following sources in order:
+/// 1. The environment variable `AWS_ENDPOINT_URL_DYNAMODB`
+/// 2. The environment variable `AWS_ENDPOINT_URL`
+/// 3. The `dynamodb` property of the `[services <name>]` section referenced by the selected profile:
+///    ```ini
+///    [profile dev]
+///    services = local
+///
+///    [services local]
+///    dynamodb =
+///      endpoint_url = http://localhost:8000
+///    ```
+/// 4. The `endpoint_url` property of the selected profile
+#[derive(Debug)]
+pub(crate) struct EnvServiceConfig {
+    env: Env,
+    profile: Option<ProfileSet>,
+}
+
+impl EnvServiceConfig {
+    pub(crate) async fn load(provider_config: &ProviderConfig) -> Self {
+        Self {
+            env: provider_config.env(),
+            profile: provider_config.profile().await.cloned(),
+        }
+    }
+
+    fn load_from_profile(&self, key: ServiceConfigKey<'_>) -> Option<String> {
+        let profile = self.profile.as_ref()?;
+        let service_specific = profile
+            .get(SERVICES)
+            .and_then(|services| profile.get_services_section(services))
+            .and_then(|section| {
+                section.get_sub_property(&profile_service_name(key.service_id()), key.profile())
+            });
+        service_specific
+            .or_else(|| profile.get(key.profile()))
+            .map(ToOwned::to_owned)
+    }
+}
+
+impl LoadServiceConfig for EnvServiceConfig {
+    fn load_config(&self, key: ServiceConfigKey<'_>) -> Option<String> {
+        let service_specific_env = format!("{}_{}", key.env(), env_service_name(key.service_id()));
+        self.env
+            .get(&service_specific_env)
+            .or_else(|_| self.env.get(key.env()))
+            .ok()
+            .or_else(|| self.load_from_profile(key))
+    }
+}
+
+/// `DynamoDB Streams` => `DYNAMODB_STREAMS`
+fn env_service_name(service_id: &str) -> String {
+    service_id.to_ascii_uppercase().replace(' ', "_")
+}
+
+/// `DynamoDB Streams` => `dynamodb_streams`
+fn profile_service_name(service_id: &str) -> String {
+    service_id.to_ascii_lowercase().replace(' ', "_")
+}
+
+#[cfg(test)]
+mod test {
+    use crate::profile::profile_file::{ProfileFileKind, ProfileFiles};
+    use crate::provider_config::ProviderConfig;
+    use crate::service_config::EnvServiceConfig;
+    use aws_types::os_shim_internal::{Env, Fs};
+    use aws_types::service_config::{LoadServiceConfig, ServiceConfigKey};
+
+    const CONFIG: &str = r#"
+[default]
+services = local
+endpoint_url = http://global-profile
+
+[services local]
+dynamodb_streams =
+  endpoint_url = http://streams-profile
+"#;
+
+    async fn service_config(env: &[(&str, &str)]) -> EnvServiceConfig {
+        let conf = ProviderConfig::empty()
+            .with_env(Env::from_slice(env))
+            .with_profile_config(
+                Some(
+                    ProfileFiles::builder()
+                        .with_file(ProfileFileKind::Config, "conf")
+                        .build(),
+                ),
+                None,
+            )
+            .with_fs(Fs::from_slice(&[("conf", CONFIG)]));
+        EnvServiceConfig::load(&conf).await
+    }
+
+    fn endpoint_url(config: &EnvServiceConfig, service_id: &str) -> Option<String> {
+        config.load_config(ServiceConfigKey::new(
+            service_id,
+            "AWS_ENDPOINT_URL",
+            "endpoint_url",
+        ))
+    }
+
+    #[tokio::test]
+    async fn profile_services_section() {
+        let config = service_config(&[]).await;
+        assert_eq!(
+            Some("http://streams-profile"),
+            endpoint_url(&config, "DynamoDB Streams").as_deref()
+        );
+        assert_eq!(
+            Some("http://global-profile"),
+            endpoint_url(&config, "S3").as_deref()
+        );
+    }
+
+    #[tokio::test]
+    async fn environment_priority() {
+        let config = service_config(&[
+            ("AWS_ENDPOINT_URL_DYNAMODB_STREAMS", "http://streams-env"),
+            ("AWS_ENDPOINT_URL", "http://global-env"),
+        ])
+        .await;
+        assert_eq!(
+            Some("http://streams-env"),
+            endpoint_url(&config, "DynamoDB Streams").as_deref()
+        );
+        assert_eq!(
+            Some("http://global-env"),
+            endpoint_url(&config, "S3").as_deref()
+        );
+    }
+
+    #[tokio::test]
+    async fn nothing_configured() {
+        let config = EnvServiceConfig::load(&ProviderConfig::empty()).await;
+        assert_eq!(None, endpoint_url(&config, "S3"));
+    }
+}
@@ -10,6 +10,10 @@
 //! 2. [AWS profile](#aws-profile-configuration) defined in `~/.aws/config`
 //! 3. Static configuration via [`static_configuration`](Builder::static_configuration)
 //!
+//! Tokens can also be loaded from other sources, such as environment variables, the output of a
+//! command, or an async callback, with a [`WebIdentityTokenSource`](token_source::WebIdentityTokenSource).
+//! See [`token_source`] for more details.
+//!
 //! _Note: [WebIdentityTokenCredentialsProvider] is part of the [default provider chain](crate::default_provider).
 //! Unless you need specific behavior or configuration overrides, it is recommended to use the
 //! default chain instead of using this provider directly. This client should be considered a "low level"
@@ -45,6 +49,27 @@
 //!   web_identity_token_file = /token.jwt
 //!   ```
 //!
+//! A provider with a custom token source can be referenced from a profile as a named
+//! `credential_source` by registering it with
+//! [`with_custom_provider`](crate::profile::credentials::Builder::with_custom_provider):
+//!
+//! ```ini
+//! [profile ci]
+//! credential_source = CiOidc
+//! role_arn = arn:aws:iam::123456789012:role/deploy
+//! ```
+//!
+//! Profiles with a `credential_source` always assume their `role_arn`, so this makes two calls to
+//! STS: the `CiOidc` provider calls `AssumeRoleWithWebIdentity` for the role it was built with,
+//! and those credentials are then used to call `AssumeRole` for the `deploy` role. The first role
+//! must be allowed to assume the second one. To assume a single role, use the provider directly
+//! instead of through a profile.
+//!
+//! ## Session tags and source identity
+//! `AssumeRoleWithWebIdentity` doesn't accept session tags or a source identity as parameters.
+//! They are taken from the `https://aws.amazon.com/tags` and `https://aws.amazon.com/source_identity`
+//! claims of the token, so they must be configured with the OIDC identity provider.
+//!
 //! # Examples
 //! Web Identity Token providers are part of the [default chain](crate::default_provider::credentials).
 //! However, they may be directly constructed if you don't want to use the default provider chain.
@@ -64,12 +89,16 @@
 use crate::provider_config::ProviderConfig;
 use crate::sts;
 use aws_credential_types::provider::{self, error::CredentialsError, future, ProvideCredentials};
+use aws_sdk_sts::types::PolicyDescriptorType;
 use aws_sdk_sts::Client as StsClient;
 use aws_smithy_async::time::SharedTimeSource;
 use aws_smithy_types::error::display::DisplayErrorContext;
 use aws_types::os_shim_internal::{Env, Fs};
-use std::borrow::Cow;
-use std::path::{Path, PathBuf};
+use std::path::PathBuf;
+use std::time::Duration;
+use token_source::WebIdentityTokenSource;
+
+pub mod token_source;
 
 const ENV_VAR_TOKEN_FILE: &str = "AWS_WEB_IDENTITY_TOKEN_FILE";
 const ENV_VAR_ROLE_ARN: &str = "AWS_ROLE_ARN";
@@ -83,7 +112,9 @@ pub struct WebIdentityTokenCredentialsProvider {
     source: Source,
     time_source: SharedTimeSource,
     fs: Fs,
+    env: Env,
     sts_client: StsClient,
+    session_settings: SessionSettings,
 }
 
 impl WebIdentityTokenCredentialsProvider {
@@ -97,6 +128,19 @@ impl WebIdentityTokenCredentialsProvider {
 enum Source {
     Env(Env),
     Static(StaticConfiguration),
+    TokenSource {
+        token_source: WebIdentityTokenSource,
+        role_arn: Option<String>,
+        session_name: Option<String>,
+    },
+}
+
+/// Optional `AssumeRoleWithWebIdentity` parameters
+#[derive(Debug, Default)]
+struct SessionSettings {
+    policy: Option<String>,
+    policy_arns: Option<Vec<PolicyDescriptorType>>,
+    session_length: Option<Duration>,
 }
 
 /// Statically configured WebIdentityToken configuration
@@ -121,8 +165,15 @@ impl ProvideCredentials for WebIdentityTokenCredentialsProvider {
     }
 }
 
+/// The token source, role ARN and session name to assume the role with
+struct ResolvedSource {
+    token_source: WebIdentityTokenSource,
+    role_arn: String,
+    session_name: String,
+}
+
 impl WebIdentityTokenCredentialsProvider {
-    fn source(&self) -> Result<Cow<'_, StaticConfiguration>, CredentialsError> {
+    fn source(&self) -> Result<ResolvedSource, CredentialsError> {
         match &self.source {
             Source::Env(env) => {
                 let token_file = env.get(ENV_VAR_TOKEN_FILE).map_err(|_| {
@@ -133,26 +184,54 @@ impl WebIdentityTokenCredentialsProvider {
                         "AWS_ROLE_ARN environment variable must be set",
                     )
                 })?;
-                let session_name = env.get(ENV_VAR_SESSION_NAME).unwrap_or_else(|_| {
-                    sts::util::default_session_name("web-identity-token", self.time_source.now())
-                });
-                Ok(Cow::Owned(StaticConfiguration {
-                    web_identity_token_file: token_file.into(),
+                let session_name = env
+                    .get(ENV_VAR_SESSION_NAME)
+                    .unwrap_or_else(|_| self.default_session_name());
+                Ok(ResolvedSource {
+                    token_source: WebIdentityTokenSource::file(token_file),
                     role_arn,
                     session_name,
-                }))
+                })
+            }
+            Source::Static(conf) => Ok(ResolvedSource {
+                token_source: WebIdentityTokenSource::file(conf.web_identity_token_file.clone()),
+                role_arn: conf.role_arn.clone(),
+                session_name: conf.session_name.clone(),
+            }),
+            Source::TokenSource {
+                token_source,
+                role_arn,
+                session_name,
+            } => {
+                let role_arn = role_arn.clone().ok_or_else(|| {
+                    CredentialsError::invalid_configuration(
+                        "a role ARN must be set when using a web identity token source",
+                    )
+                })?;
+                Ok(ResolvedSource {
+                    token_source: token_source.clone(),
+                    role_arn,
+                    session_name: session_name
+                        .clone()
+                        .unwrap_or_else(|| self.default_session_name()),
+                })
             }
-            Source::Static(conf) => Ok(Cow::Borrowed(conf)),
         }
     }
+
+    fn default_session_name(&self) -> String {
+        sts::util::default_session_name("web-identity-token", self.time_source.now())
+    }
+
     async fn credentials(&self) -> provider::Result {
-        let conf = self.source()?;
+        let source = self.source()?;
+        let token = source.token_source.load(&self.fs, &self.env).await?;
         load_credentials(
-            &self.fs,
             &self.sts_client,
-            &conf.web_identity_token_file,
-            &conf.role_arn,
-            &conf.session_name,
+            token,
+            &source.role_arn,
+            &source.session_name,
+            &self.session_settings,
         )
         .await
     }
@@ -163,6 +242,10 @@ impl WebIdentityTokenCredentialsProvider {
 pub struct Builder {
     source: Option<Source>,
     config: Option<ProviderConfig>,
+    token_source: Option<WebIdentityTokenSource>,
+    role_arn: Option<String>,
+    session_name: Option<String>,
+    session_settings: SessionSettings,
 }
 
 impl Builder {
@@ -193,6 +276,63 @@ impl Builder {
         self
     }
 
+    /// Load web identity tokens from `token_source` instead of a file
+    ///
+    /// When a token source is set, the role to assume must be set with [`role_arn`](Self::role_arn),
+    /// and environment variables are not used. This overrides [`static_configuration`](Self::static_configuration).
+    pub fn token_source(mut self, token_source: WebIdentityTokenSource) -> Self {
+        self.token_source = Some(token_source);
+        self
+    }
+
+    /// Set the ARN of the role to assume when using a [`token_source`](Self::token_source)
+    ///
+    /// This has no effect unless a token source is set.
+    pub fn role_arn(mut self, role_arn: impl Into<String>) -> Self {
+        self.role_arn = Some(role_arn.into());
+        self
+    }
+
+    /// Set the session name to use when using a [`token_source`](Self::token_source)
+    ///
+    /// If unset, a session name is generated. This has no effect unless a token source is set.
+    pub fn session_name(mut self, session_name: impl Into<String>) -> Self {
+        self.session_name = Some(session_name.into());
+        self
+    }
+
+    /// Set an IAM policy in JSON format that you want to use as an inline session policy.
+    ///
+    /// This parameter is optional
+    /// For more information, see
+    /// [policy](aws_sdk_sts::operation::assume_role_with_web_identity::builders::AssumeRoleWithWebIdentityInputBuilder::policy)
+    pub fn policy(mut self, policy: impl Into<String>) -> Self {
+        self.session_settings.policy = Some(policy.into());
+        self
+    }
+
+    /// Set the Amazon Resource Names (ARNs) of the IAM managed policies that you want to use as managed session policies.
+    ///
+    /// This parameter is optional.
+    /// For more information, see
+    /// [policy_arns](aws_sdk_sts::operation::assume_role_with_web_identity::builders::AssumeRoleWithWebIdentityInputBuilder::policy_arns)
+    pub fn policy_arns(mut self, policy_arns: Vec<PolicyDescriptorType>) -> Self {
+        self.session_settings.policy_arns = Some(policy_arns);
+        self
+    }
+
+    /// Set the expiration time of the role session.
+    ///
+    /// When unset, this value defaults to 1 hour. The value can range from 15 minutes up to the
+    /// maximum session duration set for the role.
+    ///
+    /// For more information, see
+    /// [duration_seconds](aws_sdk_sts::operation::assume_role_with_web_identity::builders::AssumeRoleWithWebIdentityInputBuilder::duration_seconds)
+    pub fn session_length(mut self, length: Duration) -> Self {
+        self.session_settings.session_length = Some(length);
+        self
+    }
+
     /// Build a [`WebIdentityTokenCredentialsProvider`]
     ///
     /// ## Panics
@@ -200,35 +340,39 @@ impl Builder {
     /// builder, this function will panic.
     pub fn build(self) -> WebIdentityTokenCredentialsProvider {
         let conf = self.config.unwrap_or_default();
-        let source = self.source.unwrap_or_else(|| Source::Env(conf.env()));
+        let source = match self.token_source {
+            Some(token_source) => Source::TokenSource {
+                token_source,
+                role_arn: self.role_arn,
+                session_name: self.session_name,
+            },
+            None => self.source.unwrap_or_else(|| Source::Env(conf.env())),
+        };
         WebIdentityTokenCredentialsProvider {
             source,
             fs: conf.fs(),
+            env: conf.env(),
             sts_client: StsClient::from_conf(conf.sts_client_config().build()),
             time_source: conf.time_source(),
+            session_settings: self.session_settings,
         }
     }
 }
 
 async fn load_credentials(
-    fs: &Fs,
     sts_client: &StsClient,
-    token_file: impl AsRef<Path>,
+    token: String,
     role_arn: &str,
     session_name: &str,
+    session_settings: &SessionSettings,
 ) -> provider::Result {
-    let token = fs
-        .read_to_end(token_file)
-        .await
-        .map_err(CredentialsError::provider_error)?;
-    let token = String::from_utf8(token).map_err(|_utf_8_error| {
-        CredentialsError::unhandled("WebIdentityToken was not valid UTF-8")
-    })?;
-
     let resp = sts_client.assume_role_with_web_identity()
         .role_arn(role_arn)
         .role_session_name(session_name)
         .web_identity_token(token)
+        .set_policy(session_settings.policy.clone())
+        .set_policy_arns(session_settings.policy_arns.clone())
+        .set_duration_seconds(session_settings.session_length.map(|dur| dur.as_secs() as i32))
         .send()
         .await
         .map_err(|sdk_error| {
@@ -240,17 +384,26 @@ async fn load_credentials(
 
 #[cfg(test)]
 mod test {
+    use crate::profile::credentials::ProfileFileCredentialsProvider;
     use crate::provider_config::ProviderConfig;
     use crate::test_case::no_traffic_connector;
+    use crate::web_identity_token::token_source::WebIdentityTokenSource;
     use crate::web_identity_token::{
         Builder, ENV_VAR_ROLE_ARN, ENV_VAR_SESSION_NAME, ENV_VAR_TOKEN_FILE,
     };
     use aws_credential_types::provider::error::CredentialsError;
+    use aws_credential_types::provider::ProvideCredentials;
+    use aws_credential_types::time_source::{TestingTimeSource, TimeSource};
     use aws_sdk_sts::config::Region;
+    use aws_sdk_sts::types::PolicyDescriptorType;
     use aws_smithy_async::rt::sleep::TokioSleep;
+    use aws_smithy_client::erase::DynConnector;
+    use aws_smithy_client::test_connection::{capture_request, TestConnection};
+    use aws_smithy_http::body::SdkBody;
     use aws_smithy_types::error::display::DisplayErrorContext;
     use aws_types::os_shim_internal::{Env, Fs};
     use std::collections::HashMap;
+    use std::time::{Duration, UNIX_EPOCH};
 
     #[tokio::test]
     async fn unloaded_provider() {
@@ -324,4 +477,167 @@ mod test {
             _ => panic!("incorrect error variant"),
         }
     }
+
+    #[tokio::test]
+    async fn token_source_requires_role_arn() {
+        let provider = Builder::default()
+            .configure(
+                &ProviderConfig::empty()
+                    .with_sleep(TokioSleep::new())
+                    .with_http_connector(no_traffic_connector())
+                    .with_region(Some(Region::new("us-east-1")))
+                    .with_env(Env::from_slice(&[("CI_OIDC_TOKEN", "token")])),
+            )
+            .token_source(WebIdentityTokenSource::environment_variable(
+                "CI_OIDC_TOKEN",
+            ))
+            .build();
+        let err = provider.credentials().await.expect_err("no role ARN");
+        match err {
+            CredentialsError::InvalidConfiguration { .. } => { /* ok */ }
+            _ => panic!("incorrect error variant"),
+        }
+    }
+
+    #[tokio::test]
+    async fn token_source_and_session_settings() {
+        let (server, request) = capture_request(None);
+        let provider = Builder::default()
+            .configure(
+                &ProviderConfig::empty()
+                    .with_sleep(TokioSleep::new())
+                    .with_http_connector(DynConnector::new(server))
+                    .with_region(Some(Region::new("us-east-1")))
+                    .with_env(Env::from_slice(&[("CI_OIDC_TOKEN", "token-from-env")])),
+            )
+            .token_source(WebIdentityTokenSource::environment_variable(
+                "CI_OIDC_TOKEN",
+            ))
+            .role_arn("arn:aws:iam::123456789123:role/test-role")
+            .session_name("test-session")
+            .policy_arns(vec![PolicyDescriptorType::builder()
+                .arn("arn:aws:iam::aws:policy/ReadOnlyAccess")
+                .build()])
+            .session_length(Duration::from_secs(900))
+            .build();
+        let _ = provider.credentials().await;
+
+        let req = request.expect_request();
+        let body = std::str::from_utf8(req.body().bytes().unwrap()).unwrap();
+        for expected in [
+            "Action=AssumeRoleWithWebIdentity",
+            "WebIdentityToken=token-from-env",
+            "RoleSessionName=test-session",
+            "PolicyArns.member.1.arn=arn%3Aaws%3Aiam%3A%3Aaws%3Apolicy%2FReadOnlyAccess",
+            "DurationSeconds=900",
+        ] {
+            assert!(
+                body.contains(expected),
+                "{} should contain {}",
+                body,
+                expected
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn token_source_as_profile_credential_source() {
+        fn credentials_xml(action: &str) -> String {
+            format!(
+                "<{action}Response xmlns=\"https://sts.amazonaws.com/doc/2011-06-15/\">\
+                <{action}Result><Credentials>\
+                <AccessKeyId>ASIA{action}</AccessKeyId>\
+                <SecretAccessKey>secret</SecretAccessKey>\
+                <SessionToken>token</SessionToken>\
+                <Expiration>2009-02-13T23:31:30Z</Expiration>\
+                </Credentials></{action}Result></{action}Response>",
+                action = action
+            )
+        }
+        let response = |action: &str| {
+            http::Response::builder()
+                .status(200)
+                .body(SdkBody::from(credentials_xml(action)))
+                .unwrap()
+        };
+        let conn = TestConnection::new(vec![
+            (
+                http::Request::new(SdkBody::from("request body")),
+                response("AssumeRoleWithWebIdentity"),
+            ),
+            (
+                http::Request::new(SdkBody::from("request body")),
+                response("AssumeRole"),
+            ),
+        ]);
+        let conf = ProviderConfig::empty()
+            .with_sleep(TokioSleep::new())
+            .with_time_source(TimeSource::testing(&TestingTimeSource::new(
+                UNIX_EPOCH + Duration::from_secs(1234567890 - 120),
+            )))
+            .with_http_connector(DynConnector::new(conn.clone()))
+            .with_region(Some(Region::new("us-east-1")))
+            .with_env(Env::from_slice(&[
+                ("HOME", "/home"),
+                ("AWS_PROFILE", "ci"),
+                ("CI_OIDC_TOKEN", "token-from-env"),
+            ]))
+            .with_fs(Fs::from_slice(&[(
+                "/home/.aws/config",
+                "[profile ci]\n\
+                credential_source = CiOidc\n\
+                role_arn = arn:aws:iam::123456789012:role/deploy\n",
+            )]));
+        let web_identity = Builder::default()
+            .configure(&conf)
+            .token_source(WebIdentityTokenSource::environment_variable(
+                "CI_OIDC_TOKEN",
+            ))
+            .role_arn("arn:aws:iam::123456789012:role/ci-web-identity")
+            .build();
+        let provider = ProfileFileCredentialsProvider::builder()
+            .configure(&conf)
+            .with_custom_provider("CiOidc", web_identity)
+            .build();
+
+        let credentials = provider
+            .provide_credentials()
+            .await
+            .expect("credentials from the profile");
+        assert_eq!("ASIAAssumeRole", credentials.access_key_id());
+
+        let requests = conn.requests();
+        let bodies: Vec<_> = requests
+            .iter()
+            .map(|request| std::str::from_utf8(request.actual.body().bytes().unwrap()).unwrap())
+            .collect();
+        assert_eq!(2, bodies.len());
+        for expected in [
+            "Action=AssumeRoleWithWebIdentity",
+            "WebIdentityToken=token-from-env",
+            "role%2Fci-web-identity",
+        ] {
+            assert!(
+                bodies[0].contains(expected),
+                "{} should contain {}",
+                bodies[0],
+                expected
+            );
+        }
+        for expected in ["Action=AssumeRole&", "role%2Fdeploy"] {
+            assert!(
+                bodies[1].contains(expected),
+                "{} should contain {}",
+                bodies[1],
+                expected
+            );
+        }
+        assert!(
+            requests[1].actual.headers()["authorization"]
+                .to_str()
+                .unwrap()
+                .contains("Credential=ASIAAssumeRoleWithWebIdentity/"),
+            "the second role is assumed with the credentials of the first"
+        );
+    }
 }
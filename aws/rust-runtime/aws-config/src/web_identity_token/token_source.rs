@@ -0,0 +1,270 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Sources of OIDC tokens for the [`WebIdentityTokenCredentialsProvider`](super::WebIdentityTokenCredentialsProvider)
+//!
+//! By default, web identity tokens are read from the file named by `AWS_WEB_IDENTITY_TOKEN_FILE`.
+//! A [`WebIdentityTokenSource`] can be used instead to read the token from another file, an
+//! environment variable, the output of a command, or any [`ProvideWebIdentityToken`] implementation.
+//!
+//! # Examples
+//! Fetch tokens from an HTTP endpoint with a user provided async function:
+//! ```no_run
+//! use aws_config::web_identity_token::token_source::{
+//!     provide_web_identity_token_fn, WebIdentityTokenSource,
+//! };
+//! use aws_config::web_identity_token::WebIdentityTokenCredentialsProvider;
+//! # async fn fetch_oidc_token_from_ci() -> Result<String, std::io::Error> { todo!() }
+//!
+//! let provider = WebIdentityTokenCredentialsProvider::builder()
+//!     .token_source(WebIdentityTokenSource::custom(provide_web_identity_token_fn(|| async {
+//!         fetch_oidc_token_from_ci()
+//!             .await
+//!             .map_err(aws_credential_types::provider::error::CredentialsError::provider_error)
+//!     })))
+//!     .role_arn("arn:aws:iam::123456789012:role/ci-deploy")
+//!     .build();
+//! ```
+
+use crate::credential_process::{run_command, CommandWithSensitiveArgs};
+use aws_credential_types::provider::error::CredentialsError;
+use aws_types::os_shim_internal::{Env, Fs};
+use std::fmt;
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Result type for web identity token providers
+pub type Result = std::result::Result<String, CredentialsError>;
+
+/// Convenience `ProvideWebIdentityToken` struct that implements the `ProvideWebIdentityToken` trait.
+pub mod future {
+    use aws_smithy_async::future::now_or_later::NowOrLater;
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+
+    type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+    /// Future new-type that `ProvideWebIdentityToken::provide_web_identity_token` must return.
+    #[derive(Debug)]
+    pub struct ProvideWebIdentityToken<'a>(NowOrLater<super::Result, BoxFuture<'a, super::Result>>);
+
+    impl<'a> ProvideWebIdentityToken<'a> {
+        /// Creates a `ProvideWebIdentityToken` struct from a future.
+        pub fn new(future: impl Future<Output = super::Result> + Send + 'a) -> Self {
+            ProvideWebIdentityToken(NowOrLater::new(Box::pin(future)))
+        }
+
+        /// Creates a `ProvideWebIdentityToken` struct from a resolved token value.
+        pub fn ready(token: super::Result) -> Self {
+            ProvideWebIdentityToken(NowOrLater::ready(token))
+        }
+    }
+
+    impl Future for ProvideWebIdentityToken<'_> {
+        type Output = super::Result;
+
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+            Pin::new(&mut self.0).poll(cx)
+        }
+    }
+}
+
+/// Asynchronous provider of OIDC web identity tokens
+///
+/// A token is requested every time credentials are loaded, so implementations should return a
+/// fresh token if the previous one may have expired.
+pub trait ProvideWebIdentityToken: Send + Sync + fmt::Debug {
+    /// Returns a future that provides a web identity token.
+    fn provide_web_identity_token<'a>(&'a self) -> future::ProvideWebIdentityToken<'a>
+    where
+        Self: 'a;
+}
+
+/// A [`ProvideWebIdentityToken`] implemented by a closure.
+///
+/// See [`provide_web_identity_token_fn`] for more details.
+#[derive(Copy, Clone)]
+pub struct ProvideWebIdentityTokenFn<T> {
+    f: T,
+}
+
+impl<T> fmt::Debug for ProvideWebIdentityTokenFn<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ProvideWebIdentityTokenFn")
+    }
+}
+
+impl<T, F> ProvideWebIdentityToken for ProvideWebIdentityTokenFn<T>
+where
+    T: Fn() -> F + Send + Sync,
+    F: Future<Output = Result> + Send + 'static,
+{
+    fn provide_web_identity_token<'a>(&'a self) -> future::ProvideWebIdentityToken<'a>
+    where
+        Self: 'a,
+    {
+        future::ProvideWebIdentityToken::new((self.f)())
+    }
+}
+
+/// Returns a new web identity token provider built with the given closure. This allows you
+/// to create a [`ProvideWebIdentityToken`] implementation from an async block that returns
+/// a [`token_source::Result`](Result).
+pub fn provide_web_identity_token_fn<T, F>(f: T) -> ProvideWebIdentityTokenFn<T>
+where
+    T: Fn() -> F + Send + Sync,
+    F: Future<Output = Result> + Send + 'static,
+{
+    ProvideWebIdentityTokenFn { f }
+}
+
+/// Where a [`WebIdentityTokenCredentialsProvider`](super::WebIdentityTokenCredentialsProvider)
+/// loads OIDC tokens from
+#[derive(Clone, Debug)]
+pub struct WebIdentityTokenSource {
+    inner: Inner,
+}
+
+#[derive(Clone, Debug)]
+enum Inner {
+    File(PathBuf),
+    EnvironmentVariable(String),
+    Command(CommandWithSensitiveArgs<String>),
+    Custom(Arc<dyn ProvideWebIdentityToken>),
+}
+
+impl WebIdentityTokenSource {
+    /// Reads the token from the file at `path`.
+    ///
+    /// The file is read every time credentials are loaded, so it may be rotated by another process.
+    pub fn file(path: impl Into<PathBuf>) -> Self {
+        Self {
+            inner: Inner::File(path.into()),
+        }
+    }
+
+    /// Reads the token from the environment variable `name`.
+    pub fn environment_variable(name: impl Into<String>) -> Self {
+        Self {
+            inner: Inner::EnvironmentVariable(name.into()),
+        }
+    }
+
+    /// Runs `command` with the system shell and uses its standard output as the token.
+    ///
+    /// The command must exit with status 0. Surrounding whitespace is removed from the output.
+    pub fn command(command: impl Into<String>) -> Self {
+        Self {
+            inner: Inner::Command(CommandWithSensitiveArgs::new(command.into())),
+        }
+    }
+
+    /// Loads the token from a custom [`ProvideWebIdentityToken`] implementation.
+    pub fn custom(provider: impl ProvideWebIdentityToken + 'static) -> Self {
+        Self {
+            inner: Inner::Custom(Arc::new(provider)),
+        }
+    }
+
+    pub(super) async fn load(&self, fs: &Fs, env: &Env) -> Result {
+        match &self.inner {
+            Inner::File(path) => {
+                let token = fs
+                    .read_to_end(path)
+                    .await
+                    .map_err(CredentialsError::provider_error)?;
+                String::from_utf8(token).map_err(|_utf_8_error| {
+                    CredentialsError::unhandled("WebIdentityToken was not valid UTF-8")
+                })
+            }
+            Inner::EnvironmentVariable(name) => env
+                .get(name)
+                .map_err(|_| CredentialsError::not_loaded(format!("${} was not set", name))),
+            Inner::Command(command) => {
+                // Security: command arguments must be redacted at debug level
+                tracing::debug!(command = %command, "loading web identity token from external process");
+                let token = run_command(command, "web identity token")?;
+                let token = String::from_utf8(token).map_err(|_utf_8_error| {
+                    CredentialsError::unhandled("WebIdentityToken was not valid UTF-8")
+                })?;
+                Ok(token.trim().to_string())
+            }
+            Inner::Custom(provider) => provider.provide_web_identity_token().await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{provide_web_identity_token_fn, WebIdentityTokenSource};
+    use aws_credential_types::provider::error::CredentialsError;
+    use aws_smithy_types::error::display::DisplayErrorContext;
+    use aws_types::os_shim_internal::{Env, Fs};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    async fn load(source: WebIdentityTokenSource) -> super::Result {
+        let fs = Fs::from_slice(&[("/token.jwt", "file-token")]);
+        let env = Env::from_slice(&[("CI_OIDC_TOKEN", "env-token")]);
+        source.load(&fs, &env).await
+    }
+
+    #[tokio::test]
+    async fn file_and_environment_variable() {
+        assert_eq!(
+            "file-token",
+            load(WebIdentityTokenSource::file("/token.jwt"))
+                .await
+                .unwrap()
+        );
+        assert_eq!(
+            "env-token",
+            load(WebIdentityTokenSource::environment_variable(
+                "CI_OIDC_TOKEN"
+            ))
+            .await
+            .unwrap()
+        );
+        match load(WebIdentityTokenSource::environment_variable("MISSING")).await {
+            Err(CredentialsError::CredentialsNotLoaded { .. }) => { /* ok */ }
+            other => panic!("incorrect result: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    #[cfg_attr(windows, ignore)]
+    async fn command() {
+        assert_eq!(
+            "command-token",
+            load(WebIdentityTokenSource::command("echo command-token"))
+                .await
+                .unwrap()
+        );
+        let err = load(WebIdentityTokenSource::command("echo oops >&2; exit 1"))
+            .await
+            .expect_err("command failed");
+        assert!(
+            format!("{}", DisplayErrorContext(&err)).contains("oops"),
+            "{}",
+            DisplayErrorContext(&err)
+        );
+    }
+
+    #[tokio::test]
+    async fn custom() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let source = WebIdentityTokenSource::custom(provide_web_identity_token_fn({
+            let calls = calls.clone();
+            move || {
+                let call = calls.fetch_add(1, Ordering::SeqCst);
+                async move { Ok(format!("token-{}", call)) }
+            }
+        }));
+        assert_eq!("token-0", load(source.clone()).await.unwrap());
+        assert_eq!("token-1", load(source).await.unwrap());
+    }
+}
@@ -28,6 +28,9 @@ pub mod no_credentials;
 /// Support types required for adding presigning to an operation in a generated service.
 pub mod presigning;
 
+/// Presigned POST policies for browser uploads to S3
+pub mod presigned_post;
+
 /// Presigning tower service
 pub mod presigning_service;
 
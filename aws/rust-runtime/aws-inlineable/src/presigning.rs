@@ -5,6 +5,7 @@
 
 //! Presigned request types and configuration.
 
+use http::HeaderName;
 use std::fmt;
 use std::time::{Duration, SystemTime};
 
@@ -16,6 +17,7 @@ const ONE_WEEK: Duration = Duration::from_secs(604800);
 pub struct PresigningConfig {
     start_time: SystemTime,
     expires_in: Duration,
+    header_hoisting: HeaderHoisting,
 }
 
 impl PresigningConfig {
@@ -46,6 +48,44 @@ impl PresigningConfig {
     pub fn start_time(&self) -> SystemTime {
         self.start_time
     }
+
+    /// Returns the rules for moving request headers into the query string of the presigned request.
+    pub fn header_hoisting(&self) -> &HeaderHoisting {
+        &self.header_hoisting
+    }
+}
+
+/// Rules for moving request headers into the query string when presigning.
+///
+/// Headers that are left in place are signed, so they must be sent with exactly the same
+/// values by whoever uses the presigned request. Headers that are moved into the query string
+/// are part of the presigned URI instead.
+#[non_exhaustive]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub enum HeaderHoisting {
+    /// Leave all headers in place.
+    #[default]
+    Disabled,
+
+    /// Move all `x-amz-*` headers into the query string, except for `x-amz-target`, which
+    /// services that use it require as a header.
+    AmzHeaders,
+
+    /// Move the given headers into the query string.
+    Headers(Vec<HeaderName>),
+}
+
+impl HeaderHoisting {
+    /// Returns true if the header named `name` should be moved into the query string.
+    pub fn hoists(&self, name: &HeaderName) -> bool {
+        match self {
+            HeaderHoisting::Disabled => false,
+            HeaderHoisting::AmzHeaders => {
+                name.as_str().starts_with("x-amz-") && name.as_str() != "x-amz-target"
+            }
+            HeaderHoisting::Headers(headers) => headers.contains(name),
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -88,6 +128,7 @@ impl From<ErrorKind> for PresigningConfigError {
 pub struct PresigningConfigBuilder {
     start_time: Option<SystemTime>,
     expires_in: Option<Duration>,
+    header_hoisting: Option<HeaderHoisting>,
 }
 
 impl PresigningConfigBuilder {
@@ -139,6 +180,25 @@ impl PresigningConfigBuilder {
         self.expires_in = expires_in;
     }
 
+    /// Sets the rules for moving request headers into the query string of the presigned request.
+    ///
+    /// If not specified, this defaults to [`HeaderHoisting::Disabled`].
+    ///
+    /// Optional.
+    pub fn header_hoisting(mut self, header_hoisting: HeaderHoisting) -> Self {
+        self.set_header_hoisting(Some(header_hoisting));
+        self
+    }
+
+    /// Sets the rules for moving request headers into the query string of the presigned request.
+    ///
+    /// If not specified, this defaults to [`HeaderHoisting::Disabled`].
+    ///
+    /// Optional.
+    pub fn set_header_hoisting(&mut self, header_hoisting: Option<HeaderHoisting>) {
+        self.header_hoisting = header_hoisting;
+    }
+
     /// Builds the `PresigningConfig`. This will error if `expires_in` is not
     /// given, or if it's longer than one week.
     pub fn build(self) -> Result<PresigningConfig, PresigningConfigError> {
@@ -149,6 +209,7 @@ impl PresigningConfigBuilder {
         Ok(PresigningConfig {
             start_time: self.start_time.unwrap_or_else(SystemTime::now),
             expires_in,
+            header_hoisting: self.header_hoisting.unwrap_or_default(),
         })
     }
 }
@@ -5,7 +5,7 @@
 
 #![allow(dead_code)]
 
-use crate::presigning::PresigningConfig;
+use crate::presigning::{HeaderHoisting, PresigningConfig};
 use crate::serialization_settings::HeaderSerializationSettings;
 use aws_runtime::auth::sigv4::{HttpSignatureType, SigV4OperationSigningConfig};
 use aws_runtime::invocation_id::InvocationIdInterceptor;
@@ -13,26 +13,42 @@ use aws_runtime::request_info::RequestInfoInterceptor;
 use aws_runtime::user_agent::UserAgentInterceptor;
 use aws_sigv4::http_request::SignableBody;
 use aws_smithy_async::time::{SharedTimeSource, StaticTimeSource};
+use aws_smithy_http::body::SdkBody;
+use aws_smithy_http::query_writer::QueryWriter;
 use aws_smithy_runtime::client::retries::strategy::NeverRetryStrategy;
 use aws_smithy_runtime_api::client::interceptors::{
     disable_interceptor, BeforeSerializationInterceptorContextMut,
     BeforeTransmitInterceptorContextMut, BoxError, Interceptor, InterceptorRegistrar,
     SharedInterceptor,
 };
-use aws_smithy_runtime_api::client::orchestrator::ConfigBagAccessors;
+use aws_smithy_runtime_api::client::orchestrator::{ConfigBagAccessors, HttpRequest};
 use aws_smithy_runtime_api::client::runtime_plugin::RuntimePlugin;
 use aws_smithy_types::config_bag::{ConfigBag, FrozenLayer, Layer};
+use http::header::{CONTENT_LENGTH, CONTENT_TYPE};
+use http::{Method, Uri};
+use std::sync::{Arc, Mutex};
+
+const FORM_URL_ENCODED: &str = "application/x-www-form-urlencoded";
 
 /// Interceptor that tells the SigV4 signer to add the signature to query params,
 /// and sets the request expiration time from the presigning config.
+///
+/// Without a payload override, the request body is signed as-is. Since a [`PresignedRequest`]
+/// doesn't include a body, only empty bodies are allowed, except for the form encoded bodies of
+/// the query protocols, which are moved into the query string.
+///
+/// [`PresignedRequest`]: crate::presigning::PresignedRequest
 #[derive(Debug)]
 pub(crate) struct SigV4PresigningInterceptor {
     config: PresigningConfig,
-    payload_override: SignableBody<'static>,
+    payload_override: Option<SignableBody<'static>>,
 }
 
 impl SigV4PresigningInterceptor {
-    pub(crate) fn new(config: PresigningConfig, payload_override: SignableBody<'static>) -> Self {
+    pub(crate) fn new(
+        config: PresigningConfig,
+        payload_override: Option<SignableBody<'static>>,
+    ) -> Self {
         Self {
             config,
             payload_override,
@@ -60,23 +76,178 @@ impl Interceptor for SigV4PresigningInterceptor {
 
     fn modify_before_signing(
         &self,
-        _context: &mut BeforeTransmitInterceptorContextMut<'_>,
+        context: &mut BeforeTransmitInterceptorContextMut<'_>,
         cfg: &mut ConfigBag,
     ) -> Result<(), BoxError> {
         if let Some(mut config) = cfg.get::<SigV4OperationSigningConfig>().cloned() {
+            let request = context.request_mut();
+            if self.payload_override.is_none() {
+                move_form_body_to_query(request)?;
+            }
+            hoist_headers(request, self.config.header_hoisting());
+
             config.signing_options.expires_in = Some(self.config.expires());
             config.signing_options.signature_type = HttpSignatureType::HttpRequestQueryParams;
-            config.signing_options.payload_override = Some(self.payload_override.clone());
+            if let Some(payload_override) = &self.payload_override {
+                config.signing_options.payload_override = Some(payload_override.clone());
+            }
             cfg.interceptor_state()
                 .put::<SigV4OperationSigningConfig>(config);
             Ok(())
         } else {
-            Err(
-                "SigV4 presigning requires the SigV4OperationSigningConfig to be in the config bag. \
-                This is a bug. Please file an issue.".into(),
+            Err("only operations that are signed with SigV4 can be presigned".into())
+        }
+    }
+}
+
+/// Interceptor that presigns the request of a `CustomizableOperation` instead of sending it.
+///
+/// A customizable operation can't be stopped before transmit, so this interceptor applies the
+/// same config as the [`SigV4PresigningRuntimePlugin`], and then takes the signed request out
+/// of the context and fails the operation before the request is transmitted.
+#[derive(Debug)]
+pub(crate) struct CustomizedPresigningInterceptor {
+    inner: SigV4PresigningInterceptor,
+    presigned_request: PresignedRequestSlot,
+}
+
+/// Holds the request taken by a [`CustomizedPresigningInterceptor`].
+#[derive(Clone, Debug, Default)]
+pub(crate) struct PresignedRequestSlot(Arc<Mutex<Option<HttpRequest>>>);
+
+impl PresignedRequestSlot {
+    /// Takes the presigned request, if the operation got as far as transmitting it.
+    pub(crate) fn take(&self) -> Option<HttpRequest> {
+        self.0.lock().unwrap().take()
+    }
+}
+
+impl CustomizedPresigningInterceptor {
+    pub(crate) fn new(config: PresigningConfig) -> Self {
+        Self {
+            inner: SigV4PresigningInterceptor::new(config, None),
+            presigned_request: PresignedRequestSlot::default(),
+        }
+    }
+
+    /// Returns the slot that the presigned request is placed in.
+    pub(crate) fn presigned_request(&self) -> PresignedRequestSlot {
+        self.presigned_request.clone()
+    }
+}
+
+impl Interceptor for CustomizedPresigningInterceptor {
+    fn modify_before_serialization(
+        &self,
+        context: &mut BeforeSerializationInterceptorContextMut<'_>,
+        cfg: &mut ConfigBag,
+    ) -> Result<(), BoxError> {
+        let layer = cfg.interceptor_state();
+        layer.set_retry_strategy(NeverRetryStrategy::new());
+        layer.put(disable_interceptor::<InvocationIdInterceptor>("presigning"));
+        layer.put(disable_interceptor::<RequestInfoInterceptor>("presigning"));
+        layer.put(disable_interceptor::<UserAgentInterceptor>("presigning"));
+        self.inner.modify_before_serialization(context, cfg)
+    }
+
+    fn modify_before_signing(
+        &self,
+        context: &mut BeforeTransmitInterceptorContextMut<'_>,
+        cfg: &mut ConfigBag,
+    ) -> Result<(), BoxError> {
+        self.inner.modify_before_signing(context, cfg)
+    }
+
+    fn modify_before_transmit(
+        &self,
+        context: &mut BeforeTransmitInterceptorContextMut<'_>,
+        _cfg: &mut ConfigBag,
+    ) -> Result<(), BoxError> {
+        let request = std::mem::replace(context.request_mut(), HttpRequest::new(SdkBody::empty()));
+        *self.presigned_request.0.lock().unwrap() = Some(request);
+        Err("the request was presigned, so it wasn't sent".into())
+    }
+}
+
+/// Checks that the request body can be presigned, and moves form encoded bodies into the query
+/// string so that the request can be sent as a `GET`.
+///
+/// Any other body must be empty, because it wouldn't be part of the presigned request.
+fn move_form_body_to_query(request: &mut HttpRequest) -> Result<(), BoxError> {
+    let body = match request.body().bytes() {
+        Some(body) => body,
+        None => {
+            return Err(
+                "requests with streaming bodies, including event streams, can't be presigned"
+                    .into(),
             )
         }
+    };
+    let is_form = request
+        .headers()
+        .get(CONTENT_TYPE)
+        .map(|content_type| {
+            content_type
+                .as_bytes()
+                .starts_with(FORM_URL_ENCODED.as_bytes())
+        })
+        .unwrap_or(false);
+    if !is_form {
+        return if body.is_empty() {
+            Ok(())
+        } else {
+            Err(
+                "requests with a body can't be presigned, since the presigned request \
+                wouldn't include the body"
+                    .into(),
+            )
+        };
+    }
+
+    // The body is already form encoded, which is also how query strings are encoded
+    let form = std::str::from_utf8(body)
+        .map_err(|_| "form encoded request body must be UTF-8")?
+        .to_owned();
+    let uri = request.uri();
+    let path_and_query = match uri.query() {
+        Some(query) if !query.is_empty() => format!("{}?{}&{}", uri.path(), query, form),
+        _ => format!("{}?{}", uri.path(), form),
+    };
+    let mut parts = uri.clone().into_parts();
+    parts.path_and_query = Some(path_and_query.parse()?);
+    *request.uri_mut() = Uri::from_parts(parts)?;
+    *request.method_mut() = Method::GET;
+    *request.body_mut() = SdkBody::empty();
+    request.headers_mut().remove(CONTENT_TYPE);
+    request.headers_mut().remove(CONTENT_LENGTH);
+    Ok(())
+}
+
+/// Moves the headers selected by `header_hoisting` into the query string.
+fn hoist_headers(request: &mut HttpRequest, header_hoisting: &HeaderHoisting) {
+    let hoisted: Vec<_> = request
+        .headers()
+        .keys()
+        .filter(|name| header_hoisting.hoists(name))
+        .cloned()
+        .collect();
+    if hoisted.is_empty() {
+        return;
     }
+    let mut query = QueryWriter::new(request.uri());
+    for name in hoisted {
+        // Headers with multiple values are combined the same way the signer combines them
+        let value = request
+            .headers()
+            .get_all(&name)
+            .iter()
+            .map(|value| String::from_utf8_lossy(value.as_bytes()).trim().to_owned())
+            .collect::<Vec<_>>()
+            .join(",");
+        request.headers_mut().remove(&name);
+        query.insert(name.as_str(), &value);
+    }
+    *request.uri_mut() = query.build_uri();
 }
 
 /// Runtime plugin that registers the SigV4PresigningInterceptor.
@@ -86,7 +257,10 @@ pub(crate) struct SigV4PresigningRuntimePlugin {
 }
 
 impl SigV4PresigningRuntimePlugin {
-    pub(crate) fn new(config: PresigningConfig, payload_override: SignableBody<'static>) -> Self {
+    pub(crate) fn new(
+        config: PresigningConfig,
+        payload_override: Option<SignableBody<'static>>,
+    ) -> Self {
         Self {
             interceptor: SharedInterceptor::new(SigV4PresigningInterceptor::new(
                 config,
@@ -110,3 +284,143 @@ impl RuntimePlugin for SigV4PresigningRuntimePlugin {
         interceptors.register(self.interceptor.clone());
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{hoist_headers, move_form_body_to_query, CustomizedPresigningInterceptor};
+    use crate::presigning::{HeaderHoisting, PresigningConfig};
+    use aws_smithy_http::body::{BoxBody, SdkBody};
+    use aws_smithy_runtime_api::client::interceptors::{Interceptor, InterceptorContext};
+    use aws_smithy_types::config_bag::ConfigBag;
+    use aws_smithy_types::type_erasure::TypeErasedBox;
+    use http::header::{CONTENT_TYPE, HOST};
+    use http::{HeaderName, Method};
+    use std::time::Duration;
+
+    #[test]
+    fn form_body_moved_to_query() {
+        let mut request = http::Request::builder()
+            .method(Method::POST)
+            .uri("https://sts.amazonaws.com/")
+            .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .header("content-length", "43")
+            .body(SdkBody::from("Action=GetCallerIdentity&Version=2011-06-15"))
+            .unwrap();
+        move_form_body_to_query(&mut request).unwrap();
+
+        assert_eq!(Method::GET, request.method());
+        assert_eq!(
+            "https://sts.amazonaws.com/?Action=GetCallerIdentity&Version=2011-06-15",
+            request.uri().to_string()
+        );
+        assert_eq!(Some(&b""[..]), request.body().bytes());
+        assert!(request.headers().is_empty());
+    }
+
+    #[test]
+    fn empty_bodies_left_in_place() {
+        let mut request = http::Request::builder()
+            .method(Method::GET)
+            .uri("https://example.amazonaws.com/resource?existing=1")
+            .body(SdkBody::empty())
+            .unwrap();
+        move_form_body_to_query(&mut request).unwrap();
+
+        assert_eq!(Method::GET, request.method());
+        assert_eq!(
+            "https://example.amazonaws.com/resource?existing=1",
+            request.uri().to_string()
+        );
+    }
+
+    #[test]
+    fn other_bodies_rejected() {
+        let mut request = http::Request::builder()
+            .method(Method::POST)
+            .uri("https://example.amazonaws.com/")
+            .header(CONTENT_TYPE, "application/x-amz-json-1.1")
+            .body(SdkBody::from("{}"))
+            .unwrap();
+        let err = move_form_body_to_query(&mut request).expect_err("can't presign");
+        assert!(err.to_string().contains("with a body"), "{}", err);
+    }
+
+    #[test]
+    fn streaming_bodies_rejected() {
+        let mut request = http::Request::builder()
+            .uri("https://example.amazonaws.com/")
+            .body(SdkBody::from_dyn(BoxBody::default()))
+            .unwrap();
+        let err = move_form_body_to_query(&mut request).expect_err("can't presign");
+        assert!(err.to_string().contains("streaming"), "{}", err);
+    }
+
+    #[test]
+    fn hoisting() {
+        let request = || {
+            http::Request::builder()
+                .uri("https://bucket.s3.us-east-1.amazonaws.com/key?x-id=PutObject")
+                .header(HOST, "bucket.s3.us-east-1.amazonaws.com")
+                .header("x-amz-acl", "public-read")
+                .header("x-amz-meta-tag", "a b")
+                .header("x-amz-meta-tag", "c")
+                .header("x-amz-target", "Service.Operation")
+                .body(SdkBody::empty())
+                .unwrap()
+        };
+
+        let mut unchanged = request();
+        hoist_headers(&mut unchanged, &HeaderHoisting::Disabled);
+        assert_eq!(request().uri(), unchanged.uri());
+        assert_eq!(5, unchanged.headers().len());
+
+        let mut amz = request();
+        hoist_headers(&mut amz, &HeaderHoisting::AmzHeaders);
+        assert_eq!(
+            "https://bucket.s3.us-east-1.amazonaws.com/key?x-id=PutObject&x-amz-acl=public-read&x-amz-meta-tag=a%20b%2Cc",
+            amz.uri().to_string()
+        );
+        let remaining: Vec<_> = amz.headers().keys().map(|name| name.as_str()).collect();
+        assert_eq!(vec!["host", "x-amz-target"], remaining);
+
+        let mut selected = request();
+        hoist_headers(
+            &mut selected,
+            &HeaderHoisting::Headers(vec![HeaderName::from_static("x-amz-acl")]),
+        );
+        assert_eq!(
+            "https://bucket.s3.us-east-1.amazonaws.com/key?x-id=PutObject&x-amz-acl=public-read",
+            selected.uri().to_string()
+        );
+        assert!(!selected.headers().contains_key("x-amz-acl"));
+    }
+
+    #[test]
+    fn customized_presigning_takes_the_request_before_transmit() {
+        let mut context = InterceptorContext::new(TypeErasedBox::new("input"));
+        context.enter_serialization_phase();
+        context.set_request(
+            http::Request::builder()
+                .uri("https://sts.amazonaws.com/?Action=GetCallerIdentity")
+                .body(SdkBody::empty())
+                .unwrap(),
+        );
+        let _ = context.take_input();
+        context.enter_before_transmit_phase();
+
+        let interceptor = CustomizedPresigningInterceptor::new(
+            PresigningConfig::expires_in(Duration::from_secs(60)).unwrap(),
+        );
+        let presigned_request = interceptor.presigned_request();
+        assert!(presigned_request.take().is_none());
+
+        let mut ctx = (&mut context).into();
+        interceptor
+            .modify_before_transmit(&mut ctx, &mut ConfigBag::base())
+            .expect_err("the request must not be transmitted");
+        assert_eq!(
+            "https://sts.amazonaws.com/?Action=GetCallerIdentity",
+            presigned_request.take().expect("taken").uri().to_string()
+        );
+    }
+}
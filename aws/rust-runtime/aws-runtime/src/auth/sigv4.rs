@@ -8,6 +8,7 @@ use aws_sigv4::http_request::{
     sign, PayloadChecksumKind, PercentEncodingMode, SessionTokenMode, SignableBody,
     SignableRequest, SignatureLocation, SigningParams, SigningSettings, UriPathNormalizationMode,
 };
+use aws_smithy_runtime::client::orchestrator::interceptors::ServiceClockSkew;
 use aws_smithy_runtime_api::client::auth::{
     AuthSchemeEndpointConfig, AuthSchemeId, HttpAuthScheme, HttpRequestSigner,
 };
@@ -25,6 +26,9 @@ use std::time::{Duration, SystemTime};
 const EXPIRATION_WARNING: &str = "Presigned request will expire before the given \
         `expires_in` duration because the credentials used to sign it will expire first.";
 
+/// Skews smaller than this are left alone since SigV4 tolerates up to five minutes of difference.
+const CLOCK_SKEW_CORRECTION_THRESHOLD: Duration = Duration::from_secs(4 * 60);
+
 /// Auth scheme ID for SigV4.
 pub const SCHEME_ID: AuthSchemeId = AuthSchemeId::new("sigv4");
 
@@ -247,6 +251,24 @@ impl SigV4HttpRequestSigner {
         Ok(builder.build().expect("all required fields set"))
     }
 
+    /// Returns the clock skew measured from earlier responses that the signing time should be
+    /// corrected by.
+    ///
+    /// Presigned requests are not corrected since they may be sent from a different clock.
+    fn clock_skew<'a>(
+        operation_config: &SigV4OperationSigningConfig,
+        config_bag: &'a ConfigBag,
+    ) -> Option<&'a ServiceClockSkew> {
+        if operation_config.signing_options.signature_type
+            == HttpSignatureType::HttpRequestQueryParams
+        {
+            return None;
+        }
+        config_bag
+            .get::<ServiceClockSkew>()
+            .filter(|skew| skew.skew() >= CLOCK_SKEW_CORRECTION_THRESHOLD)
+    }
+
     fn extract_operation_config<'a>(
         auth_scheme_endpoint_config: AuthSchemeEndpointConfig<'a>,
         config_bag: &'a ConfigBag,
@@ -315,7 +337,12 @@ impl HttpRequestSigner for SigV4HttpRequestSigner {
     ) -> Result<(), BoxError> {
         let operation_config =
             Self::extract_operation_config(auth_scheme_endpoint_config, config_bag)?;
-        let request_time = config_bag.request_time().unwrap_or_default().now();
+        let clock_skew = Self::clock_skew(&operation_config, config_bag);
+        let mut request_time = config_bag.request_time().unwrap_or_default().now();
+        if let Some(skew) = clock_skew {
+            tracing::debug!(skew = ?skew, "correcting signing time for clock skew");
+            request_time = skew.apply(request_time);
+        }
 
         let credentials = if let Some(creds) = identity.data::<Credentials>() {
             creds
@@ -361,11 +388,15 @@ impl HttpRequestSigner for SigV4HttpRequestSigner {
         // If this is an event stream operation, set up the event stream signer
         #[cfg(feature = "event-stream")]
         {
+            use aws_smithy_async::time::SharedTimeSource;
             use aws_smithy_eventstream::frame::DeferredSignerSender;
-            use event_stream::SigV4MessageSigner;
+            use event_stream::{SigV4MessageSigner, SkewedTimeSource};
 
             if let Some(signer_sender) = config_bag.get::<DeferredSignerSender>() {
-                let time_source = config_bag.request_time().unwrap_or_default();
+                let mut time_source = config_bag.request_time().unwrap_or_default();
+                if let Some(skew) = clock_skew {
+                    time_source = SharedTimeSource::new(SkewedTimeSource::new(time_source, *skew));
+                }
                 signer_sender
                     .send(Box::new(SigV4MessageSigner::new(
                         _signature,
@@ -388,10 +419,31 @@ mod event_stream {
     use aws_credential_types::Credentials;
     use aws_sigv4::event_stream::{sign_empty_message, sign_message};
     use aws_sigv4::SigningParams;
-    use aws_smithy_async::time::SharedTimeSource;
+    use aws_smithy_async::time::{SharedTimeSource, TimeSource};
     use aws_smithy_eventstream::frame::{Message, SignMessage, SignMessageError};
+    use aws_smithy_runtime::client::orchestrator::interceptors::ServiceClockSkew;
     use aws_types::region::SigningRegion;
     use aws_types::SigningService;
+    use std::time::SystemTime;
+
+    /// Time source that corrects another time source for the service's clock skew.
+    #[derive(Debug)]
+    pub(super) struct SkewedTimeSource {
+        inner: SharedTimeSource,
+        skew: ServiceClockSkew,
+    }
+
+    impl SkewedTimeSource {
+        pub(super) fn new(inner: SharedTimeSource, skew: ServiceClockSkew) -> Self {
+            Self { inner, skew }
+        }
+    }
+
+    impl TimeSource for SkewedTimeSource {
+        fn now(&self) -> SystemTime {
+            self.skew.apply(self.inner.now())
+        }
+    }
 
     /// Event Stream SigV4 signing implementation.
     #[derive(Debug)]
@@ -506,6 +558,7 @@ mod tests {
     use super::*;
     use aws_credential_types::Credentials;
     use aws_sigv4::http_request::SigningSettings;
+    use aws_smithy_http::body::SdkBody;
     use aws_smithy_types::config_bag::Layer;
     use aws_types::region::SigningRegion;
     use aws_types::SigningService;
@@ -614,4 +667,97 @@ mod tests {
         assert_eq!(result.service, Some(SigningService::from_static("qldb")));
         assert!(matches!(result, Cow::Borrowed(_)));
     }
+
+    /// 2020-09-13T12:26:40Z
+    const REQUEST_TIME: u64 = 1_600_000_000;
+
+    fn skew(minutes: u64, service_is_behind: bool) -> ServiceClockSkew {
+        ServiceClockSkew::for_tests(Duration::from_secs(minutes * 60), service_is_behind)
+    }
+
+    fn signing_layer(
+        signature_type: HttpSignatureType,
+        clock_skew: Option<ServiceClockSkew>,
+    ) -> Layer {
+        let mut layer = Layer::new("test");
+        layer.put(SigV4OperationSigningConfig {
+            region: Some(SigningRegion::from_static("us-east-1")),
+            service: Some(SigningService::from_static("test")),
+            signing_options: SigningOptions {
+                signature_type,
+                expires_in: Some(Duration::from_secs(900)),
+                ..Default::default()
+            },
+        });
+        layer.set_request_time(SystemTime::UNIX_EPOCH + Duration::from_secs(REQUEST_TIME));
+        if let Some(clock_skew) = clock_skew {
+            layer.put(clock_skew);
+        }
+        layer
+    }
+
+    fn sign(layer: Layer) -> HttpRequest {
+        let mut request = http::Request::builder()
+            .uri("https://test.us-east-1.amazonaws.com/")
+            .body(SdkBody::from("body"))
+            .unwrap();
+        SigV4HttpRequestSigner
+            .sign_request(
+                &mut request,
+                &Identity::new(Credentials::for_tests(), None),
+                AuthSchemeEndpointConfig::empty(),
+                &ConfigBag::of_layers(vec![layer]),
+            )
+            .unwrap();
+        request
+    }
+
+    #[test]
+    fn signing_time_is_corrected_for_clock_skew() {
+        let signed_at = |clock_skew| {
+            let request = sign(signing_layer(
+                HttpSignatureType::HttpRequestHeaders,
+                clock_skew,
+            ));
+            request.headers()["x-amz-date"].to_str().unwrap().to_owned()
+        };
+        assert_eq!("20200913T122640Z", signed_at(None));
+        assert_eq!("20200913T123640Z", signed_at(Some(skew(10, false))));
+        assert_eq!("20200913T121640Z", signed_at(Some(skew(10, true))));
+        // Skews SigV4 tolerates are left alone
+        assert_eq!("20200913T122640Z", signed_at(Some(skew(1, false))));
+    }
+
+    #[test]
+    fn presigned_requests_are_not_corrected_for_clock_skew() {
+        let request = sign(signing_layer(
+            HttpSignatureType::HttpRequestQueryParams,
+            Some(skew(10, false)),
+        ));
+        let query = request.uri().query().unwrap();
+        assert!(query.contains("X-Amz-Date=20200913T122640Z"), "{query}");
+    }
+
+    #[cfg(feature = "event-stream")]
+    #[test]
+    fn event_stream_messages_are_corrected_for_clock_skew() {
+        use aws_smithy_eventstream::frame::{DeferredSigner, HeaderValue, Message, SignMessage};
+        use aws_smithy_types::DateTime;
+
+        let (mut signer, sender) = DeferredSigner::new();
+        let mut layer = signing_layer(HttpSignatureType::HttpRequestHeaders, Some(skew(10, false)));
+        layer.put(sender);
+        sign(layer);
+
+        let message = signer.sign(Message::new(&b"message"[..])).unwrap();
+        let date = message
+            .headers()
+            .iter()
+            .find(|header| header.name().as_str() == ":date")
+            .unwrap();
+        assert_eq!(
+            &HeaderValue::Timestamp(DateTime::from_secs((REQUEST_TIME + 600) as i64)),
+            date.value()
+        );
+    }
 }
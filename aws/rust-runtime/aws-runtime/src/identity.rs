@@ -6,6 +6,8 @@
 /// Credentials-based identity support.
 pub mod credentials {
     use aws_credential_types::cache::SharedCredentialsCache;
+    use aws_credential_types::Credentials;
+    use aws_smithy_runtime::client::orchestrator::interceptors::response_cache::IdentityCacheKey;
     use aws_smithy_runtime_api::client::identity::{Identity, IdentityResolver};
     use aws_smithy_runtime_api::client::orchestrator::{BoxError, Future};
     use aws_smithy_types::config_bag::ConfigBag;
@@ -33,4 +35,38 @@ pub mod credentials {
             }))
         }
     }
+
+    /// Returns the response cache key for AWS credentials, which is their access key ID.
+    ///
+    /// Credentials with the same access key ID belong to the same principal, so they can share
+    /// cached responses, and the access key ID isn't a secret.
+    pub fn identity_cache_key() -> IdentityCacheKey {
+        IdentityCacheKey::new(|identity| {
+            identity
+                .data::<Credentials>()
+                .map(|credentials| format!("sigv4:{}", credentials.access_key_id()))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::credentials::identity_cache_key;
+    use aws_credential_types::Credentials;
+    use aws_smithy_runtime_api::client::identity::Identity;
+
+    #[test]
+    fn credentials_are_keyed_on_their_access_key_id() {
+        let key = identity_cache_key();
+        let credentials = |secret| Credentials::new("ANOTREAL", secret, None, None, "test");
+        let a = key
+            .key(&Identity::new(credentials("secret-a"), None))
+            .unwrap();
+        let b = key
+            .key(&Identity::new(credentials("secret-b"), None))
+            .unwrap();
+        assert_eq!(a, b);
+        assert!(!a.contains("secret"), "{}", a);
+        assert_eq!(None, key.key(&Identity::new("not credentials", None)));
+    }
 }
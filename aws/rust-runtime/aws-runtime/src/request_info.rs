@@ -15,7 +15,7 @@ use aws_smithy_types::timeout::TimeoutConfig;
 use aws_smithy_types::DateTime;
 use http::{HeaderName, HeaderValue};
 use std::borrow::Cow;
-use std::time::{Duration, SystemTime};
+use std::time::SystemTime;
 
 #[allow(clippy::declare_interior_mutable_const)] // we will never mutate this
 const AMZ_SDK_REQUEST: HeaderName = HeaderName::from_static("amz-sdk-request");
@@ -65,9 +65,9 @@ impl RequestInfoInterceptor {
     fn build_ttl_pair(&self, cfg: &ConfigBag) -> Option<(Cow<'static, str>, Cow<'static, str>)> {
         let timeout_config = cfg.get::<TimeoutConfig>()?;
         let socket_read = timeout_config.read_timeout()?;
-        let estimated_skew: Duration = cfg.get::<ServiceClockSkew>().cloned()?.into();
-        let current_time = SystemTime::now();
-        let ttl = current_time.checked_add(socket_read + estimated_skew)?;
+        let estimated_skew = cfg.get::<ServiceClockSkew>()?;
+        let current_time = estimated_skew.apply(SystemTime::now());
+        let ttl = current_time.checked_add(socket_read)?;
         let mut timestamp = DateTime::from(ttl);
         // Set subsec_nanos to 0 so that the formatted `DateTime` won't have fractional seconds.
         timestamp.set_subsec_nanos(0);
@@ -3,14 +3,19 @@
  * SPDX-License-Identifier: Apache-2.0
  */
 
+use aws_smithy_async::time::SharedTimeSource;
 use aws_smithy_http::http::HttpHeaders;
 use aws_smithy_runtime_api::client::interceptors::InterceptorContext;
-use aws_smithy_runtime_api::client::orchestrator::OrchestratorError;
+use aws_smithy_runtime_api::client::orchestrator::{ConfigBagAccessors, OrchestratorError};
 use aws_smithy_runtime_api::client::retries::{ClassifyRetry, RetryReason};
+use aws_smithy_types::config_bag::ConfigBag;
+use aws_smithy_types::date_time::Format;
 use aws_smithy_types::error::metadata::ProvideErrorMetadata;
 use aws_smithy_types::retry::ErrorKind;
+use aws_smithy_types::DateTime;
 use std::error::Error as StdError;
 use std::marker::PhantomData;
+use std::time::Duration;
 
 /// AWS error codes that represent throttling errors.
 pub const THROTTLING_ERRORS: &[&str] = &[
@@ -33,6 +38,51 @@ pub const THROTTLING_ERRORS: &[&str] = &[
 /// AWS error codes that represent transient errors.
 pub const TRANSIENT_ERRORS: &[&str] = &["RequestTimeout", "RequestTimeoutException"];
 
+/// AWS error codes that indicate the client's clock is skewed from the service's clock.
+///
+/// These are retried immediately since the retry will be signed with a corrected time.
+pub const CLOCK_SKEW_ERRORS: &[&str] = &[
+    "RequestTimeTooSkewed",
+    "RequestExpired",
+    "RequestInTheFuture",
+];
+
+/// AWS error codes that may be caused by clock skew, but may also be caused by bad credentials.
+///
+/// These are only retried when the response's `Date` header shows that the clocks are skewed.
+pub const POSSIBLE_CLOCK_SKEW_ERRORS: &[&str] = &[
+    "InvalidSignatureException",
+    "SignatureDoesNotMatch",
+    "AuthFailure",
+];
+
+/// How far the service's clock must be from ours for a signature error to be blamed on skew.
+const CLOCK_SKEW_THRESHOLD: Duration = Duration::from_secs(4 * 60);
+
+/// Compares the response's `Date` header against the request's time source. Without a time
+/// source, the clocks can't be compared and aren't considered skewed.
+fn response_clock_is_skewed(
+    ctx: &InterceptorContext,
+    request_time: Option<SharedTimeSource>,
+) -> bool {
+    let request_time = match request_time {
+        Some(request_time) => request_time,
+        None => return false,
+    };
+    let time_sent = ctx
+        .response()
+        .and_then(|res| res.http_headers().get("date"))
+        .and_then(|header| header.to_str().ok())
+        .and_then(|header| DateTime::from_str(header, Format::HttpDate).ok());
+    match time_sent {
+        Some(time_sent) => {
+            let skew = time_sent.as_secs_f64() - DateTime::from(request_time.now()).as_secs_f64();
+            skew.abs() >= CLOCK_SKEW_THRESHOLD.as_secs_f64()
+        }
+        None => false,
+    }
+}
+
 /// A retry classifier for determining if the response sent by an AWS service requires a retry.
 #[derive(Debug, Default)]
 pub struct AwsErrorCodeClassifier<E> {
@@ -48,11 +98,15 @@ impl<E> AwsErrorCodeClassifier<E> {
     }
 }
 
-impl<E> ClassifyRetry for AwsErrorCodeClassifier<E>
+impl<E> AwsErrorCodeClassifier<E>
 where
     E: StdError + ProvideErrorMetadata + Send + Sync + 'static,
 {
-    fn classify_retry(&self, ctx: &InterceptorContext) -> Option<RetryReason> {
+    fn classify(
+        &self,
+        ctx: &InterceptorContext,
+        request_time: Option<SharedTimeSource>,
+    ) -> Option<RetryReason> {
         let error = ctx
             .output_or_error()?
             .err()
@@ -64,11 +118,33 @@ where
                 return Some(RetryReason::Error(ErrorKind::ThrottlingError));
             } else if TRANSIENT_ERRORS.contains(&error_code) {
                 return Some(RetryReason::Error(ErrorKind::TransientError));
+            } else if CLOCK_SKEW_ERRORS.contains(&error_code)
+                || (POSSIBLE_CLOCK_SKEW_ERRORS.contains(&error_code)
+                    && response_clock_is_skewed(ctx, request_time))
+            {
+                return Some(RetryReason::Explicit(Duration::ZERO));
             }
         };
 
         None
     }
+}
+
+impl<E> ClassifyRetry for AwsErrorCodeClassifier<E>
+where
+    E: StdError + ProvideErrorMetadata + Send + Sync + 'static,
+{
+    fn classify_retry(&self, ctx: &InterceptorContext) -> Option<RetryReason> {
+        self.classify(ctx, None)
+    }
+
+    fn classify_retry_with_config(
+        &self,
+        ctx: &InterceptorContext,
+        cfg: &ConfigBag,
+    ) -> Option<RetryReason> {
+        self.classify(ctx, cfg.request_time())
+    }
 
     fn name(&self) -> &'static str {
         "AWS Error Code"
@@ -106,16 +182,20 @@ impl ClassifyRetry for AmzRetryAfterHeaderClassifier {
 #[cfg(test)]
 mod test {
     use super::{AmzRetryAfterHeaderClassifier, AwsErrorCodeClassifier};
+    use aws_smithy_async::time::StaticTimeSource;
     use aws_smithy_http::body::SdkBody;
     use aws_smithy_runtime_api::client::interceptors::InterceptorContext;
-    use aws_smithy_runtime_api::client::orchestrator::OrchestratorError;
+    use aws_smithy_runtime_api::client::orchestrator::{ConfigBagAccessors, OrchestratorError};
     use aws_smithy_runtime_api::client::retries::{ClassifyRetry, RetryReason};
+    use aws_smithy_types::config_bag::{ConfigBag, Layer};
+    use aws_smithy_types::date_time::Format;
     use aws_smithy_types::error::metadata::ProvideErrorMetadata;
     use aws_smithy_types::error::ErrorMetadata;
     use aws_smithy_types::retry::{ErrorKind, ProvideErrorKind};
     use aws_smithy_types::type_erasure::{TypeErasedBox, TypeErasedError};
+    use aws_smithy_types::DateTime;
     use std::fmt;
-    use std::time::Duration;
+    use std::time::{Duration, UNIX_EPOCH};
 
     #[derive(Debug)]
     struct UnmodeledError;
@@ -185,9 +265,58 @@ mod test {
         assert_eq!(
             policy.classify_retry(&ctx),
             Some(RetryReason::Error(ErrorKind::TransientError))
+        );
+
+        let mut ctx = InterceptorContext::new(TypeErasedBox::new("doesntmatter"));
+        ctx.set_output_or_error(Err(OrchestratorError::operation(TypeErasedError::new(
+            CodedError::new("RequestTimeTooSkewed"),
+        ))));
+        assert_eq!(
+            policy.classify_retry(&ctx),
+            Some(RetryReason::Explicit(Duration::ZERO))
         )
     }
 
+    #[test]
+    fn classify_possible_clock_skew_by_date_header() {
+        let policy = AwsErrorCodeClassifier::<CodedError>::new();
+        let ctx_with_date = |date: &str| {
+            let mut ctx = InterceptorContext::new(TypeErasedBox::new("doesntmatter"));
+            ctx.set_response(
+                http::Response::builder()
+                    .header("date", date)
+                    .body(SdkBody::empty())
+                    .unwrap(),
+            );
+            ctx.set_output_or_error(Err(OrchestratorError::operation(TypeErasedError::new(
+                CodedError::new("SignatureDoesNotMatch"),
+            ))));
+            ctx
+        };
+
+        // Sun, 09 Sep 2001 01:46:40 GMT
+        let request_time = UNIX_EPOCH + Duration::from_secs(1_000_000_000);
+        let mut layer = Layer::new("test");
+        layer.set_request_time(StaticTimeSource::new(request_time));
+        let cfg = ConfigBag::of_layers(vec![layer]);
+
+        let now = DateTime::from(request_time).fmt(Format::HttpDate).unwrap();
+        assert_eq!(
+            policy.classify_retry_with_config(&ctx_with_date(&now), &cfg),
+            None
+        );
+        assert_eq!(
+            policy
+                .classify_retry_with_config(&ctx_with_date("Sun, 09 Sep 2001 01:56:40 GMT"), &cfg),
+            Some(RetryReason::Explicit(Duration::ZERO))
+        );
+        // Without a request time, the clocks can't be compared
+        assert_eq!(
+            policy.classify_retry(&ctx_with_date("Sun, 09 Sep 2001 01:56:40 GMT")),
+            None
+        );
+    }
+
     #[test]
     fn classify_generic() {
         let policy = AwsErrorCodeClassifier::<ErrorMetadata>::new();
@@ -41,6 +41,8 @@ use crate::sign::{calculate_signature, generate_signing_key, sha256_hex_string};
 use crate::SigningOutput;
 use aws_smithy_eventstream::frame::{write_headers_to, Header, HeaderValue, Message};
 use bytes::Bytes;
+use std::error::Error;
+use std::fmt;
 use std::io::Write;
 use std::time::SystemTime;
 
@@ -140,6 +142,79 @@ fn sign_payload<'a>(
     )
 }
 
+/// Verifies the signatures of a sequence of signed Event Stream messages.
+///
+/// `signed_messages` are the signed wrapper messages in the order they were sent, and
+/// `initial_signature` is the signature of the HTTP request that started the stream. The time
+/// in `params` is ignored; each message is verified with the time in its `:date` header.
+///
+/// Returns the signature of the last message so that verification can continue later.
+pub fn verify_signature_chain(
+    signed_messages: &[Message],
+    initial_signature: &str,
+    params: &SigningParams<'_>,
+) -> Result<String, InvalidSignatureChain> {
+    let mut last_signature = initial_signature.to_string();
+    for (index, signed) in signed_messages.iter().enumerate() {
+        let invalid = |reason| InvalidSignatureChain { index, reason };
+        let mut signature = None;
+        let mut time = None;
+        for header in signed.headers() {
+            match header.name().as_str() {
+                ":chunk-signature" => {
+                    signature = header.value().as_byte_array().ok().map(hex::encode)
+                }
+                ":date" => {
+                    time = header
+                        .value()
+                        .as_timestamp()
+                        .ok()
+                        .and_then(|time| SystemTime::try_from(time).ok())
+                }
+                _ => {}
+            }
+        }
+        let signature = signature.ok_or_else(|| invalid("missing `:chunk-signature` header"))?;
+        let time = time.ok_or_else(|| invalid("missing `:date` header"))?;
+
+        let signing_key =
+            generate_signing_key(params.secret_key, time, params.region, params.service_name);
+        let string_to_sign =
+            calculate_string_to_sign(signed.payload(), &last_signature, time, params);
+        if calculate_signature(signing_key, &string_to_sign) != signature {
+            return Err(invalid("signature didn't match"));
+        }
+        last_signature = signature;
+    }
+    Ok(last_signature)
+}
+
+/// A signed Event Stream message didn't have the expected signature
+#[derive(Debug)]
+pub struct InvalidSignatureChain {
+    index: usize,
+    reason: &'static str,
+}
+
+impl InvalidSignatureChain {
+    /// The position of the first message with an invalid signature
+    pub fn index(&self) -> usize {
+        self.index
+    }
+}
+
+impl fmt::Display for InvalidSignatureChain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "event stream message {} has an invalid signature: {}",
+            self.index, self.reason
+        )
+    }
+}
+
+impl Error for InvalidSignatureChain {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -220,4 +295,49 @@ mod tests {
             panic!("expected timestamp for :date header");
         }
     }
+
+    #[test]
+    fn verify_chain() {
+        let mut params = SigningParams {
+            access_key: "fake access key",
+            secret_key: "fake secret key",
+            security_token: None,
+            region: "us-east-1",
+            service_name: "testservice",
+            time: UNIX_EPOCH + Duration::from_secs(123_456_789),
+            settings: (),
+        };
+        let initial_signature = sha256_hex_string(b"initial request");
+
+        let mut last_signature = initial_signature.clone();
+        let mut signed_messages = Vec::new();
+        for payload in ["first", "second"] {
+            let (signed, signature) =
+                sign_message(&Message::new(payload.as_bytes()), &last_signature, &params)
+                    .into_parts();
+            signed_messages.push(signed);
+            last_signature = signature;
+            params.time += Duration::from_secs(1);
+        }
+        let (signed, signature) = sign_empty_message(&last_signature, &params).into_parts();
+        signed_messages.push(signed);
+
+        assert_eq!(
+            signature,
+            verify_signature_chain(&signed_messages, &initial_signature, &params).unwrap()
+        );
+
+        // messages verified out of order fail
+        signed_messages.swap(0, 1);
+        let err = verify_signature_chain(&signed_messages, &initial_signature, &params)
+            .expect_err("out of order");
+        assert_eq!(0, err.index());
+
+        // unsigned messages fail
+        signed_messages.swap(0, 1);
+        signed_messages[2] = Message::new(&b""[..]);
+        let err = verify_signature_chain(&signed_messages, &initial_signature, &params)
+            .expect_err("unsigned");
+        assert_eq!(2, err.index());
+    }
 }
@@ -21,6 +21,7 @@ pub mod endpoint_config;
 pub mod os_shim_internal;
 pub mod region;
 pub mod sdk_config;
+pub mod service_config;
 
 pub use aws_smithy_client::http_connector;
 pub use sdk_config::SdkConfig;
@@ -5,14 +5,14 @@
 
 //! Abstractions for testing code that interacts with the operating system:
 //! - Reading environment variables
-//! - Reading from the file system
+//! - Reading from and writing to the file system
 
 use std::collections::HashMap;
 use std::env::VarError;
 use std::ffi::OsString;
 use std::fmt::Debug;
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 use crate::os_shim_internal::fs::Fake;
 
@@ -50,7 +50,7 @@ impl Fs {
     }
 
     pub fn from_raw_map(fs: HashMap<OsString, Vec<u8>>) -> Self {
-        Fs(fs::Inner::Fake(Arc::new(Fake::MapFs(fs))))
+        Fs(fs::Inner::Fake(Arc::new(Fake::MapFs(Mutex::new(fs)))))
     }
 
     pub fn from_map(data: HashMap<String, impl Into<Vec<u8>>>) -> Self {
@@ -128,6 +128,8 @@ impl Fs {
             Inner::Real => std::fs::read(path),
             Inner::Fake(fake) => match fake.as_ref() {
                 Fake::MapFs(fs) => fs
+                    .lock()
+                    .unwrap()
                     .get(path.as_os_str())
                     .cloned()
                     .ok_or_else(|| std::io::ErrorKind::NotFound.into()),
@@ -143,13 +145,56 @@ impl Fs {
             },
         }
     }
+
+    /// Atomically replace the contents of a file, creating it if it doesn't exist
+    ///
+    /// The contents are written to a temporary file next to `path` which is then renamed over
+    /// `path`, so readers never observe a partially written file. An existing file keeps its
+    /// permissions; on Unix, new files are only readable by their owner. If `path` is a symlink,
+    /// the file it points to is replaced and the symlink is left in place.
+    ///
+    /// _Note: Like [`Fs::read_to_end`], this function performs IO directly within the function._
+    pub async fn write(
+        &self,
+        path: impl AsRef<Path>,
+        contents: impl AsRef<[u8]>,
+    ) -> std::io::Result<()> {
+        use fs::Inner;
+        let path = path.as_ref();
+        match &self.0 {
+            Inner::Real => fs::write_atomic(path, contents.as_ref()),
+            Inner::Fake(fake) => match fake.as_ref() {
+                Fake::MapFs(fs) => {
+                    fs.lock()
+                        .unwrap()
+                        .insert(path.as_os_str().to_owned(), contents.as_ref().to_vec());
+                    Ok(())
+                }
+                Fake::NamespacedFs {
+                    real_path,
+                    namespaced_to,
+                } => {
+                    let actual_path = path
+                        .strip_prefix(namespaced_to)
+                        .map_err(|_| std::io::Error::from(std::io::ErrorKind::NotFound))?;
+                    fs::write_atomic(&real_path.join(actual_path), contents.as_ref())
+                }
+            },
+        }
+    }
 }
 
 mod fs {
     use std::collections::HashMap;
     use std::ffi::OsString;
-    use std::path::PathBuf;
-    use std::sync::Arc;
+    use std::fs::{File, OpenOptions};
+    use std::io::{self, Write};
+    use std::path::{Path, PathBuf};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+
+    /// Distinguishes the temporary files of concurrent writes within this process.
+    static NEXT_TEMP_FILE: AtomicUsize = AtomicUsize::new(0);
 
     #[derive(Clone, Debug)]
     pub(super) enum Inner {
@@ -159,12 +204,68 @@ mod fs {
 
     #[derive(Debug)]
     pub(super) enum Fake {
-        MapFs(HashMap<OsString, Vec<u8>>),
+        MapFs(Mutex<HashMap<OsString, Vec<u8>>>),
         NamespacedFs {
             real_path: PathBuf,
             namespaced_to: PathBuf,
         },
     }
+
+    pub(super) fn write_atomic(path: &Path, contents: &[u8]) -> io::Result<()> {
+        // Renaming over a symlink would replace the link itself rather than the file it points to
+        let path = match std::fs::canonicalize(path) {
+            Ok(resolved) => resolved,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => path.to_path_buf(),
+            Err(err) => return Err(err),
+        };
+        let path = path.as_path();
+        let file_name = path
+            .file_name()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "path is not a file"))?;
+        let mut temp_name = OsString::from(".");
+        temp_name.push(file_name);
+        temp_name.push(format!(
+            ".{}.{}.tmp",
+            std::process::id(),
+            NEXT_TEMP_FILE.fetch_add(1, Ordering::Relaxed)
+        ));
+        let temp_path = path.with_file_name(temp_name);
+        if let Some(parent) = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+        {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let result = (|| {
+            let mut temp_file = create_private(&temp_path)?;
+            if let Ok(metadata) = std::fs::metadata(path) {
+                temp_file.set_permissions(metadata.permissions())?;
+            }
+            temp_file.write_all(contents)?;
+            temp_file.sync_all()?;
+            std::fs::rename(&temp_path, path)
+        })();
+        if result.is_err() {
+            let _ = std::fs::remove_file(&temp_path);
+        }
+        result
+    }
+
+    #[cfg(unix)]
+    fn create_private(path: &Path) -> io::Result<File> {
+        use std::os::unix::fs::OpenOptionsExt;
+        OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .mode(0o600)
+            .open(path)
+    }
+
+    #[cfg(not(unix))]
+    fn create_private(path: &Path) -> io::Result<File> {
+        OpenOptions::new().write(true).create_new(true).open(path)
+    }
 }
 
 /// Environment variable abstraction
@@ -271,4 +372,101 @@ mod test {
             .expect("future should not poll")
             .expect_err("file doesnt exists");
     }
+
+    #[test]
+    fn fake_fs_write() {
+        let fs = Fs::from_slice(&[("config", "old")]);
+        fs.write("config", "new")
+            .now_or_never()
+            .expect("future should not poll")
+            .expect("write succeeds");
+        fs.write("credentials", "created")
+            .now_or_never()
+            .expect("future should not poll")
+            .expect("write succeeds");
+        let read = |path| {
+            fs.read_to_end(path)
+                .now_or_never()
+                .expect("future should not poll")
+                .expect("file exists")
+        };
+        assert_eq!(b"new", read("config").as_slice());
+        assert_eq!(b"created", read("credentials").as_slice());
+    }
+
+    #[test]
+    fn real_fs_write() {
+        let dir = std::env::temp_dir().join(format!("aws-types-fs-write-{}", std::process::id()));
+        let path = dir.join(".aws").join("credentials");
+        let fs = Fs::real();
+        for contents in ["first", "second"] {
+            fs.write(&path, contents)
+                .now_or_never()
+                .expect("future should not poll")
+                .expect("write succeeds");
+            assert_eq!(
+                contents.as_bytes(),
+                std::fs::read(&path).unwrap().as_slice()
+            );
+        }
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(0o600, mode & 0o777);
+        }
+        // Only the written file is left behind
+        assert_eq!(
+            1,
+            std::fs::read_dir(path.parent().unwrap()).unwrap().count()
+        );
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn real_fs_write_through_symlink() {
+        let dir = std::env::temp_dir().join(format!("aws-types-fs-symlink-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("dotfiles")).unwrap();
+        let target = dir.join("dotfiles").join("config");
+        std::fs::write(&target, "old").unwrap();
+        let link = dir.join("config");
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+
+        Fs::real()
+            .write(&link, "new")
+            .now_or_never()
+            .expect("future should not poll")
+            .expect("write succeeds");
+        assert!(std::fs::symlink_metadata(&link)
+            .unwrap()
+            .file_type()
+            .is_symlink());
+        assert_eq!(b"new", std::fs::read(&target).unwrap().as_slice());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn concurrent_real_fs_writes() {
+        let dir =
+            std::env::temp_dir().join(format!("aws-types-fs-concurrent-{}", std::process::id()));
+        let path = dir.join("config");
+        let writers: Vec<_> = (0..8)
+            .map(|i| {
+                let path = path.clone();
+                std::thread::spawn(move || {
+                    Fs::real()
+                        .write(&path, format!("writer {i}"))
+                        .now_or_never()
+                        .expect("future should not poll")
+                })
+            })
+            .collect();
+        for writer in writers {
+            writer.join().unwrap().expect("write succeeds");
+        }
+        let contents = String::from_utf8(std::fs::read(&path).unwrap()).unwrap();
+        assert!(contents.starts_with("writer "), "{contents}");
+        std::fs::remove_dir_all(dir).unwrap();
+    }
 }
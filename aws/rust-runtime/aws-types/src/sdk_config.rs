@@ -16,10 +16,12 @@ use aws_smithy_async::time::{SharedTimeSource, TimeSource};
 use aws_smithy_client::http_connector::HttpConnector;
 use aws_smithy_types::retry::RetryConfig;
 use aws_smithy_types::timeout::TimeoutConfig;
+use std::sync::Arc;
 
 use crate::app_name::AppName;
 use crate::docs_for;
 use crate::region::Region;
+use crate::service_config::LoadServiceConfig;
 
 #[doc(hidden)]
 /// Unified docstrings to keep crates in sync. Not intended for public use
@@ -59,6 +61,7 @@ pub struct SdkConfig {
     http_connector: Option<HttpConnector>,
     use_fips: Option<bool>,
     use_dual_stack: Option<bool>,
+    service_config: Option<Arc<dyn LoadServiceConfig>>,
 }
 
 /// Builder for AWS Shared Configuration
@@ -80,6 +83,7 @@ pub struct Builder {
     http_connector: Option<HttpConnector>,
     use_fips: Option<bool>,
     use_dual_stack: Option<bool>,
+    service_config: Option<Arc<dyn LoadServiceConfig>>,
 }
 
 impl Builder {
@@ -513,6 +517,21 @@ impl Builder {
         self
     }
 
+    /// Set the loader for service-specific configuration, such as a per-service endpoint URL.
+    pub fn service_config(mut self, service_config: impl LoadServiceConfig + 'static) -> Self {
+        self.set_service_config(Some(Arc::new(service_config)));
+        self
+    }
+
+    /// Set the loader for service-specific configuration, such as a per-service endpoint URL.
+    pub fn set_service_config(
+        &mut self,
+        service_config: Option<Arc<dyn LoadServiceConfig>>,
+    ) -> &mut Self {
+        self.service_config = service_config;
+        self
+    }
+
     /// Build a [`SdkConfig`](SdkConfig) from this builder
     pub fn build(self) -> SdkConfig {
         SdkConfig {
@@ -528,6 +547,7 @@ impl Builder {
             use_fips: self.use_fips,
             use_dual_stack: self.use_dual_stack,
             time_source: self.time_source,
+            service_config: self.service_config,
         }
     }
 }
@@ -594,6 +614,11 @@ impl SdkConfig {
         self.use_dual_stack
     }
 
+    /// Loader for service-specific configuration
+    pub fn service_config(&self) -> Option<&dyn LoadServiceConfig> {
+        self.service_config.as_deref()
+    }
+
     /// Config builder
     ///
     /// _Important:_ Using the `aws-config` crate to configure the SDK is preferred to invoking this
@@ -0,0 +1,56 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Configuration that may be set differently for each service.
+
+use std::fmt::Debug;
+
+/// Loads configuration values that may be set for a specific service.
+///
+/// `aws-config` implements this by looking up service-specific environment variables such as
+/// `AWS_ENDPOINT_URL_DYNAMODB` and `[services]` sections of the AWS config file.
+pub trait LoadServiceConfig: Debug + Send + Sync {
+    /// Returns the value configured for `key`, if any.
+    fn load_config(&self, key: ServiceConfigKey<'_>) -> Option<String>;
+}
+
+/// The name of a configuration value, along with the service it is loaded for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ServiceConfigKey<'a> {
+    service_id: &'a str,
+    env: &'a str,
+    profile: &'a str,
+}
+
+impl<'a> ServiceConfigKey<'a> {
+    /// Creates a new key.
+    ///
+    /// - `service_id` is the service's SDK ID, e.g. `DynamoDB`.
+    /// - `env` is the name of the environment variable that sets the value for all services,
+    ///   e.g. `AWS_ENDPOINT_URL`.
+    /// - `profile` is the name of the profile property that sets the value, e.g. `endpoint_url`.
+    pub fn new(service_id: &'a str, env: &'a str, profile: &'a str) -> Self {
+        Self {
+            service_id,
+            env,
+            profile,
+        }
+    }
+
+    /// The SDK ID of the service this value is loaded for.
+    pub fn service_id(&self) -> &'a str {
+        self.service_id
+    }
+
+    /// The name of the environment variable that sets this value for all services.
+    pub fn env(&self) -> &'a str {
+        self.env
+    }
+
+    /// The name of the profile property that sets this value.
+    pub fn profile(&self) -> &'a str {
+        self.profile
+    }
+}
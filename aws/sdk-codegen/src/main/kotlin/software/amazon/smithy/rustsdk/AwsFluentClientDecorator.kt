@@ -68,7 +68,13 @@ class AwsFluentClientDecorator : ClientCodegenDecorator {
                 AwsFluentClientDocs(codegenContext),
             ),
             retryClassifier = AwsRuntimeType.awsHttp(runtimeConfig).resolve("retry::AwsResponseRetryClassifier"),
-        ).render(rustCrate, listOf(CustomizableOperationTestHelpers(runtimeConfig)))
+        ).render(
+            rustCrate,
+            listOf(
+                AwsPresignedCustomizableOperationMethod(codegenContext),
+                CustomizableOperationTestHelpers(runtimeConfig),
+            ),
+        )
         rustCrate.withModule(ClientRustModule.Client.customize) {
             renderCustomizableOperationSend(codegenContext, generics, this)
         }
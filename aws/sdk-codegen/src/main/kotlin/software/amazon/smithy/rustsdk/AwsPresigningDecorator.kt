@@ -5,9 +5,11 @@
 
 package software.amazon.smithy.rustsdk
 
+import software.amazon.smithy.aws.traits.auth.SigV4Trait
 import software.amazon.smithy.model.Model
 import software.amazon.smithy.model.knowledge.HttpBinding
 import software.amazon.smithy.model.knowledge.HttpBindingIndex
+import software.amazon.smithy.model.knowledge.ServiceIndex
 import software.amazon.smithy.model.shapes.MemberShape
 import software.amazon.smithy.model.shapes.OperationShape
 import software.amazon.smithy.model.shapes.ServiceShape
@@ -17,9 +19,12 @@ import software.amazon.smithy.model.traits.HttpQueryTrait
 import software.amazon.smithy.model.traits.HttpTrait
 import software.amazon.smithy.model.transform.ModelTransformer
 import software.amazon.smithy.rust.codegen.client.smithy.ClientCodegenContext
+import software.amazon.smithy.rust.codegen.client.smithy.ClientRustModule
 import software.amazon.smithy.rust.codegen.client.smithy.customize.ClientCodegenDecorator
 import software.amazon.smithy.rust.codegen.client.smithy.generators.OperationCustomization
 import software.amazon.smithy.rust.codegen.client.smithy.generators.OperationSection
+import software.amazon.smithy.rust.codegen.client.smithy.generators.client.CustomizableOperationCustomization
+import software.amazon.smithy.rust.codegen.client.smithy.generators.client.CustomizableOperationSection
 import software.amazon.smithy.rust.codegen.client.smithy.generators.client.FluentClientCustomization
 import software.amazon.smithy.rust.codegen.client.smithy.generators.client.FluentClientSection
 import software.amazon.smithy.rust.codegen.client.smithy.generators.protocol.MakeOperationGenerator
@@ -88,6 +93,27 @@ internal val PRESIGNABLE_OPERATIONS by lazy {
     )
 }
 
+/**
+ * Operations in the presignable set can always be presigned. With the orchestrator, SigV4 operations listed in
+ * the `presignableOperations` SDK setting can be presigned too, since their requests are built up to the point of
+ * transmission. Any other operation can be presigned at runtime through `CustomizableOperation::presigned`.
+ */
+internal fun isPresignable(codegenContext: ClientCodegenContext, operationShape: OperationShape): Boolean {
+    if (operationShape.hasTrait<PresignableTrait>()) {
+        return true
+    }
+    if (!codegenContext.smithyRuntimeMode.defaultToOrchestrator ||
+        !codegenContext.sdkSettings().presignableOperations.contains(operationShape.id)
+    ) {
+        return false
+    }
+    check(
+        ServiceIndex.of(codegenContext.model).getEffectiveAuthSchemes(codegenContext.serviceShape, operationShape)
+            .containsKey(SigV4Trait.ID),
+    ) { "${operationShape.id} is listed in `presignableOperations`, but isn't signed with SigV4" }
+    return true
+}
+
 class AwsPresigningDecorator internal constructor(
     private val presignableOperations: Map<ShapeId, PresignableOperation> = PRESIGNABLE_OPERATIONS,
 ) : ClientCodegenDecorator {
@@ -270,7 +296,9 @@ class AwsPresignedFluentBuilderMethod(
 
     override fun section(section: FluentClientSection): Writable =
         writable {
-            if (section is FluentClientSection.FluentBuilderImpl && section.operationShape.hasTrait(PresignableTrait::class.java)) {
+            if (section is FluentClientSection.FluentBuilderImpl &&
+                isPresignable(codegenContext, section.operationShape)
+            ) {
                 documentPresignedMethod(hasConfigArg = false)
                 rustBlockTemplate(
                     """
@@ -308,8 +336,10 @@ class AwsPresignedFluentBuilderMethod(
     }
 
     private fun RustWriter.renderPresignedMethodBody(section: FluentClientSection.FluentBuilderImpl) {
-        val presignableOp = PRESIGNABLE_OPERATIONS.getValue(section.operationShape.id)
-        val operationShape = if (presignableOp.hasModelTransforms()) {
+        // Operations outside of the presignable set are presigned with their regular serializer and signed body,
+        // which must be empty or form encoded
+        val presignableOp = PRESIGNABLE_OPERATIONS[section.operationShape.id]
+        val operationShape = if (presignableOp?.hasModelTransforms() == true) {
             codegenContext.model.expectShape(syntheticShapeId(section.operationShape.id), OperationShape::class.java)
         } else {
             section.operationShape
@@ -353,7 +383,7 @@ class AwsPresignedFluentBuilderMethod(
             "TypedBox" to RuntimeType.smithyTypes(runtimeConfig).resolve("type_erasure::TypedBox"),
             "USER_AGENT" to CargoDependency.Http.toType().resolve("header::USER_AGENT"),
             "alternate_presigning_serializer" to writable {
-                if (presignableOp.hasModelTransforms()) {
+                if (presignableOp?.hasModelTransforms() == true) {
                     val smithyTypes = RuntimeType.smithyTypes(codegenContext.runtimeConfig)
                     rustTemplate(
                         """
@@ -378,19 +408,22 @@ class AwsPresignedFluentBuilderMethod(
                 }
             },
             "alternate_presigning_serializer_registration" to writable {
-                if (presignableOp.hasModelTransforms()) {
+                if (presignableOp?.hasModelTransforms() == true) {
                     rust(".with_operation_plugin(AlternatePresigningSerializerRuntimePlugin)")
                 }
             },
             "payload_override" to writable {
-                rustTemplate(
-                    "#{aws_sigv4}::http_request::SignableBody::" +
-                        when (presignableOp.payloadSigningType) {
-                            PayloadSigningType.EMPTY -> "Bytes(b\"\")"
-                            PayloadSigningType.UNSIGNED_PAYLOAD -> "UnsignedPayload"
-                        },
-                    "aws_sigv4" to AwsRuntimeType.awsSigv4(runtimeConfig),
-                )
+                when (presignableOp?.payloadSigningType) {
+                    null -> rust("None")
+                    PayloadSigningType.EMPTY -> rustTemplate(
+                        "Some(#{aws_sigv4}::http_request::SignableBody::Bytes(b\"\"))",
+                        "aws_sigv4" to AwsRuntimeType.awsSigv4(runtimeConfig),
+                    )
+                    PayloadSigningType.UNSIGNED_PAYLOAD -> rustTemplate(
+                        "Some(#{aws_sigv4}::http_request::SignableBody::UnsignedPayload)",
+                        "aws_sigv4" to AwsRuntimeType.awsSigv4(runtimeConfig),
+                    )
+                }
             },
         )
     }
@@ -415,6 +448,70 @@ class AwsPresignedFluentBuilderMethod(
         }
 }
 
+/**
+ * Adds a `presigned()` method to `CustomizableOperation`, so that SigV4 operations without a generated `presigned()`
+ * method on their fluent builder can still be presigned, as long as their request has no body that would be lost.
+ */
+class AwsPresignedCustomizableOperationMethod(
+    private val codegenContext: ClientCodegenContext,
+) : CustomizableOperationCustomization() {
+    private val runtimeConfig = codegenContext.runtimeConfig
+    private val codegenScope = (
+        presigningTypes + arrayOf(
+            *RuntimeType.preludeScope,
+            "CustomizedPresigningInterceptor" to AwsRuntimeType.presigningInterceptor(runtimeConfig)
+                .resolve("CustomizedPresigningInterceptor"),
+            "SdkError" to RuntimeType.sdkError(runtimeConfig),
+            "SendResult" to ClientRustModule.Client.customize.toType().resolve("internal::SendResult"),
+            "SharedInterceptor" to RuntimeType.smithyRuntimeApi(runtimeConfig)
+                .resolve("client::interceptors::SharedInterceptor"),
+        )
+        ).toTypedArray()
+
+    override fun section(section: CustomizableOperationSection): Writable =
+        writable {
+            if (section is CustomizableOperationSection.CustomizableOperationImpl &&
+                section.isRuntimeModeOrchestrator &&
+                codegenContext.smithyRuntimeMode.defaultToOrchestrator &&
+                codegenContext.serviceShape.hasTrait<SigV4Trait>()
+            ) {
+                documentPresignedMethod(hasConfigArg = false)
+                rustTemplate(
+                    """
+                    ///
+                    /// Unlike the `presigned()` method of fluent builders, this can be used with any operation that is
+                    /// signed with SigV4. The request is serialized and signed the same way as when it's sent, except
+                    /// that the signature is added to the query string. Since a presigned request doesn't include a
+                    /// body, form encoded request bodies are moved into the query string, and operations with any
+                    /// other request body, including streaming and event stream input, fail with an error.
+                    pub async fn presigned(
+                        mut self,
+                        presigning_config: #{PresigningConfig},
+                    ) -> #{SendResult}<#{PresignedRequest}, E>
+                    where
+                        E: std::error::Error + #{Send} + #{Sync} + 'static,
+                    {
+                        let interceptor = #{CustomizedPresigningInterceptor}::new(presigning_config);
+                        let presigned_request = interceptor.presigned_request();
+                        self.interceptors.push(#{SharedInterceptor}::new(interceptor));
+                        let result = self.send().await;
+                        match presigned_request.take() {
+                            #{Some}(request) => #{Ok}(#{PresignedRequest}::new(request.map(|_| ()))),
+                            #{None} => #{Err}(match result {
+                                #{Err}(err) => err,
+                                #{Ok}(_) => #{SdkError}::construction_failure(
+                                    "the request was sent instead of being presigned",
+                                ),
+                            }),
+                        }
+                    }
+                    """,
+                    *codegenScope,
+                )
+            }
+        }
+}
+
 interface PresignModelTransform {
     fun transform(model: Model): Model
 }
@@ -53,6 +53,18 @@ object AwsRuntimeType {
             ),
         )
 
+    fun presignedPost(runtimeConfig: RuntimeConfig): RuntimeType =
+        RuntimeType.forInlineDependency(
+            InlineAwsDependency.forRustFile(
+                "presigned_post",
+                visibility = Visibility.PUBCRATE,
+                AwsCargoDependency.awsCredentialTypes(runtimeConfig),
+                AwsCargoDependency.awsSigv4(runtimeConfig),
+                CargoDependency.smithyJson(runtimeConfig),
+                CargoDependency.smithyTypes(runtimeConfig),
+            ),
+        )
+
     // TODO(enableNewSmithyRuntimeCleanup): Delete the `presigning_service.rs` inlineable when cleaning up middleware
     fun presigningService(): RuntimeType =
         RuntimeType.forInlineDependency(InlineAwsDependency.forRustFile("presigning_service", visibility = Visibility.PUBCRATE))
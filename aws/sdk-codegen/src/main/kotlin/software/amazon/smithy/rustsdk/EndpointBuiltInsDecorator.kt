@@ -5,6 +5,7 @@
 
 package software.amazon.smithy.rustsdk
 
+import software.amazon.smithy.aws.traits.ServiceTrait
 import software.amazon.smithy.model.Model
 import software.amazon.smithy.model.node.BooleanNode
 import software.amazon.smithy.model.node.Node
@@ -32,9 +33,11 @@ import software.amazon.smithy.rust.codegen.core.rustlang.writable
 import software.amazon.smithy.rust.codegen.core.smithy.RuntimeConfig
 import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType
 import software.amazon.smithy.rust.codegen.core.smithy.customize.AdHocCustomization
+import software.amazon.smithy.rust.codegen.core.smithy.customize.adhocCustomization
 import software.amazon.smithy.rust.codegen.core.util.PANIC
 import software.amazon.smithy.rust.codegen.core.util.dq
 import software.amazon.smithy.rust.codegen.core.util.extendIf
+import software.amazon.smithy.rust.codegen.core.util.getTrait
 import software.amazon.smithy.rust.codegen.core.util.orNull
 import software.amazon.smithy.rust.codegen.core.util.toPascalCase
 import java.util.Optional
@@ -97,6 +100,31 @@ fun Model.sdkConfigSetter(
     return SdkConfigCustomization.copyField(fieldName, map)
 }
 
+/**
+ * Copies the endpoint URL from `SdkConfig`, falling back to the service-specific endpoint URL that `aws-config` loads
+ * from `AWS_ENDPOINT_URL_<SERVICE>` environment variables and `[services]` profile sections.
+ */
+private fun ClientCodegenContext.endpointUrlSdkConfigSetter(fieldName: String): AdHocCustomization? {
+    val serviceTrait = serviceShape.getTrait<ServiceTrait>()
+        ?: return model.sdkConfigSetter(serviceShape.id, Builtins.SDK_ENDPOINT, fieldName)
+    model.loadBuiltIn(serviceShape.id, Builtins.SDK_ENDPOINT) ?: return null
+    val serviceConfigKey = AwsRuntimeType.awsTypes(runtimeConfig).resolve("service_config::ServiceConfigKey")
+    return adhocCustomization<SdkConfigSection.CopySdkConfigToClientConfig> { section ->
+        rustTemplate(
+            """
+            ${section.serviceConfigBuilder}.set_$fieldName(
+                ${section.sdkConfig}.endpoint_url().map(|s| s.to_string()).or_else(|| {
+                    ${section.sdkConfig}.service_config().and_then(|conf| {
+                        conf.load_config(#{ServiceConfigKey}::new(${serviceTrait.sdkId.dq()}, "AWS_ENDPOINT_URL", "endpoint_url"))
+                    })
+                }),
+            );
+            """,
+            "ServiceConfigKey" to serviceConfigKey,
+        )
+    }
+}
+
 /**
  * Create a client codegen decorator that creates bindings for a builtIn parameter. Optionally, you can provide
  * [clientParam.Builder] which allows control over the config parameter that will be generated.
@@ -116,7 +144,11 @@ fun decoratorForBuiltIn(
 
         override fun extraSections(codegenContext: ClientCodegenContext): List<AdHocCustomization> {
             return listOfNotNull(
-                codegenContext.model.sdkConfigSetter(codegenContext.serviceShape.id, builtIn, clientParamBuilder?.name),
+                if (builtIn == Builtins.SDK_ENDPOINT) {
+                    codegenContext.endpointUrlSdkConfigSetter(name)
+                } else {
+                    codegenContext.model.sdkConfigSetter(codegenContext.serviceShape.id, builtIn, clientParamBuilder?.name)
+                },
             )
         }
 
@@ -9,8 +9,11 @@ import software.amazon.smithy.rust.codegen.client.smithy.ClientCodegenContext
 import software.amazon.smithy.rust.codegen.client.smithy.customize.ClientCodegenDecorator
 import software.amazon.smithy.rust.codegen.client.smithy.generators.ServiceRuntimePluginCustomization
 import software.amazon.smithy.rust.codegen.client.smithy.generators.ServiceRuntimePluginSection
+import software.amazon.smithy.rust.codegen.client.smithy.generators.config.ConfigCustomization
+import software.amazon.smithy.rust.codegen.client.smithy.generators.config.ServiceConfig
 import software.amazon.smithy.rust.codegen.core.rustlang.Writable
 import software.amazon.smithy.rust.codegen.core.rustlang.rust
+import software.amazon.smithy.rust.codegen.core.rustlang.rustTemplate
 import software.amazon.smithy.rust.codegen.core.rustlang.writable
 import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType
 import software.amazon.smithy.rust.codegen.core.util.letIf
@@ -26,6 +29,69 @@ class RetryInformationHeaderDecorator : ClientCodegenDecorator {
         baseCustomizations.letIf(codegenContext.smithyRuntimeMode.generateOrchestrator) {
             it + listOf(AddRetryInformationHeaderInterceptors(codegenContext))
         }
+
+    override fun configCustomizations(
+        codegenContext: ClientCodegenContext,
+        baseCustomizations: List<ConfigCustomization>,
+    ): List<ConfigCustomization> =
+        baseCustomizations.letIf(codegenContext.smithyRuntimeMode.generateOrchestrator) {
+            it + ClockSkewCacheConfigCustomization(codegenContext)
+        }
+}
+
+/**
+ * Stores a clock skew cache in the client's config so that every operation sent by the client
+ * corrects its signing time with the skew last measured against the same endpoint.
+ */
+private class ClockSkewCacheConfigCustomization(codegenContext: ClientCodegenContext) : ConfigCustomization() {
+    private val runtimeMode = codegenContext.smithyRuntimeMode
+    private val codegenScope = arrayOf(
+        "ClockSkewCache" to RuntimeType.smithyRuntime(codegenContext.runtimeConfig)
+            .resolve("client::orchestrator::interceptors::ClockSkewCache"),
+    )
+
+    override fun section(section: ServiceConfig) =
+        writable {
+            when (section) {
+                is ServiceConfig.ConfigStruct -> {
+                    if (runtimeMode.defaultToMiddleware) {
+                        rustTemplate("pub(crate) clock_skew_cache: #{ClockSkewCache},", *codegenScope)
+                    }
+                }
+
+                is ServiceConfig.ConfigImpl -> {
+                    if (runtimeMode.defaultToOrchestrator) {
+                        rustTemplate(
+                            """
+                            pub(crate) fn clock_skew_cache(&self) -> #{ClockSkewCache} {
+                                self.inner.load::<#{ClockSkewCache}>().cloned().unwrap_or_default()
+                            }
+                            """,
+                            *codegenScope,
+                        )
+                    } else {
+                        rustTemplate(
+                            """
+                            pub(crate) fn clock_skew_cache(&self) -> #{ClockSkewCache} {
+                                self.clock_skew_cache.clone()
+                            }
+                            """,
+                            *codegenScope,
+                        )
+                    }
+                }
+
+                ServiceConfig.BuilderBuild -> {
+                    if (runtimeMode.defaultToOrchestrator) {
+                        rustTemplate("layer.store_put(#{ClockSkewCache}::new());", *codegenScope)
+                    } else {
+                        rustTemplate("clock_skew_cache: #{ClockSkewCache}::new(),", *codegenScope)
+                    }
+                }
+
+                else -> emptySection
+            }
+        }
 }
 
 private class AddRetryInformationHeaderInterceptors(codegenContext: ClientCodegenContext) :
@@ -36,10 +102,10 @@ private class AddRetryInformationHeaderInterceptors(codegenContext: ClientCodege
 
     override fun section(section: ServiceRuntimePluginSection): Writable = writable {
         if (section is ServiceRuntimePluginSection.RegisterInterceptor) {
-            // Track the latency between client and server.
+            // Track the clock skew between client and server, remembering it for each endpoint across operations.
             section.registerInterceptor(runtimeConfig, this) {
                 rust(
-                    "#T::new()",
+                    "#T::with_cache(self.handle.conf.clock_skew_cache())",
                     smithyRuntime.resolve("client::orchestrator::interceptors::ServiceClockSkewInterceptor"),
                 )
             }
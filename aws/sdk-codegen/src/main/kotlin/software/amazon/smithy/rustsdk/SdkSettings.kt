@@ -5,6 +5,7 @@
 package software.amazon.smithy.rustsdk
 
 import software.amazon.smithy.model.node.ObjectNode
+import software.amazon.smithy.model.shapes.ShapeId
 import software.amazon.smithy.rust.codegen.client.smithy.ClientCodegenContext
 import software.amazon.smithy.rust.codegen.core.smithy.CoreRustSettings
 import software.amazon.smithy.rust.codegen.core.util.orNull
@@ -52,6 +53,15 @@ class SdkSettings private constructor(private val awsSdk: ObjectNode?) {
 
     val requireEndpointResolver: Boolean
         get() = awsSdk?.getBooleanMember("requireEndpointResolver")?.orNull()?.value ?: true
+
+    /**
+     * Shape IDs of SigV4 operations to generate `presigned()` methods for with the orchestrator, in addition to
+     * the operations that are always presignable
+     */
+    val presignableOperations: Set<ShapeId>
+        get() =
+            awsSdk?.getArrayMember("presignableOperations")?.orNull()
+                ?.map { node -> ShapeId.from(node.expectStringNode().value) }?.toSet() ?: emptySet()
 }
 
 fun ClientCodegenContext.sdkSettings() = SdkSettings.from(this.settings)
@@ -56,6 +56,7 @@ private class AuthServiceRuntimePluginCustomization(private val codegenContext:
         arrayOf(
             "SIGV4_SCHEME_ID" to awsRuntime.resolve("auth::sigv4::SCHEME_ID"),
             "SigV4HttpAuthScheme" to awsRuntime.resolve("auth::sigv4::SigV4HttpAuthScheme"),
+            "identity_cache_key" to awsRuntime.resolve("identity::credentials::identity_cache_key"),
             "SigningRegion" to AwsRuntimeType.awsTypes(runtimeConfig).resolve("region::SigningRegion"),
             "SigningService" to AwsRuntimeType.awsTypes(runtimeConfig).resolve("SigningService"),
         )
@@ -81,6 +82,10 @@ private class AuthServiceRuntimePluginCustomization(private val codegenContext:
                 section.putConfigValue(this) {
                     rustTemplate("#{SigningService}::from_static(self.handle.conf.signing_service())", *codegenScope)
                 }
+                // Lets the response cache interceptor cache responses to requests signed with AWS credentials
+                section.putConfigValue(this) {
+                    rustTemplate("#{identity_cache_key}()", *codegenScope)
+                }
                 rustTemplate(
                     """
                     if let Some(region) = self.handle.conf.region() {
@@ -23,17 +23,21 @@ import software.amazon.smithy.rust.codegen.client.smithy.endpoint.EndpointCustom
 import software.amazon.smithy.rust.codegen.client.smithy.endpoint.rustName
 import software.amazon.smithy.rust.codegen.client.smithy.generators.OperationGenerator
 import software.amazon.smithy.rust.codegen.client.smithy.protocols.ClientRestXmlFactory
+import software.amazon.smithy.rust.codegen.core.rustlang.Visibility
 import software.amazon.smithy.rust.codegen.core.rustlang.Writable
 import software.amazon.smithy.rust.codegen.core.rustlang.rustBlockTemplate
 import software.amazon.smithy.rust.codegen.core.rustlang.rustTemplate
 import software.amazon.smithy.rust.codegen.core.rustlang.writable
 import software.amazon.smithy.rust.codegen.core.smithy.CodegenContext
 import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType
+import software.amazon.smithy.rust.codegen.core.smithy.RustCrate
 import software.amazon.smithy.rust.codegen.core.smithy.protocols.ProtocolFunctions
 import software.amazon.smithy.rust.codegen.core.smithy.protocols.ProtocolMap
 import software.amazon.smithy.rust.codegen.core.smithy.protocols.RestXml
 import software.amazon.smithy.rust.codegen.core.smithy.traits.AllowInvalidXmlRoot
 import software.amazon.smithy.rust.codegen.core.util.letIf
+import software.amazon.smithy.rustsdk.AwsRuntimeType
+import software.amazon.smithy.rustsdk.InlineAwsDependency
 import software.amazon.smithy.rustsdk.getBuiltIn
 import software.amazon.smithy.rustsdk.toWritable
 import java.util.logging.Logger
@@ -101,6 +105,50 @@ class S3Decorator : ClientCodegenDecorator {
         )
     }
 
+    override fun extras(codegenContext: ClientCodegenContext, rustCrate: RustCrate) {
+        // Presigned POST policies are specific to S3, so they're re-exported from the `presigning` module of S3 only
+        val presigning = InlineAwsDependency.forRustFile("presigning", visibility = Visibility.PUBLIC)
+        val moduleUseName = codegenContext.moduleUseName()
+        rustCrate.withModule(presigning.module) {
+            rustTemplate(
+                """
+                /// A presigned POST for uploading objects to S3 directly from HTML forms.
+                ///
+                /// The form fields, followed by a `file` field containing the object content, are submitted as a
+                /// `multipart/form-data` POST to the URL. S3 rejects the upload unless it satisfies every condition in
+                /// the signed policy.
+                ///
+                /// ## Examples
+                /// ```no_run
+                /// use std::time::Duration;
+                /// use $moduleUseName::config::Credentials;
+                /// use $moduleUseName::presigning::{PresignedPost, PresignedPostCondition, PresigningConfig};
+                ///
+                /// ## fn example(credentials: &Credentials) -> Result<(), Box<dyn std::error::Error>> {
+                /// let post = PresignedPost::builder()
+                ///     .bucket("my-bucket")
+                ///     .region("us-west-2")
+                ///     .key("uploads/${'$'}{filename}")
+                ///     .key_starts_with("uploads/")
+                ///     .field("Content-Type", "image/png")
+                ///     .condition(PresignedPostCondition::content_length_range(0, 10 * 1024 * 1024))
+                ///     .build(credentials, &PresigningConfig::expires_in(Duration::from_secs(900))?)?;
+                ///
+                /// println!("<form action=\"{}\" method=\"post\" enctype=\"multipart/form-data\">", post.url());
+                /// for (name, value) in post.fields() {
+                ///     println!("<input type=\"hidden\" name=\"{}\" value=\"{}\" />", name, value);
+                /// }
+                /// ## Ok(())
+                /// ## }
+                /// ```
+                pub use #{presigned_post}::PresignedPost;
+                pub use #{presigned_post}::{PresignedPostBuilder, PresignedPostCondition, PresignedPostError};
+                """,
+                "presigned_post" to AwsRuntimeType.presignedPost(codegenContext.runtimeConfig),
+            )
+        }
+    }
+
     private fun isInInvalidXmlRootAllowList(shape: Shape): Boolean {
         return shape.isStructureShape && invalidXmlRootAllowList.contains(shape.id)
     }
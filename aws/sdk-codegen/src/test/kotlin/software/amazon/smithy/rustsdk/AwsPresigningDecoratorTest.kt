@@ -5,15 +5,20 @@
 
 package software.amazon.smithy.rustsdk
 
+import io.kotest.assertions.throwables.shouldThrow
 import io.kotest.matchers.shouldBe
 import org.junit.jupiter.api.Test
 import software.amazon.smithy.model.Model
 import software.amazon.smithy.model.knowledge.HttpBinding
 import software.amazon.smithy.model.knowledge.HttpBindingIndex
+import software.amazon.smithy.model.node.Node
 import software.amazon.smithy.model.shapes.OperationShape
 import software.amazon.smithy.model.shapes.ServiceShape
 import software.amazon.smithy.model.shapes.ShapeId
 import software.amazon.smithy.model.traits.HttpTrait
+import software.amazon.smithy.rust.codegen.client.smithy.SmithyRuntimeMode
+import software.amazon.smithy.rust.codegen.client.testutil.testClientRustSettings
+import software.amazon.smithy.rust.codegen.client.testutil.withSmithyRuntimeMode
 import software.amazon.smithy.rust.codegen.core.testutil.asSmithyModel
 import software.amazon.smithy.rust.codegen.core.util.expectTrait
 import software.amazon.smithy.rust.codegen.core.util.orNull
@@ -62,6 +67,65 @@ class AwsPresigningDecoratorTest {
         """.asSmithyModel()
 }
 
+class IsPresignableTest {
+    private val model = """
+        namespace test
+        use aws.auth#sigv4
+        use aws.protocols#restJson1
+
+        @restJson1
+        @sigv4(name: "test")
+        service TestService {
+            version: "2019-12-16",
+            operations: ["Listed", "NotListed", "Unsigned"],
+        }
+
+        structure TestInputOutput { }
+
+        @http(uri: "/listed", method: "POST")
+        operation Listed { input: TestInputOutput, output: TestInputOutput }
+
+        @http(uri: "/not-listed", method: "POST")
+        operation NotListed { input: TestInputOutput, output: TestInputOutput }
+
+        @auth([])
+        @http(uri: "/unsigned", method: "POST")
+        operation Unsigned { input: TestInputOutput, output: TestInputOutput }
+    """.asSmithyModel()
+
+    private fun codegenContext(smithyRuntimeMode: SmithyRuntimeMode) = awsTestCodegenContext(
+        model,
+        testClientRustSettings(
+            runtimeConfig = AwsTestRuntimeConfig,
+            customizationConfig = Node.parse(
+                """{ "awsSdk": { "presignableOperations": ["test#Listed", "test#Unsigned"] } }""",
+            ).expectObjectNode(),
+        ),
+    ).withSmithyRuntimeMode(smithyRuntimeMode)
+
+    private fun operation(name: String): OperationShape =
+        model.expectShape(ShapeId.fromParts("test", name), OperationShape::class.java)
+
+    @Test
+    fun `only operations listed in the SDK settings are presignable with the orchestrator`() {
+        val codegenContext = codegenContext(SmithyRuntimeMode.Orchestrator)
+        isPresignable(codegenContext, operation("Listed")) shouldBe true
+        isPresignable(codegenContext, operation("NotListed")) shouldBe false
+    }
+
+    @Test
+    fun `listed operations are not presignable with middleware`() {
+        isPresignable(codegenContext(SmithyRuntimeMode.Middleware), operation("Listed")) shouldBe false
+    }
+
+    @Test
+    fun `listed operations must be signed with SigV4`() {
+        shouldThrow<IllegalStateException> {
+            isPresignable(codegenContext(SmithyRuntimeMode.Orchestrator), operation("Unsigned"))
+        }
+    }
+}
+
 class OverrideHttpMethodTransformTest {
     @Test
     fun `it should override the HTTP method for the listed operations`() {
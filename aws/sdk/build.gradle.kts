@@ -119,7 +119,8 @@ fun generateSmithyBuild(services: AwsServices): String {
                                 "awsConfigVersion": "$awsConfigVersion",
                                 "defaultConfigPath": "${services.defaultConfigPath}",
                                 "endpointsConfigPath": "${services.endpointsConfigPath}",
-                                "integrationTestPath": "${project.projectDir.resolve("integration-tests")}"
+                                "integrationTestPath": "${project.projectDir.resolve("integration-tests")}",
+                                "presignableOperations": ["com.amazonaws.sts#GetCallerIdentity"]
                             }
                         }
                         ${service.extraConfig ?: ""}
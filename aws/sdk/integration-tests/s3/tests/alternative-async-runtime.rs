@@ -12,7 +12,7 @@ use aws_sdk_s3::types::{
 };
 use aws_sdk_s3::{Client, Config};
 use aws_smithy_async::assert_elapsed;
-use aws_smithy_async::rt::sleep::{AsyncSleep, SharedAsyncSleep, Sleep};
+use aws_smithy_async::rt::sleep::{AsyncStdSleep, SharedAsyncSleep, SmolSleep};
 use aws_smithy_client::never::NeverConnector;
 use aws_smithy_http::result::SdkError;
 use aws_smithy_types::error::display::DisplayErrorContext;
@@ -20,20 +20,10 @@ use aws_smithy_types::timeout::TimeoutConfig;
 use std::fmt::Debug;
 use std::time::{Duration, Instant};
 
-#[derive(Debug)]
-struct SmolSleep;
-
-impl AsyncSleep for SmolSleep {
-    fn sleep(&self, duration: Duration) -> Sleep {
-        Sleep::new(async move {
-            smol::Timer::after(duration).await;
-        })
-    }
-}
-
 #[test]
 fn test_smol_runtime_timeouts() {
-    if let Err(err) = smol::block_on(async { timeout_test(SharedAsyncSleep::new(SmolSleep)).await })
+    if let Err(err) =
+        smol::block_on(async { timeout_test(SharedAsyncSleep::new(SmolSleep::new())).await })
     {
         println!("{err}");
         panic!();
@@ -42,25 +32,18 @@ fn test_smol_runtime_timeouts() {
 
 #[test]
 fn test_smol_runtime_retry() {
-    if let Err(err) = smol::block_on(async { retry_test(SharedAsyncSleep::new(SmolSleep)).await }) {
+    if let Err(err) =
+        smol::block_on(async { retry_test(SharedAsyncSleep::new(SmolSleep::new())).await })
+    {
         println!("{err}");
         panic!();
     }
 }
 
-#[derive(Debug)]
-struct AsyncStdSleep;
-
-impl AsyncSleep for AsyncStdSleep {
-    fn sleep(&self, duration: Duration) -> Sleep {
-        Sleep::new(async move { async_std::task::sleep(duration).await })
-    }
-}
-
 #[test]
 fn test_async_std_runtime_timeouts() {
     if let Err(err) = async_std::task::block_on(async {
-        timeout_test(SharedAsyncSleep::new(AsyncStdSleep)).await
+        timeout_test(SharedAsyncSleep::new(AsyncStdSleep::new())).await
     }) {
         println!("{err}");
         panic!();
@@ -69,9 +52,9 @@ fn test_async_std_runtime_timeouts() {
 
 #[test]
 fn test_async_std_runtime_retry() {
-    if let Err(err) =
-        async_std::task::block_on(async { retry_test(SharedAsyncSleep::new(AsyncStdSleep)).await })
-    {
+    if let Err(err) = async_std::task::block_on(async {
+        retry_test(SharedAsyncSleep::new(AsyncStdSleep::new())).await
+    }) {
         println!("{err}");
         panic!();
     }
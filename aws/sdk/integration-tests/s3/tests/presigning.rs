@@ -13,7 +13,10 @@ use s3::operation::get_object::builders::GetObjectFluentBuilder;
 use s3::operation::head_object::builders::HeadObjectFluentBuilder;
 use s3::operation::put_object::builders::PutObjectFluentBuilder;
 use s3::operation::upload_part::builders::UploadPartFluentBuilder;
-use s3::presigning::{PresignedRequest, PresigningConfig};
+use s3::presigning::{
+    HeaderHoisting, PresignedPost, PresignedPostCondition, PresignedRequest, PresigningConfig,
+};
+use s3::types::ObjectCannedAcl;
 use std::pin::Pin;
 use std::time::{Duration, SystemTime};
 
@@ -188,3 +191,62 @@ async fn test_presigned_head_object() {
         presigned.uri().to_string(),
     );
 }
+
+#[tokio::test]
+async fn test_presigned_put_object_with_hoisted_headers() {
+    let client = s3::Client::from_conf(
+        s3::Config::builder()
+            .credentials_provider(Credentials::for_tests())
+            .region(Region::new("us-east-1"))
+            .build(),
+    );
+    let presigned = client
+        .put_object()
+        .bucket("bucket")
+        .key("key")
+        .acl(ObjectCannedAcl::PublicRead)
+        .presigned(
+            PresigningConfig::builder()
+                .start_time(SystemTime::UNIX_EPOCH + Duration::from_secs(1234567891))
+                .expires_in(Duration::from_secs(30))
+                .header_hoisting(HeaderHoisting::AmzHeaders)
+                .build()
+                .unwrap(),
+        )
+        .await
+        .expect("success");
+
+    let query = presigned.uri().query().unwrap();
+    assert!(query.contains("x-amz-acl=public-read"), "{}", query);
+    assert_eq!(None, presigned.headers().get("x-amz-acl"));
+}
+
+#[test]
+fn test_presigned_post() {
+    let config = PresigningConfig::builder()
+        .start_time(SystemTime::UNIX_EPOCH + Duration::from_secs(1234567891))
+        .expires_in(Duration::from_secs(30))
+        .build()
+        .unwrap();
+    let post = PresignedPost::builder()
+        .bucket("bucket")
+        .region("us-east-1")
+        .key("key")
+        .condition(PresignedPostCondition::content_length_range(0, 1024))
+        .build(&Credentials::for_tests(), &config)
+        .expect("valid policy");
+
+    assert_eq!("https://bucket.s3.us-east-1.amazonaws.com", post.url());
+    pretty_assertions::assert_eq!(
+        vec![
+            ("key", "key"),
+            ("policy", "eyJleHBpcmF0aW9uIjoiMjAwOS0wMi0xM1QyMzozMjowMS4wMDBaIiwiY29uZGl0aW9ucyI6W3siYnVja2V0IjoiYnVja2V0In0seyJrZXkiOiJrZXkifSxbImNvbnRlbnQtbGVuZ3RoLXJhbmdlIiwwLDEwMjRdLHsieC1hbXotY3JlZGVudGlhbCI6IkFOT1RSRUFMLzIwMDkwMjEzL3VzLWVhc3QtMS9zMy9hd3M0X3JlcXVlc3QifSx7IngtYW16LWFsZ29yaXRobSI6IkFXUzQtSE1BQy1TSEEyNTYifSx7IngtYW16LWRhdGUiOiIyMDA5MDIxM1QyMzMxMzFaIn0seyJ4LWFtei1zZWN1cml0eS10b2tlbiI6Im5vdGFyZWFsc2Vzc2lvbnRva2VuIn1dfQ=="),
+            ("x-amz-credential", "ANOTREAL/20090213/us-east-1/s3/aws4_request"),
+            ("x-amz-algorithm", "AWS4-HMAC-SHA256"),
+            ("x-amz-date", "20090213T233131Z"),
+            ("x-amz-security-token", "notarealsessiontoken"),
+            ("x-amz-signature", "add4b9f43f422c73834e9f853d00bd0e01706a5fb182958da2f3cb1a0934cc65"),
+        ],
+        post.fields().collect::<Vec<_>>()
+    );
+}
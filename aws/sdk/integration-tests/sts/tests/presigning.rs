@@ -0,0 +1,94 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use aws_sdk_sts::config::{Credentials, Region};
+use aws_sdk_sts::presigning::PresigningConfig;
+use std::time::{Duration, SystemTime};
+
+#[tokio::test]
+async fn presigned_get_caller_identity() {
+    let conf = aws_sdk_sts::Config::builder()
+        .credentials_provider(Credentials::for_tests())
+        .region(Region::new("us-east-1"))
+        .build();
+    let client = aws_sdk_sts::Client::from_conf(conf);
+    let presigned = client
+        .get_caller_identity()
+        .presigned(
+            PresigningConfig::builder()
+                .start_time(SystemTime::UNIX_EPOCH + Duration::from_secs(1234567891))
+                .expires_in(Duration::from_secs(60))
+                .build()
+                .unwrap(),
+        )
+        .await
+        .expect("GetCallerIdentity is listed in the presignable operations");
+
+    // the form encoded body of the query protocol is moved into the query string
+    assert_eq!("GET", presigned.method().as_str());
+    assert_eq!("/", presigned.uri().path());
+    let query = presigned.uri().query().unwrap();
+    assert!(
+        query.starts_with("Action=GetCallerIdentity&Version=2011-06-15&"),
+        "{}",
+        query
+    );
+    for param in [
+        "X-Amz-Algorithm=AWS4-HMAC-SHA256",
+        "X-Amz-Credential=ANOTREAL%2F20090213%2Fus-east-1%2Fsts%2Faws4_request",
+        "X-Amz-Date=20090213T233131Z",
+        "X-Amz-Expires=60",
+        "X-Amz-SignedHeaders=host",
+        "X-Amz-Signature=",
+        "X-Amz-Security-Token=notarealsessiontoken",
+    ] {
+        assert!(query.contains(param), "{} should contain {}", query, param);
+    }
+    assert_eq!(None, presigned.headers().get("content-type"));
+}
+
+#[tokio::test]
+async fn presigned_assume_role_through_customize() {
+    let conf = aws_sdk_sts::Config::builder()
+        .credentials_provider(Credentials::for_tests())
+        .region(Region::new("us-east-1"))
+        .build();
+    let client = aws_sdk_sts::Client::from_conf(conf);
+    let presigned = client
+        .assume_role()
+        .role_arn("arn:aws:iam::123456789012:role/test")
+        .role_session_name("test")
+        .customize()
+        .await
+        .unwrap()
+        .presigned(
+            PresigningConfig::builder()
+                .start_time(SystemTime::UNIX_EPOCH + Duration::from_secs(1234567891))
+                .expires_in(Duration::from_secs(60))
+                .build()
+                .unwrap(),
+        )
+        .await
+        .expect("any operation with a form encoded body can be presigned");
+
+    assert_eq!("GET", presigned.method().as_str());
+    let query = presigned.uri().query().unwrap();
+    assert!(
+        query.starts_with(
+            "Action=AssumeRole&Version=2011-06-15\
+            &RoleArn=arn%3Aaws%3Aiam%3A%3A123456789012%3Arole%2Ftest&RoleSessionName=test&"
+        ),
+        "{}",
+        query
+    );
+    for param in [
+        "X-Amz-Date=20090213T233131Z",
+        "X-Amz-Expires=60",
+        "X-Amz-SignedHeaders=host",
+        "X-Amz-Signature=",
+    ] {
+        assert!(query.contains(param), "{} should contain {}", query, param);
+    }
+}
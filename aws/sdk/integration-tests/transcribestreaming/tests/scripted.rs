@@ -0,0 +1,140 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use async_stream::stream;
+use aws_sdk_transcribestreaming::config::{Credentials, Region};
+use aws_sdk_transcribestreaming::error::SdkError;
+use aws_sdk_transcribestreaming::primitives::Blob;
+use aws_sdk_transcribestreaming::types::error::TranscriptResultStreamError;
+use aws_sdk_transcribestreaming::types::{
+    AudioEvent, AudioStream, LanguageCode, MediaEncoding, TranscriptResultStream,
+};
+use aws_sdk_transcribestreaming::{Client, Config};
+use aws_sigv4::event_stream::{verify_signature_chain, SigningParams};
+use aws_smithy_client::test_connection::event_stream::{EventStreamConnection, EventStreamScript};
+use std::time::{Duration, SystemTime};
+
+const TRANSCRIPT: &str = r#"{"Transcript":{"Results":[{"Alternatives":[{"Items":[],"Transcript":"Hello."}],"EndTime":0.5,"IsPartial":false,"ResultId":"1","StartTime":0.0}]}}"#;
+
+fn client(connection: &EventStreamConnection) -> Client {
+    let config = Config::builder()
+        .region(Region::from_static("us-west-2"))
+        .http_connector(connection.clone())
+        .credentials_provider(Credentials::for_tests())
+        .build();
+    Client::from_conf(config)
+}
+
+async fn transcribe(
+    connection: &EventStreamConnection,
+    chunks: usize,
+) -> Result<Vec<String>, String> {
+    let input_stream = stream! {
+        for chunk in 0..chunks {
+            yield Ok(AudioStream::AudioEvent(AudioEvent::builder().audio_chunk(Blob::new(vec![chunk as u8; 16])).build()));
+        }
+    };
+    let mut output = client(connection)
+        .start_stream_transcription()
+        .language_code(LanguageCode::EnGb)
+        .media_sample_rate_hertz(8000)
+        .media_encoding(MediaEncoding::Pcm)
+        .audio_stream(input_stream.into())
+        .send()
+        .await
+        .unwrap();
+
+    let mut transcripts = Vec::new();
+    loop {
+        match output.transcript_result_stream.recv().await {
+            Ok(Some(TranscriptResultStream::TranscriptEvent(event))) => {
+                for result in event.transcript.unwrap().results.unwrap_or_default() {
+                    let alternative = &result.alternatives.as_ref().unwrap()[0];
+                    transcripts.push(alternative.transcript.clone().unwrap());
+                }
+            }
+            Ok(Some(otherwise)) => panic!("unexpected event: {:?}", otherwise),
+            Ok(None) => return Ok(transcripts),
+            Err(SdkError::ServiceError(context)) => match context.err() {
+                TranscriptResultStreamError::BadRequestException(err) => {
+                    return Err(err.message().unwrap().to_string())
+                }
+                otherwise => panic!("unexpected error: {:?}", otherwise),
+            },
+            Err(otherwise) => return Err(format!("{:?}", otherwise)),
+        }
+    }
+}
+
+fn request_signature(connection: &EventStreamConnection) -> String {
+    let request = connection.request();
+    let authorization = request.headers()["authorization"].to_str().unwrap();
+    authorization
+        .rsplit("Signature=")
+        .next()
+        .unwrap()
+        .to_string()
+}
+
+#[tokio::test]
+async fn events_and_signature_chain() {
+    let connection = EventStreamConnection::new(
+        EventStreamScript::new()
+            .event("TranscriptEvent", "application/json", TRANSCRIPT)
+            .delay(Duration::from_millis(10))
+            .event("TranscriptEvent", "application/json", TRANSCRIPT),
+    );
+    let transcripts = transcribe(&connection, 3).await.unwrap();
+    assert_eq!(vec!["Hello.", "Hello."], transcripts);
+
+    connection.wait_for_request_body().await;
+    let audio: Vec<_> = connection.unsigned_messages();
+    assert_eq!(3, audio.len());
+    assert_eq!(&[2u8; 16][..], audio[2].payload().as_ref());
+
+    let credentials = Credentials::for_tests();
+    let params = SigningParams::builder()
+        .access_key(credentials.access_key_id())
+        .secret_key(credentials.secret_access_key())
+        .region("us-west-2")
+        .service_name("transcribe")
+        .time(SystemTime::now())
+        .settings(())
+        .build()
+        .unwrap();
+    // three audio events and the empty message that ends the stream
+    let signed = connection.messages();
+    assert_eq!(4, signed.len());
+    verify_signature_chain(&signed, &request_signature(&connection), &params).unwrap();
+}
+
+#[tokio::test]
+async fn exception_mid_stream() {
+    let connection = EventStreamConnection::new(
+        EventStreamScript::new()
+            .event("TranscriptEvent", "application/json", TRANSCRIPT)
+            .exception(
+                "BadRequestException",
+                "application/json",
+                r#"{"Message":"bad audio"}"#,
+            ),
+    );
+    assert_eq!(
+        Err("bad audio".to_string()),
+        transcribe(&connection, 1).await
+    );
+}
+
+#[tokio::test]
+async fn disconnect_mid_stream() {
+    let connection = EventStreamConnection::new(
+        EventStreamScript::new()
+            .event("TranscriptEvent", "application/json", TRANSCRIPT)
+            .disconnect(),
+    );
+    transcribe(&connection, 1)
+        .await
+        .expect_err("the response stream was interrupted");
+}
@@ -7,6 +7,7 @@ use async_stream::stream;
 use aws_sdk_transcribestreaming::config::{Credentials, Region};
 use aws_sdk_transcribestreaming::error::SdkError;
 use aws_sdk_transcribestreaming::operation::start_stream_transcription::StartStreamTranscriptionOutput;
+use aws_sdk_transcribestreaming::presigning::PresigningConfig;
 use aws_sdk_transcribestreaming::primitives::Blob;
 use aws_sdk_transcribestreaming::types::error::{AudioStreamError, TranscriptResultStreamError};
 use aws_sdk_transcribestreaming::types::{
@@ -19,6 +20,7 @@ use bytes::BufMut;
 use futures_core::Stream;
 use std::collections::{BTreeMap, BTreeSet};
 use std::error::Error as StdError;
+use std::time::Duration;
 
 const CHUNK_SIZE: usize = 8192;
 
@@ -94,6 +96,33 @@ async fn test_error() {
         .unwrap();
 }
 
+#[tokio::test]
+async fn event_stream_input_cannot_be_presigned() {
+    let config = Config::builder()
+        .region(Region::from_static("us-west-2"))
+        .credentials_provider(Credentials::for_tests())
+        .build();
+    let client = Client::from_conf(config);
+    let input_stream = stream! {
+        yield Ok(AudioStream::AudioEvent(AudioEvent::builder().audio_chunk(Blob::new(vec![0u8; 16])).build()));
+    };
+
+    let err = client
+        .start_stream_transcription()
+        .language_code(LanguageCode::EnGb)
+        .media_sample_rate_hertz(8000)
+        .media_encoding(MediaEncoding::Pcm)
+        .audio_stream(input_stream.into())
+        .customize()
+        .await
+        .unwrap()
+        .presigned(PresigningConfig::expires_in(Duration::from_secs(60)).unwrap())
+        .await
+        .expect_err("event streams can't be presigned");
+    let message = format!("{:?}", err);
+    assert!(message.contains("can't be presigned"), "{}", message);
+}
+
 async fn start_request(
     region: &'static str,
     events_json: &str,
@@ -0,0 +1,149 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Runs the `wasi_http` tests of the `webassembly` crate in wasmtime.
+//!
+//! The tests are built for the `wasm32-wasip2` target, which must be installed. Outgoing
+//! `wasi:http` requests are served by a stand-in server running in this process, whose address is
+//! passed to the guest in `WASI_HTTP_TEST_ENDPOINT`.
+
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Request, Response, Server, StatusCode};
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use wasmtime::component::{Component, Linker, ResourceTable};
+use wasmtime::{Config, Engine, Store};
+use wasmtime_wasi::bindings::Command as WasiCommand;
+use wasmtime_wasi::{WasiCtx, WasiCtxBuilder, WasiView};
+use wasmtime_wasi_http::{WasiHttpCtx, WasiHttpView};
+
+const TARGET: &str = "wasm32-wasip2";
+
+struct Host {
+    table: ResourceTable,
+    wasi: WasiCtx,
+    http: WasiHttpCtx,
+}
+
+impl WasiView for Host {
+    fn table(&mut self) -> &mut ResourceTable {
+        &mut self.table
+    }
+
+    fn ctx(&mut self) -> &mut WasiCtx {
+        &mut self.wasi
+    }
+}
+
+impl WasiHttpView for Host {
+    fn ctx(&mut self) -> &mut WasiHttpCtx {
+        &mut self.http
+    }
+
+    fn table(&mut self) -> &mut ResourceTable {
+        &mut self.table
+    }
+}
+
+/// Echoes the body of `PUT /echo?query=value` requests carrying an `x-test: wasi` header, and
+/// rejects anything else so that the guest sees which part of the request was lost
+async fn echo(request: Request<Body>) -> Result<Response<Body>, Infallible> {
+    let rejection = if request.method() != hyper::Method::PUT {
+        Some("unexpected method")
+    } else if request.uri().path() != "/echo" || request.uri().query() != Some("query=value") {
+        Some("unexpected path")
+    } else if request
+        .headers()
+        .get("x-test")
+        .map(|value| value != "wasi")
+        .unwrap_or(true)
+    {
+        Some("missing x-test header")
+    } else {
+        None
+    };
+    let response = match rejection {
+        Some(reason) => Response::builder()
+            .status(StatusCode::BAD_REQUEST)
+            .body(Body::from(reason)),
+        None => Response::builder().body(request.into_body()),
+    };
+    Ok(response.expect("valid response"))
+}
+
+fn start_stand_in() -> SocketAddr {
+    let server = Server::bind(&SocketAddr::from(([127, 0, 0, 1], 0))).serve(make_service_fn(
+        |_| async { Ok::<_, Infallible>(service_fn(echo)) },
+    ));
+    let addr = server.local_addr();
+    tokio::spawn(server);
+    addr
+}
+
+/// Builds the tests of the `webassembly` crate and returns the path to the test component
+fn build_test_component() -> PathBuf {
+    let crate_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("../webassembly");
+    let output = Command::new(env!("CARGO"))
+        .current_dir(&crate_dir)
+        .args([
+            "test",
+            "--no-run",
+            "--target",
+            TARGET,
+            "--message-format=json",
+        ])
+        .output()
+        .expect("cargo runs");
+    assert!(
+        output.status.success(),
+        "failed to build the webassembly tests:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout)
+        .expect("cargo output is UTF-8")
+        .lines()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+        .filter(|message| message["reason"] == "compiler-artifact")
+        .filter(|message| message["profile"]["test"] == true)
+        .find_map(|message| message["executable"].as_str().map(PathBuf::from))
+        .expect("cargo built a test component")
+}
+
+#[tokio::test]
+async fn wasi_http_tests_pass_in_wasmtime() -> anyhow::Result<()> {
+    let endpoint = format!("http://{}", start_stand_in());
+    let component_path = tokio::task::spawn_blocking(build_test_component).await?;
+
+    let mut config = Config::new();
+    config.async_support(true).wasm_component_model(true);
+    let engine = Engine::new(&config)?;
+    let component = Component::from_file(&engine, &component_path)?;
+
+    let mut linker = Linker::new(&engine);
+    wasmtime_wasi::add_to_linker_async(&mut linker)?;
+    wasmtime_wasi_http::add_only_http_to_linker_async(&mut linker)?;
+
+    let wasi = WasiCtxBuilder::new()
+        .inherit_stdio()
+        // Run only the `wasi:http` tests; the others don't need a host
+        .args(&["webassembly", "wasi_http"])
+        .env("WASI_HTTP_TEST_ENDPOINT", &endpoint)
+        .build();
+    let mut store = Store::new(
+        &engine,
+        Host {
+            table: ResourceTable::new(),
+            wasi,
+            http: WasiHttpCtx::new(),
+        },
+    );
+
+    let command = WasiCommand::instantiate_async(&mut store, &component, &linker).await?;
+    let result = command.wasi_cli_run().call_run(&mut store).await?;
+    assert!(result.is_ok(), "the wasi_http tests failed");
+    Ok(())
+}
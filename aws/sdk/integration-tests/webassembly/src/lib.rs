@@ -6,6 +6,8 @@
 mod adapter;
 mod default_config;
 mod list_buckets;
+#[cfg(test)]
+mod wasi_http;
 
 #[tokio::main(flavor = "current_thread")]
 pub async fn main() {
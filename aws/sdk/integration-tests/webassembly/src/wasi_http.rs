@@ -0,0 +1,56 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Tests for the `wasi:http` connection, sleep, and time source.
+//!
+//! These require a host that provides `wasi:http`. The `webassembly-harness` crate builds them for
+//! `wasm32-wasip2` and runs them in wasmtime, with requests served by an in-process stand-in
+//! whose address is passed in `WASI_HTTP_TEST_ENDPOINT`. Run elsewhere, e.g. with
+//! `wasmtime run -S http`, the connection test is skipped when that variable isn't set.
+
+use aws_smithy_async::rt::sleep::{AsyncSleep, WasiSleep};
+use aws_smithy_async::time::{TimeSource, WasiTimeSource};
+use aws_smithy_http::body::SdkBody;
+use aws_smithy_runtime::client::connections::wasi::WasiHttpConnection;
+use aws_smithy_runtime_api::client::orchestrator::Connection;
+use std::time::{Duration, Instant, UNIX_EPOCH};
+
+#[tokio::test]
+async fn wasi_sleep() {
+    let start = Instant::now();
+    WasiSleep::new().sleep(Duration::from_millis(10)).await;
+    assert!(start.elapsed() >= Duration::from_millis(10));
+}
+
+#[test]
+fn wasi_time_source() {
+    let now = WasiTimeSource::new().now();
+    // 2023-01-01T00:00:00Z
+    assert!(now > UNIX_EPOCH + Duration::from_secs(1_672_531_200));
+}
+
+#[tokio::test]
+async fn wasi_http_connection() {
+    let endpoint = match std::env::var("WASI_HTTP_TEST_ENDPOINT") {
+        Ok(endpoint) => endpoint,
+        Err(_) => {
+            println!("WASI_HTTP_TEST_ENDPOINT is not set; skipping");
+            return;
+        }
+    };
+    let request = http::Request::builder()
+        .method("PUT")
+        .uri(format!("{}/echo?query=value", endpoint))
+        .header("x-test", "wasi")
+        .body(SdkBody::from("hello from wasm"))
+        .unwrap();
+    let response = WasiHttpConnection::new()
+        .connect_timeout(Duration::from_secs(5))
+        .call(request)
+        .await
+        .expect("request succeeds");
+    assert_eq!(200, response.status().as_u16());
+    assert_eq!(b"hello from wasm", response.body().bytes().unwrap());
+}
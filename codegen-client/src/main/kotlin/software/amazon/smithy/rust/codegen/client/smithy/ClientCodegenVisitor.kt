@@ -81,6 +81,7 @@ class ClientCodegenVisitor(
             nullabilityCheckMode = NullableIndex.CheckMode.CLIENT_ZERO_VALUE_V1,
             moduleProvider = ClientModuleProvider,
             nameBuilderFor = { symbol -> "${symbol.name}Builder" },
+            generateSerde = settings.codegenConfig.generateSerde,
         )
         val baseModel = baselineTransform(context.model)
         val untransformedService = settings.getService(baseModel)
@@ -118,6 +118,7 @@ enum class SmithyRuntimeMode {
 data class ClientCodegenConfig(
     override val formatTimeoutSeconds: Int = defaultFormatTimeoutSeconds,
     override val debugMode: Boolean = defaultDebugMode,
+    override val generateSerde: Boolean = defaultGenerateSerde,
     val renameExceptions: Boolean = defaultRenameExceptions,
     val includeFluentClient: Boolean = defaultIncludeFluentClient,
     val addMessageToErrors: Boolean = defaultAddMessageToErrors,
@@ -126,7 +127,7 @@ data class ClientCodegenConfig(
     // TODO(SmithyRuntime): Remove this once we commit to switch to aws-smithy-runtime and aws-smithy-runtime-api
     val enableNewSmithyRuntime: SmithyRuntimeMode = defaultEnableNewSmithyRuntime,
 ) : CoreCodegenConfig(
-    formatTimeoutSeconds, debugMode,
+    formatTimeoutSeconds, debugMode, generateSerde,
 ) {
     companion object {
         private const val defaultRenameExceptions = true
@@ -140,6 +141,7 @@ data class ClientCodegenConfig(
                 ClientCodegenConfig(
                     formatTimeoutSeconds = coreCodegenConfig.formatTimeoutSeconds,
                     debugMode = coreCodegenConfig.debugMode,
+                    generateSerde = coreCodegenConfig.generateSerde,
                     eventStreamAllowList = node.get().getArrayMember("eventStreamAllowList")
                         .map { array -> array.toList().mapNotNull { node -> node.asStringNode().orNull()?.value } }
                         .orNull()?.toSet() ?: defaultEventStreamAllowList,
@@ -154,6 +156,7 @@ data class ClientCodegenConfig(
                 ClientCodegenConfig(
                     formatTimeoutSeconds = coreCodegenConfig.formatTimeoutSeconds,
                     debugMode = coreCodegenConfig.debugMode,
+                    generateSerde = coreCodegenConfig.generateSerde,
                 )
             }
     }
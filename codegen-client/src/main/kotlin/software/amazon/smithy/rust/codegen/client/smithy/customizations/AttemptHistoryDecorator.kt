@@ -0,0 +1,121 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+package software.amazon.smithy.rust.codegen.client.smithy.customizations
+
+import software.amazon.smithy.rust.codegen.client.smithy.ClientCodegenContext
+import software.amazon.smithy.rust.codegen.client.smithy.ClientRustModule
+import software.amazon.smithy.rust.codegen.client.smithy.customize.ClientCodegenDecorator
+import software.amazon.smithy.rust.codegen.core.rustlang.Attribute
+import software.amazon.smithy.rust.codegen.core.rustlang.Writable
+import software.amazon.smithy.rust.codegen.core.rustlang.rust
+import software.amazon.smithy.rust.codegen.core.rustlang.rustTemplate
+import software.amazon.smithy.rust.codegen.core.rustlang.writable
+import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType
+import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType.Companion.preludeScope
+import software.amazon.smithy.rust.codegen.core.smithy.RustCrate
+import software.amazon.smithy.rust.codegen.core.smithy.customizations.SerdeCustomization
+import software.amazon.smithy.rust.codegen.core.smithy.generators.BuilderCustomization
+import software.amazon.smithy.rust.codegen.core.smithy.generators.BuilderSection
+import software.amazon.smithy.rust.codegen.core.smithy.generators.StructureCustomization
+import software.amazon.smithy.rust.codegen.core.smithy.generators.StructureSection
+import software.amazon.smithy.rust.codegen.core.smithy.traits.SyntheticOutputTrait
+import software.amazon.smithy.rust.codegen.core.util.hasTrait
+import software.amazon.smithy.rust.codegen.core.util.letIf
+
+/**
+ * Adds the history of the request attempts made by the orchestrator to operation outputs.
+ *
+ * Errors carry the same history through `SdkError`, which implements `ProvideAttemptHistory`.
+ */
+class AttemptHistoryDecorator : ClientCodegenDecorator {
+    override val name: String = "AttemptHistory"
+    override val order: Byte = 0
+
+    override fun structureCustomizations(
+        codegenContext: ClientCodegenContext,
+        baseCustomizations: List<StructureCustomization>,
+    ): List<StructureCustomization> = baseCustomizations.letIf(codegenContext.smithyRuntimeMode.generateOrchestrator) {
+        it + AttemptHistoryStructureCustomization(codegenContext)
+    }
+
+    override fun builderCustomizations(
+        codegenContext: ClientCodegenContext,
+        baseCustomizations: List<BuilderCustomization>,
+    ): List<BuilderCustomization> = baseCustomizations.letIf(codegenContext.smithyRuntimeMode.generateOrchestrator) {
+        it + AttemptHistoryBuilderCustomization()
+    }
+
+    override fun extras(codegenContext: ClientCodegenContext, rustCrate: RustCrate) {
+        if (codegenContext.smithyRuntimeMode.generateOrchestrator) {
+            rustCrate.withModule(ClientRustModule.Operation) {
+                rust("pub use #T;", provideAttemptHistory(codegenContext))
+            }
+        }
+    }
+}
+
+private fun provideAttemptHistory(codegenContext: ClientCodegenContext): RuntimeType =
+    RuntimeType.smithyTypes(codegenContext.runtimeConfig).resolve("retry::ProvideAttemptHistory")
+
+private class AttemptHistoryStructureCustomization(private val codegenContext: ClientCodegenContext) :
+    StructureCustomization() {
+    private val codegenScope = arrayOf(
+        *preludeScope,
+        "AttemptHistory" to RuntimeType.smithyTypes(codegenContext.runtimeConfig).resolve("retry::AttemptHistory"),
+        "ProvideAttemptHistory" to provideAttemptHistory(codegenContext),
+    )
+
+    override fun section(section: StructureSection): Writable = writable {
+        if (section.shape.hasTrait<SyntheticOutputTrait>()) {
+            when (section) {
+                is StructureSection.AdditionalFields -> {
+                    // The history describes a single invocation, so it isn't part of the serialized output
+                    if (codegenContext.settings.codegenConfig.generateSerde &&
+                        SerdeCustomization.supportsSerde(section.shape, codegenContext.model)
+                    ) {
+                        Attribute("").SerdeSkip().render(this)
+                    }
+                    rustTemplate("_attempt_history: #{Option}<#{AttemptHistory}>,", *codegenScope)
+                }
+
+                is StructureSection.AdditionalTraitImpls -> {
+                    rustTemplate(
+                        """
+                        impl #{ProvideAttemptHistory} for ${section.structName} {
+                            fn attempt_history(&self) -> #{Option}<&#{AttemptHistory}> {
+                                self._attempt_history.as_ref()
+                            }
+                        }
+
+                        impl ${section.structName} {
+                            pub(crate) fn _set_attempt_history(&mut self, attempt_history: #{AttemptHistory}) {
+                                self._attempt_history = #{Some}(attempt_history);
+                            }
+                        }
+                        """,
+                        *codegenScope,
+                    )
+                }
+
+                is StructureSection.AdditionalDebugFields -> {
+                    rust("""${section.formatterName}.field("_attempt_history", &self._attempt_history);""")
+                }
+            }
+        }
+    }
+}
+
+private class AttemptHistoryBuilderCustomization : BuilderCustomization() {
+    override fun section(section: BuilderSection): Writable = writable {
+        if (section.shape.hasTrait<SyntheticOutputTrait>()) {
+            when (section) {
+                // The history is only known once the operation completes, so it's set on the output directly
+                is BuilderSection.AdditionalFieldsInBuild -> rust("_attempt_history: None,")
+                else -> {}
+            }
+        }
+    }
+}
@@ -46,7 +46,10 @@ fun codegenScope(runtimeConfig: RuntimeConfig): Array<Pair<String, Any>> {
         "StaticAuthOptionResolver" to smithyRuntimeApi.resolve("client::auth::option_resolver::StaticAuthOptionResolver"),
         "BasicAuthScheme" to authHttp.resolve("BasicAuthScheme"),
         "BearerAuthScheme" to authHttp.resolve("BearerAuthScheme"),
+        "DigestAuthCache" to authHttp.resolve("DigestAuthCache"),
         "DigestAuthScheme" to authHttp.resolve("DigestAuthScheme"),
+        "DigestChallengeClassifier" to authHttp.resolve("DigestChallengeClassifier"),
+        "DigestChallengeInterceptor" to authHttp.resolve("DigestChallengeInterceptor"),
         "HTTP_API_KEY_AUTH_SCHEME_ID" to authHttpApi.resolve("HTTP_API_KEY_AUTH_SCHEME_ID"),
         "HTTP_BASIC_AUTH_SCHEME_ID" to authHttpApi.resolve("HTTP_BASIC_AUTH_SCHEME_ID"),
         "HTTP_BEARER_AUTH_SCHEME_ID" to authHttpApi.resolve("HTTP_BEARER_AUTH_SCHEME_ID"),
@@ -114,7 +117,7 @@ class HttpAuthDecorator : ClientCodegenDecorator {
     ): List<OperationCustomization> =
         HttpAuthSchemes.from(codegenContext).let { authSchemes ->
             baseCustomizations.letIf(authSchemes.anyEnabled()) {
-                it + HttpAuthOperationCustomization(codegenContext)
+                it + HttpAuthOperationCustomization(codegenContext, authSchemes)
             }
         }
 
@@ -139,7 +142,8 @@ private class HttpAuthServiceRuntimePluginCustomization(
     private val authSchemes: HttpAuthSchemes,
 ) : ServiceRuntimePluginCustomization() {
     private val serviceShape = codegenContext.serviceShape
-    private val codegenScope = codegenScope(codegenContext.runtimeConfig)
+    private val runtimeConfig = codegenContext.runtimeConfig
+    private val codegenScope = codegenScope(runtimeConfig)
 
     override fun section(section: ServiceRuntimePluginSection): Writable = writable {
         when (section) {
@@ -192,6 +196,18 @@ private class HttpAuthServiceRuntimePluginCustomization(
                 if (authSchemes.anyEnabled()) {
                     rust("cfg.set_identity_resolvers(self.handle.conf.identity_resolvers().clone());")
                 }
+                if (authSchemes.digest) {
+                    // Digest challenges are cached on the config so that nonces outlive a single operation
+                    rust("cfg.store_put(self.handle.conf.digest_auth_cache.clone());")
+                }
+            }
+
+            is ServiceRuntimePluginSection.RegisterInterceptor -> {
+                if (authSchemes.digest) {
+                    section.registerInterceptor(runtimeConfig, this) {
+                        rustTemplate("#{DigestChallengeInterceptor}::new()", *codegenScope)
+                    }
+                }
             }
 
             else -> emptySection
@@ -199,7 +215,10 @@ private class HttpAuthServiceRuntimePluginCustomization(
     }
 }
 
-private class HttpAuthOperationCustomization(codegenContext: ClientCodegenContext) : OperationCustomization() {
+private class HttpAuthOperationCustomization(
+    codegenContext: ClientCodegenContext,
+    private val authSchemes: HttpAuthSchemes,
+) : OperationCustomization() {
     private val serviceShape = codegenContext.serviceShape
     private val codegenScope = codegenScope(codegenContext.runtimeConfig)
 
@@ -227,6 +246,13 @@ private class HttpAuthOperationCustomization(codegenContext: ClientCodegenContex
                 rustTemplate("${section.newLayerName}.set_auth_option_resolver(auth_option_resolver);", *codegenScope)
             }
 
+            is OperationSection.RetryClassifier -> {
+                if (authSchemes.digest) {
+                    // Digest challenges are answered by the retry strategy
+                    rustTemplate(".with_classifier(#{DigestChallengeClassifier}::new())", *codegenScope)
+                }
+            }
+
             else -> emptySection
         }
     }
@@ -327,11 +353,17 @@ private class HttpAuthConfigCustomization(
             is ServiceConfig.BuilderBuild -> {
                 if (runtimeMode.defaultToMiddleware) {
                     rust("identity_resolvers: self.identity_resolvers,")
+                    if (authSchemes.digest) {
+                        rustTemplate("digest_auth_cache: #{DigestAuthCache}::new(),", *codegenScope)
+                    }
                 }
             }
 
             is ServiceConfig.ConfigStruct -> {
                 rustTemplate("identity_resolvers: #{IdentityResolvers},", *codegenScope)
+                if (authSchemes.digest) {
+                    rustTemplate("digest_auth_cache: #{DigestAuthCache},", *codegenScope)
+                }
             }
 
             is ServiceConfig.ConfigImpl -> {
@@ -348,6 +380,9 @@ private class HttpAuthConfigCustomization(
 
             is ServiceConfig.BuilderBuildExtras -> {
                 rust("identity_resolvers: self.identity_resolvers,")
+                if (authSchemes.digest) {
+                    rustTemplate("digest_auth_cache: #{DigestAuthCache}::new(),", *codegenScope)
+                }
             }
 
             else -> {}
@@ -25,9 +25,11 @@ import software.amazon.smithy.rust.codegen.core.rustlang.Feature
 import software.amazon.smithy.rust.codegen.core.smithy.RustCrate
 import software.amazon.smithy.rust.codegen.core.smithy.customizations.AllowLintsCustomization
 import software.amazon.smithy.rust.codegen.core.smithy.customizations.CrateVersionCustomization
+import software.amazon.smithy.rust.codegen.core.smithy.customizations.SerdeCustomization
 import software.amazon.smithy.rust.codegen.core.smithy.customizations.pubUseSmithyErrorTypes
 import software.amazon.smithy.rust.codegen.core.smithy.customizations.pubUseSmithyPrimitives
 import software.amazon.smithy.rust.codegen.core.smithy.generators.LibRsCustomization
+import software.amazon.smithy.rust.codegen.core.smithy.generators.ManifestCustomizations
 
 val TestUtilFeature = Feature("test-util", false, listOf())
 
@@ -71,8 +73,10 @@ class RequiredCustomizations : ClientCodegenDecorator {
         baseCustomizations + AllowLintsCustomization()
 
     override fun extras(codegenContext: ClientCodegenContext, rustCrate: RustCrate) {
-        // Add rt-tokio feature for `ByteStream::from_path`
-        rustCrate.mergeFeature(Feature("rt-tokio", true, listOf("aws-smithy-http/rt-tokio")))
+        // Add rt-tokio feature for `ByteStream::from_path` and paginator prefetching
+        rustCrate.mergeFeature(
+            Feature("rt-tokio", true, listOf("aws-smithy-async/rt-tokio", "aws-smithy-http/rt-tokio")),
+        )
 
         rustCrate.mergeFeature(TestUtilFeature)
 
@@ -91,8 +95,19 @@ class RequiredCustomizations : ClientCodegenDecorator {
                 CrateVersionCustomization.extras(rustCrate, metaModule)
             }
         }
+
+        if (codegenContext.settings.codegenConfig.generateSerde) {
+            SerdeCustomization.extras(rustCrate)
+        }
     }
 
+    override fun crateManifestCustomizations(codegenContext: ClientCodegenContext): ManifestCustomizations =
+        if (codegenContext.settings.codegenConfig.generateSerde) {
+            SerdeCustomization.manifestCustomizations()
+        } else {
+            emptyMap()
+        }
+
     override fun serviceRuntimePluginCustomizations(
         codegenContext: ClientCodegenContext,
         baseCustomizations: List<ServiceRuntimePluginCustomization>,
@@ -160,8 +160,11 @@ open class OperationGenerator(
                         let context = Self::orchestrate_with_stop_point(&runtime_plugins, input, #{StopPoint}::None)
                             .await
                             .map_err(map_err)?;
+                        let attempt_history = context.attempt_history().clone();
                         let output = context.finalize().map_err(map_err)?;
-                        #{Ok}(#{TypedBox}::<#{OperationOutput}>::assume_from(output).expect("correct output type").unwrap())
+                        let mut output = #{TypedBox}::<#{OperationOutput}>::assume_from(output).expect("correct output type").unwrap();
+                        output._set_attempt_history(attempt_history);
+                        #{Ok}(output)
                     }
 
                     pub(crate) async fn orchestrate_with_stop_point(
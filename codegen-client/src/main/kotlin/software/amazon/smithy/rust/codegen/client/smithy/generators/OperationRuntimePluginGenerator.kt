@@ -6,6 +6,8 @@
 package software.amazon.smithy.rust.codegen.client.smithy.generators
 
 import software.amazon.smithy.model.shapes.OperationShape
+import software.amazon.smithy.model.traits.IdempotentTrait
+import software.amazon.smithy.model.traits.ReadonlyTrait
 import software.amazon.smithy.rust.codegen.client.smithy.ClientCodegenContext
 import software.amazon.smithy.rust.codegen.core.rustlang.RustWriter
 import software.amazon.smithy.rust.codegen.core.rustlang.rustTemplate
@@ -14,6 +16,7 @@ import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType
 import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType.Companion.preludeScope
 import software.amazon.smithy.rust.codegen.core.smithy.customize.writeCustomizations
 import software.amazon.smithy.rust.codegen.core.util.dq
+import software.amazon.smithy.rust.codegen.core.util.hasTrait
 
 /**
  * Generates operation-level runtime plugins
@@ -29,6 +32,8 @@ class OperationRuntimePluginGenerator(
             "BoxError" to runtimeApi.resolve("client::runtime_plugin::BoxError"),
             "Layer" to smithyTypes.resolve("config_bag::Layer"),
             "FrozenLayer" to smithyTypes.resolve("config_bag::FrozenLayer"),
+            "IdempotentOperation" to runtimeApi.resolve("client::retries::IdempotentOperation"),
+            "ReadonlyOperation" to runtimeApi.resolve("client::retries::ReadonlyOperation"),
             "ConfigBag" to smithyTypes.resolve("config_bag::ConfigBag"),
             "ConfigBagAccessors" to runtimeApi.resolve("client::orchestrator::ConfigBagAccessors"),
             "InterceptorRegistrar" to runtimeApi.resolve("client::interceptors::InterceptorRegistrar"),
@@ -60,6 +65,7 @@ class OperationRuntimePluginGenerator(
                     let retry_classifiers = #{RetryClassifiers}::new()
                         #{retry_classifier_customizations};
                     cfg.set_retry_classifiers(retry_classifiers);
+                    #{idempotent_operation}
 
                     #{additional_config}
                     Some(cfg.freeze())
@@ -84,6 +90,16 @@ class OperationRuntimePluginGenerator(
                     ),
                 )
             },
+            "idempotent_operation" to writable {
+                // Requests for operations that are safe to send more than once may be hedged
+                if (operationShape.hasTrait<ReadonlyTrait>() || operationShape.hasTrait<IdempotentTrait>()) {
+                    rustTemplate("cfg.store_put(#{IdempotentOperation}::new());", *codegenScope)
+                }
+                // Only responses to operations that don't change anything may be served from a cache
+                if (operationShape.hasTrait<ReadonlyTrait>()) {
+                    rustTemplate("cfg.store_put(#{ReadonlyOperation}::new());", *codegenScope)
+                }
+            },
             "retry_classifier_customizations" to writable {
                 writeCustomizations(
                     customizations,
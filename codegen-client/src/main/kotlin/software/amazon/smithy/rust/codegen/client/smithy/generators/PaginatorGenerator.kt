@@ -8,6 +8,7 @@ package software.amazon.smithy.rust.codegen.client.smithy.generators
 import software.amazon.smithy.model.Model
 import software.amazon.smithy.model.knowledge.PaginatedIndex
 import software.amazon.smithy.model.shapes.OperationShape
+import software.amazon.smithy.model.shapes.StringShape
 import software.amazon.smithy.model.traits.IdempotencyTokenTrait
 import software.amazon.smithy.model.traits.PaginatedTrait
 import software.amazon.smithy.rust.codegen.client.smithy.ClientCodegenContext
@@ -109,9 +110,12 @@ class PaginatorGenerator private constructor(
         "SdkError" to RuntimeType.sdkError(runtimeConfig),
         "client" to RuntimeType.smithyClient(runtimeConfig),
         "fn_stream" to RuntimeType.smithyAsync(runtimeConfig).resolve("future::fn_stream"),
+        "pagination" to RuntimeType.smithyAsync(runtimeConfig).resolve("future::pagination"),
+        "PaginationCheckpoint" to RuntimeType.smithyAsync(runtimeConfig).resolve("future::pagination::PaginationCheckpoint"),
 
         // External Types
         "Stream" to RuntimeType.TokioStream.resolve("Stream"),
+        "StreamExt" to RuntimeType.TokioStream.resolve("StreamExt"),
 
     )
 
@@ -129,6 +133,7 @@ class PaginatorGenerator private constructor(
                 handle: std::sync::Arc<crate::client::Handle${generics.inst}>,
                 builder: #{Builder},
                 stop_on_duplicate_token: bool,
+                completed: bool,
             }
 
             impl${generics.inst} ${paginatorName}${generics.inst} #{bounds:W} {
@@ -138,6 +143,7 @@ class PaginatorGenerator private constructor(
                         handle,
                         builder,
                         stop_on_duplicate_token: true,
+                        completed: false,
                     }
                 }
 
@@ -145,6 +151,8 @@ class PaginatorGenerator private constructor(
 
                 #{items_fn:W}
 
+                #{checkpoint_fns:W}
+
                 /// Stop paginating when the service returns the same pagination token twice in a row.
                 ///
                 /// Defaults to true.
@@ -167,6 +175,10 @@ class PaginatorGenerator private constructor(
                     let handle = self.handle;
                     #{runtime_plugin_init};
                     #{fn_stream}::FnStream::new(move |tx| #{Box}::pin(async move {
+                        // The paginator was resumed from a checkpoint taken after the last page
+                        if self.completed {
+                            return;
+                        }
                         // Build the input for the first time. If required fields are missing, this is where we'll produce an early error.
                         let mut input = match builder.build().map_err(#{SdkError}::construction_failure) {
                             #{Ok}(input) => input,
@@ -198,18 +210,16 @@ class PaginatorGenerator private constructor(
                         }
                     }))
                 }
+
+                #{consumer_fns:W}
             }
             """,
             *codegenScope,
             "items_fn" to itemsFn(),
+            "checkpoint_fns" to checkpointFns(outputTokenLens),
+            "consumer_fns" to consumerFns("self.send()", "#{Output}", "pages"),
             "output_token" to outputTokenLens,
-            "item_type" to writable {
-                if (codegenContext.smithyRuntimeMode.defaultToMiddleware) {
-                    rustTemplate("#{Result}<#{Output}, #{SdkError}<#{Error}>>", *codegenScope)
-                } else {
-                    rustTemplate("#{Result}<#{Output}, #{SdkError}<#{Error}, #{HttpResponse}>>", *codegenScope)
-                }
-            },
+            "item_type" to resultType("#{Output}"),
             "orchestrate" to writable {
                 if (codegenContext.smithyRuntimeMode.defaultToMiddleware) {
                     rustTemplate(
@@ -310,6 +320,17 @@ class PaginatorGenerator private constructor(
                     #{send_bounds:W} {
                         #{fn_stream}::TryFlatMap::new(self.0.send()).flat_map(|page| #{extract_items}(page).unwrap_or_default().into_iter())
                     }
+
+                    /// Create a pagination stream that fetches up to `pages` pages ahead of the reader
+                    ///
+                    /// Requests are still sent one at a time. See [`prefetch`](#{pagination}::prefetch) for details.
+                    ##[cfg(feature = "rt-tokio")]
+                    pub fn send_prefetch(self, pages: usize) -> impl #{Stream}<Item = #{item_type}> + #{Unpin}
+                    #{send_bounds:W} {
+                        #{fn_stream}::TryFlatMap::new(self.0.send_prefetch(pages)).flat_map(|page| #{extract_items}(page).unwrap_or_default().into_iter())
+                    }
+
+                    #{consumer_fns:W}
                 }
 
                 """,
@@ -317,18 +338,100 @@ class PaginatorGenerator private constructor(
                     outputShape,
                     paginationInfo.itemsMemberPath,
                 ),
-                "item_type" to writable {
-                    if (codegenContext.smithyRuntimeMode.defaultToMiddleware) {
-                        rustTemplate("#{Result}<${itemType()}, #{SdkError}<#{Error}>>", *codegenScope)
-                    } else {
-                        rustTemplate("#{Result}<${itemType()}, #{SdkError}<#{Error}, #{HttpResponse}>>", *codegenScope)
-                    }
-                },
+                "item_type" to resultType(itemType()),
+                "consumer_fns" to consumerFns("self.send()", itemType(), "items", prefetch = false),
                 *codegenScope,
             )
         }
     }
 
+    /** Type of the items yielded by a paginator stream, wrapped in a `Result` */
+    private fun resultType(item: String) = writable {
+        if (codegenContext.smithyRuntimeMode.defaultToMiddleware) {
+            rustTemplate("#{Result}<$item, #{SdkError}<#{Error}>>", *codegenScope)
+        } else {
+            rustTemplate("#{Result}<$item, #{SdkError}<#{Error}, #{HttpResponse}>>", *codegenScope)
+        }
+    }
+
+    /** Generate functions that consume the pagination stream created by `send` */
+    private fun consumerFns(stream: String, item: String, unit: String, prefetch: Boolean = true) = writable {
+        if (prefetch) {
+            rustTemplate(
+                """
+                /// Create a pagination stream that fetches up to `pages` pages ahead of the reader
+                ///
+                /// This allows the next pages to be fetched while the current one is being processed. Requests are
+                /// still sent one at a time. See [`prefetch`](#{pagination}::prefetch) for details.
+                ##[cfg(feature = "rt-tokio")]
+                pub fn send_prefetch(self, pages: usize) -> impl #{Stream}<Item = #{item_type}> + #{Unpin}
+                #{send_bounds:W} {
+                    #{pagination}::prefetch($stream, pages)
+                }
+                """,
+                *codegenScope,
+                "item_type" to resultType(item),
+            )
+        }
+        rustTemplate(
+            """
+            /// Read the pagination stream into a `Vec`, stopping after `limit` $unit
+            ///
+            /// When `limit` is `None`, every $unit is read. The first error encountered is returned.
+            pub async fn try_collect(self, limit: #{Option}<usize>) -> #{collect_type}
+            #{send_bounds:W} {
+                #{pagination}::try_collect($stream, limit).await
+            }
+            """,
+            *codegenScope,
+            "collect_type" to resultType("#{Vec}<$item>"),
+        )
+    }
+
+    /**
+     * Generate functions to checkpoint and resume pagination
+     *
+     * Checkpoints are only supported when the pagination token is a string.
+     */
+    private fun checkpointFns(outputTokenLens: RuntimeType) = writable {
+        val inputToken = paginationInfo.inputTokenMember
+        val outputToken = paginationInfo.outputTokenMemberPath.last()
+        if (model.expectShape(inputToken.target) !is StringShape || model.expectShape(outputToken.target) !is StringShape) {
+            return@writable
+        }
+        val inputTokenMember = symbolProvider.toMemberName(inputToken)
+        rustTemplate(
+            """
+            /// Resume pagination from a checkpoint created by [`send_with_checkpoints`](Self::send_with_checkpoints)
+            ///
+            /// If the checkpoint was taken after the last page, the paginator will not send any requests.
+            ///
+            /// _Note: this method will override any previously set value for `$inputTokenMember`_
+            pub fn resume_from(mut self, checkpoint: #{PaginationCheckpoint}) -> Self {
+                self.completed = checkpoint.is_complete();
+                self.builder.$inputTokenMember = checkpoint.next_token().map(|token| token.to_owned());
+                self
+            }
+
+            /// Create a pagination stream that yields each page along with a checkpoint taken after it
+            ///
+            /// Save the checkpoint once a page has been processed. It can be serialized with
+            /// [`to_string`](std::string::ToString::to_string), parsed back with [`str::parse`], and passed to
+            /// [`resume_from`](Self::resume_from) to continue with the next page.
+            pub fn send_with_checkpoints(self) -> impl #{Stream}<Item = #{item_type}> + #{Unpin}
+            #{send_bounds:W} {
+                #{StreamExt}::map(self.send(), |page| page.map(|page| {
+                    let checkpoint = #{PaginationCheckpoint}::from_next_token(#{output_token}(&page).map(|token| token.as_str()));
+                    (page, checkpoint)
+                }))
+            }
+            """,
+            *codegenScope,
+            "output_token" to outputTokenLens,
+            "item_type" to resultType("(#{Output}, #{PaginationCheckpoint})"),
+        )
+    }
+
     private fun pageSizeSetter() = writable {
         paginationInfo.pageSizeMember.orNull()?.also {
             val memberName = symbolProvider.toMemberName(it)
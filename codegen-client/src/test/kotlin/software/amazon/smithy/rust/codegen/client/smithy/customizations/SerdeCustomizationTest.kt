@@ -0,0 +1,167 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+package software.amazon.smithy.rust.codegen.client.smithy.customizations
+
+import org.junit.jupiter.api.Test
+import software.amazon.smithy.model.node.Node
+import software.amazon.smithy.rust.codegen.client.testutil.clientIntegrationTest
+import software.amazon.smithy.rust.codegen.core.rustlang.CargoDependency
+import software.amazon.smithy.rust.codegen.core.rustlang.rustTemplate
+import software.amazon.smithy.rust.codegen.core.testutil.IntegrationTestParams
+import software.amazon.smithy.rust.codegen.core.testutil.asSmithyModel
+import software.amazon.smithy.rust.codegen.core.testutil.integrationTest
+import software.amazon.smithy.rust.codegen.core.util.runCommand
+
+internal class SerdeCustomizationTest {
+    private val model = """
+        namespace test
+
+        use aws.protocols#restJson1
+
+        @restJson1
+        service TestService {
+            version: "2023-01-01",
+            operations: [PutPerson]
+        }
+
+        @http(uri: "/person", method: "PUT")
+        operation PutPerson {
+            input: PutPersonInput,
+            output: PutPersonOutput,
+        }
+
+        structure PutPersonInput {
+            person: Person,
+        }
+
+        structure PutPersonOutput {
+            person: Person,
+        }
+
+        structure Person {
+            @jsonName("FullName")
+            name: String,
+            secret: Secret,
+            role: Role,
+            tags: Tags,
+            attributes: Attributes,
+            contact: Contact,
+            updated: Timestamp,
+            picture: Blob,
+        }
+
+        @sensitive
+        string Secret
+
+        @enum([
+            { value: "admin", name: "ADMIN" },
+            { value: "user", name: "USER" },
+        ])
+        string Role
+
+        list Tags {
+            member: String,
+        }
+
+        map Attributes {
+            key: String,
+            value: String,
+        }
+
+        union Contact {
+            email: String,
+            phone: String,
+        }
+    """.asSmithyModel()
+
+    private fun serdeParams(smithyRuntimeMode: String = "middleware") = IntegrationTestParams(
+        additionalSettings = Node.objectNodeBuilder().withMember(
+            "codegen",
+            Node.objectNodeBuilder()
+                .withMember("generateSerde", true)
+                .withMember("enableNewSmithyRuntime", smithyRuntimeMode)
+                .build(),
+        ).build(),
+        command = {
+            "cargo test --all-features".runCommand(it, mapOf("RUSTFLAGS" to "--cfg aws_sdk_unstable"))
+        },
+    )
+
+    @Test
+    fun `generated shapes round-trip through serde`() {
+        clientIntegrationTest(model, serdeParams()) { codegenContext, rustCrate ->
+            val moduleName = codegenContext.moduleUseName()
+            rustCrate.integrationTest("serde_round_trip") {
+                rustTemplate(
+                    """
+                    use $moduleName::primitives::Blob;
+                    use $moduleName::types::{Contact, Person, Role};
+
+                    ##[test]
+                    fn round_trip() {
+                        let person = Person::builder()
+                            .name("Jane")
+                            .secret("hunter2")
+                            .role(Role::Admin)
+                            .tags("a")
+                            .attributes("k", "v")
+                            .contact(Contact::Email("jane@example.com".to_string()))
+                            .picture(Blob::new("picture"))
+                            .build();
+                        let json = #{serde_json}::to_string(&person).unwrap();
+                        assert!(json.contains(r##""FullName":"Jane""##), "{}", json);
+                        assert!(json.contains(r##""role":"admin""##), "{}", json);
+                        // Sensitive members are redacted, and deserialize as the placeholder
+                        assert!(!json.contains("hunter2"), "{}", json);
+                        assert!(json.contains(r##""secret":"*** Sensitive Data Redacted ***""##), "{}", json);
+
+                        let parsed: Person = #{serde_json}::from_str(&json).unwrap();
+                        assert_eq!(Some("*** Sensitive Data Redacted ***"), parsed.secret());
+                        let mut person = person;
+                        person.secret = Some("*** Sensitive Data Redacted ***".to_string());
+                        assert_eq!(person, parsed);
+                    }
+
+                    ##[test]
+                    fn unknown_enum_values_round_trip() {
+                        let role: Role = #{serde_json}::from_str(r##""owner""##).unwrap();
+                        assert_eq!(r##""owner""##, #{serde_json}::to_string(&role).unwrap());
+                    }
+                    """,
+                    "serde_json" to CargoDependency.SerdeJson.toType(),
+                )
+            }
+        }
+    }
+
+    @Test
+    fun `orchestrator outputs serialize without their attempt history`() {
+        clientIntegrationTest(model, serdeParams("orchestrator")) { codegenContext, rustCrate ->
+            val moduleName = codegenContext.moduleUseName()
+            rustCrate.integrationTest("serde_output") {
+                rustTemplate(
+                    """
+                    use $moduleName::operation::put_person::PutPersonOutput;
+                    use $moduleName::types::Person;
+
+                    ##[test]
+                    fn output_round_trip() {
+                        let output = PutPersonOutput::builder()
+                            .person(Person::builder().name("Jane").build())
+                            .build();
+                        let json = #{serde_json}::to_string(&output).unwrap();
+                        assert!(!json.contains("attempt_history"), "{}", json);
+
+                        let parsed: PutPersonOutput = #{serde_json}::from_str(&json).unwrap();
+                        assert_eq!(output, parsed);
+                    }
+                    """,
+                    "serde_json" to CargoDependency.SerdeJson.toType(),
+                )
+            }
+        }
+    }
+}
@@ -10,7 +10,10 @@ import software.amazon.smithy.model.node.ObjectNode
 import software.amazon.smithy.model.node.StringNode
 import software.amazon.smithy.rust.codegen.client.testutil.clientIntegrationTest
 import software.amazon.smithy.rust.codegen.core.rustlang.Attribute
+import software.amazon.smithy.rust.codegen.core.rustlang.CargoDependency
 import software.amazon.smithy.rust.codegen.core.rustlang.rust
+import software.amazon.smithy.rust.codegen.core.rustlang.rustTemplate
+import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType
 import software.amazon.smithy.rust.codegen.core.testutil.IntegrationTestParams
 import software.amazon.smithy.rust.codegen.core.testutil.asSmithyModel
 import software.amazon.smithy.rust.codegen.core.testutil.integrationTest
@@ -101,4 +104,148 @@ internal class PaginatorGeneratorTest {
             }
         }
     }
+
+    @Test
+    fun `prefetch, collect and resume paginators`() {
+        clientIntegrationTest(
+            model,
+            params = IntegrationTestParams(additionalSettings = enableNewSmithyRuntime()),
+        ) { clientCodegenContext, rustCrate ->
+            val moduleName = clientCodegenContext.moduleUseName()
+            val runtimeConfig = clientCodegenContext.runtimeConfig
+            rustCrate.integrationTest("paginator_consumers") {
+                rustTemplate(
+                    """
+                    use #{StreamExt};
+
+                    /// An exchange for a page with a single item
+                    fn page(
+                        input_token: Option<&str>,
+                        output_token: Option<&str>,
+                        item: &str,
+                    ) -> (http::Request<#{SdkBody}>, http::Response<#{SdkBody}>) {
+                        let request = match input_token {
+                            Some(token) => format!("{{\"nextToken\":\"{}\"}}", token),
+                            None => "{}".to_string(),
+                        };
+                        let token = output_token
+                            .map(|token| format!("\"token\":\"{}\",", token))
+                            .unwrap_or_default();
+                        let response = format!(
+                            "{{\"inner\":{{{}\"items\":[\"{}\"],\"mapItems\":{{}}}}}}",
+                            token, item
+                        );
+                        (
+                            http::Request::builder()
+                                .uri("http://localhost:1234/")
+                                .body(#{SdkBody}::from(request))
+                                .unwrap(),
+                            http::Response::builder()
+                                .status(200)
+                                .body(#{SdkBody}::from(response))
+                                .unwrap(),
+                        )
+                    }
+
+                    fn test_client(
+                        pages: Vec<(http::Request<#{SdkBody}>, http::Response<#{SdkBody}>)>,
+                    ) -> ($moduleName::Client, #{TestConnection}<#{SdkBody}>) {
+                        let connector = #{TestConnection}::new(pages);
+                        let config = $moduleName::Config::builder()
+                            .endpoint_resolver("http://localhost:1234")
+                            .http_connector(connector.clone())
+                            .build();
+                        let smithy_client = aws_smithy_client::Builder::new()
+                            .connector(connector.clone())
+                            .middleware_fn(|r| r)
+                            .build_dyn();
+                        ($moduleName::Client::with_config(smithy_client, config), connector)
+                    }
+
+                    fn three_pages() -> Vec<(http::Request<#{SdkBody}>, http::Response<#{SdkBody}>)> {
+                        vec![
+                            page(None, Some("t1"), "a"),
+                            page(Some("t1"), Some("t2"), "b"),
+                            page(Some("t2"), None, "c"),
+                        ]
+                    }
+
+                    ##[tokio::test]
+                    async fn send_prefetch() {
+                        let (client, connector) = test_client(three_pages());
+                        let items = client
+                            .paginated_list()
+                            .into_paginator()
+                            .items()
+                            .send_prefetch(2)
+                            .collect::<Result<Vec<_>, _>>()
+                            .await
+                            .unwrap();
+                        assert_eq!(vec!["a", "b", "c"], items);
+                        connector.assert_requests_match(&[]);
+                    }
+
+                    ##[tokio::test]
+                    async fn try_collect_stops_at_limit() {
+                        let (client, connector) = test_client(three_pages().into_iter().take(2).collect());
+                        let items = client
+                            .paginated_list()
+                            .into_paginator()
+                            .items()
+                            .try_collect(Some(2))
+                            .await
+                            .unwrap();
+                        assert_eq!(vec!["a", "b"], items);
+                        connector.assert_requests_match(&[]);
+                    }
+
+                    ##[tokio::test]
+                    async fn checkpoint_and_resume() {
+                        let (client, connector) = test_client(three_pages());
+                        let checkpoints = client
+                            .paginated_list()
+                            .into_paginator()
+                            .send_with_checkpoints()
+                            .map(|page| page.unwrap().1.to_string())
+                            .collect::<Vec<_>>()
+                            .await;
+                        assert_eq!(vec!["next:t1", "next:t2", "complete"], checkpoints);
+                        connector.assert_requests_match(&[]);
+
+                        // Resume after the first page
+                        let (client, connector) = test_client(three_pages().into_iter().skip(1).collect());
+                        let pages = client
+                            .paginated_list()
+                            .into_paginator()
+                            .resume_from(checkpoints[0].parse::<#{PaginationCheckpoint}>().unwrap())
+                            .try_collect(None)
+                            .await
+                            .unwrap();
+                        assert_eq!(2, pages.len());
+                        connector.assert_requests_match(&[]);
+
+                        // Resuming after the last page sends no requests
+                        let (client, connector) = test_client(vec![]);
+                        let pages = client
+                            .paginated_list()
+                            .into_paginator()
+                            .resume_from(checkpoints[2].parse::<#{PaginationCheckpoint}>().unwrap())
+                            .try_collect(None)
+                            .await
+                            .unwrap();
+                        assert!(pages.is_empty());
+                        connector.assert_requests_match(&[]);
+                    }
+                    """,
+                    "StreamExt" to RuntimeType.TokioStream.resolve("StreamExt"),
+                    "SdkBody" to RuntimeType.sdkBody(runtimeConfig),
+                    "TestConnection" to CargoDependency.smithyClient(runtimeConfig)
+                        .withFeature("test-util").toType()
+                        .resolve("test_connection::TestConnection"),
+                    "PaginationCheckpoint" to RuntimeType.smithyAsync(runtimeConfig)
+                        .resolve("future::pagination::PaginationCheckpoint"),
+                )
+            }
+        }
+    }
 }
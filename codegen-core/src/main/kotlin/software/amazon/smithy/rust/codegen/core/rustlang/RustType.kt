@@ -479,17 +479,17 @@ class Attribute(val inner: Writable, val isDeriveHelper: Boolean = false) {
     // You can find the discussion here.
     // https://github.com/awslabs/smithy-rs/discussions/2248
     public fun SerdeSerialize(): Attribute {
-        return Attribute(cfgAttr(all(writable("aws_sdk_unstable"), feature("serde-serialize")), derive(RuntimeType.SerdeSerialize)))
+        return Attribute(cfgAttr(serdeSerializeCfg(), derive(RuntimeType.SerdeSerialize)))
     }
     public fun SerdeDeserialize(): Attribute {
-        return Attribute(cfgAttr(all(writable("aws_sdk_unstable"), feature("serde-deserialize")), derive(RuntimeType.SerdeDeserialize)))
+        return Attribute(cfgAttr(serdeDeserializeCfg(), derive(RuntimeType.SerdeDeserialize)))
     }
     public fun SerdeSkip(): Attribute {
-        return Attribute(cfgAttr(all(writable("aws_sdk_unstable"), any(feature("serde-serialize"), feature("serde-deserialize"))), serde("skip")))
+        return Attribute(cfgAttr(serdeSerializeOrDeserializeCfg(), serde("skip")))
     }
 
     public fun SerdeSerializeOrDeserialize(): Attribute {
-        return Attribute(cfg(all(writable("aws_sdk_unstable"), any(feature("serde-serialize"), feature("serde-deserialize")))))
+        return Attribute(cfg(serdeSerializeOrDeserializeCfg()))
     }
 
     companion object {
@@ -565,6 +565,16 @@ class Attribute(val inner: Writable, val isDeriveHelper: Boolean = false) {
 
         fun feature(feature: String) = writable("feature = ${feature.dq()}")
 
+        /** The predicate gating `serde::Serialize` implementations, see [SerdeSerialize]. */
+        fun serdeSerializeCfg(): Writable = all(writable("aws_sdk_unstable"), feature("serde-serialize"))
+
+        /** The predicate gating `serde::Deserialize` implementations, see [SerdeDeserialize]. */
+        fun serdeDeserializeCfg(): Writable = all(writable("aws_sdk_unstable"), feature("serde-deserialize"))
+
+        /** The predicate gating code that is needed by either `serde` implementation, see [SerdeSerializeOrDeserialize]. */
+        fun serdeSerializeOrDeserializeCfg(): Writable =
+            all(writable("aws_sdk_unstable"), any(feature("serde-serialize"), feature("serde-deserialize")))
+
         fun deprecated(since: String? = null, note: String? = null): Writable {
             val optionalFields = mutableListOf<Writable>()
             if (!note.isNullOrEmpty()) {
@@ -37,25 +37,31 @@ const val CODEGEN_SETTINGS = "codegen"
  *
  * [formatTimeoutSeconds]: Timeout for running cargo fmt at the end of code generation
  * [debugMode]: Generate comments in the generated code indicating where code was generated from
+ * [generateSerde]: Generate `serde` implementations for structures, unions and enums, gated behind
+ *   `--cfg aws_sdk_unstable` and the `serde-serialize`/`serde-deserialize` crate features
  */
 open class CoreCodegenConfig(
     open val formatTimeoutSeconds: Int = defaultFormatTimeoutSeconds,
     open val debugMode: Boolean = defaultDebugMode,
+    open val generateSerde: Boolean = defaultGenerateSerde,
 ) {
     companion object {
         const val defaultFormatTimeoutSeconds = 20
         const val defaultDebugMode = false
+        const val defaultGenerateSerde = false
 
         fun fromNode(node: Optional<ObjectNode>): CoreCodegenConfig =
             if (node.isPresent) {
                 CoreCodegenConfig(
                     node.get().getNumberMemberOrDefault("formatTimeoutSeconds", defaultFormatTimeoutSeconds).toInt(),
                     node.get().getBooleanMemberOrDefault("debugMode", defaultDebugMode),
+                    node.get().getBooleanMemberOrDefault("generateSerde", defaultGenerateSerde),
                 )
             } else {
                 CoreCodegenConfig(
                     formatTimeoutSeconds = defaultFormatTimeoutSeconds,
                     debugMode = defaultDebugMode,
+                    generateSerde = defaultGenerateSerde,
                 )
             }
     }
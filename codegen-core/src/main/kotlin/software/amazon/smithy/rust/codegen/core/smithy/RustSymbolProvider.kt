@@ -15,6 +15,7 @@ import software.amazon.smithy.model.shapes.ServiceShape
 import software.amazon.smithy.model.shapes.Shape
 import software.amazon.smithy.model.shapes.UnionShape
 import software.amazon.smithy.rust.codegen.core.rustlang.RustModule
+import software.amazon.smithy.rust.codegen.core.smithy.customizations.SerdeCustomization
 
 /**
  * SymbolProvider interface that carries additional configuration and module/symbol resolution.
@@ -82,6 +83,8 @@ data class RustSymbolProviderConfig(
     val nullabilityCheckMode: NullableIndex.CheckMode,
     val moduleProvider: ModuleProvider,
     val nameBuilderFor: (Symbol) -> String = { _ -> "Builder" },
+    /** Whether generated shapes should implement `serde` traits, see [SerdeCustomization] */
+    val generateSerde: Boolean = false,
 )
 
 /**
@@ -24,6 +24,7 @@ import software.amazon.smithy.model.traits.StreamingTrait
 import software.amazon.smithy.rust.codegen.core.rustlang.Attribute
 import software.amazon.smithy.rust.codegen.core.rustlang.RustMetadata
 import software.amazon.smithy.rust.codegen.core.rustlang.Visibility
+import software.amazon.smithy.rust.codegen.core.smithy.customizations.SerdeCustomization
 import software.amazon.smithy.rust.codegen.core.util.hasTrait
 
 /**
@@ -98,6 +99,17 @@ class BaseSymbolMetadataProvider(
 ) : SymbolMetadataProvider(base) {
 
     override fun memberMeta(memberShape: MemberShape): RustMetadata =
+        baseMemberMeta(memberShape).let { meta ->
+            val container = model.expectShape(memberShape.container)
+            if (config.generateSerde && SerdeCustomization.supportsSerde(container, model)) {
+                val serdeAttributes = SerdeCustomization.memberAttributes(memberShape, model, config.runtimeConfig)
+                meta.copy(additionalAttributes = meta.additionalAttributes + serdeAttributes)
+            } else {
+                meta
+            }
+        }
+
+    private fun baseMemberMeta(memberShape: MemberShape): RustMetadata =
         when (val container = model.expectShape(memberShape.container)) {
             is StructureShape -> {
                 // TODO(https://github.com/awslabs/smithy-rs/issues/943): Once streaming accessors are usable,
@@ -122,8 +134,17 @@ class BaseSymbolMetadataProvider(
             else -> TODO("Unrecognized container type: $container")
         }
 
-    override fun structureMeta(structureShape: StructureShape) = containerDefaultMetadata(structureShape, model, additionalAttributes)
-    override fun unionMeta(unionShape: UnionShape) = containerDefaultMetadata(unionShape, model, additionalAttributes)
+    override fun structureMeta(structureShape: StructureShape) =
+        containerDefaultMetadata(structureShape, model, additionalAttributes + serdeAttributes(structureShape))
+    override fun unionMeta(unionShape: UnionShape) =
+        containerDefaultMetadata(unionShape, model, additionalAttributes + serdeAttributes(unionShape))
+
+    private fun serdeAttributes(shape: Shape): List<Attribute> =
+        if (config.generateSerde && SerdeCustomization.supportsSerde(shape, model)) {
+            SerdeCustomization.containerAttributes()
+        } else {
+            emptyList()
+        }
 
     override fun enumMeta(stringShape: StringShape): RustMetadata =
         containerDefaultMetadata(stringShape, model, additionalAttributes).withDerives(
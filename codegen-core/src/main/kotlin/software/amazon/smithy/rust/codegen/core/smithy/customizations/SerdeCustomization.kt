@@ -0,0 +1,146 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+package software.amazon.smithy.rust.codegen.core.smithy.customizations
+
+import software.amazon.smithy.model.Model
+import software.amazon.smithy.model.shapes.CollectionShape
+import software.amazon.smithy.model.shapes.MapShape
+import software.amazon.smithy.model.shapes.MemberShape
+import software.amazon.smithy.model.shapes.Shape
+import software.amazon.smithy.model.shapes.StructureShape
+import software.amazon.smithy.model.shapes.UnionShape
+import software.amazon.smithy.model.traits.ErrorTrait
+import software.amazon.smithy.model.traits.SensitiveTrait
+import software.amazon.smithy.rust.codegen.core.rustlang.Attribute
+import software.amazon.smithy.rust.codegen.core.rustlang.Attribute.Companion.cfg
+import software.amazon.smithy.rust.codegen.core.rustlang.Attribute.Companion.cfgAttr
+import software.amazon.smithy.rust.codegen.core.rustlang.Attribute.Companion.serde
+import software.amazon.smithy.rust.codegen.core.rustlang.Feature
+import software.amazon.smithy.rust.codegen.core.rustlang.Writable
+import software.amazon.smithy.rust.codegen.core.rustlang.rustTemplate
+import software.amazon.smithy.rust.codegen.core.rustlang.writable
+import software.amazon.smithy.rust.codegen.core.smithy.RuntimeConfig
+import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType
+import software.amazon.smithy.rust.codegen.core.smithy.RustCrate
+import software.amazon.smithy.rust.codegen.core.smithy.generators.ManifestCustomizations
+import software.amazon.smithy.rust.codegen.core.smithy.protocols.restJsonFieldName
+import software.amazon.smithy.rust.codegen.core.util.dq
+import software.amazon.smithy.rust.codegen.core.util.hasEventStreamMember
+import software.amazon.smithy.rust.codegen.core.util.hasStreamingMember
+import software.amazon.smithy.rust.codegen.core.util.hasTrait
+import software.amazon.smithy.rust.codegen.core.util.isEventStream
+
+/**
+ * Opt-in `serde::Serialize` and `serde::Deserialize` implementations for generated shapes, enabled with the
+ * `generateSerde` codegen setting.
+ *
+ * Everything is gated behind `--cfg aws_sdk_unstable` and the `serde-serialize`/`serde-deserialize` crate features,
+ * mirroring the serde support for `Blob`, `DateTime`, `Document` and `Number` in `aws-smithy-types`.
+ *
+ * - Fields and union variants are named after the `@jsonName` trait if present, and the member name otherwise.
+ * - Enums serialize as their string value, so unknown values round-trip.
+ * - Members targeting `@sensitive` shapes, or collections of them, serialize as a redacted placeholder, just like their
+ *   `Debug` output. Sensitive strings therefore deserialize as the placeholder, and other sensitive shapes fail to.
+ * - Error shapes and shapes with streaming members (blobs or event streams) don't implement serde.
+ */
+object SerdeCustomization {
+    private const val SerializeFeature = "serde-serialize"
+    private const val DeserializeFeature = "serde-deserialize"
+
+    /** Crate features that turn on the serde implementations, along with those of the smithy types. */
+    fun extras(rustCrate: RustCrate) {
+        rustCrate.mergeFeature(Feature(SerializeFeature, false, listOf("aws-smithy-types/$SerializeFeature")))
+        rustCrate.mergeFeature(Feature(DeserializeFeature, false, listOf("aws-smithy-types/$DeserializeFeature")))
+    }
+
+    /** `serde` is only a dependency when compiling with `--cfg aws_sdk_unstable`. */
+    fun manifestCustomizations(): ManifestCustomizations = mapOf(
+        "target" to mapOf(
+            "cfg(aws_sdk_unstable)" to mapOf(
+                "dependencies" to mapOf(
+                    "serde" to mapOf("version" to "1", "features" to listOf("derive")),
+                ),
+            ),
+        ),
+    )
+
+    /** Returns true if serde can be derived for [shape], a structure or union. */
+    fun supportsSerde(shape: Shape, model: Model): Boolean = when (shape) {
+        is StructureShape -> !shape.hasTrait<ErrorTrait>() &&
+            !shape.hasStreamingMember(model) &&
+            !shape.hasEventStreamMember(model)
+        is UnionShape -> !shape.isEventStream()
+        else -> false
+    }
+
+    /** Derives for a structure or union that [supportsSerde]. */
+    fun containerAttributes(): List<Attribute> = listOf(
+        Attribute("").SerdeSerialize(),
+        Attribute("").SerdeDeserialize(),
+    )
+
+    /** Field or variant attributes for [member] of a structure or union that [supportsSerde]. */
+    fun memberAttributes(member: MemberShape, model: Model, runtimeConfig: RuntimeConfig): List<Attribute> {
+        val rename = Attribute(
+            cfgAttr(Attribute.serdeSerializeOrDeserializeCfg(), serde("rename = ${restJsonFieldName(member).dq()}")),
+            isDeriveHelper = true,
+        )
+        if (!isSensitive(member, model)) {
+            return listOf(rename)
+        }
+        val serializeRedacted = RuntimeType.smithyTypes(runtimeConfig).resolve("sensitive::serialize_redacted")
+        return listOf(
+            rename,
+            Attribute(
+                cfgAttr(
+                    Attribute.serdeSerializeCfg(),
+                    serde("serialize_with = ${serializeRedacted.fullyQualifiedName().dq()}"),
+                ),
+                isDeriveHelper = true,
+            ),
+        )
+    }
+
+    private fun isSensitive(member: MemberShape, model: Model): Boolean =
+        member.getMemberTrait(model, SensitiveTrait::class.java).isPresent ||
+            when (val target = model.expectShape(member.target)) {
+                is CollectionShape -> isSensitive(target.member, model)
+                is MapShape -> isSensitive(target.value, model)
+                else -> false
+            }
+
+    /**
+     * Manual serde implementations for an enum named [enumName], which serializes as its `as_str()` value and
+     * deserializes with its `TryFrom<&str>` implementation.
+     */
+    fun enumImpls(enumName: String): Writable = writable {
+        rustTemplate(
+            """
+            ##[#{cfgSerialize}]
+            impl #{serde}::Serialize for $enumName {
+                fn serialize<S: #{serde}::Serializer>(&self, serializer: S) -> #{Result}<S::Ok, S::Error> {
+                    serializer.serialize_str(self.as_str())
+                }
+            }
+
+            ##[#{cfgDeserialize}]
+            impl<'de> #{serde}::Deserialize<'de> for $enumName {
+                fn deserialize<D: #{serde}::Deserializer<'de>>(deserializer: D) -> #{Result}<Self, D::Error> {
+                    let value = <#{String} as #{serde}::Deserialize>::deserialize(deserializer)?;
+                    <Self as #{TryFrom}<&str>>::try_from(value.as_str())
+                        .map_err(|err| <D::Error as #{serde}::de::Error>::custom(format!("{:?}", err)))
+                }
+            }
+            """,
+            "cfgSerialize" to cfg(Attribute.serdeSerializeCfg()),
+            "cfgDeserialize" to cfg(Attribute.serdeDeserializeCfg()),
+            "serde" to RuntimeType.Serde,
+            "Result" to RuntimeType.std.resolve("result::Result"),
+            "String" to RuntimeType.String,
+            "TryFrom" to RuntimeType.TryFrom,
+        )
+    }
+}
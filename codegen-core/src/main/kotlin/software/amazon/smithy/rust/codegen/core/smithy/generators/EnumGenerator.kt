@@ -29,6 +29,7 @@ import software.amazon.smithy.rust.codegen.core.smithy.MaybeRenamed
 import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType
 import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType.Companion.preludeScope
 import software.amazon.smithy.rust.codegen.core.smithy.RustSymbolProvider
+import software.amazon.smithy.rust.codegen.core.smithy.customizations.SerdeCustomization
 import software.amazon.smithy.rust.codegen.core.smithy.expectRustMetadata
 import software.amazon.smithy.rust.codegen.core.smithy.renamedFrom
 import software.amazon.smithy.rust.codegen.core.util.REDACTION
@@ -187,6 +188,9 @@ open class EnumGenerator(
         if (shape.shouldRedact(model)) {
             writer.renderDebugImplForSensitiveEnum()
         }
+        if (symbolProvider.config.generateSerde) {
+            SerdeCustomization.enumImpls(context.enumName)(writer)
+        }
     }
 
     private fun RustWriter.renderNamedEnum() {
@@ -0,0 +1,98 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+package software.amazon.smithy.rust.codegen.core.smithy.customizations
+
+import io.kotest.matchers.string.shouldContain
+import io.kotest.matchers.string.shouldNotContain
+import org.junit.jupiter.api.Test
+import software.amazon.smithy.model.shapes.ServiceShape
+import software.amazon.smithy.model.shapes.Shape
+import software.amazon.smithy.rust.codegen.core.rustlang.RustWriter
+import software.amazon.smithy.rust.codegen.core.smithy.BaseSymbolMetadataProvider
+import software.amazon.smithy.rust.codegen.core.smithy.SymbolVisitor
+import software.amazon.smithy.rust.codegen.core.smithy.expectRustMetadata
+import software.amazon.smithy.rust.codegen.core.testutil.TestRustSymbolProviderConfig
+import software.amazon.smithy.rust.codegen.core.testutil.asSmithyModel
+import software.amazon.smithy.rust.codegen.core.testutil.testRustSettings
+import software.amazon.smithy.rust.codegen.core.util.lookup
+
+class SerdeCustomizationTest {
+    private val model = """
+        namespace test
+
+        structure Person {
+            @jsonName("FullName")
+            name: String,
+            secret: Secret,
+            pastSecrets: Secrets,
+        }
+
+        list Secrets {
+            member: Secret,
+        }
+
+        @sensitive
+        string Secret
+
+        union Shape {
+            circle: Integer,
+        }
+
+        @error("client")
+        structure Oops {
+            message: String,
+        }
+
+        @streaming
+        blob Body
+
+        structure Download {
+            body: Body,
+        }
+    """.asSmithyModel()
+
+    private fun render(generateSerde: Boolean, shapeId: String): String {
+        val symbolProvider = SymbolVisitor(
+            testRustSettings(),
+            model,
+            ServiceShape.builder().version("test").id("test#Service").build(),
+            TestRustSymbolProviderConfig.copy(generateSerde = generateSerde),
+        ).let { BaseSymbolMetadataProvider(it, additionalAttributes = listOf()) }
+        val writer = RustWriter.forModule("model")
+        symbolProvider.toSymbol(model.lookup<Shape>(shapeId)).expectRustMetadata().render(writer)
+        return writer.toString()
+    }
+
+    @Test
+    fun `structures and unions derive serde when enabled`() {
+        render(generateSerde = true, "test#Person") shouldContain "serde::Serialize"
+        render(generateSerde = true, "test#Person") shouldContain "serde::Deserialize"
+        render(generateSerde = true, "test#Shape") shouldContain "serde::Serialize"
+        render(generateSerde = false, "test#Person") shouldNotContain "serde"
+    }
+
+    @Test
+    fun `members are renamed after their json name`() {
+        render(generateSerde = true, "test#Person\$name") shouldContain "serde(rename = \"FullName\")"
+        render(generateSerde = true, "test#Shape\$circle") shouldContain "serde(rename = \"circle\")"
+    }
+
+    @Test
+    fun `sensitive members are redacted`() {
+        val redacted = "serde(serialize_with = \"::aws_smithy_types::sensitive::serialize_redacted\")"
+        render(generateSerde = true, "test#Person\$secret") shouldContain "serde(rename = \"secret\")"
+        render(generateSerde = true, "test#Person\$secret") shouldContain redacted
+        render(generateSerde = true, "test#Person\$pastSecrets") shouldContain redacted
+        render(generateSerde = true, "test#Person\$name") shouldNotContain "serialize_with"
+    }
+
+    @Test
+    fun `errors and streaming shapes do not derive serde`() {
+        render(generateSerde = true, "test#Oops") shouldNotContain "serde"
+        render(generateSerde = true, "test#Oops\$message") shouldNotContain "serde"
+        render(generateSerde = true, "test#Download") shouldNotContain "serde"
+    }
+}
@@ -20,6 +20,7 @@ import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType
 import software.amazon.smithy.rust.codegen.core.smithy.RustSymbolProvider
 import software.amazon.smithy.rust.codegen.core.smithy.SymbolMetadataProvider
 import software.amazon.smithy.rust.codegen.core.smithy.containerDefaultMetadata
+import software.amazon.smithy.rust.codegen.core.smithy.customizations.SerdeCustomization
 import software.amazon.smithy.rust.codegen.core.smithy.expectRustMetadata
 
 /**
@@ -53,6 +54,13 @@ class ConstrainedShapeSymbolMetadataProvider(
         // The wrapped type may not _derive_ `Debug` though, hence why this line is required; see https://github.com/awslabs/smithy-rs/issues/2582.
         derives += RuntimeType.Debug
 
+        // Public constrained newtypes can appear in the fields of generated structures and unions, so they need to
+        // implement `serde` traits as well. Their generators make `Deserialize` go through `TryFrom`, see
+        // `renderSerdeTryFrom`.
+        if (config.generateSerde && constrainedTypes && shape.isDirectlyConstrained(base)) {
+            additionalAttributes += SerdeCustomization.containerAttributes()
+        }
+
         val visibility = Visibility.publicIf(constrainedTypes, Visibility.PUBCRATE)
         return RustMetadata(derives, additionalAttributes, visibility)
     }
@@ -115,6 +115,7 @@ open class ServerCodegenVisitor(
             renameExceptions = false,
             nullabilityCheckMode = NullableIndex.CheckMode.SERVER,
             moduleProvider = ServerModuleProvider,
+            generateSerde = settings.codegenConfig.generateSerde,
         )
 
         val baseModel = baselineTransform(context.model)
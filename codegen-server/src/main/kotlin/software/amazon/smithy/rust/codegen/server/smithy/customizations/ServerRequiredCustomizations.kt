@@ -9,9 +9,11 @@ import software.amazon.smithy.rust.codegen.core.rustlang.Feature
 import software.amazon.smithy.rust.codegen.core.smithy.RustCrate
 import software.amazon.smithy.rust.codegen.core.smithy.customizations.AllowLintsCustomization
 import software.amazon.smithy.rust.codegen.core.smithy.customizations.CrateVersionCustomization
+import software.amazon.smithy.rust.codegen.core.smithy.customizations.SerdeCustomization
 import software.amazon.smithy.rust.codegen.core.smithy.customizations.pubUseSmithyErrorTypes
 import software.amazon.smithy.rust.codegen.core.smithy.customizations.pubUseSmithyPrimitives
 import software.amazon.smithy.rust.codegen.core.smithy.generators.LibRsCustomization
+import software.amazon.smithy.rust.codegen.core.smithy.generators.ManifestCustomizations
 import software.amazon.smithy.rust.codegen.server.smithy.ServerCodegenContext
 import software.amazon.smithy.rust.codegen.server.smithy.ServerRustModule
 import software.amazon.smithy.rust.codegen.server.smithy.customize.ServerCodegenDecorator
@@ -45,5 +47,16 @@ class ServerRequiredCustomizations : ServerCodegenDecorator {
         rustCrate.withModule(ServerRustModule.root) {
             CrateVersionCustomization.extras(rustCrate, ServerRustModule.root)
         }
+
+        if (codegenContext.settings.codegenConfig.generateSerde) {
+            SerdeCustomization.extras(rustCrate)
+        }
     }
+
+    override fun crateManifestCustomizations(codegenContext: ServerCodegenContext): ManifestCustomizations =
+        if (codegenContext.settings.codegenConfig.generateSerde) {
+            SerdeCustomization.manifestCustomizations()
+        } else {
+            emptyMap()
+        }
 }
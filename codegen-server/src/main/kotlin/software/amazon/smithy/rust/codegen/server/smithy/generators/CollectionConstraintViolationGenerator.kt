@@ -29,6 +29,7 @@ class CollectionConstraintViolationGenerator(
     private val model = codegenContext.model
     private val symbolProvider = codegenContext.symbolProvider
     private val publicConstrainedTypes = codegenContext.settings.codegenConfig.publicConstrainedTypes
+    private val implementsSerde = codegenContext.constrainedTypesImplementSerde()
     private val constraintViolationSymbolProvider =
         with(codegenContext.constraintViolationSymbolProvider) {
             if (publicConstrainedTypes) {
@@ -83,6 +84,9 @@ class CollectionConstraintViolationGenerator(
                 """,
                 "ConstraintViolationVariants" to constraintViolationVariants.join(",\n"),
             )
+            if (implementsSerde) {
+                renderConstraintViolationSerdeDisplay(constraintViolationName)
+            }
 
             if (shape.isReachableFromOperationInput()) {
                 rustTemplate(
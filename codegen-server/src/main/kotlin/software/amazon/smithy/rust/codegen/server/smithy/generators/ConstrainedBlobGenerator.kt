@@ -62,6 +62,9 @@ class ConstrainedBlobGenerator(
         writer.docs(rustDocsConstrainedTypeEpilogue(name))
         val metadata = symbol.expectRustMetadata()
         metadata.render(writer)
+        if (codegenContext.constrainedTypesImplementSerde()) {
+            writer.renderSerdeTryFrom(inner)
+        }
         writer.rust("struct $name(pub(crate) $inner);")
         writer.rustBlock("impl $name") {
             if (metadata.visibility == Visibility.PUBLIC) {
@@ -126,6 +129,9 @@ class ConstrainedBlobGenerator(
             """,
             "Variants" to constraintsInfo.map { it.constraintViolationVariant }.join(",\n"),
         )
+        if (codegenContext.constrainedTypesImplementSerde()) {
+            writer.renderConstraintViolationSerdeDisplay(constraintViolation.name)
+        }
 
         if (shape.isReachableFromOperationInput()) {
             writer.rustTemplate(
@@ -87,6 +87,9 @@ class ConstrainedCollectionGenerator(
         writer.docs(rustDocsConstrainedTypeEpilogue(name))
         val metadata = constrainedSymbol.expectRustMetadata()
         metadata.render(writer)
+        if (codegenContext.constrainedTypesImplementSerde()) {
+            writer.renderSerdeTryFrom(inner, *codegenScope)
+        }
         writer.rustTemplate(
             """
             struct $name(pub(crate) $inner);
@@ -77,6 +77,9 @@ class ConstrainedMapGenerator(
         writer.docs(rustDocsConstrainedTypeEpilogue(name))
         val metadata = constrainedSymbol.expectRustMetadata()
         metadata.render(writer)
+        if (codegenContext.constrainedTypesImplementSerde()) {
+            writer.renderSerdeTryFrom(inner, *codegenScope)
+        }
         writer.rustTemplate("struct $name(pub(crate) $inner);", *codegenScope)
         writer.rustBlockTemplate("impl $name", *codegenScope) {
             if (metadata.visibility == Visibility.PUBLIC) {
@@ -81,6 +81,9 @@ class ConstrainedNumberGenerator(
         writer.docs(rustDocsConstrainedTypeEpilogue(name))
         val metadata = symbol.expectRustMetadata()
         metadata.render(writer)
+        if (codegenContext.constrainedTypesImplementSerde()) {
+            writer.renderSerdeTryFrom(unconstrainedTypeName)
+        }
         writer.rust("struct $name(pub(crate) $unconstrainedTypeName);")
 
         if (metadata.visibility == Visibility.PUBCRATE) {
@@ -142,6 +145,9 @@ class ConstrainedNumberGenerator(
                 }
                 """,
             )
+            if (codegenContext.constrainedTypesImplementSerde()) {
+                renderConstraintViolationSerdeDisplay(constraintViolation.name)
+            }
 
             if (shape.isReachableFromOperationInput()) {
                 rustTemplate(
@@ -5,6 +5,17 @@
 
 package software.amazon.smithy.rust.codegen.server.smithy.generators
 
+import software.amazon.smithy.rust.codegen.core.rustlang.Attribute
+import software.amazon.smithy.rust.codegen.core.rustlang.Attribute.Companion.cfg
+import software.amazon.smithy.rust.codegen.core.rustlang.Attribute.Companion.cfgAttr
+import software.amazon.smithy.rust.codegen.core.rustlang.RustWriter
+import software.amazon.smithy.rust.codegen.core.rustlang.rustInlineTemplate
+import software.amazon.smithy.rust.codegen.core.rustlang.rustTemplate
+import software.amazon.smithy.rust.codegen.core.rustlang.writable
+import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType
+import software.amazon.smithy.rust.codegen.core.util.dq
+import software.amazon.smithy.rust.codegen.server.smithy.ServerCodegenContext
+
 /**
  * Functions shared amongst the constrained shape generators, to keep them DRY and consistent.
  */
@@ -24,3 +35,45 @@ fun rustDocsInnerMethod(inner: String) =
 
 fun rustDocsIntoInnerMethod(inner: String) =
     "Consumes the value, returning the underlying [`$inner`]."
+
+/**
+ * Whether constrained newtypes implement `serde` traits; see `ConstrainedShapeSymbolMetadataProvider`.
+ */
+fun ServerCodegenContext.constrainedTypesImplementSerde() =
+    settings.codegenConfig.generateSerde && settings.codegenConfig.publicConstrainedTypes
+
+/**
+ * Renders the attribute that makes a constrained newtype deserialize through its `TryFrom<inner>` implementation, so
+ * that deserialized values satisfy the modeled constraints. Must be rendered after the newtype's metadata.
+ */
+fun RustWriter.renderSerdeTryFrom(inner: String, vararg args: Pair<String, Any>) {
+    Attribute(
+        cfgAttr(
+            Attribute.serdeDeserializeCfg(),
+            writable { rustInlineTemplate("serde(try_from = ${inner.dq()})", *args) },
+        ),
+        isDeriveHelper = true,
+    ).render(this)
+}
+
+/**
+ * `serde`'s `try_from` requires the error to implement `Display`; constraint violations display as their `Debug`
+ * representation.
+ */
+fun RustWriter.renderConstraintViolationSerdeDisplay(constraintViolationName: String) {
+    rustTemplate(
+        """
+        ##[#{cfg}]
+        impl #{Display} for $constraintViolationName {
+            fn fmt(&self, f: &mut #{Formatter}<'_>) -> #{FmtResult} {
+                #{Debug}::fmt(self, f)
+            }
+        }
+        """,
+        "cfg" to cfg(Attribute.serdeDeserializeCfg()),
+        "Display" to RuntimeType.Display,
+        "Debug" to RuntimeType.Debug,
+        "Formatter" to RuntimeType.std.resolve("fmt::Formatter"),
+        "FmtResult" to RuntimeType.std.resolve("fmt::Result"),
+    )
+}
@@ -82,6 +82,9 @@ class ConstrainedStringGenerator(
         writer.docs(rustDocsConstrainedTypeEpilogue(name))
         val metadata = symbol.expectRustMetadata()
         metadata.render(writer)
+        if (codegenContext.constrainedTypesImplementSerde()) {
+            writer.renderSerdeTryFrom(inner)
+        }
         writer.rust("struct $name(pub(crate) $inner);")
         if (metadata.visibility == Visibility.PUBCRATE) {
             Attribute.AllowDeadCode.render(writer)
@@ -157,6 +160,9 @@ class ConstrainedStringGenerator(
             """,
             "Variants" to constraintsInfo.map { it.constraintViolationVariant }.join(",\n"),
         )
+        if (codegenContext.constrainedTypesImplementSerde()) {
+            writer.renderConstraintViolationSerdeDisplay(constraintViolation.name)
+        }
 
         if (shape.isReachableFromOperationInput()) {
             writer.rustTemplate(
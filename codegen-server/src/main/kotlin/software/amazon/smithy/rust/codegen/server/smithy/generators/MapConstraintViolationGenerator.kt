@@ -29,6 +29,7 @@ class MapConstraintViolationGenerator(
     private val constrainedShapeSymbolProvider = codegenContext.constrainedShapeSymbolProvider
     private val symbolProvider = codegenContext.symbolProvider
     private val publicConstrainedTypes = codegenContext.settings.codegenConfig.publicConstrainedTypes
+    private val implementsSerde = codegenContext.constrainedTypesImplementSerde()
     private val constraintViolationSymbolProvider =
         with(codegenContext.constraintViolationSymbolProvider) {
             if (publicConstrainedTypes) {
@@ -86,6 +87,9 @@ class MapConstraintViolationGenerator(
                 """,
                 *constraintViolationCodegenScope,
             )
+            if (implementsSerde) {
+                renderConstraintViolationSerdeDisplay(constraintViolationName)
+            }
 
             if (shape.isReachableFromOperationInput()) {
                 rustTemplate(
@@ -5,7 +5,12 @@
 
 package software.amazon.smithy.rust.codegen.server.smithy.generators
 
+import software.amazon.smithy.model.knowledge.ServiceIndex
 import software.amazon.smithy.model.shapes.OperationShape
+import software.amazon.smithy.model.traits.HttpApiKeyAuthTrait
+import software.amazon.smithy.model.traits.HttpBasicAuthTrait
+import software.amazon.smithy.model.traits.HttpBearerAuthTrait
+import software.amazon.smithy.model.traits.OptionalAuthTrait
 import software.amazon.smithy.rust.codegen.core.rustlang.RustWriter
 import software.amazon.smithy.rust.codegen.core.rustlang.Writable
 import software.amazon.smithy.rust.codegen.core.rustlang.documentShape
@@ -14,6 +19,9 @@ import software.amazon.smithy.rust.codegen.core.rustlang.rustTemplate
 import software.amazon.smithy.rust.codegen.core.rustlang.writable
 import software.amazon.smithy.rust.codegen.core.smithy.CodegenContext
 import software.amazon.smithy.rust.codegen.core.util.dq
+import software.amazon.smithy.rust.codegen.core.util.hasStreamingMember
+import software.amazon.smithy.rust.codegen.core.util.hasTrait
+import software.amazon.smithy.rust.codegen.core.util.inputShape
 import software.amazon.smithy.rust.codegen.core.util.toPascalCase
 import software.amazon.smithy.rust.codegen.server.smithy.ServerCargoDependency
 
@@ -29,6 +37,7 @@ class ServerOperationGenerator(
         )
     private val symbolProvider = codegenContext.symbolProvider
     private val model = codegenContext.model
+    private val serviceShape = codegenContext.serviceShape
 
     private val operationName = symbolProvider.toSymbol(operation).name.toPascalCase()
     private val operationId = operation.id
@@ -43,6 +52,40 @@ class ServerOperationGenerator(
         }
     }
 
+    /** Returns the operation's effective auth schemes, in priority order, as `AuthScheme`s. */
+    private fun authSchemes(): Writable = writable {
+        val authSchemes = ServiceIndex.of(model).getEffectiveAuthSchemes(serviceShape, operation)
+        rust("vec![")
+        for ((schemeId, trait) in authSchemes) {
+            when (trait) {
+                is HttpBasicAuthTrait -> rustTemplate("#{SmithyHttpServer}::plugin::auth::AuthScheme::HttpBasic,", *codegenScope)
+                is HttpBearerAuthTrait -> rustTemplate("#{SmithyHttpServer}::plugin::auth::AuthScheme::HttpBearer,", *codegenScope)
+                is HttpApiKeyAuthTrait -> {
+                    val definition = when (trait.`in`) {
+                        HttpApiKeyAuthTrait.Location.HEADER -> {
+                            val scheme = trait.scheme.map { "Some(${it.dq()}.to_owned())" }.orElse("None")
+                            "header(${trait.name.dq()}, $scheme)"
+                        }
+                        HttpApiKeyAuthTrait.Location.QUERY -> "query(${trait.name.dq()})"
+                        null -> throw IllegalStateException("`@httpApiKeyAuth` must have an `in` property")
+                    }
+                    rustTemplate(
+                        "#{SmithyHttpServer}::plugin::auth::AuthScheme::HttpApiKey(#{SmithyHttpServer}::plugin::auth::HttpAuthDefinition::$definition),",
+                        *codegenScope,
+                    )
+                }
+                else -> {
+                    val schemeIdAbsolute = schemeId.toString().replace("#", "##")
+                    rustTemplate(
+                        "#{SmithyHttpServer}::plugin::auth::AuthScheme::Other(#{SmithyHttpServer}::shape_id::ShapeId::new(${schemeIdAbsolute.dq()}, ${schemeId.namespace.dq()}, ${schemeId.name.dq()})),",
+                        *codegenScope,
+                    )
+                }
+            }
+        }
+        rust("]")
+    }
+
     fun render(writer: RustWriter) {
         writer.documentShape(operation, model)
 
@@ -63,6 +106,18 @@ class ServerOperationGenerator(
                 type Error = #{Error:W};
             }
 
+            impl #{SmithyHttpServer}::plugin::auth::OperationAuth for $operationName {
+                const OPTIONAL_AUTH: bool = ${operation.hasTrait<OptionalAuthTrait>()};
+
+                fn auth_schemes() -> Vec<#{SmithyHttpServer}::plugin::auth::AuthScheme> {
+                    #{AuthSchemes:W}
+                }
+            }
+
+            impl #{SmithyHttpServer}::plugin::body_limit::OperationBodyShape for $operationName {
+                const STREAMING_INPUT: bool = ${operation.inputShape(model).hasStreamingMember(model)};
+            }
+
             impl #{SmithyHttpServer}::instrumentation::sensitivity::Sensitivity for $operationName {
                 type RequestFmt = #{RequestType:W};
                 type ResponseFmt = #{ResponseType:W};
@@ -77,6 +132,7 @@ class ServerOperationGenerator(
             }
             """,
             "Error" to operationError(),
+            "AuthSchemes" to authSchemes(),
             "RequestValue" to requestFmt.value,
             "RequestType" to requestFmt.type,
             "ResponseValue" to responseFmt.value,
@@ -8,7 +8,12 @@ package software.amazon.smithy.rust.codegen.server.smithy.generators
 import software.amazon.smithy.model.knowledge.TopDownIndex
 import software.amazon.smithy.model.neighbor.Walker
 import software.amazon.smithy.model.shapes.OperationShape
+import software.amazon.smithy.model.shapes.ShapeId
 import software.amazon.smithy.model.shapes.StringShape
+import software.amazon.smithy.model.shapes.StructureShape
+import software.amazon.smithy.model.traits.CorsTrait
+import software.amazon.smithy.model.traits.HttpHeaderTrait
+import software.amazon.smithy.model.traits.HttpPrefixHeadersTrait
 import software.amazon.smithy.model.traits.PatternTrait
 import software.amazon.smithy.rust.codegen.core.rustlang.RustReservedWords
 import software.amazon.smithy.rust.codegen.core.rustlang.RustWriter
@@ -19,12 +24,15 @@ import software.amazon.smithy.rust.codegen.core.rustlang.rust
 import software.amazon.smithy.rust.codegen.core.rustlang.rustTemplate
 import software.amazon.smithy.rust.codegen.core.rustlang.writable
 import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType
+import software.amazon.smithy.rust.codegen.core.util.dq
+import software.amazon.smithy.rust.codegen.core.util.getTrait
 import software.amazon.smithy.rust.codegen.core.util.hasTrait
 import software.amazon.smithy.rust.codegen.core.util.letIf
 import software.amazon.smithy.rust.codegen.core.util.toPascalCase
 import software.amazon.smithy.rust.codegen.core.util.toSnakeCase
 import software.amazon.smithy.rust.codegen.server.smithy.ServerCargoDependency
 import software.amazon.smithy.rust.codegen.server.smithy.ServerCodegenContext
+import software.amazon.smithy.rust.codegen.server.smithy.generators.protocol.ServerAwsJsonProtocol
 import software.amazon.smithy.rust.codegen.server.smithy.generators.protocol.ServerProtocol
 import software.amazon.smithy.rust.codegen.server.smithy.ServerRustModule.Error as ErrorModule
 import software.amazon.smithy.rust.codegen.server.smithy.ServerRustModule.Input as InputModule
@@ -444,6 +452,56 @@ class ServerServiceGenerator(
         )
     }
 
+    /**
+     * Returns a `Writable` building the service's `CorsConfig` from its `@cors` trait, or the trait's defaults if it
+     * doesn't have one.
+     *
+     * Besides the trait's additional headers, requests may set `Authorization`, `Content-Type` and the headers bound
+     * to operation inputs (including those starting with an `@httpPrefixHeaders` prefix), and scripts may read the
+     * headers bound to operation outputs and errors.
+     */
+    private fun corsConfig(): Writable = writable {
+        val cors = service.getTrait<CorsTrait>() ?: CorsTrait.builder().build()
+        fun boundHeaders(shapeIds: List<ShapeId>) = shapeIds
+            .flatMap { model.expectShape(it, StructureShape::class.java).members() }
+            .mapNotNull { it.getTrait<HttpHeaderTrait>()?.value }
+
+        val allowedHeaderPrefixes = operations
+            .flatMap { model.expectShape(it.inputShape, StructureShape::class.java).members() }
+            .mapNotNull { it.getTrait<HttpPrefixHeadersTrait>()?.value?.lowercase() }
+            .toSortedSet()
+
+        val protocolHeaders = listOf("authorization", "content-type") +
+            if (protocol is ServerAwsJsonProtocol) listOf("x-amz-target") else listOf()
+        val allowedHeaders = (
+            protocolHeaders +
+                boundHeaders(operations.map { it.inputShape }) +
+                cors.additionalAllowedHeaders
+            ).map { it.lowercase() }.toSortedSet()
+        val exposedHeaders = (
+            boundHeaders(operations.flatMap { listOf(it.outputShape) + it.getErrors(service) }) +
+                cors.additionalExposedHeaders
+            ).map { it.lowercase() }.toSortedSet()
+
+        rustTemplate(
+            """
+            #{SmithyHttpServer}::routing::cors::CorsConfig::new()
+                .allow_origin(#{Http}::HeaderValue::from_static(${cors.origin.dq()}))
+                .max_age(std::time::Duration::from_secs(${cors.maxAge}))
+            """,
+            *codegenScope,
+        )
+        for (header in allowedHeaders) {
+            rustTemplate(".allow_header(#{Http}::HeaderName::from_static(${header.dq()}))", *codegenScope)
+        }
+        for (prefix in allowedHeaderPrefixes) {
+            rust(".allow_header_prefix(${prefix.dq()})")
+        }
+        for (header in exposedHeaders) {
+            rustTemplate(".expose_header(#{Http}::HeaderName::from_static(${header.dq()}))", *codegenScope)
+        }
+    }
+
     /** Returns a `Writable` comma delimited sequence of `builder_field: None`. */
     private val notSetFields = builderFieldNames.values.map {
         writable {
@@ -489,6 +547,12 @@ class ServerServiceGenerator(
                 pub fn builder_without_plugins<Body>() -> $builderName<Body, #{SmithyHttpServer}::plugin::IdentityPlugin, #{SmithyHttpServer}::plugin::IdentityPlugin> {
                     Self::builder_with_plugins(#{SmithyHttpServer}::plugin::IdentityPlugin, #{SmithyHttpServer}::plugin::IdentityPlugin)
                 }
+
+                /// Returns the [`CorsConfig`](#{SmithyHttpServer}::routing::cors::CorsConfig) described by the service's
+                /// `@cors` trait and HTTP bindings, to be passed to [`$serviceName::cors`].
+                pub fn cors_config() -> #{SmithyHttpServer}::routing::cors::CorsConfig {
+                    #{CorsConfig:W}
+                }
             }
 
             impl<S> $serviceName<S> {
@@ -513,6 +577,17 @@ class ServerServiceGenerator(
                     }
                 }
 
+                /// Enables [CORS](#{SmithyHttpServer}::routing::cors) handling: preflight requests for URIs handled by
+                /// one of the service's operations are answered directly, and CORS headers are added to the responses
+                /// of requests from allowed origins.
+                ///
+                /// Use [`$serviceName::cors_config`] to apply the configuration described by the model.
+                pub fn cors(self, config: #{SmithyHttpServer}::routing::cors::CorsConfig) -> Self {
+                    $serviceName {
+                        router: self.router.cors(config)
+                    }
+                }
+
                 /// Applies [`Route::new`](#{SmithyHttpServer}::routing::Route::new) to all routes.
                 ///
                 /// This has the effect of erasing all types accumulated via [`layer`]($serviceName::layer).
@@ -549,6 +624,7 @@ class ServerServiceGenerator(
             }
             """,
             "NotSetFields" to notSetFields.join(", "),
+            "CorsConfig" to corsConfig(),
             "Router" to protocol.routerType(),
             "Protocol" to protocol.markerStruct(),
             *codegenScope,
@@ -0,0 +1,113 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+package software.amazon.smithy.rust.codegen.server.smithy.customizations
+
+import org.junit.jupiter.api.Test
+import software.amazon.smithy.model.node.Node
+import software.amazon.smithy.rust.codegen.core.rustlang.CargoDependency
+import software.amazon.smithy.rust.codegen.core.rustlang.rustTemplate
+import software.amazon.smithy.rust.codegen.core.testutil.IntegrationTestParams
+import software.amazon.smithy.rust.codegen.core.testutil.asSmithyModel
+import software.amazon.smithy.rust.codegen.core.testutil.integrationTest
+import software.amazon.smithy.rust.codegen.core.util.runCommand
+import software.amazon.smithy.rust.codegen.server.smithy.testutil.serverIntegrationTest
+
+internal class SerdeCustomizationTest {
+    private val model = """
+        namespace test
+
+        use aws.protocols#restJson1
+        use smithy.framework#ValidationException
+
+        @restJson1
+        service TestService {
+            version: "2023-01-01",
+            operations: [PutPerson]
+        }
+
+        @http(uri: "/person", method: "PUT")
+        operation PutPerson {
+            input: PutPersonInput,
+            output: PutPersonOutput,
+            errors: [ValidationException],
+        }
+
+        structure PutPersonInput {
+            person: Person,
+        }
+
+        structure PutPersonOutput {}
+
+        structure Person {
+            name: Name,
+            age: Age,
+            nicknames: Nicknames,
+            picture: Picture,
+        }
+
+        @length(min: 2)
+        string Name
+
+        @range(max: 150)
+        integer Age
+
+        @length(max: 2)
+        list Nicknames {
+            member: Name,
+        }
+
+        @length(max: 8)
+        blob Picture
+    """.asSmithyModel()
+
+    @Test
+    fun `constrained shapes are validated when deserializing`() {
+        serverIntegrationTest(
+            model,
+            IntegrationTestParams(
+                additionalSettings = Node.objectNodeBuilder().withMember(
+                    "codegen",
+                    Node.objectNodeBuilder().withMember("generateSerde", true).build(),
+                ).build(),
+                command = {
+                    "cargo test --all-features".runCommand(it, mapOf("RUSTFLAGS" to "--cfg aws_sdk_unstable"))
+                },
+            ),
+        ) { codegenContext, rustCrate ->
+            val moduleName = codegenContext.moduleUseName()
+            rustCrate.integrationTest("serde_constraints") {
+                rustTemplate(
+                    """
+                    use $moduleName::model::Person;
+
+                    ##[test]
+                    fn valid_values_round_trip() {
+                        let json = r##"{"name":"Jane","age":30,"nicknames":["JJ"]}"##;
+                        let person: Person = #{serde_json}::from_str(json).unwrap();
+                        assert_eq!("Jane", person.name().unwrap().as_str());
+                        let reserialized = #{serde_json}::to_string(&person).unwrap();
+                        assert_eq!(person, #{serde_json}::from_str::<Person>(&reserialized).unwrap());
+                    }
+
+                    ##[test]
+                    fn invalid_values_are_rejected() {
+                        for json in [
+                            r##"{"name":"J"}"##,
+                            r##"{"age":151}"##,
+                            r##"{"nicknames":["JJ","JD","JE"]}"##,
+                            r##"{"nicknames":["J"]}"##,
+                        ] {
+                            let err = #{serde_json}::from_str::<Person>(json).expect_err(json);
+                            assert!(err.to_string().contains("Length") || err.to_string().contains("Range"), "{}", err);
+                        }
+                    }
+                    """,
+                    "serde_json" to CargoDependency.SerdeJson.toType(),
+                )
+            }
+        }
+    }
+}
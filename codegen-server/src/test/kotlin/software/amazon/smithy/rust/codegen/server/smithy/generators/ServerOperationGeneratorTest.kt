@@ -0,0 +1,91 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+package software.amazon.smithy.rust.codegen.server.smithy.generators
+
+import org.junit.jupiter.api.Test
+import software.amazon.smithy.rust.codegen.core.rustlang.rustTemplate
+import software.amazon.smithy.rust.codegen.core.testutil.asSmithyModel
+import software.amazon.smithy.rust.codegen.core.testutil.integrationTest
+import software.amazon.smithy.rust.codegen.server.smithy.ServerCargoDependency
+import software.amazon.smithy.rust.codegen.server.smithy.testutil.serverIntegrationTest
+
+class ServerOperationGeneratorTest {
+    private val model = """
+        namespace test
+
+        use aws.auth#sigv4
+        use aws.protocols#restJson1
+
+        @restJson1
+        @httpBasicAuth
+        @httpApiKeyAuth(name: "X-Api-Key", in: "header")
+        @sigv4(name: "test")
+        @auth([httpApiKeyAuth, httpBasicAuth, sigv4])
+        service TestService {
+            version: "2023-01-01",
+            operations: [Inherited, SigV4Only, Optional, Anonymous]
+        }
+
+        @http(uri: "/inherited", method: "GET")
+        operation Inherited {}
+
+        @http(uri: "/sigv4-only", method: "GET")
+        @auth([sigv4])
+        operation SigV4Only {}
+
+        @http(uri: "/optional", method: "GET")
+        @auth([httpBasicAuth])
+        @optionalAuth
+        operation Optional {}
+
+        @http(uri: "/anonymous", method: "GET")
+        @auth([])
+        operation Anonymous {}
+    """.asSmithyModel()
+
+    @Test
+    fun `generates the effective auth schemes of operations`() {
+        serverIntegrationTest(model) { codegenContext, rustCrate ->
+            val moduleName = codegenContext.moduleUseName()
+            rustCrate.integrationTest("operation_auth") {
+                rustTemplate(
+                    """
+                    use #{SmithyHttpServer}::plugin::auth::{AuthScheme, HttpAuthDefinition, OperationAuth};
+                    use #{SmithyHttpServer}::shape_id::ShapeId;
+                    use $moduleName::operation_shape::{Anonymous, Inherited, Optional, SigV4Only};
+
+                    fn sigv4() -> AuthScheme {
+                        AuthScheme::Other(ShapeId::new("aws.auth##sigv4", "aws.auth", "sigv4"))
+                    }
+
+                    ##[test]
+                    fn operations_inherit_the_service_schemes_in_priority_order() {
+                        assert!(!Inherited::OPTIONAL_AUTH);
+                        assert_eq!(
+                            vec![
+                                AuthScheme::HttpApiKey(HttpAuthDefinition::header("X-Api-Key", None)),
+                                AuthScheme::HttpBasic,
+                                sigv4(),
+                            ],
+                            Inherited::auth_schemes()
+                        );
+                    }
+
+                    ##[test]
+                    fn operations_can_override_the_service_schemes() {
+                        assert_eq!(vec![sigv4()], SigV4Only::auth_schemes());
+                        assert!(Optional::OPTIONAL_AUTH);
+                        assert_eq!(vec![AuthScheme::HttpBasic], Optional::auth_schemes());
+                        assert!(!Anonymous::OPTIONAL_AUTH);
+                        assert!(Anonymous::auth_schemes().is_empty());
+                    }
+                    """,
+                    "SmithyHttpServer" to ServerCargoDependency.smithyHttpServer(codegenContext.runtimeConfig).toType(),
+                )
+            }
+        }
+    }
+}
@@ -8,5 +8,6 @@
 pub mod fn_stream;
 pub mod never;
 pub mod now_or_later;
+pub mod pagination;
 pub mod rendezvous;
 pub mod timeout;
@@ -0,0 +1,297 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Utilities for consuming paginated results
+//!
+//! Generated paginators produce a [`Stream`] of pages. The utilities in this module make it
+//! possible to:
+//! - fetch the next pages while the caller is still processing the current one ([`prefetch`])
+//! - stop reading after a certain number of pages or items ([`try_collect`])
+//! - save the position of a paginator and resume it later, for example after a process restart
+//!   ([`PaginationCheckpoint`])
+
+use futures_util::StreamExt;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+use tokio_stream::Stream;
+
+const COMPLETE: &str = "complete";
+const NEXT_TOKEN_PREFIX: &str = "next:";
+
+/// The position of a paginator, which can be saved and used to resume pagination later
+///
+/// A checkpoint is taken from the last page that was fully processed. Resuming from it continues
+/// with the page after that one. A checkpoint taken from the last page is _complete_ and resuming
+/// from it produces no further pages.
+///
+/// Checkpoints serialize to a string with [`Display`](fmt::Display) and are read back with
+/// [`FromStr`]. The format is stable, so it is safe to persist checkpoints across SDK upgrades.
+///
+/// # Examples
+/// ```
+/// use aws_smithy_async::future::pagination::PaginationCheckpoint;
+///
+/// let checkpoint = PaginationCheckpoint::from_next_token(Some("token-for-page-3"));
+/// let saved = checkpoint.to_string();
+///
+/// let restored: PaginationCheckpoint = saved.parse().expect("valid checkpoint");
+/// assert_eq!(Some("token-for-page-3"), restored.next_token());
+/// assert!(!restored.is_complete());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PaginationCheckpoint {
+    next_token: Option<String>,
+}
+
+impl PaginationCheckpoint {
+    /// Creates a checkpoint from the next token returned by a service
+    ///
+    /// A missing or empty token means that there are no more pages, so the checkpoint is complete.
+    pub fn from_next_token(next_token: Option<&str>) -> Self {
+        Self {
+            next_token: next_token
+                .filter(|token| !token.is_empty())
+                .map(ToOwned::to_owned),
+        }
+    }
+
+    /// Creates a checkpoint for a paginator that has no more pages
+    pub fn complete() -> Self {
+        Self { next_token: None }
+    }
+
+    /// The token used to request the next page, or `None` if pagination is complete
+    pub fn next_token(&self) -> Option<&str> {
+        self.next_token.as_deref()
+    }
+
+    /// Returns true if there are no more pages after this checkpoint
+    pub fn is_complete(&self) -> bool {
+        self.next_token.is_none()
+    }
+}
+
+impl fmt::Display for PaginationCheckpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.next_token {
+            Some(token) => write!(f, "{}{}", NEXT_TOKEN_PREFIX, token),
+            None => f.write_str(COMPLETE),
+        }
+    }
+}
+
+impl FromStr for PaginationCheckpoint {
+    type Err = InvalidCheckpoint;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == COMPLETE {
+            return Ok(Self::complete());
+        }
+        match s.strip_prefix(NEXT_TOKEN_PREFIX) {
+            Some(token) if !token.is_empty() => Ok(Self::from_next_token(Some(token))),
+            _ => Err(InvalidCheckpoint { _private: () }),
+        }
+    }
+}
+
+/// A string could not be parsed as a [`PaginationCheckpoint`]
+#[derive(Debug)]
+pub struct InvalidCheckpoint {
+    _private: (),
+}
+
+impl fmt::Display for InvalidCheckpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid pagination checkpoint")
+    }
+}
+
+impl Error for InvalidCheckpoint {}
+
+/// Collects a stream of results into a `Vec`, reading at most `limit` items
+///
+/// The stream is not polled again once `limit` items have been read, so for a stream of pages no
+/// further requests are sent. For a stream of flattened items, the page containing the last item
+/// will have been fetched in full. The first error encountered is returned.
+///
+/// # Examples
+/// ```no_run
+/// # async fn docs() -> Result<(), &'static str> {
+/// use aws_smithy_async::future::pagination::try_collect;
+///
+/// let pages = tokio_stream::iter(vec![Ok(1), Ok(2), Ok(3)]);
+/// assert_eq!(vec![1, 2], try_collect(pages, Some(2)).await?);
+/// # Ok(())
+/// # }
+/// ```
+pub async fn try_collect<S, T, E>(stream: S, limit: Option<usize>) -> Result<Vec<T>, E>
+where
+    S: Stream<Item = Result<T, E>>,
+{
+    let limit = limit.unwrap_or(usize::MAX);
+    futures_util::pin_mut!(stream);
+    let mut out = Vec::new();
+    while out.len() < limit {
+        match stream.next().await {
+            Some(item) => out.push(item?),
+            None => break,
+        }
+    }
+    Ok(out)
+}
+
+#[cfg(feature = "rt-tokio")]
+pub use self::prefetch::{prefetch, Prefetch};
+
+#[cfg(feature = "rt-tokio")]
+mod prefetch {
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+    use tokio::sync::mpsc;
+    use tokio::task::JoinHandle;
+    use tokio_stream::{Stream, StreamExt};
+
+    /// Drives `stream` on a background Tokio task, buffering up to `pages` items ahead of the reader
+    ///
+    /// This allows the next pages of a paginator to be fetched while the caller is still processing
+    /// the current one. Each request depends on the token returned by the previous one, so requests
+    /// are still sent one at a time—`pages` only bounds how far ahead of the reader they may get.
+    /// A `pages` value of zero is treated as one.
+    ///
+    /// Dropping the returned stream cancels the background task, including any in-flight request.
+    ///
+    /// This must be called from within a Tokio runtime.
+    pub fn prefetch<S>(stream: S, pages: usize) -> Prefetch<S::Item>
+    where
+        S: Stream + Send + 'static,
+        S::Item: Send + 'static,
+    {
+        let (tx, rx) = mpsc::channel(pages.max(1));
+        let task = tokio::spawn(async move {
+            futures_util::pin_mut!(stream);
+            while let Some(item) = stream.next().await {
+                if tx.send(item).await.is_err() {
+                    // the reader was dropped
+                    return;
+                }
+            }
+        });
+        Prefetch { rx, task }
+    }
+
+    /// Stream returned by [`prefetch`]
+    #[derive(Debug)]
+    pub struct Prefetch<Item> {
+        rx: mpsc::Receiver<Item>,
+        task: JoinHandle<()>,
+    }
+
+    impl<Item> Stream for Prefetch<Item> {
+        type Item = Item;
+
+        fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+            self.rx.poll_recv(cx)
+        }
+    }
+
+    impl<Item> Drop for Prefetch<Item> {
+        fn drop(&mut self) {
+            self.task.abort();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{try_collect, PaginationCheckpoint};
+    use crate::future::fn_stream::FnStream;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn checkpoint_round_trip() {
+        for checkpoint in [
+            PaginationCheckpoint::from_next_token(Some("abc:def")),
+            PaginationCheckpoint::complete(),
+        ] {
+            assert_eq!(
+                checkpoint,
+                checkpoint
+                    .to_string()
+                    .parse::<PaginationCheckpoint>()
+                    .unwrap()
+            );
+        }
+        assert!(PaginationCheckpoint::from_next_token(Some("")).is_complete());
+        assert!(PaginationCheckpoint::from_next_token(None).is_complete());
+        "next:"
+            .parse::<PaginationCheckpoint>()
+            .expect_err("empty token");
+        "abc"
+            .parse::<PaginationCheckpoint>()
+            .expect_err("missing prefix");
+    }
+
+    #[tokio::test]
+    async fn try_collect_stops_at_limit() {
+        let requests = Arc::new(Mutex::new(0));
+        let stream = FnStream::new(|tx| {
+            let requests = requests.clone();
+            Box::pin(async move {
+                for page in 0..5 {
+                    *requests.lock().unwrap() += 1;
+                    if tx.send(Ok::<_, &str>(page)).await.is_err() {
+                        return;
+                    }
+                }
+            })
+        });
+        assert_eq!(Ok(vec![0, 1]), try_collect(stream, Some(2)).await);
+        assert_eq!(2, *requests.lock().unwrap());
+
+        let all = tokio_stream::iter(vec![Ok(1), Ok(2)]);
+        assert_eq!(Ok::<_, &str>(vec![1, 2]), try_collect(all, None).await);
+
+        let failed = tokio_stream::iter(vec![Ok(1), Err("bummer"), Ok(3)]);
+        assert_eq!(Err("bummer"), try_collect(failed, Some(5)).await);
+    }
+
+    #[cfg(feature = "rt-tokio")]
+    #[tokio::test(start_paused = true)]
+    async fn prefetch_reads_ahead() {
+        use super::prefetch;
+        use std::time::Duration;
+        use tokio::sync::mpsc;
+        use tokio_stream::StreamExt;
+
+        let (requests_tx, mut requests) = mpsc::unbounded_channel();
+        let stream = FnStream::new(|tx| {
+            Box::pin(async move {
+                for page in 0..10 {
+                    requests_tx.send(page).unwrap();
+                    if tx.send(page).await.is_err() {
+                        return;
+                    }
+                }
+            })
+        });
+        let mut stream = prefetch(stream, 2);
+        assert_eq!(Some(0), stream.next().await);
+        // the page that was read, two buffered pages, and one waiting to be buffered
+        for page in 0..4 {
+            assert_eq!(Some(page), requests.recv().await);
+        }
+        // with time paused, the timeout only elapses once every task is blocked, i.e. once the
+        // buffer is full
+        tokio::time::timeout(Duration::from_secs(1), requests.recv())
+            .await
+            .expect_err("no more pages are fetched until the buffer has room");
+
+        assert_eq!(
+            (1..10).collect::<Vec<_>>(),
+            stream.collect::<Vec<_>>().await
+        );
+    }
+}
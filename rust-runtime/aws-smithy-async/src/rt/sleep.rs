@@ -138,3 +138,104 @@ impl AsyncSleep for TokioSleep {
 fn sleep_tokio() -> Arc<dyn AsyncSleep> {
     Arc::new(TokioSleep::new())
 }
+
+/// Implementation of [`AsyncSleep`] for async-std.
+#[non_exhaustive]
+#[cfg(feature = "rt-async-std")]
+#[derive(Debug, Default)]
+pub struct AsyncStdSleep;
+
+#[cfg(feature = "rt-async-std")]
+impl AsyncStdSleep {
+    /// Create a new [`AsyncSleep`] implementation using async-std's sleep
+    pub fn new() -> AsyncStdSleep {
+        Default::default()
+    }
+}
+
+#[cfg(feature = "rt-async-std")]
+impl AsyncSleep for AsyncStdSleep {
+    fn sleep(&self, duration: Duration) -> Sleep {
+        Sleep::new(async_std::task::sleep(duration))
+    }
+}
+
+/// Implementation of [`AsyncSleep`] for smol.
+///
+/// This uses the `async-io` timers that smol is built on, so it works with any executor that
+/// drives the `async-io` reactor.
+#[non_exhaustive]
+#[cfg(feature = "rt-smol")]
+#[derive(Debug, Default)]
+pub struct SmolSleep;
+
+#[cfg(feature = "rt-smol")]
+impl SmolSleep {
+    /// Create a new [`AsyncSleep`] implementation using smol's timers
+    pub fn new() -> SmolSleep {
+        Default::default()
+    }
+}
+
+#[cfg(feature = "rt-smol")]
+impl AsyncSleep for SmolSleep {
+    fn sleep(&self, duration: Duration) -> Sleep {
+        Sleep::new(async move {
+            async_io::Timer::after(duration).await;
+        })
+    }
+}
+
+/// Implementation of [`AsyncSleep`] for WebAssembly components running under WASI 0.2.
+///
+/// Sleeps are backed by a `wasi:clocks/monotonic-clock` pollable. WASI doesn't provide a way to
+/// register a waker with the host, so a pending sleep blocks in `wasi:io/poll` until its deadline
+/// passes instead of spinning the executor. Other tasks on the same executor don't run while it
+/// blocks, which suits the single-threaded executors typically used in WASI components.
+#[non_exhaustive]
+#[cfg(all(feature = "rt-wasi", target_family = "wasm", target_os = "wasi"))]
+#[derive(Debug, Default)]
+pub struct WasiSleep;
+
+#[cfg(all(feature = "rt-wasi", target_family = "wasm", target_os = "wasi"))]
+impl WasiSleep {
+    /// Create a new [`AsyncSleep`] implementation using the WASI monotonic clock
+    pub fn new() -> WasiSleep {
+        Default::default()
+    }
+}
+
+#[cfg(all(feature = "rt-wasi", target_family = "wasm", target_os = "wasi"))]
+impl AsyncSleep for WasiSleep {
+    fn sleep(&self, duration: Duration) -> Sleep {
+        let nanos = u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX);
+        let pollable = wasi::clocks::monotonic_clock::subscribe_duration(nanos);
+        Sleep::new(async move {
+            if !pollable.ready() {
+                wasi::io::poll::poll(&[&pollable]);
+            }
+        })
+    }
+}
+
+#[cfg(all(test, any(feature = "rt-async-std", feature = "rt-smol")))]
+mod test {
+    use super::AsyncSleep;
+    use std::time::{Duration, Instant};
+
+    #[cfg(feature = "rt-async-std")]
+    #[test]
+    fn async_std_sleep() {
+        let start = Instant::now();
+        async_std::task::block_on(super::AsyncStdSleep::new().sleep(Duration::from_millis(10)));
+        assert!(start.elapsed() >= Duration::from_millis(10));
+    }
+
+    #[cfg(feature = "rt-smol")]
+    #[test]
+    fn smol_sleep() {
+        let start = Instant::now();
+        async_io::block_on(super::SmolSleep::new().sleep(Duration::from_millis(10)));
+        assert!(start.elapsed() >= Duration::from_millis(10));
+    }
+}
@@ -33,6 +33,28 @@ impl TimeSource for SystemTimeSource {
     }
 }
 
+/// Time source that reads the `wasi:clocks/wall-clock` interface of WASI 0.2
+#[non_exhaustive]
+#[cfg(all(feature = "rt-wasi", target_family = "wasm", target_os = "wasi"))]
+#[derive(Debug, Default)]
+pub struct WasiTimeSource;
+
+#[cfg(all(feature = "rt-wasi", target_family = "wasm", target_os = "wasi"))]
+impl WasiTimeSource {
+    /// Creates a new WasiTimeSource
+    pub fn new() -> Self {
+        WasiTimeSource
+    }
+}
+
+#[cfg(all(feature = "rt-wasi", target_family = "wasm", target_os = "wasi"))]
+impl TimeSource for WasiTimeSource {
+    fn now(&self) -> SystemTime {
+        let now = wasi::clocks::wall_clock::now();
+        std::time::UNIX_EPOCH + std::time::Duration::new(now.seconds, now.nanoseconds)
+    }
+}
+
 impl Default for SharedTimeSource {
     fn default() -> Self {
         SharedTimeSource(Arc::new(SystemTimeSource))
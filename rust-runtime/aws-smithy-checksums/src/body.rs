@@ -6,4 +6,5 @@
 //! HTTP body-wrappers that calculate and validate checksums.
 
 pub mod calculate;
+pub mod record;
 pub mod validate;
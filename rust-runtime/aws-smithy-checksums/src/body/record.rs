@@ -0,0 +1,205 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Functionality for calculating the checksum of an HTTP body while it's being streamed, and
+//! making the result available once the body has been read.
+//!
+//! This is useful when the checksum must be sent somewhere other than the body's trailers, e.g.
+//! in the `CompleteMultipartUpload` request that follows the upload of each part of a file:
+//!
+//! ```no_run
+//! # async fn example() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+//! use aws_smithy_checksums::body::record::RecordedChecksum;
+//! use aws_smithy_checksums::ChecksumAlgorithm;
+//! use aws_smithy_http::byte_stream::ByteStream;
+//!
+//! let partitions = ByteStream::read_from()
+//!     .path("docs/some-large-file.csv")
+//!     .partition(8 * 1024 * 1024)
+//!     .await?;
+//! for part in partitions.iter() {
+//!     let (body, checksum) =
+//!         RecordedChecksum::wrap(part.into_body().into_inner(), ChecksumAlgorithm::Crc32);
+//!     // ... upload `body` ...
+//!     # let _ = body;
+//!     let _crc32 = checksum.header_value();
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::http::HttpChecksum;
+use crate::ChecksumAlgorithm;
+
+use aws_smithy_http::body::SdkBody;
+use aws_smithy_types::base64;
+
+use bytes::Bytes;
+use http::{HeaderMap, HeaderValue};
+use http_body::SizeHint;
+use pin_project_lite::pin_project;
+
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+
+/// A handle to the checksum of a body wrapped with [`RecordedChecksum::wrap`].
+///
+/// The checksum is only available after the wrapped body has been read to the end. Cloning the
+/// body (e.g. for a retry) keeps the recorded checksum; it's replaced once a new attempt has been
+/// read to the end.
+#[derive(Clone, Debug)]
+pub struct RecordedChecksum {
+    algorithm: ChecksumAlgorithm,
+    value: Arc<Mutex<Option<Bytes>>>,
+}
+
+impl RecordedChecksum {
+    /// Wraps `body` so that its checksum is calculated with `algorithm` as it's read.
+    ///
+    /// Returns the wrapped body and a handle to retrieve the checksum with once the body has been
+    /// read. The wrapped body is retryable if the given body was.
+    pub fn wrap(body: SdkBody, algorithm: ChecksumAlgorithm) -> (SdkBody, Self) {
+        let recorded = Self {
+            algorithm,
+            value: Default::default(),
+        };
+        let value = recorded.value.clone();
+        let body = body.map(move |body| {
+            SdkBody::from_dyn(http_body::combinators::BoxBody::new(RecordingBody::new(
+                body,
+                algorithm.into_impl(),
+                value.clone(),
+            )))
+        });
+        (body, recorded)
+    }
+
+    /// The algorithm used to calculate this checksum.
+    pub fn algorithm(&self) -> ChecksumAlgorithm {
+        self.algorithm
+    }
+
+    /// Returns the calculated checksum, or `None` if the body hasn't been read to the end yet.
+    pub fn get(&self) -> Option<Bytes> {
+        self.value.lock().unwrap().clone()
+    }
+
+    /// Returns the calculated checksum as a base64-encoded `HeaderValue`, or `None` if the body
+    /// hasn't been read to the end yet.
+    pub fn header_value(&self) -> Option<HeaderValue> {
+        self.get().map(|checksum| {
+            HeaderValue::from_str(&base64::encode(&checksum[..]))
+                .expect("base64 encoded bytes are always valid header values")
+        })
+    }
+}
+
+pin_project! {
+    /// A body-wrapper that calculates the `InnerBody`'s checksum and stores it once the body
+    /// has been fully read.
+    struct RecordingBody<InnerBody> {
+        #[pin]
+        inner: InnerBody,
+        checksum: Option<Box<dyn HttpChecksum>>,
+        value: Arc<Mutex<Option<Bytes>>>,
+    }
+}
+
+impl RecordingBody<SdkBody> {
+    fn new(
+        body: SdkBody,
+        checksum: Box<dyn HttpChecksum>,
+        value: Arc<Mutex<Option<Bytes>>>,
+    ) -> Self {
+        Self {
+            inner: body,
+            checksum: Some(checksum),
+            value,
+        }
+    }
+}
+
+impl http_body::Body for RecordingBody<SdkBody> {
+    type Data = Bytes;
+    type Error = aws_smithy_http::body::Error;
+
+    fn poll_data(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Self::Data, Self::Error>>> {
+        let this = self.project();
+        let poll_res = this.inner.poll_data(cx);
+        match &poll_res {
+            Poll::Ready(Some(Ok(data))) => {
+                if let Some(checksum) = this.checksum {
+                    checksum.update(data);
+                }
+            }
+            Poll::Ready(None) => {
+                if let Some(checksum) = this.checksum.take() {
+                    *this.value.lock().unwrap() = Some(checksum.finalize());
+                }
+            }
+            _ => {}
+        }
+        poll_res
+    }
+
+    fn poll_trailers(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Option<HeaderMap>, Self::Error>> {
+        self.project().inner.poll_trailers(cx)
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.inner.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RecordedChecksum;
+    use crate::ChecksumAlgorithm;
+    use aws_smithy_http::body::SdkBody;
+    use aws_smithy_http::byte_stream::ByteStream;
+
+    #[tokio::test]
+    async fn checksum_is_recorded_once_the_body_is_read() {
+        let input_text = "This is some test text for an SdkBody";
+        let (body, checksum) =
+            RecordedChecksum::wrap(SdkBody::from(input_text), ChecksumAlgorithm::Crc32);
+        assert_eq!(checksum.algorithm(), ChecksumAlgorithm::Crc32);
+        assert_eq!(checksum.get(), None);
+
+        let output = ByteStream::new(body).collect().await.unwrap().into_bytes();
+        assert_eq!(&output[..], input_text.as_bytes());
+        assert_eq!(
+            checksum.header_value().unwrap().to_str().unwrap(),
+            // 0x99B01F72, the same value as in the `calculate` tests, base64-encoded
+            "mbAfcg=="
+        );
+    }
+
+    #[tokio::test]
+    async fn retries_keep_the_recorded_checksum() {
+        let (body, checksum) =
+            RecordedChecksum::wrap(SdkBody::from("hello"), ChecksumAlgorithm::Sha256);
+        let retry = body.try_clone().expect("wrapped body is retryable");
+        ByteStream::new(body).collect().await.unwrap();
+        let first = checksum.get().unwrap();
+
+        // Starting a new attempt keeps the previously recorded checksum
+        let retry = retry.try_clone().expect("wrapped body is retryable");
+        assert_eq!(checksum.get().as_ref(), Some(&first));
+        ByteStream::new(retry).collect().await.unwrap();
+        assert_eq!(checksum.get().unwrap(), first);
+    }
+}
@@ -25,6 +25,8 @@ use aws_smithy_protocol_test::{assert_ok, validate_body, MediaType};
 #[doc(inline)]
 pub use crate::never;
 
+pub mod event_stream;
+
 /// Test Connection to capture a single request
 #[derive(Debug, Clone)]
 pub struct CaptureRequestHandler(Arc<Mutex<Inner>>);
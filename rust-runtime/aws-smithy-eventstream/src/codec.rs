@@ -0,0 +1,296 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Tokio codec for reading and writing Event Stream messages.
+//!
+//! This makes it possible to process event stream data outside of generated clients, for example
+//! to replay captured event streams from disk:
+//!
+//! ```no_run
+//! # async fn docs() -> Result<(), aws_smithy_eventstream::error::Error> {
+//! use aws_smithy_eventstream::codec::MessageStream;
+//! use tokio_stream::StreamExt;
+//!
+//! let file = tokio::fs::File::open("captured-event-stream").await?;
+//! let mut messages = MessageStream::new(file);
+//! while let Some(message) = messages.next().await {
+//!     println!("{:?}", message?.headers());
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::error::{Error, ErrorKind};
+use crate::frame::{Message, PRELUDE_LENGTH_BYTES_USIZE};
+use bytes::{Buf, BytesMut};
+use futures_core::Stream;
+use pin_project_lite::pin_project;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::io::AsyncRead;
+use tokio_util::codec::{Decoder, Encoder, FramedRead};
+
+/// Default maximum size of a message, including the prelude and checksums (16 MiB)
+pub const DEFAULT_MAX_MESSAGE_SIZE: u32 = 16 * 1024 * 1024;
+
+/// Default maximum size of the headers of a message (128 KiB)
+pub const DEFAULT_MAX_HEADERS_SIZE: u32 = 128 * 1024;
+
+/// [`Decoder`] and [`Encoder`] for Event Stream [`Message`]s.
+///
+/// The decoder checks the prelude before buffering a message, so a frame that exceeds the
+/// configured limits is rejected without reading its payload.
+#[derive(Clone, Debug)]
+pub struct MessageCodec {
+    max_message_size: u32,
+    max_headers_size: u32,
+    validate_checksums: bool,
+}
+
+impl Default for MessageCodec {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MessageCodec {
+    /// Creates a new codec with the default size limits that validates checksums.
+    pub fn new() -> Self {
+        Self {
+            max_message_size: DEFAULT_MAX_MESSAGE_SIZE,
+            max_headers_size: DEFAULT_MAX_HEADERS_SIZE,
+            validate_checksums: true,
+        }
+    }
+
+    /// Sets the maximum size of a message in bytes, including the prelude and checksums.
+    pub fn max_message_size(mut self, max_message_size: u32) -> Self {
+        self.max_message_size = max_message_size;
+        self
+    }
+
+    /// Sets the maximum size of the headers of a message in bytes.
+    pub fn max_headers_size(mut self, max_headers_size: u32) -> Self {
+        self.max_headers_size = max_headers_size;
+        self
+    }
+
+    /// Sets whether the prelude and message checksums are validated when decoding.
+    ///
+    /// Checksums are always written when encoding. Disabling validation is only intended for
+    /// benchmarking and for inspecting data that is known to be corrupt.
+    pub fn validate_checksums(mut self, validate_checksums: bool) -> Self {
+        self.validate_checksums = validate_checksums;
+        self
+    }
+
+    fn check_sizes(&self, total_len: u32, headers_len: u32) -> Result<(), Error> {
+        if total_len > self.max_message_size {
+            return Err(ErrorKind::MessageTooLarge(total_len, self.max_message_size).into());
+        }
+        if headers_len > self.max_headers_size {
+            return Err(ErrorKind::HeadersTooLarge(headers_len, self.max_headers_size).into());
+        }
+        Ok(())
+    }
+}
+
+impl Decoder for MessageCodec {
+    type Item = Message;
+    type Error = Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Message>, Error> {
+        if src.len() < PRELUDE_LENGTH_BYTES_USIZE {
+            return Ok(None);
+        }
+        let mut prelude = &src[..PRELUDE_LENGTH_BYTES_USIZE];
+        let (total_len, headers_len) = (prelude.get_u32(), prelude.get_u32());
+        // Don't trust the lengths until the prelude checksum has been verified
+        if self.validate_checksums {
+            let expected_crc = crc32fast::hash(&src[..PRELUDE_LENGTH_BYTES_USIZE - 4]);
+            let prelude_crc = prelude.get_u32();
+            if expected_crc != prelude_crc {
+                return Err(ErrorKind::PreludeChecksumMismatch(expected_crc, prelude_crc).into());
+            }
+        }
+        self.check_sizes(total_len, headers_len)?;
+
+        let total_len = total_len as usize;
+        if src.len() < total_len {
+            src.reserve(total_len - src.len());
+            return Ok(None);
+        }
+        let frame = src.split_to(total_len).freeze();
+        Message::read_from_with_checksums(frame, self.validate_checksums).map(Some)
+    }
+}
+
+impl Encoder<Message> for MessageCodec {
+    type Error = Error;
+
+    fn encode(&mut self, item: Message, dst: &mut BytesMut) -> Result<(), Error> {
+        Encoder::<&Message>::encode(self, &item, dst)
+    }
+}
+
+impl Encoder<&Message> for MessageCodec {
+    type Error = Error;
+
+    fn encode(&mut self, item: &Message, dst: &mut BytesMut) -> Result<(), Error> {
+        let mut frame = Vec::new();
+        item.write_to(&mut frame)?;
+        let mut prelude = &frame[..];
+        self.check_sizes(prelude.get_u32(), prelude.get_u32())?;
+        dst.extend_from_slice(&frame);
+        Ok(())
+    }
+}
+
+pin_project! {
+    /// A [`Stream`] of [`Message`]s read from an [`AsyncRead`].
+    ///
+    /// The stream ends when the reader reaches end of file on a message boundary. Reaching end
+    /// of file in the middle of a message results in an error.
+    #[derive(Debug)]
+    pub struct MessageStream<R> {
+        #[pin]
+        inner: FramedRead<R, MessageCodec>,
+    }
+}
+
+impl<R: AsyncRead> MessageStream<R> {
+    /// Creates a stream that reads messages from `reader` with a default [`MessageCodec`].
+    pub fn new(reader: R) -> Self {
+        Self::with_codec(reader, MessageCodec::new())
+    }
+
+    /// Creates a stream that reads messages from `reader` with the given `codec`.
+    pub fn with_codec(reader: R, codec: MessageCodec) -> Self {
+        Self {
+            inner: FramedRead::new(reader, codec),
+        }
+    }
+
+    /// Consumes the stream, returning the underlying reader.
+    ///
+    /// Any data that was buffered but not yet decoded is lost.
+    pub fn into_inner(self) -> R {
+        self.inner.into_inner()
+    }
+}
+
+impl<R: AsyncRead> Stream for MessageStream<R> {
+    type Item = Result<Message, Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.project().inner.poll_next(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MessageCodec, MessageStream};
+    use crate::error::ErrorKind;
+    use crate::frame::{Header, HeaderValue, Message};
+    use bytes::BytesMut;
+    use std::error::Error as _;
+    use tokio_stream::StreamExt;
+    use tokio_util::codec::{Decoder, Encoder};
+
+    const VALID: &[u8] = include_bytes!("../test_data/valid_with_all_headers_and_payload");
+
+    fn message() -> Message {
+        Message::new(&b"some payload"[..])
+            .add_header(Header::new("str", HeaderValue::String("value".into())))
+    }
+
+    fn encoded(messages: &[Message]) -> Vec<u8> {
+        let mut out = BytesMut::new();
+        for message in messages {
+            MessageCodec::new().encode(message, &mut out).unwrap();
+        }
+        out.to_vec()
+    }
+
+    #[test]
+    fn decode_byte_at_a_time() {
+        let mut codec = MessageCodec::new();
+        let mut buf = BytesMut::new();
+        for byte in &VALID[..VALID.len() - 1] {
+            buf.extend_from_slice(&[*byte]);
+            assert!(codec.decode(&mut buf).unwrap().is_none());
+        }
+        buf.extend_from_slice(&VALID[VALID.len() - 1..]);
+        let decoded = codec.decode(&mut buf).unwrap().expect("complete frame");
+        assert_eq!(Message::read_from(VALID).unwrap(), decoded);
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn size_limits() {
+        let data = encoded(&[message()]);
+        let mut too_small = MessageCodec::new().max_message_size(data.len() as u32 - 1);
+        let err = too_small
+            .decode(&mut BytesMut::from(&data[..12]))
+            .expect_err("too large");
+        assert!(err.is_too_large());
+        assert!(
+            matches!(err.kind(), ErrorKind::MessageTooLarge(size, _) if *size as usize == data.len())
+        );
+        too_small
+            .encode(message(), &mut BytesMut::new())
+            .expect_err("too large");
+
+        let mut small_headers = MessageCodec::new().max_headers_size(4);
+        let err = small_headers
+            .decode(&mut BytesMut::from(&data[..]))
+            .expect_err("headers too large");
+        assert!(err.is_too_large());
+        assert!(matches!(err.kind(), ErrorKind::HeadersTooLarge(_, 4)));
+    }
+
+    #[test]
+    fn checksum_validation() {
+        let mut data = encoded(&[message()]);
+        let last = data.len() - 1;
+        data[last] ^= 0xFF;
+        let err = MessageCodec::new()
+            .decode(&mut BytesMut::from(&data[..]))
+            .expect_err("corrupt checksum");
+        assert!(err.is_checksum_mismatch());
+        assert!(matches!(err.kind(), ErrorKind::MessageChecksumMismatch(..)));
+
+        data[8] ^= 0xFF;
+        let err = MessageCodec::new()
+            .decode(&mut BytesMut::from(&data[..]))
+            .expect_err("corrupt prelude checksum");
+        assert!(err.is_checksum_mismatch() && !err.is_too_large());
+        assert!(matches!(err.kind(), ErrorKind::PreludeChecksumMismatch(..)));
+
+        let decoded = MessageCodec::new()
+            .validate_checksums(false)
+            .decode(&mut BytesMut::from(&data[..]))
+            .unwrap();
+        assert_eq!(Some(message()), decoded);
+    }
+
+    #[tokio::test]
+    async fn message_stream() {
+        let data = encoded(&[message(), Message::new(&b"second"[..])]);
+        let messages: Vec<Message> = MessageStream::new(&data[..])
+            .collect::<Result<_, _>>()
+            .await
+            .unwrap();
+        assert_eq!(vec![message(), Message::new(&b"second"[..])], messages);
+
+        let mut truncated = MessageStream::new(&data[..data.len() - 1]);
+        assert_eq!(Some(message()), truncated.next().await.transpose().unwrap());
+        let err = truncated.next().await.unwrap().expect_err("truncated");
+        assert!(err.is_io());
+        // The I/O error is only reported as the source, so it isn't printed twice
+        assert_eq!("failed to read or write event stream data", err.to_string());
+        assert!(err.source().is_some());
+    }
+}
@@ -10,6 +10,8 @@ use std::fmt;
 #[derive(Debug)]
 pub(crate) enum ErrorKind {
     HeadersTooLong,
+    #[cfg(feature = "tokio-codec")]
+    HeadersTooLarge(u32, u32),
     HeaderValueTooLong,
     InvalidHeaderNameLength,
     InvalidHeaderValue,
@@ -17,8 +19,11 @@ pub(crate) enum ErrorKind {
     InvalidHeadersLength,
     InvalidMessageLength,
     InvalidUtf8String,
+    Io(std::io::Error),
     MessageChecksumMismatch(u32, u32),
     MessageTooLong,
+    #[cfg(feature = "tokio-codec")]
+    MessageTooLarge(u32, u32),
     PayloadTooLong,
     PreludeChecksumMismatch(u32, u32),
     TimestampValueTooLarge(DateTime),
@@ -38,6 +43,31 @@ impl Error {
         &self.kind
     }
 
+    /// Returns true if a message or its headers exceeded the size limits of a
+    /// [`MessageCodec`](crate::codec::MessageCodec)
+    pub fn is_too_large(&self) -> bool {
+        match self.kind {
+            #[cfg(feature = "tokio-codec")]
+            ErrorKind::MessageTooLarge(..) | ErrorKind::HeadersTooLarge(..) => true,
+            _ => false,
+        }
+    }
+
+    /// Returns true if a message's prelude or message checksum didn't match its contents,
+    /// indicating that it was corrupted
+    pub fn is_checksum_mismatch(&self) -> bool {
+        matches!(
+            self.kind,
+            ErrorKind::PreludeChecksumMismatch(..) | ErrorKind::MessageChecksumMismatch(..)
+        )
+    }
+
+    /// Returns true if reading or writing the underlying I/O failed, including when it ended in the
+    /// middle of a message. The I/O error is available as the [`source`](StdError::source).
+    pub fn is_io(&self) -> bool {
+        matches!(self.kind, ErrorKind::Io(_))
+    }
+
     /// Create an `Error` for failure to marshall a message from a Smithy shape
     pub fn marshalling(message: impl Into<String>) -> Self {
         Self {
@@ -59,13 +89,32 @@ impl From<ErrorKind> for Error {
     }
 }
 
-impl StdError for Error {}
+impl From<std::io::Error> for Error {
+    fn from(err: std::io::Error) -> Self {
+        ErrorKind::Io(err).into()
+    }
+}
+
+impl StdError for Error {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match &self.kind {
+            ErrorKind::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         use ErrorKind::*;
         match &self.kind {
             HeadersTooLong => write!(f, "headers too long to fit in event stream frame"),
+            #[cfg(feature = "tokio-codec")]
+            HeadersTooLarge(size, max) => write!(
+                f,
+                "event stream headers of {} bytes exceed the maximum of {} bytes",
+                size, max
+            ),
             HeaderValueTooLong => write!(f, "header value too long to fit in event stream frame"),
             InvalidHeaderNameLength => write!(f, "invalid header name length"),
             InvalidHeaderValue => write!(f, "invalid header value"),
@@ -73,12 +122,19 @@ impl fmt::Display for Error {
             InvalidHeadersLength => write!(f, "invalid headers length"),
             InvalidMessageLength => write!(f, "invalid message length"),
             InvalidUtf8String => write!(f, "encountered invalid UTF-8 string"),
+            Io(_) => write!(f, "failed to read or write event stream data"),
             MessageChecksumMismatch(expected, actual) => write!(
                 f,
                 "message checksum 0x{:X} didn't match expected checksum 0x{:X}",
                 actual, expected
             ),
             MessageTooLong => write!(f, "message too long to fit in event stream frame"),
+            #[cfg(feature = "tokio-codec")]
+            MessageTooLarge(size, max) => write!(
+                f,
+                "event stream message of {} bytes exceeds the maximum of {} bytes",
+                size, max
+            ),
             PayloadTooLong => write!(f, "message payload too long to fit in event stream frame"),
             PreludeChecksumMismatch(expected, actual) => write!(
                 f,
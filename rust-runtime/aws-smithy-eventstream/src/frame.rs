@@ -17,7 +17,7 @@ use std::mem::size_of;
 use std::sync::{mpsc, Mutex};
 
 const PRELUDE_LENGTH_BYTES: u32 = 3 * size_of::<u32>() as u32;
-const PRELUDE_LENGTH_BYTES_USIZE: usize = PRELUDE_LENGTH_BYTES as usize;
+pub(crate) const PRELUDE_LENGTH_BYTES_USIZE: usize = PRELUDE_LENGTH_BYTES as usize;
 const MESSAGE_CRC_LENGTH_BYTES: u32 = size_of::<u32>() as u32;
 const MAX_HEADER_NAME_LEN: usize = 255;
 const MIN_HEADER_LEN: usize = 2;
@@ -501,7 +501,10 @@ impl Message {
     }
 
     // Returns (total_len, header_len)
-    fn read_prelude_from<B: Buf>(mut buffer: B) -> Result<(u32, u32), Error> {
+    fn read_prelude_from<B: Buf>(
+        mut buffer: B,
+        validate_checksums: bool,
+    ) -> Result<(u32, u32), Error> {
         let mut crc_buffer = CrcBuf::new(&mut buffer);
 
         // If the buffer doesn't have the entire, then error
@@ -513,7 +516,7 @@ impl Message {
         // Validate the prelude
         let header_len = crc_buffer.get_u32();
         let (expected_crc, prelude_crc) = (crc_buffer.into_crc(), buffer.get_u32());
-        if expected_crc != prelude_crc {
+        if validate_checksums && expected_crc != prelude_crc {
             return Err(ErrorKind::PreludeChecksumMismatch(expected_crc, prelude_crc).into());
         }
         // The header length can be 0 or >= 2, but must fit within the frame size
@@ -525,14 +528,23 @@ impl Message {
 
     /// Reads a message from the given `buffer`. For streaming use cases, use
     /// the [`MessageFrameDecoder`] instead of this.
-    pub fn read_from<B: Buf>(mut buffer: B) -> Result<Message, Error> {
+    pub fn read_from<B: Buf>(buffer: B) -> Result<Message, Error> {
+        Self::read_from_with_checksums(buffer, true)
+    }
+
+    /// Reads a message from the given `buffer`, only verifying the prelude and message
+    /// checksums if `validate_checksums` is true.
+    pub(crate) fn read_from_with_checksums<B: Buf>(
+        mut buffer: B,
+        validate_checksums: bool,
+    ) -> Result<Message, Error> {
         if buffer.remaining() < PRELUDE_LENGTH_BYTES_USIZE {
             return Err(ErrorKind::InvalidMessageLength.into());
         }
 
         // Calculate a CRC as we go and read the prelude
         let mut crc_buffer = CrcBuf::new(&mut buffer);
-        let (total_len, header_len) = Self::read_prelude_from(&mut crc_buffer)?;
+        let (total_len, header_len) = Self::read_prelude_from(&mut crc_buffer, validate_checksums)?;
 
         // Verify we have the full frame before continuing
         let remaining_len = total_len
@@ -560,7 +572,7 @@ impl Message {
 
         let expected_crc = crc_buffer.into_crc();
         let message_crc = buffer.get_u32();
-        if expected_crc != message_crc {
+        if validate_checksums && expected_crc != message_crc {
             return Err(ErrorKind::MessageChecksumMismatch(expected_crc, message_crc).into());
         }
 
@@ -14,6 +14,8 @@
 //! AWS Event Stream frame serialization/deserialization implementation.
 
 mod buf;
+#[cfg(feature = "tokio-codec")]
+pub mod codec;
 pub mod error;
 pub mod frame;
 pub mod smithy;
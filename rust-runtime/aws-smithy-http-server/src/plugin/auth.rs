@@ -0,0 +1,648 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Authentication of requests for operations modeled with the [`@httpBasicAuth`], [`@httpBearerAuth`] and
+//! [`@httpApiKeyAuth`] traits.
+//!
+//! The [`AuthPlugin`] extracts [`Credentials`] from the location described by the first of the operation's
+//! [effective auth schemes](OperationAuth::auth_schemes) present in the request, and passes them to an
+//! [`Authenticator`]. The principal returned by the authenticator is inserted into the request extensions, from where
+//! handlers can extract it with [`Extension`](crate::Extension):
+//!
+//! - requests without credentials are rejected with `401 Unauthorized`, unless the operation is modeled with
+//!   [`@optionalAuth`] or has no auth schemes the plugin can extract credentials for, such as a `@sigv4`-only
+//!   operation, in which case no principal is inserted;
+//! - requests with credentials the authenticator rejects as [`AuthError::Unauthenticated`] are rejected with
+//!   `401 Unauthorized`, even if the operation is modeled with [`@optionalAuth`];
+//! - requests the authenticator rejects as [`AuthError::AccessDenied`] are rejected with `403 Forbidden`.
+//!
+//! Rejections are rendered according to the service's protocol.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use aws_smithy_http_server::plugin::auth::{AuthError, AuthExt, Authenticator, Credentials};
+//! # use aws_smithy_http_server::plugin::PluginPipeline;
+//! # use aws_smithy_http_server::shape_id::ShapeId;
+//! #[derive(Clone, Debug)]
+//! struct User(String);
+//!
+//! struct StaticTokens;
+//!
+//! #[async_trait::async_trait]
+//! impl Authenticator for StaticTokens {
+//!     type Principal = User;
+//!
+//!     async fn authenticate(&self, _operation: &ShapeId, credentials: Credentials) -> Result<User, AuthError> {
+//!         match credentials {
+//!             Credentials::Bearer(token) if token.token() == "secret" => Ok(User("admin".to_string())),
+//!             _ => Err(AuthError::Unauthenticated),
+//!         }
+//!     }
+//! }
+//!
+//! // Handlers can now take `Extension<User>` as an argument.
+//! let plugins = PluginPipeline::new().authenticate(StaticTokens);
+//! ```
+//!
+//! [`@httpBasicAuth`]: https://smithy.io/2.0/spec/authentication-traits.html#httpbasicauth-trait
+//! [`@httpBearerAuth`]: https://smithy.io/2.0/spec/authentication-traits.html#httpbearerauth-trait
+//! [`@httpApiKeyAuth`]: https://smithy.io/2.0/spec/authentication-traits.html#httpapikeyauth-trait
+//! [`@optionalAuth`]: https://smithy.io/2.0/spec/authentication-traits.html#optionalauth-trait
+
+use std::fmt;
+use std::future::Future;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use async_trait::async_trait;
+pub use aws_smithy_http_auth::api_key::AuthApiKey;
+pub use aws_smithy_http_auth::definition::HttpAuthDefinition;
+use aws_smithy_http_auth::location::HttpAuthLocation;
+use http::header::WWW_AUTHENTICATE;
+use http::{HeaderValue, Request, Response};
+use tower::Service;
+
+use crate::body::BoxBody;
+use crate::operation::OperationShape;
+use crate::plugin::{Plugin, PluginPipeline, PluginStack};
+use crate::response::IntoResponse;
+use crate::runtime_error::{AccessDeniedException, UnauthorizedException};
+use crate::service::ServiceShape;
+use crate::shape_id::ShapeId;
+
+/// An auth scheme an operation can be invoked with.
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq)]
+pub enum AuthScheme {
+    /// The `@httpBasicAuth` scheme.
+    HttpBasic,
+    /// The `@httpBearerAuth` scheme.
+    HttpBearer,
+    /// The `@httpApiKeyAuth` scheme, sent where the definition says.
+    HttpApiKey(HttpAuthDefinition),
+    /// A scheme the [`AuthPlugin`] can't extract credentials for, such as `@httpDigestAuth` or `@sigv4`.
+    Other(ShapeId),
+}
+
+impl AuthScheme {
+    /// Returns the challenge sent in the `WWW-Authenticate` header of `401` responses, if the scheme has one.
+    fn challenge(&self) -> Option<&str> {
+        match self {
+            AuthScheme::HttpBasic => Some("Basic"),
+            AuthScheme::HttpBearer => Some("Bearer"),
+            AuthScheme::HttpApiKey(definition) => definition.scheme(),
+            AuthScheme::Other(_) => None,
+        }
+    }
+}
+
+/// The auth schemes of an operation.
+///
+/// This should not be implemented by hand. Code generation implements it for every operation based on the
+/// operation's effective auth schemes and the [`@optionalAuth`] trait.
+///
+/// [`@optionalAuth`]: https://smithy.io/2.0/spec/authentication-traits.html#optionalauth-trait
+pub trait OperationAuth {
+    /// Whether the operation can be invoked without credentials.
+    const OPTIONAL_AUTH: bool = false;
+
+    /// Returns the auth schemes the operation can be invoked with, in priority order.
+    fn auth_schemes() -> Vec<AuthScheme>;
+}
+
+/// A bearer token. Its value is redacted from the `Debug` output.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Token(AuthApiKey);
+
+impl Token {
+    /// Creates a token.
+    pub fn new(token: impl Into<String>) -> Self {
+        Self(AuthApiKey::new(token))
+    }
+
+    /// Returns the value of the token.
+    pub fn token(&self) -> &str {
+        self.0.api_key()
+    }
+}
+
+impl fmt::Debug for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Token").field(&"** redacted **").finish()
+    }
+}
+
+/// A username and password. The password is redacted from the `Debug` output.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Login {
+    username: String,
+    password: AuthApiKey,
+}
+
+impl Login {
+    /// Creates a login.
+    pub fn new(username: impl Into<String>, password: impl Into<String>) -> Self {
+        Self {
+            username: username.into(),
+            password: AuthApiKey::new(password),
+        }
+    }
+
+    /// Returns the username.
+    pub fn username(&self) -> &str {
+        &self.username
+    }
+
+    /// Returns the password.
+    pub fn password(&self) -> &str {
+        self.password.api_key()
+    }
+}
+
+impl fmt::Debug for Login {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Login")
+            .field("username", &self.username)
+            .field("password", &"** redacted **")
+            .finish()
+    }
+}
+
+/// Credentials extracted from a request.
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Credentials {
+    /// Credentials for the `@httpBasicAuth` scheme.
+    Basic(Login),
+    /// Credentials for the `@httpBearerAuth` scheme.
+    Bearer(Token),
+    /// Credentials for the `@httpApiKeyAuth` scheme.
+    ApiKey(AuthApiKey),
+}
+
+/// The reason an [`Authenticator`] rejected a request.
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AuthError {
+    /// The credentials are invalid. The request is rejected with `401 Unauthorized`.
+    Unauthenticated,
+    /// The credentials are valid, but the principal is not allowed to invoke the operation. The request is rejected
+    /// with `403 Forbidden`.
+    AccessDenied,
+}
+
+/// Verifies the [`Credentials`] of requests.
+#[async_trait]
+pub trait Authenticator: Send + Sync + 'static {
+    /// The authenticated identity, inserted into the request extensions.
+    type Principal: Clone + Send + Sync + 'static;
+
+    /// Returns the principal identified by `credentials`, if it is allowed to invoke `operation`.
+    async fn authenticate(&self, operation: &ShapeId, credentials: Credentials) -> Result<Self::Principal, AuthError>;
+}
+
+/// A [`Plugin`] that authenticates requests with an [`Authenticator`]. See the [module](self) documentation for more
+/// information.
+pub struct AuthPlugin<A> {
+    authenticator: Arc<A>,
+}
+
+impl<A> AuthPlugin<A> {
+    /// Creates a plugin that authenticates requests with `authenticator`.
+    pub fn new(authenticator: A) -> Self {
+        Self {
+            authenticator: Arc::new(authenticator),
+        }
+    }
+}
+
+impl<A> fmt::Debug for AuthPlugin<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AuthPlugin").finish_non_exhaustive()
+    }
+}
+
+impl<Ser, Op, T, A> Plugin<Ser, Op, T> for AuthPlugin<A>
+where
+    Ser: ServiceShape,
+    Op: OperationShape + OperationAuth,
+{
+    type Output = AuthService<Ser::Protocol, T, A>;
+
+    fn apply(&self, inner: T) -> Self::Output {
+        AuthService {
+            inner,
+            authenticator: self.authenticator.clone(),
+            operation: Arc::new(Op::ID),
+            schemes: Op::auth_schemes().into(),
+            optional: Op::OPTIONAL_AUTH,
+            _protocol: PhantomData,
+        }
+    }
+}
+
+/// An extension trait for applying [`AuthPlugin`].
+pub trait AuthExt<CurrentPlugin> {
+    /// Authenticates requests to every operation with `authenticator`. See the [module](self) documentation for more
+    /// information.
+    fn authenticate<A>(self, authenticator: A) -> PluginPipeline<PluginStack<AuthPlugin<A>, CurrentPlugin>>;
+}
+
+impl<CurrentPlugin> AuthExt<CurrentPlugin> for PluginPipeline<CurrentPlugin> {
+    fn authenticate<A>(self, authenticator: A) -> PluginPipeline<PluginStack<AuthPlugin<A>, CurrentPlugin>> {
+        self.push(AuthPlugin::new(authenticator))
+    }
+}
+
+/// A [`Service`] that authenticates requests before passing them to the inner service. Applied by [`AuthPlugin`].
+pub struct AuthService<P, S, A> {
+    inner: S,
+    authenticator: Arc<A>,
+    operation: Arc<ShapeId>,
+    schemes: Arc<[AuthScheme]>,
+    optional: bool,
+    _protocol: PhantomData<fn() -> P>,
+}
+
+impl<P, S, A> Clone for AuthService<P, S, A>
+where
+    S: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            authenticator: self.authenticator.clone(),
+            operation: self.operation.clone(),
+            schemes: self.schemes.clone(),
+            optional: self.optional,
+            _protocol: PhantomData,
+        }
+    }
+}
+
+impl<P, S, A> fmt::Debug for AuthService<P, S, A>
+where
+    S: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AuthService")
+            .field("inner", &self.inner)
+            .field("operation", &self.operation)
+            .field("schemes", &self.schemes)
+            .field("optional", &self.optional)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<P, S, A, B> Service<Request<B>> for AuthService<P, S, A>
+where
+    S: Service<Request<B>, Response = Response<BoxBody>> + Clone + Send + 'static,
+    S::Future: Send,
+    A: Authenticator,
+    B: Send + 'static,
+    UnauthorizedException: IntoResponse<P>,
+    AccessDeniedException: IntoResponse<P>,
+{
+    type Response = Response<BoxBody>;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: Request<B>) -> Self::Future {
+        // The inner service was driven to readiness, so it's the one that must be called.
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+
+        let credentials = match extract_credentials(&self.schemes, &request) {
+            Extracted::Credentials(credentials) => credentials,
+            Extracted::Missing if self.optional || !has_supported_scheme(&self.schemes) => {
+                return Box::pin(inner.call(request))
+            }
+            Extracted::Missing | Extracted::Malformed => {
+                let response = unauthorized::<P>(&self.schemes);
+                return Box::pin(async move { Ok(response) });
+            }
+        };
+
+        let authenticator = self.authenticator.clone();
+        let operation = self.operation.clone();
+        let schemes = self.schemes.clone();
+        Box::pin(async move {
+            match authenticator.authenticate(&operation, credentials).await {
+                Ok(principal) => {
+                    request.extensions_mut().insert(principal);
+                    inner.call(request).await
+                }
+                Err(AuthError::Unauthenticated) => Ok(unauthorized::<P>(&schemes)),
+                Err(AuthError::AccessDenied) => Ok(IntoResponse::<P>::into_response(AccessDeniedException)),
+            }
+        })
+    }
+}
+
+/// Renders a `401 Unauthorized` response, with a `WWW-Authenticate` challenge for each scheme that has one.
+fn unauthorized<P>(schemes: &[AuthScheme]) -> Response<BoxBody>
+where
+    UnauthorizedException: IntoResponse<P>,
+{
+    let mut response = IntoResponse::<P>::into_response(UnauthorizedException);
+    for challenge in schemes.iter().filter_map(AuthScheme::challenge) {
+        if let Ok(value) = HeaderValue::from_str(challenge) {
+            response.headers_mut().append(WWW_AUTHENTICATE, value);
+        }
+    }
+    response
+}
+
+/// Returns whether the plugin can extract credentials for any of `schemes`.
+fn has_supported_scheme(schemes: &[AuthScheme]) -> bool {
+    schemes.iter().any(|scheme| !matches!(scheme, AuthScheme::Other(_)))
+}
+
+#[derive(Debug, PartialEq)]
+enum Extracted {
+    Credentials(Credentials),
+    Missing,
+    Malformed,
+}
+
+/// Extracts the credentials of the first scheme, in priority order, whose credentials are present in the request.
+///
+/// Malformed credentials for one scheme don't prevent the next scheme from being tried. They're only reported if no
+/// scheme has well-formed credentials.
+fn extract_credentials<B>(schemes: &[AuthScheme], request: &Request<B>) -> Extracted {
+    let mut malformed = false;
+    for scheme in schemes {
+        let extracted = match scheme {
+            AuthScheme::HttpBasic => authorization(request, "Basic").map(|value| match parse_basic(value) {
+                Some(login) => Extracted::Credentials(Credentials::Basic(login)),
+                None => Extracted::Malformed,
+            }),
+            AuthScheme::HttpBearer => authorization(request, "Bearer")
+                .map(|token| Extracted::Credentials(Credentials::Bearer(Token::new(token)))),
+            AuthScheme::HttpApiKey(definition) => api_key(request, definition)
+                .map(|api_key| Extracted::Credentials(Credentials::ApiKey(AuthApiKey::new(api_key)))),
+            AuthScheme::Other(_) => None,
+        };
+        match extracted {
+            Some(Extracted::Malformed) => malformed = true,
+            Some(extracted) => return extracted,
+            None => {}
+        }
+    }
+    if malformed {
+        Extracted::Malformed
+    } else {
+        Extracted::Missing
+    }
+}
+
+/// Returns the value of the `Authorization` header, without the `scheme` prefix, if it uses `scheme`.
+fn authorization<'a, B>(request: &'a Request<B>, scheme: &str) -> Option<&'a str> {
+    header_with_scheme(request, "authorization", scheme)
+}
+
+fn header_with_scheme<'a, B>(request: &'a Request<B>, name: &str, scheme: &str) -> Option<&'a str> {
+    let value = request.headers().get(name)?.to_str().ok()?;
+    let (value_scheme, credentials) = value.split_once(' ')?;
+    value_scheme
+        .eq_ignore_ascii_case(scheme)
+        .then(|| credentials.trim())
+        .filter(|credentials| !credentials.is_empty())
+}
+
+fn parse_basic(value: &str) -> Option<Login> {
+    let decoded = aws_smithy_types::base64::decode(value).ok()?;
+    let decoded = String::from_utf8(decoded).ok()?;
+    let (username, password) = decoded.split_once(':')?;
+    Some(Login::new(username, password))
+}
+
+fn api_key<B>(request: &Request<B>, definition: &HttpAuthDefinition) -> Option<String> {
+    match definition.location() {
+        HttpAuthLocation::Header => match definition.scheme() {
+            Some(scheme) => header_with_scheme(request, definition.name(), scheme).map(str::to_owned),
+            None => request
+                .headers()
+                .get(definition.name())?
+                .to_str()
+                .ok()
+                .filter(|value| !value.is_empty())
+                .map(str::to_owned),
+        },
+        HttpAuthLocation::Query => {
+            let query = request.uri().query()?;
+            serde_urlencoded::from_str::<Vec<(String, String)>>(query)
+                .ok()?
+                .into_iter()
+                .find(|(name, value)| name == definition.name() && !value.is_empty())
+                .map(|(_, value)| value)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use http::StatusCode;
+    use tower::util::BoxCloneService;
+    use tower::{service_fn, ServiceExt};
+
+    use super::*;
+    use crate::body::{boxed, Body};
+    use crate::proto::rest_json_1::RestJson1;
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct User(String);
+
+    struct TestAuthenticator;
+
+    #[async_trait]
+    impl Authenticator for TestAuthenticator {
+        type Principal = User;
+
+        async fn authenticate(&self, _operation: &ShapeId, credentials: Credentials) -> Result<User, AuthError> {
+            match credentials {
+                Credentials::Basic(login) if login.username() == "guest" => Err(AuthError::AccessDenied),
+                Credentials::Basic(login) if login.password() == "hunter2" => Ok(User(login.username().to_owned())),
+                Credentials::Bearer(token) if token.token() == "token" => Ok(User("bearer".to_owned())),
+                Credentials::ApiKey(key) if key.api_key() == "key" => Ok(User("api-key".to_owned())),
+                _ => Err(AuthError::Unauthenticated),
+            }
+        }
+    }
+
+    const OPERATION: ShapeId = ShapeId::new("test#Operation", "test", "Operation");
+
+    fn service(
+        schemes: Vec<AuthScheme>,
+        optional: bool,
+    ) -> AuthService<RestJson1, BoxCloneService<Request<Body>, Response<BoxBody>, Infallible>, TestAuthenticator> {
+        let inner = BoxCloneService::new(service_fn(|request: Request<Body>| async move {
+            let body = match request.extensions().get::<User>() {
+                Some(User(name)) => name.clone(),
+                None => "anonymous".to_owned(),
+            };
+            Ok::<_, Infallible>(Response::new(boxed(Body::from(body))))
+        }));
+        AuthService {
+            inner,
+            authenticator: Arc::new(TestAuthenticator),
+            operation: Arc::new(OPERATION),
+            schemes: schemes.into(),
+            optional,
+            _protocol: PhantomData,
+        }
+    }
+
+    async fn call(
+        service: impl Service<Request<Body>, Response = Response<BoxBody>, Error = Infallible>,
+        request: http::request::Builder,
+    ) -> (StatusCode, http::HeaderMap, String) {
+        let response = service.oneshot(request.body(Body::empty()).unwrap()).await.unwrap();
+        let (parts, body) = response.into_parts();
+        let body = hyper::body::to_bytes(body).await.unwrap();
+        (parts.status, parts.headers, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    fn basic(username: &str, password: &str) -> String {
+        format!(
+            "Basic {}",
+            aws_smithy_types::base64::encode(format!("{username}:{password}"))
+        )
+    }
+
+    #[tokio::test]
+    async fn basic_auth() {
+        let schemes = || vec![AuthScheme::HttpBasic];
+        let request = Request::builder().header("Authorization", basic("alice", "hunter2"));
+        let (status, _, body) = call(service(schemes(), false), request).await;
+        assert_eq!((StatusCode::OK, "alice"), (status, body.as_str()));
+
+        let request = Request::builder().header("Authorization", basic("alice", "wrong"));
+        let (status, headers, _) = call(service(schemes(), false), request).await;
+        assert_eq!(StatusCode::UNAUTHORIZED, status);
+        assert_eq!("Basic", headers[WWW_AUTHENTICATE]);
+        assert_eq!("UnauthorizedException", headers["X-Amzn-Errortype"]);
+
+        let request = Request::builder().header("Authorization", basic("guest", "hunter2"));
+        let (status, headers, _) = call(service(schemes(), false), request).await;
+        assert_eq!(StatusCode::FORBIDDEN, status);
+        assert_eq!("AccessDeniedException", headers["X-Amzn-Errortype"]);
+
+        let request = Request::builder().header("Authorization", "Basic not-base64!");
+        let (status, _, _) = call(service(schemes(), false), request).await;
+        assert_eq!(StatusCode::UNAUTHORIZED, status);
+    }
+
+    #[tokio::test]
+    async fn malformed_credentials_fall_through_to_the_next_scheme() {
+        let schemes = || {
+            vec![
+                AuthScheme::HttpBasic,
+                AuthScheme::HttpApiKey(HttpAuthDefinition::query("api_key")),
+            ]
+        };
+        let request = Request::builder()
+            .uri("/?api_key=key")
+            .header("Authorization", "Basic not-base64!");
+        let (status, _, body) = call(service(schemes(), false), request).await;
+        assert_eq!((StatusCode::OK, "api-key"), (status, body.as_str()));
+
+        // Malformed credentials are still rejected when no other scheme has credentials, even with `@optionalAuth`
+        let request = Request::builder().header("Authorization", "Basic not-base64!");
+        let (status, _, _) = call(service(schemes(), true), request).await;
+        assert_eq!(StatusCode::UNAUTHORIZED, status);
+    }
+
+    #[tokio::test]
+    async fn schemes_are_tried_in_priority_order() {
+        let schemes = || {
+            vec![
+                AuthScheme::Other(ShapeId::new("aws.auth#sigv4", "aws.auth", "sigv4")),
+                AuthScheme::HttpBearer,
+                AuthScheme::HttpApiKey(HttpAuthDefinition::query("api_key")),
+            ]
+        };
+        let request = Request::builder().uri("/?api_key=key");
+        let (status, _, body) = call(service(schemes(), false), request).await;
+        assert_eq!((StatusCode::OK, "api-key"), (status, body.as_str()));
+
+        let request = Request::builder()
+            .uri("/?api_key=key")
+            .header("Authorization", "bearer token");
+        let (status, _, body) = call(service(schemes(), false), request).await;
+        assert_eq!((StatusCode::OK, "bearer"), (status, body.as_str()));
+
+        let (status, headers, _) = call(service(schemes(), false), Request::builder()).await;
+        assert_eq!(StatusCode::UNAUTHORIZED, status);
+        assert_eq!(
+            vec!["Bearer"],
+            headers.get_all(WWW_AUTHENTICATE).iter().collect::<Vec<_>>()
+        );
+    }
+
+    #[tokio::test]
+    async fn api_key_in_header() {
+        let schemes = || {
+            vec![
+                AuthScheme::HttpApiKey(HttpAuthDefinition::header("X-Api-Key", None)),
+                AuthScheme::HttpApiKey(HttpAuthDefinition::header("Authorization", "ApiKey".to_owned())),
+            ]
+        };
+        let request = Request::builder().header("X-Api-Key", "key");
+        let (status, _, body) = call(service(schemes(), false), request).await;
+        assert_eq!((StatusCode::OK, "api-key"), (status, body.as_str()));
+
+        let request = Request::builder().header("Authorization", "ApiKey key");
+        let (status, _, body) = call(service(schemes(), false), request).await;
+        assert_eq!((StatusCode::OK, "api-key"), (status, body.as_str()));
+
+        let request = Request::builder().header("Authorization", "Bearer key");
+        let (status, headers, _) = call(service(schemes(), false), request).await;
+        assert_eq!(StatusCode::UNAUTHORIZED, status);
+        assert_eq!("ApiKey", headers[WWW_AUTHENTICATE]);
+    }
+
+    #[tokio::test]
+    async fn optional_auth() {
+        let (status, _, body) = call(service(vec![AuthScheme::HttpBearer], true), Request::builder()).await;
+        assert_eq!((StatusCode::OK, "anonymous"), (status, body.as_str()));
+
+        let request = Request::builder().header("Authorization", "Bearer wrong");
+        let (status, _, _) = call(service(vec![AuthScheme::HttpBearer], true), request).await;
+        assert_eq!(StatusCode::UNAUTHORIZED, status);
+
+        let (status, _, body) = call(service(vec![], false), Request::builder()).await;
+        assert_eq!((StatusCode::OK, "anonymous"), (status, body.as_str()));
+    }
+
+    #[tokio::test]
+    async fn operations_without_supported_schemes_are_passed_through() {
+        let schemes = || vec![AuthScheme::Other(ShapeId::new("aws.auth#sigv4", "aws.auth", "sigv4"))];
+        let (status, _, body) = call(service(schemes(), false), Request::builder()).await;
+        assert_eq!((StatusCode::OK, "anonymous"), (status, body.as_str()));
+
+        // Credentials for a scheme the operation isn't modeled with are ignored.
+        let request = Request::builder().header("Authorization", basic("alice", "hunter2"));
+        let (status, _, body) = call(service(schemes(), false), request).await;
+        assert_eq!((StatusCode::OK, "anonymous"), (status, body.as_str()));
+    }
+
+    #[test]
+    fn credentials_are_redacted() {
+        let credentials = format!("{:?}", Credentials::Basic(Login::new("alice", "hunter2")));
+        assert!(!credentials.contains("hunter2"), "{credentials}");
+        let credentials = format!("{:?}", Credentials::Bearer(Token::new("token")));
+        assert!(!credentials.contains("\"token\""), "{credentials}");
+    }
+}
@@ -0,0 +1,468 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Limits on the size of request bodies.
+//!
+//! The [`BodyLimitPlugin`] rejects requests whose body is larger than the limit configured for the operation with the
+//! protocol's `413 Payload Too Large` error:
+//!
+//! - requests with a `Content-Length` header larger than the limit are rejected before the body is read;
+//! - other requests have their body wrapped in a [`LimitedBody`], which counts the bytes as they are read without
+//!   buffering them, and fails with a [`LengthLimitError`] once the limit is exceeded.
+//!
+//! The Query protocols are an exception: the [`QueryActionLayer`] has to buffer the whole body to find the action
+//! to route to, before any plugin runs. It limits the buffered body on its own, to
+//! [`QueryActionLayer::body_limit`] bytes ([`DEFAULT_BODY_LIMIT`] unless configured), so set it no lower than the
+//! largest limit configured here.
+//!
+//! [`QueryActionLayer`]: crate::proto::query::router::QueryActionLayer
+//! [`QueryActionLayer::body_limit`]: crate::proto::query::router::QueryActionLayer::body_limit
+//! [`DEFAULT_BODY_LIMIT`]: crate::proto::query::router::DEFAULT_BODY_LIMIT
+//!
+//! Operations whose input has a streaming member are subject to a separate [streaming
+//! limit](BodyLimitPlugin::streaming_limit), which is unset by default. Handlers of such operations see the
+//! [`LengthLimitError`] when reading the [`ByteStream`]; the `413` response takes precedence over whatever they
+//! return.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use aws_smithy_http_server::plugin::body_limit::BodyLimitPlugin;
+//! # use aws_smithy_http_server::plugin::PluginPipeline;
+//! # use aws_smithy_http_server::shape_id::ShapeId;
+//! # const UPLOAD_DOCUMENT: ShapeId = ShapeId::new("example#UploadDocument", "example", "UploadDocument");
+//! let body_limit = BodyLimitPlugin::new(1024 * 1024)
+//!     // Uploads may stream up to 1 GiB.
+//!     .streaming_limit(1024 * 1024 * 1024)
+//!     // This operation buffers larger documents.
+//!     .operation_limit(UPLOAD_DOCUMENT, 16 * 1024 * 1024);
+//! let plugins = PluginPipeline::new().push(body_limit);
+//! ```
+
+use std::collections::HashMap;
+use std::error::Error as StdError;
+use std::fmt;
+use std::future::Future;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use aws_smithy_http::body::SdkBody;
+use aws_smithy_http::byte_stream::ByteStream;
+use bytes::Buf;
+use futures_util::ready;
+use http::header::CONTENT_LENGTH;
+use http::{HeaderMap, Request, Response};
+use http_body::SizeHint;
+use pin_project_lite::pin_project;
+use tower::Service;
+
+use crate::body::{BoxBody, HttpBody};
+use crate::error::BoxError;
+use crate::operation::OperationShape;
+use crate::plugin::Plugin;
+use crate::response::IntoResponse;
+use crate::runtime_error::PayloadTooLargeException;
+use crate::service::ServiceShape;
+use crate::shape_id::ShapeId;
+
+/// The shape of an operation's request body.
+///
+/// This should not be implemented by hand. Code generation implements it for every operation.
+pub trait OperationBodyShape {
+    /// Whether the operation input has a streaming member, which is read from the request body as it arrives.
+    const STREAMING_INPUT: bool = false;
+}
+
+/// A [`Plugin`] that limits the size of request bodies. See the [module](self) documentation for more information.
+#[derive(Clone, Debug)]
+pub struct BodyLimitPlugin {
+    limit: u64,
+    streaming_limit: Option<u64>,
+    operation_limits: HashMap<ShapeId, u64>,
+}
+
+impl BodyLimitPlugin {
+    /// Limits the request bodies of operations without a streaming input member to `limit` bytes.
+    pub fn new(limit: u64) -> Self {
+        Self {
+            limit,
+            streaming_limit: None,
+            operation_limits: HashMap::new(),
+        }
+    }
+
+    /// Limits the request bodies of operations with a streaming input member to `limit` bytes. They are not limited
+    /// by default.
+    pub fn streaming_limit(mut self, limit: u64) -> Self {
+        self.streaming_limit = Some(limit);
+        self
+    }
+
+    /// Limits the request bodies of `operation` to `limit` bytes, regardless of whether its input is streaming.
+    pub fn operation_limit(mut self, operation: ShapeId, limit: u64) -> Self {
+        self.operation_limits.insert(operation, limit);
+        self
+    }
+
+    fn limit_for(&self, operation: &ShapeId, streaming_input: bool) -> Option<u64> {
+        match self.operation_limits.get(operation) {
+            Some(limit) => Some(*limit),
+            None if streaming_input => self.streaming_limit,
+            None => Some(self.limit),
+        }
+    }
+}
+
+impl<Ser, Op, T> Plugin<Ser, Op, T> for BodyLimitPlugin
+where
+    Ser: ServiceShape,
+    Op: OperationShape + OperationBodyShape,
+{
+    type Output = BodyLimitService<Ser::Protocol, T>;
+
+    fn apply(&self, inner: T) -> Self::Output {
+        BodyLimitService {
+            inner,
+            limit: self.limit_for(&Op::ID, Op::STREAMING_INPUT),
+            _protocol: PhantomData,
+        }
+    }
+}
+
+/// A [`Service`] that limits the size of request bodies. Applied by [`BodyLimitPlugin`].
+pub struct BodyLimitService<P, S> {
+    inner: S,
+    limit: Option<u64>,
+    _protocol: PhantomData<fn() -> P>,
+}
+
+impl<P, S> Clone for BodyLimitService<P, S>
+where
+    S: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            limit: self.limit,
+            _protocol: PhantomData,
+        }
+    }
+}
+
+impl<P, S> fmt::Debug for BodyLimitService<P, S>
+where
+    S: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BodyLimitService")
+            .field("inner", &self.inner)
+            .field("limit", &self.limit)
+            .finish()
+    }
+}
+
+impl<P, S, B> Service<Request<B>> for BodyLimitService<P, S>
+where
+    S: Service<Request<LimitedBody<B>>, Response = Response<BoxBody>>,
+    PayloadTooLargeException: IntoResponse<P>,
+{
+    type Response = Response<BoxBody>;
+    type Error = S::Error;
+    type Future = BodyLimitFuture<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<B>) -> Self::Future {
+        let payload_too_large = || IntoResponse::<P>::into_response(PayloadTooLargeException);
+        if let (Some(limit), Some(content_length)) = (self.limit, content_length(request.headers())) {
+            if content_length > limit {
+                return BodyLimitFuture::Rejected {
+                    response: Some(payload_too_large()),
+                };
+            }
+        }
+
+        let exceeded = Arc::new(AtomicBool::new(false));
+        let limit = self.limit;
+        let request = request.map(|body| LimitedBody::new(body, limit, exceeded.clone()));
+        BodyLimitFuture::Inner {
+            future: self.inner.call(request),
+            exceeded,
+            payload_too_large,
+        }
+    }
+}
+
+fn content_length(headers: &HeaderMap) -> Option<u64> {
+    headers.get(CONTENT_LENGTH)?.to_str().ok()?.parse().ok()
+}
+
+pin_project! {
+    /// The [`Future`] returned by [`BodyLimitService`].
+    #[project = BodyLimitFutureProj]
+    pub enum BodyLimitFuture<F> {
+        Rejected {
+            response: Option<Response<BoxBody>>,
+        },
+        Inner {
+            #[pin]
+            future: F,
+            exceeded: Arc<AtomicBool>,
+            payload_too_large: fn() -> Response<BoxBody>,
+        },
+    }
+}
+
+impl<F, E> Future for BodyLimitFuture<F>
+where
+    F: Future<Output = Result<Response<BoxBody>, E>>,
+{
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match self.project() {
+            BodyLimitFutureProj::Rejected { response } => {
+                Poll::Ready(Ok(response.take().expect("polled after completion")))
+            }
+            BodyLimitFutureProj::Inner {
+                future,
+                exceeded,
+                payload_too_large,
+            } => {
+                let response = ready!(future.poll(cx))?;
+                // Whatever the inner service made of the body error, the request was too large.
+                if exceeded.load(Ordering::Acquire) {
+                    Poll::Ready(Ok(payload_too_large()))
+                } else {
+                    Poll::Ready(Ok(response))
+                }
+            }
+        }
+    }
+}
+
+/// The error returned by a [`LimitedBody`] once more bytes than its limit have been read.
+#[derive(Debug)]
+pub struct LengthLimitError {
+    limit: u64,
+}
+
+impl LengthLimitError {
+    /// Returns the limit that was exceeded, in bytes.
+    pub fn limit(&self) -> u64 {
+        self.limit
+    }
+}
+
+impl fmt::Display for LengthLimitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "request body is larger than the limit of {} bytes", self.limit)
+    }
+}
+
+impl StdError for LengthLimitError {}
+
+pin_project! {
+    /// A request body that fails with a [`LengthLimitError`] once more bytes than its limit have been read.
+    pub struct LimitedBody<B> {
+        #[pin]
+        inner: B,
+        limit: Option<u64>,
+        read: u64,
+        exceeded: Arc<AtomicBool>,
+    }
+}
+
+impl<B> LimitedBody<B> {
+    fn new(inner: B, limit: Option<u64>, exceeded: Arc<AtomicBool>) -> Self {
+        Self {
+            inner,
+            limit,
+            read: 0,
+            exceeded,
+        }
+    }
+
+    fn map_inner<C>(self, f: impl FnOnce(B) -> C) -> LimitedBody<C> {
+        LimitedBody {
+            inner: f(self.inner),
+            limit: self.limit,
+            read: self.read,
+            exceeded: self.exceeded,
+        }
+    }
+}
+
+impl<B> fmt::Debug for LimitedBody<B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LimitedBody")
+            .field("limit", &self.limit)
+            .field("read", &self.read)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<B> HttpBody for LimitedBody<B>
+where
+    B: HttpBody,
+    B::Error: Into<BoxError>,
+{
+    type Data = B::Data;
+    type Error = BoxError;
+
+    fn poll_data(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Result<Self::Data, Self::Error>>> {
+        let this = self.project();
+        if let Some(limit) = *this.limit {
+            if this.exceeded.load(Ordering::Acquire) {
+                return Poll::Ready(Some(Err(LengthLimitError { limit }.into())));
+            }
+        }
+        let data = match ready!(this.inner.poll_data(cx)) {
+            Some(Ok(data)) => data,
+            Some(Err(err)) => return Poll::Ready(Some(Err(err.into()))),
+            None => return Poll::Ready(None),
+        };
+        *this.read += data.remaining() as u64;
+        match *this.limit {
+            Some(limit) if *this.read > limit => {
+                this.exceeded.store(true, Ordering::Release);
+                Poll::Ready(Some(Err(LengthLimitError { limit }.into())))
+            }
+            _ => Poll::Ready(Some(Ok(data))),
+        }
+    }
+
+    fn poll_trailers(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<Option<HeaderMap>, Self::Error>> {
+        self.project().inner.poll_trailers(cx).map_err(Into::into)
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.inner.size_hint()
+    }
+}
+
+// Required by the generated deserializers of operations with a streaming input member.
+impl<B> From<LimitedBody<B>> for ByteStream
+where
+    B: Into<ByteStream>,
+{
+    fn from(body: LimitedBody<B>) -> Self {
+        let body = body.map_inner(|inner| inner.into().into_inner());
+        ByteStream::new(SdkBody::from_dyn(http_body::combinators::BoxBody::new(body)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use futures_util::TryStreamExt;
+    use http::StatusCode;
+    use tower::util::BoxCloneService;
+    use tower::{service_fn, ServiceExt};
+
+    use super::*;
+    use crate::body::{boxed, Body};
+    use crate::proto::rest_json_1::RestJson1;
+
+    type Inner = BoxCloneService<Request<LimitedBody<Body>>, Response<BoxBody>, Infallible>;
+
+    fn service(limit: Option<u64>, inner: Inner) -> BodyLimitService<RestJson1, Inner> {
+        BodyLimitService {
+            inner,
+            limit,
+            _protocol: PhantomData,
+        }
+    }
+
+    /// Buffers the body, responding with `400 Bad Request` if that fails like generated deserializers do.
+    fn buffering() -> Inner {
+        BoxCloneService::new(service_fn(|request: Request<LimitedBody<Body>>| async move {
+            let response = match hyper::body::to_bytes(request.into_body()).await {
+                Ok(bytes) => Response::new(boxed(Body::from(bytes))),
+                Err(_) => Response::builder()
+                    .status(StatusCode::BAD_REQUEST)
+                    .body(boxed(Body::empty()))
+                    .unwrap(),
+            };
+            Ok(response)
+        }))
+    }
+
+    fn chunked(chunks: &[&'static str]) -> Body {
+        let chunks: Vec<Result<_, Infallible>> = chunks.iter().map(|chunk| Ok(*chunk)).collect();
+        Body::wrap_stream(futures_util::stream::iter(chunks))
+    }
+
+    #[tokio::test]
+    async fn rejects_large_content_length_without_reading_the_body() {
+        // Reading this body would never complete.
+        let (_sender, body) = Body::channel();
+        let request = Request::builder().header(CONTENT_LENGTH, "11").body(body).unwrap();
+        let response = service(Some(10), buffering()).oneshot(request).await.unwrap();
+        assert_eq!(StatusCode::PAYLOAD_TOO_LARGE, response.status());
+        assert_eq!("PayloadTooLargeException", response.headers()["X-Amzn-Errortype"]);
+    }
+
+    #[tokio::test]
+    async fn counts_bytes_of_chunked_bodies() {
+        let request = Request::new(chunked(&["hello", " ", "world"]));
+        let response = service(Some(11), buffering()).oneshot(request).await.unwrap();
+        assert_eq!(StatusCode::OK, response.status());
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        assert_eq!("hello world", body);
+
+        let request = Request::new(chunked(&["hello", " ", "world!"]));
+        let response = service(Some(11), buffering()).oneshot(request).await.unwrap();
+        assert_eq!(StatusCode::PAYLOAD_TOO_LARGE, response.status());
+    }
+
+    #[tokio::test]
+    async fn unlimited() {
+        let request = Request::builder()
+            .header(CONTENT_LENGTH, "11")
+            .body(Body::from("hello world"))
+            .unwrap();
+        let response = service(None, buffering()).oneshot(request).await.unwrap();
+        assert_eq!(StatusCode::OK, response.status());
+    }
+
+    #[tokio::test]
+    async fn streaming_bodies_fail_with_length_limit_error() {
+        let exceeded = Arc::new(AtomicBool::new(false));
+        let body = LimitedBody::new(chunked(&["hello", " ", "world"]), Some(6), exceeded.clone());
+        let mut stream = ByteStream::from(body);
+        assert_eq!("hello", stream.try_next().await.unwrap().unwrap());
+        assert_eq!(" ", stream.try_next().await.unwrap().unwrap());
+        let err = stream.try_next().await.unwrap_err();
+        let source = err
+            .source()
+            .and_then(|source| source.downcast_ref::<LengthLimitError>());
+        assert_eq!(Some(6), source.map(LengthLimitError::limit), "{err:?}");
+        assert!(exceeded.load(Ordering::Acquire));
+    }
+
+    #[test]
+    fn operation_limits_take_precedence() {
+        const OPERATION: ShapeId = ShapeId::new("test#Operation", "test", "Operation");
+        const OTHER: ShapeId = ShapeId::new("test#Other", "test", "Other");
+
+        let plugin = BodyLimitPlugin::new(10).operation_limit(OPERATION, 20);
+        assert_eq!(Some(20), plugin.limit_for(&OPERATION, false));
+        assert_eq!(Some(20), plugin.limit_for(&OPERATION, true));
+        assert_eq!(Some(10), plugin.limit_for(&OTHER, false));
+        assert_eq!(None, plugin.limit_for(&OTHER, true));
+        assert_eq!(Some(30), plugin.streaming_limit(30).limit_for(&OTHER, true));
+    }
+}
@@ -141,6 +141,8 @@
 //!
 
 pub mod alb_health_check;
+pub mod auth;
+pub mod body_limit;
 mod closure;
 mod either;
 mod filter;
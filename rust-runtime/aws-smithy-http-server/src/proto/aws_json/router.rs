@@ -104,6 +104,15 @@ where
         let route = self.routes.get(target).ok_or(Error::NotFound)?;
         Ok(route.clone())
     }
+
+    fn allowed_methods(&self, request: &http::Request<B>) -> Vec<http::Method> {
+        // All operations are `POST`s to the root URI.
+        if request.uri().path() == "/" {
+            vec![http::Method::POST]
+        } else {
+            Vec::new()
+        }
+    }
 }
 
 impl<S> FromIterator<(String, S)> for AwsJsonRouter<S> {
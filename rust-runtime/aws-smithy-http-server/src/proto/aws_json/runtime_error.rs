@@ -5,7 +5,10 @@
 
 use crate::proto::aws_json_11::AwsJson1_1;
 use crate::response::IntoResponse;
-use crate::runtime_error::{InternalFailureException, INVALID_HTTP_RESPONSE_FOR_RUNTIME_ERROR_PANIC_MESSAGE};
+use crate::runtime_error::{
+    AccessDeniedException, InternalFailureException, PayloadTooLargeException, UnauthorizedException,
+    INVALID_HTTP_RESPONSE_FOR_RUNTIME_ERROR_PANIC_MESSAGE,
+};
 use crate::{extension::RuntimeErrorExtension, proto::aws_json_10::AwsJson1_0};
 use http::StatusCode;
 
@@ -48,47 +51,89 @@ impl IntoResponse<AwsJson1_0> for InternalFailureException {
     }
 }
 
+impl IntoResponse<AwsJson1_0> for UnauthorizedException {
+    fn into_response(self) -> http::Response<crate::body::BoxBody> {
+        error_response(Self::NAME, Self::STATUS_CODE, AWS_JSON_1_0, crate::body::to_boxed("{}"))
+    }
+}
+
+impl IntoResponse<AwsJson1_0> for AccessDeniedException {
+    fn into_response(self) -> http::Response<crate::body::BoxBody> {
+        error_response(Self::NAME, Self::STATUS_CODE, AWS_JSON_1_0, crate::body::to_boxed("{}"))
+    }
+}
+
+impl IntoResponse<AwsJson1_0> for PayloadTooLargeException {
+    fn into_response(self) -> http::Response<crate::body::BoxBody> {
+        error_response(Self::NAME, Self::STATUS_CODE, AWS_JSON_1_0, crate::body::to_boxed("{}"))
+    }
+}
+
 impl IntoResponse<AwsJson1_1> for InternalFailureException {
     fn into_response(self) -> http::Response<crate::body::BoxBody> {
         IntoResponse::<AwsJson1_1>::into_response(RuntimeError::InternalFailure(crate::Error::new(String::new())))
     }
 }
 
-impl IntoResponse<AwsJson1_0> for RuntimeError {
+impl IntoResponse<AwsJson1_1> for UnauthorizedException {
     fn into_response(self) -> http::Response<crate::body::BoxBody> {
-        let res = http::Response::builder()
-            .status(self.status_code())
-            .header("Content-Type", "application/x-amz-json-1.0")
-            .extension(RuntimeErrorExtension::new(self.name().to_string()));
+        error_response(Self::NAME, Self::STATUS_CODE, AWS_JSON_1_1, crate::body::to_boxed(""))
+    }
+}
 
+impl IntoResponse<AwsJson1_1> for AccessDeniedException {
+    fn into_response(self) -> http::Response<crate::body::BoxBody> {
+        error_response(Self::NAME, Self::STATUS_CODE, AWS_JSON_1_1, crate::body::to_boxed(""))
+    }
+}
+
+impl IntoResponse<AwsJson1_1> for PayloadTooLargeException {
+    fn into_response(self) -> http::Response<crate::body::BoxBody> {
+        error_response(Self::NAME, Self::STATUS_CODE, AWS_JSON_1_1, crate::body::to_boxed(""))
+    }
+}
+
+impl IntoResponse<AwsJson1_0> for RuntimeError {
+    fn into_response(self) -> http::Response<crate::body::BoxBody> {
+        let (name, status_code) = (self.name(), self.status_code());
         let body = match self {
             RuntimeError::Validation(reason) => crate::body::to_boxed(reason),
             // See https://awslabs.github.io/smithy/2.0/aws/protocols/aws-json-1_0-protocol.html#empty-body-serialization
             _ => crate::body::to_boxed("{}"),
         };
-
-        res.body(body)
-            .expect(INVALID_HTTP_RESPONSE_FOR_RUNTIME_ERROR_PANIC_MESSAGE)
+        error_response(name, status_code, AWS_JSON_1_0, body)
     }
 }
 
 impl IntoResponse<AwsJson1_1> for RuntimeError {
     fn into_response(self) -> http::Response<crate::body::BoxBody> {
-        let res = http::Response::builder()
-            .status(self.status_code())
-            .header("Content-Type", "application/x-amz-json-1.1")
-            .extension(RuntimeErrorExtension::new(self.name().to_string()));
-
+        let (name, status_code) = (self.name(), self.status_code());
         let body = match self {
             RuntimeError::Validation(reason) => crate::body::to_boxed(reason),
             _ => crate::body::to_boxed(""),
         };
-
-        res.body(body)
-            .expect(INVALID_HTTP_RESPONSE_FOR_RUNTIME_ERROR_PANIC_MESSAGE)
+        error_response(name, status_code, AWS_JSON_1_1, body)
     }
 }
 
+const AWS_JSON_1_0: &str = "application/x-amz-json-1.0";
+const AWS_JSON_1_1: &str = "application/x-amz-json-1.1";
+
+/// Renders an error named `name`, either a [`RuntimeError`] or a protocol-agnostic error raised by a plugin.
+fn error_response(
+    name: &'static str,
+    status_code: StatusCode,
+    content_type: &'static str,
+    body: crate::body::BoxBody,
+) -> http::Response<crate::body::BoxBody> {
+    http::Response::builder()
+        .status(status_code)
+        .header("Content-Type", content_type)
+        .extension(RuntimeErrorExtension::new(name.to_string()))
+        .body(body)
+        .expect(INVALID_HTTP_RESPONSE_FOR_RUNTIME_ERROR_PANIC_MESSAGE)
+}
+
 impl From<ResponseRejection> for RuntimeError {
     fn from(err: ResponseRejection) -> Self {
         Self::Serialization(crate::Error::new(err))
@@ -0,0 +1,112 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use crate::body::BoxBody;
+use crate::proto::query::runtime_error::aws_query_error_response;
+use crate::response::IntoResponse;
+use crate::routing::{method_disallowed, UNKNOWN_OPERATION_EXCEPTION};
+
+use super::AwsQuery;
+
+pub use crate::proto::query::router::*;
+
+impl IntoResponse<AwsQuery> for Error {
+    fn into_response(self) -> http::Response<BoxBody> {
+        match self {
+            Error::MethodNotAllowed => method_disallowed(),
+            _ => aws_query_error_response(UNKNOWN_OPERATION_EXCEPTION, http::StatusCode::NOT_FOUND, None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proto::query::envelope::aws_query_response;
+    use crate::proto::query::runtime_error::tests::body_without_request_id;
+    use crate::proto::test_helpers::get_body_as_string;
+    use crate::routing::RoutingService;
+    use aws_smithy_query::decode::QueryDocument;
+    use std::convert::Infallible;
+    use tower::{service_fn, Layer, ServiceExt};
+
+    fn request(body: &'static str) -> http::Request<hyper::Body> {
+        http::Request::builder()
+            .method(http::Method::POST)
+            .uri("/")
+            .header("content-type", "application/x-www-form-urlencoded")
+            .body(hyper::Body::from(body))
+            .unwrap()
+    }
+
+    // Echoes a flattened list, a member renamed with `@xmlName` and a map back as an AWS Query response.
+    async fn echo(request: http::Request<hyper::Body>) -> Result<http::Response<BoxBody>, Infallible> {
+        let body = hyper::body::to_bytes(request.into_body()).await.unwrap();
+        let doc = QueryDocument::parse(&body).unwrap();
+        let input = doc.reader();
+        let items: Vec<_> = input
+            .member("Item")
+            .list(true, None)
+            .unwrap_or_default()
+            .iter()
+            .map(|item| item.string().unwrap().to_string())
+            .collect();
+        let renamed = input.member("Renamed").string().unwrap_or_default().to_string();
+        let attributes = input
+            .member("Attribute")
+            .map(true, "Name", "Value")
+            .unwrap()
+            .unwrap_or_default();
+        let attributes: Vec<_> = attributes
+            .iter()
+            .map(|(key, value)| format!("{key}={}", value.string().unwrap()))
+            .collect();
+
+        let body = aws_query_response("Echo", Some("https://example.com/doc/"), Some("abc"), |result| {
+            for item in &items {
+                result.start_el("Item").finish().data(item);
+            }
+            result.start_el("Renamed").finish().data(&renamed);
+            result.start_el("Attributes").finish().data(&attributes.join(","));
+        });
+        Ok(http::Response::builder()
+            .header("content-type", "text/xml")
+            .body(crate::body::to_boxed(body))
+            .unwrap())
+    }
+
+    #[tokio::test]
+    async fn routes_and_responds_with_envelopes() {
+        let router: QueryRouter<_> = [("Echo".to_string(), service_fn(echo))].into_iter().collect();
+        let service = QueryActionLayer::<AwsQuery>::new()
+            .layer(RoutingService::<_, AwsQuery>::new(router.with_version("2020-01-08")));
+
+        let response = service
+            .clone()
+            .oneshot(request(
+                "Action=Echo&Version=2020-01-08&Item.1=a&Item.2=b&Renamed=r\
+                 &Attribute.1.Name=k&Attribute.1.Value=v",
+            ))
+            .await
+            .unwrap();
+        assert_eq!(http::StatusCode::OK, response.status());
+        assert_eq!(
+            "<EchoResponse xmlns=\"https://example.com/doc/\"><EchoResult><Item>a</Item><Item>b</Item>\
+             <Renamed>r</Renamed><Attributes>k=v</Attributes></EchoResult>\
+             <ResponseMetadata><RequestId>abc</RequestId></ResponseMetadata></EchoResponse>",
+            get_body_as_string(response.into_body()).await
+        );
+
+        let response = service
+            .oneshot(request("Action=Unknown&Version=2020-01-08"))
+            .await
+            .unwrap();
+        assert_eq!(http::StatusCode::NOT_FOUND, response.status());
+        assert_eq!(
+            "<ErrorResponse><Error><Type>Sender</Type><Code>UnknownOperationException</Code></Error></ErrorResponse>",
+            body_without_request_id(response, "RequestId").await
+        );
+    }
+}
@@ -0,0 +1,22 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use crate::body::BoxBody;
+use crate::proto::query::runtime_error::ec2_query_error_response;
+use crate::response::IntoResponse;
+use crate::routing::{method_disallowed, UNKNOWN_OPERATION_EXCEPTION};
+
+use super::Ec2Query;
+
+pub use crate::proto::query::router::*;
+
+impl IntoResponse<Ec2Query> for Error {
+    fn into_response(self) -> http::Response<BoxBody> {
+        match self {
+            Error::MethodNotAllowed => method_disallowed(),
+            _ => ec2_query_error_response(UNKNOWN_OPERATION_EXCEPTION, http::StatusCode::NOT_FOUND, None),
+        }
+    }
+}
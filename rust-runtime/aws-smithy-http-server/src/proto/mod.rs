@@ -6,6 +6,9 @@
 pub mod aws_json;
 pub mod aws_json_10;
 pub mod aws_json_11;
+pub mod aws_query;
+pub mod ec2_query;
+pub mod query;
 pub mod rest;
 pub mod rest_json_1;
 pub mod rest_xml;
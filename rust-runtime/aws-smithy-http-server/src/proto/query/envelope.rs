@@ -0,0 +1,194 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! XML envelopes wrapping [AWS Query] and [EC2 Query] responses and errors.
+//!
+//! [AWS Query]: https://smithy.io/2.0/aws/protocols/aws-query-protocol.html
+//! [EC2 Query]: https://smithy.io/2.0/aws/protocols/aws-ec2-query-protocol.html
+
+use aws_smithy_xml::encode::{ScopeWriter, XmlWriter};
+
+/// Which party is at fault for an [AWS Query] error.
+///
+/// [AWS Query]: https://smithy.io/2.0/aws/protocols/aws-query-protocol.html#operation-error-serialization
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fault {
+    /// The client sent an invalid request.
+    Sender,
+    /// The server failed to process a valid request.
+    Receiver,
+}
+
+impl Fault {
+    /// Returns the fault corresponding to an HTTP status code.
+    pub fn from_status(status: http::StatusCode) -> Self {
+        if status.is_server_error() {
+            Self::Receiver
+        } else {
+            Self::Sender
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Sender => "Sender",
+            Self::Receiver => "Receiver",
+        }
+    }
+}
+
+/// Wraps the output of `operation` in an AWS Query response envelope.
+///
+/// `write_result` writes the output members into the `<{operation}Result>` element.
+pub fn aws_query_response(
+    operation: &str,
+    namespace: Option<&str>,
+    request_id: Option<&str>,
+    write_result: impl FnOnce(&mut ScopeWriter<'_, '_>),
+) -> String {
+    let mut out = String::new();
+    let mut writer = XmlWriter::new(&mut out);
+    let response_tag = format!("{operation}Response");
+    let mut response = writer.start_el(&response_tag);
+    if let Some(namespace) = namespace {
+        response = response.write_ns(namespace, None);
+    }
+    let mut response = response.finish();
+    let result_tag = format!("{operation}Result");
+    let mut result = response.start_el(&result_tag).finish();
+    write_result(&mut result);
+    result.finish();
+    if let Some(request_id) = request_id {
+        let mut metadata = response.start_el("ResponseMetadata").finish();
+        metadata.start_el("RequestId").finish().data(request_id);
+        metadata.finish();
+    }
+    response.finish();
+    out
+}
+
+/// Wraps an AWS Query error in an `<ErrorResponse>` envelope.
+pub fn aws_query_error(fault: Fault, code: &str, message: Option<&str>, request_id: Option<&str>) -> String {
+    let mut out = String::new();
+    let mut writer = XmlWriter::new(&mut out);
+    let mut response = writer.start_el("ErrorResponse").finish();
+    let mut error = response.start_el("Error").finish();
+    error.start_el("Type").finish().data(fault.as_str());
+    error.start_el("Code").finish().data(code);
+    if let Some(message) = message {
+        error.start_el("Message").finish().data(message);
+    }
+    error.finish();
+    if let Some(request_id) = request_id {
+        response.start_el("RequestId").finish().data(request_id);
+    }
+    response.finish();
+    out
+}
+
+/// Wraps the output of `operation` in an EC2 Query response envelope.
+///
+/// Unlike AWS Query, output members are written directly into the `<{operation}Response>` element.
+pub fn ec2_query_response(
+    operation: &str,
+    namespace: Option<&str>,
+    request_id: Option<&str>,
+    write_members: impl FnOnce(&mut ScopeWriter<'_, '_>),
+) -> String {
+    let mut out = String::new();
+    let mut writer = XmlWriter::new(&mut out);
+    let response_tag = format!("{operation}Response");
+    let mut response = writer.start_el(&response_tag);
+    if let Some(namespace) = namespace {
+        response = response.write_ns(namespace, None);
+    }
+    let mut response = response.finish();
+    if let Some(request_id) = request_id {
+        response.start_el("requestId").finish().data(request_id);
+    }
+    write_members(&mut response);
+    response.finish();
+    out
+}
+
+/// Wraps an EC2 Query error in a `<Response>` envelope.
+pub fn ec2_query_error(code: &str, message: Option<&str>, request_id: Option<&str>) -> String {
+    let mut out = String::new();
+    let mut writer = XmlWriter::new(&mut out);
+    let mut response = writer.start_el("Response").finish();
+    let mut errors = response.start_el("Errors").finish();
+    let mut error = errors.start_el("Error").finish();
+    error.start_el("Code").finish().data(code);
+    if let Some(message) = message {
+        error.start_el("Message").finish().data(message);
+    }
+    error.finish();
+    errors.finish();
+    if let Some(request_id) = request_id {
+        response.start_el("RequestID").finish().data(request_id);
+    }
+    response.finish();
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn aws_query_response_envelope() {
+        let body = aws_query_response(
+            "GetQueueUrl",
+            Some("http://queue.amazonaws.com/doc/2012-11-05/"),
+            Some("req-1"),
+            |result| result.start_el("QueueUrl").finish().data("https://q/1"),
+        );
+        assert_eq!(
+            "<GetQueueUrlResponse xmlns=\"http://queue.amazonaws.com/doc/2012-11-05/\">\
+             <GetQueueUrlResult><QueueUrl>https://q/1</QueueUrl></GetQueueUrlResult>\
+             <ResponseMetadata><RequestId>req-1</RequestId></ResponseMetadata>\
+             </GetQueueUrlResponse>",
+            body
+        );
+    }
+
+    #[test]
+    fn aws_query_error_envelope() {
+        let body = aws_query_error(
+            Fault::from_status(http::StatusCode::BAD_REQUEST),
+            "InvalidParameterValue",
+            Some("a < b"),
+            None,
+        );
+        assert_eq!(
+            "<ErrorResponse><Error><Type>Sender</Type><Code>InvalidParameterValue</Code>\
+             <Message>a &lt; b</Message></Error></ErrorResponse>",
+            body
+        );
+        assert_eq!(
+            Fault::Receiver,
+            Fault::from_status(http::StatusCode::INTERNAL_SERVER_ERROR)
+        );
+    }
+
+    #[test]
+    fn ec2_query_envelopes() {
+        let body = ec2_query_response("DescribeVpcs", None, Some("req-2"), |members| {
+            members.start_el("vpcSet").finish();
+        });
+        assert_eq!(
+            "<DescribeVpcsResponse><requestId>req-2</requestId><vpcSet></vpcSet></DescribeVpcsResponse>",
+            body
+        );
+
+        let body = ec2_query_error("InvalidVpcID.NotFound", Some("missing"), Some("req-3"));
+        assert_eq!(
+            "<Response><Errors><Error><Code>InvalidVpcID.NotFound</Code><Message>missing</Message></Error>\
+             </Errors><RequestID>req-3</RequestID></Response>",
+            body
+        );
+    }
+}
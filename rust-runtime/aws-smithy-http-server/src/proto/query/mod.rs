@@ -0,0 +1,18 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Functionality shared by the [`AwsQuery`](crate::proto::aws_query::AwsQuery) and
+//! [`Ec2Query`](crate::proto::ec2_query::Ec2Query) protocols.
+//!
+//! These protocols are only supported by the runtime, for services whose operations are written by
+//! hand: the server code generator doesn't generate awsQuery or ec2Query services. The request,
+//! response and error vectors of the Smithy protocol test suites are run against the runtime instead.
+
+pub mod envelope;
+#[cfg(test)]
+mod protocol_tests;
+pub mod rejection;
+pub mod router;
+pub mod runtime_error;
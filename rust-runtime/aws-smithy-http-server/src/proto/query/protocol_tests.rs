@@ -0,0 +1,474 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Vectors from the Smithy [AWS Query] and [EC2 Query] protocol test suites, run against the runtime.
+//!
+//! Request vectors are routed through the [`QueryActionLayer`] and decoded with [`QueryDocument`], and response and
+//! error vectors are compared with the [envelopes](super::envelope) wrapping the same members.
+//!
+//! [AWS Query]: https://github.com/smithy-lang/smithy/tree/main/smithy-aws-protocol-tests/model/awsQuery
+//! [EC2 Query]: https://github.com/smithy-lang/smithy/tree/main/smithy-aws-protocol-tests/model/ec2Query
+
+use std::convert::Infallible;
+
+use aws_smithy_protocol_test::{validate_body, MediaType};
+use aws_smithy_query::decode::{QueryDocument, QueryValueReader};
+use aws_smithy_types::date_time::Format;
+use aws_smithy_types::DateTime;
+use aws_smithy_xml::encode::ScopeWriter;
+use pretty_assertions::assert_eq;
+use tower::{service_fn, Layer, ServiceExt};
+
+use super::envelope::{aws_query_error, aws_query_response, ec2_query_error, ec2_query_response, Fault};
+use super::router::{QueryActionLayer, QueryRouter};
+use crate::body::{empty, BoxBody};
+use crate::proto::aws_query::AwsQuery;
+use crate::proto::ec2_query::Ec2Query;
+use crate::response::IntoResponse;
+use crate::routing::RoutingService;
+
+const OPERATIONS: [&str; 5] = [
+    "SimpleInputParams",
+    "QueryLists",
+    "QueryMaps",
+    "QueryTimestamps",
+    "NestedStructures",
+];
+
+/// Routes a request vector to its operation, and returns the document the operation received.
+async fn received<P>(body: &'static str) -> QueryDocument
+where
+    P: Send + 'static,
+    super::router::Error: IntoResponse<P>,
+    crate::runtime_error::PayloadTooLargeException: IntoResponse<P>,
+    super::runtime_error::RuntimeError: IntoResponse<P>,
+{
+    let operation = |request: http::Request<hyper::Body>| async move {
+        let body = hyper::body::to_bytes(request.into_body()).await.unwrap();
+        let mut response = http::Response::new(empty());
+        response.extensions_mut().insert(QueryDocument::parse(&body).unwrap());
+        Ok::<_, Infallible>(response)
+    };
+    let router: QueryRouter<_> = OPERATIONS
+        .iter()
+        .map(|name| (name.to_string(), service_fn(operation)))
+        .collect();
+    let service = QueryActionLayer::<P>::new().layer(RoutingService::<_, P>::new(router.with_version("2020-01-08")));
+
+    let request = http::Request::builder()
+        .method(http::Method::POST)
+        .uri("/")
+        .header("content-type", "application/x-www-form-urlencoded")
+        .body(hyper::Body::from(body))
+        .unwrap();
+    let response: http::Response<BoxBody> = service.oneshot(request).await.unwrap();
+    assert_eq!(http::StatusCode::OK, response.status(), "{body}");
+    response.extensions().get::<QueryDocument>().unwrap().clone()
+}
+
+fn strings<'a>(list: Option<Vec<QueryValueReader<'a>>>) -> Vec<&'a str> {
+    list.unwrap().iter().map(|value| value.string().unwrap()).collect()
+}
+
+fn map_strings<'a>(map: Option<Vec<(&'a str, QueryValueReader<'a>)>>) -> Vec<(&'a str, &'a str)> {
+    map.unwrap()
+        .into_iter()
+        .map(|(key, value)| (key, value.string().unwrap()))
+        .collect()
+}
+
+#[tokio::test]
+async fn aws_query_simple_input_params() {
+    // QuerySimpleInputParamsStrings
+    let doc = received::<AwsQuery>("Action=SimpleInputParams&Version=2020-01-08&Foo=val1&Bar=val2").await;
+    assert_eq!(Some("val1"), doc.reader().member("Foo").string());
+    assert_eq!(Some("val2"), doc.reader().member("Bar").string());
+
+    // QuerySimpleInputParamsStringAndBooleanTrue, QuerySimpleInputParamsStringsAndBooleanFalse
+    let doc = received::<AwsQuery>("Action=SimpleInputParams&Version=2020-01-08&Foo=val1&Baz=true").await;
+    assert_eq!(Some(true), doc.reader().member("Baz").boolean().unwrap());
+    let doc = received::<AwsQuery>("Action=SimpleInputParams&Version=2020-01-08&Foo=val1&Baz=false").await;
+    assert_eq!(Some(false), doc.reader().member("Baz").boolean().unwrap());
+
+    // QuerySimpleInputParamsInteger, QuerySimpleInputParamsFloat
+    let doc = received::<AwsQuery>("Action=SimpleInputParams&Version=2020-01-08&Bam=10").await;
+    assert_eq!(Some(10), doc.reader().member("Bam").number::<i32>().unwrap());
+    let doc = received::<AwsQuery>("Action=SimpleInputParams&Version=2020-01-08&Boo=10.8").await;
+    assert_eq!(Some(10.8), doc.reader().member("Boo").number::<f64>().unwrap());
+
+    // QuerySimpleInputParamsBlob, QueryEnums
+    let doc = received::<AwsQuery>("Action=SimpleInputParams&Version=2020-01-08&Qux=dmFsdWU%3D").await;
+    assert_eq!(Some(b"value".to_vec()), doc.reader().member("Qux").blob().unwrap());
+    let doc = received::<AwsQuery>("Action=SimpleInputParams&Version=2020-01-08&FooEnum=Foo").await;
+    assert_eq!(Some("Foo"), doc.reader().member("FooEnum").string());
+
+    // QuerySimpleInputParamsSupportsNaNFloatInputs
+    let doc = received::<AwsQuery>("Action=SimpleInputParams&Version=2020-01-08&FloatValue=NaN&Boo=NaN").await;
+    assert!(doc
+        .reader()
+        .member("FloatValue")
+        .number::<f32>()
+        .unwrap()
+        .unwrap()
+        .is_nan());
+    assert!(doc.reader().member("Boo").number::<f64>().unwrap().unwrap().is_nan());
+}
+
+#[tokio::test]
+async fn aws_query_lists() {
+    // QueryLists
+    let doc = received::<AwsQuery>(
+        "Action=QueryLists&Version=2020-01-08&ListArg.member.1=foo&ListArg.member.2=bar&ListArg.member.3=baz\
+         &ComplexListArg.member.1.hi=hello&ComplexListArg.member.2.hi=hola",
+    )
+    .await;
+    let input = doc.reader();
+    assert_eq!(
+        vec!["foo", "bar", "baz"],
+        strings(input.member("ListArg").list(false, None))
+    );
+    let complex: Vec<_> = input
+        .member("ComplexListArg")
+        .list(false, None)
+        .unwrap()
+        .iter()
+        .map(|item| item.member("hi").string().unwrap())
+        .collect();
+    assert_eq!(vec!["hello", "hola"], complex);
+
+    // EmptyQueryLists
+    let doc = received::<AwsQuery>("Action=QueryLists&Version=2020-01-08&ListArg=").await;
+    assert!(strings(doc.reader().member("ListArg").list(false, None)).is_empty());
+
+    // FlattenedQueryLists
+    let doc =
+        received::<AwsQuery>("Action=QueryLists&Version=2020-01-08&FlattenedListArg.1=A&FlattenedListArg.2=B").await;
+    assert_eq!(
+        vec!["A", "B"],
+        strings(doc.reader().member("FlattenedListArg").list(true, None))
+    );
+
+    // QueryListArgWithXmlNameMember
+    let doc = received::<AwsQuery>(
+        "Action=QueryLists&Version=2020-01-08&ListArgWithXmlNameMember.item.1=A&ListArgWithXmlNameMember.item.2=B",
+    )
+    .await;
+    assert_eq!(
+        vec!["A", "B"],
+        strings(
+            doc.reader()
+                .member("ListArgWithXmlNameMember")
+                .list(false, Some("item"))
+        )
+    );
+
+    // QueryFlattenedListArgWithXmlName
+    let doc = received::<AwsQuery>("Action=QueryLists&Version=2020-01-08&Hi.1=A&Hi.2=B").await;
+    assert_eq!(vec!["A", "B"], strings(doc.reader().member("Hi").list(true, None)));
+
+    // QueryNestedStructWithList
+    let doc = received::<AwsQuery>(
+        "Action=QueryLists&Version=2020-01-08&NestedWithList.ListArg.member.1=A&NestedWithList.ListArg.member.2=B",
+    )
+    .await;
+    assert_eq!(
+        vec!["A", "B"],
+        strings(
+            doc.reader()
+                .member("NestedWithList")
+                .member("ListArg")
+                .list(false, None)
+        )
+    );
+}
+
+#[tokio::test]
+async fn aws_query_maps() {
+    // QuerySimpleQueryMaps
+    let doc = received::<AwsQuery>(
+        "Action=QueryMaps&Version=2020-01-08&MapArg.entry.1.key=bar&MapArg.entry.1.value=Bar\
+         &MapArg.entry.2.key=foo&MapArg.entry.2.value=Foo",
+    )
+    .await;
+    assert_eq!(
+        vec![("bar", "Bar"), ("foo", "Foo")],
+        map_strings(doc.reader().member("MapArg").map(false, "key", "value").unwrap())
+    );
+
+    // QueryComplexQueryMaps
+    let doc = received::<AwsQuery>(
+        "Action=QueryMaps&Version=2020-01-08&ComplexMapArg.entry.1.key=bar&ComplexMapArg.entry.1.value.hi=Bar\
+         &ComplexMapArg.entry.2.key=foo&ComplexMapArg.entry.2.value.hi=Foo",
+    )
+    .await;
+    let complex: Vec<_> = doc
+        .reader()
+        .member("ComplexMapArg")
+        .map(false, "key", "value")
+        .unwrap()
+        .unwrap()
+        .into_iter()
+        .map(|(key, value)| (key, value.member("hi").string().unwrap()))
+        .collect();
+    assert_eq!(vec![("bar", "Bar"), ("foo", "Foo")], complex);
+
+    // QueryEmptyQueryMaps
+    let doc = received::<AwsQuery>("Action=QueryMaps&Version=2020-01-08").await;
+    assert!(doc
+        .reader()
+        .member("MapArg")
+        .map(false, "key", "value")
+        .unwrap()
+        .is_none());
+
+    // QueryQueryMapWithMemberXmlName
+    let doc = received::<AwsQuery>(
+        "Action=QueryMaps&Version=2020-01-08&MapWithXmlMemberName.entry.1.K=bar&MapWithXmlMemberName.entry.1.V=Bar\
+         &MapWithXmlMemberName.entry.2.K=foo&MapWithXmlMemberName.entry.2.V=Foo",
+    )
+    .await;
+    assert_eq!(
+        vec![("bar", "Bar"), ("foo", "Foo")],
+        map_strings(
+            doc.reader()
+                .member("MapWithXmlMemberName")
+                .map(false, "K", "V")
+                .unwrap()
+        )
+    );
+
+    // QueryFlattenedQueryMaps
+    let doc = received::<AwsQuery>(
+        "Action=QueryMaps&Version=2020-01-08&FlattenedMap.1.key=bar&FlattenedMap.1.value=Bar\
+         &FlattenedMap.2.key=foo&FlattenedMap.2.value=Foo",
+    )
+    .await;
+    assert_eq!(
+        vec![("bar", "Bar"), ("foo", "Foo")],
+        map_strings(doc.reader().member("FlattenedMap").map(true, "key", "value").unwrap())
+    );
+
+    // QueryQueryMapOfLists
+    let doc = received::<AwsQuery>(
+        "Action=QueryMaps&Version=2020-01-08&MapOfLists.entry.1.key=bar&MapOfLists.entry.1.value.member.1=C\
+         &MapOfLists.entry.1.value.member.2=D&MapOfLists.entry.2.key=foo&MapOfLists.entry.2.value.member.1=A\
+         &MapOfLists.entry.2.value.member.2=B",
+    )
+    .await;
+    let lists: Vec<_> = doc
+        .reader()
+        .member("MapOfLists")
+        .map(false, "key", "value")
+        .unwrap()
+        .unwrap()
+        .into_iter()
+        .map(|(key, value)| (key, strings(value.list(false, None))))
+        .collect();
+    assert_eq!(vec![("bar", vec!["C", "D"]), ("foo", vec!["A", "B"])], lists);
+}
+
+#[tokio::test]
+async fn aws_query_timestamps_and_nested_structures() {
+    // QueryTimestampsInput
+    let doc = received::<AwsQuery>(
+        "Action=QueryTimestamps&Version=2020-01-08&normalFormat=2015-01-25T08%3A00%3A00Z\
+         &epochMember=1422172800&epochTarget=1422172800",
+    )
+    .await;
+    let expected = Some(DateTime::from_secs(1422172800));
+    let input = doc.reader();
+    assert_eq!(
+        expected,
+        input.member("normalFormat").date_time(Format::DateTime).unwrap()
+    );
+    assert_eq!(
+        expected,
+        input.member("epochMember").date_time(Format::EpochSeconds).unwrap()
+    );
+    assert_eq!(
+        expected,
+        input.member("epochTarget").date_time(Format::EpochSeconds).unwrap()
+    );
+
+    // NestedStructures
+    let doc = received::<AwsQuery>(
+        "Action=NestedStructures&Version=2020-01-08&Nested.StringArg=foo&Nested.OtherArg=true\
+         &Nested.RecursiveArg.StringArg=baz",
+    )
+    .await;
+    let nested = doc.reader().member("Nested");
+    assert_eq!(Some("foo"), nested.member("StringArg").string());
+    assert_eq!(Some(true), nested.member("OtherArg").boolean().unwrap());
+    assert_eq!(Some("baz"), nested.member("RecursiveArg").member("StringArg").string());
+}
+
+#[tokio::test]
+async fn ec2_query_inputs() {
+    // Ec2SimpleInputParamsStrings
+    let doc = received::<Ec2Query>("Action=SimpleInputParams&Version=2020-01-08&Foo=val1&Bar=val2").await;
+    assert_eq!(Some("val1"), doc.reader().member("Foo").string());
+    assert_eq!(Some("val2"), doc.reader().member("Bar").string());
+
+    // Ec2QueryIsPreferredOverXmlName
+    let doc = received::<Ec2Query>("Action=SimpleInputParams&Version=2020-01-08&HasQueryName=Hi").await;
+    assert_eq!(Some("Hi"), doc.reader().member("HasQueryName").string());
+
+    // Ec2Lists: EC2 Query lists are always flattened, and member names are capitalized
+    let doc = received::<Ec2Query>(
+        "Action=QueryLists&Version=2020-01-08&ListArg.1=foo&ListArg.2=bar&ListArg.3=baz\
+         &ComplexListArg.1.Hi=hello&ComplexListArg.2.Hi=hola",
+    )
+    .await;
+    let input = doc.reader();
+    assert_eq!(
+        vec!["foo", "bar", "baz"],
+        strings(input.member("ListArg").list(true, None))
+    );
+    let complex: Vec<_> = input
+        .member("ComplexListArg")
+        .list(true, None)
+        .unwrap()
+        .iter()
+        .map(|item| item.member("Hi").string().unwrap())
+        .collect();
+    assert_eq!(vec!["hello", "hola"], complex);
+
+    // Ec2EmptyQueryLists
+    let doc = received::<Ec2Query>("Action=QueryLists&Version=2020-01-08").await;
+    assert!(doc.reader().member("ListArg").list(true, None).is_none());
+
+    // Ec2ListNestedStructWithList
+    let doc = received::<Ec2Query>(
+        "Action=QueryLists&Version=2020-01-08&NestedWithList.ListArg.1=A&NestedWithList.ListArg.2=B",
+    )
+    .await;
+    assert_eq!(
+        vec!["A", "B"],
+        strings(doc.reader().member("NestedWithList").member("ListArg").list(true, None))
+    );
+
+    // Ec2TimestampsInput
+    let doc = received::<Ec2Query>(
+        "Action=QueryTimestamps&Version=2020-01-08&NormalFormat=2015-01-25T08%3A00%3A00Z\
+         &EpochMember=1422172800&EpochTarget=1422172800",
+    )
+    .await;
+    let expected = Some(DateTime::from_secs(1422172800));
+    let input = doc.reader();
+    assert_eq!(
+        expected,
+        input.member("NormalFormat").date_time(Format::DateTime).unwrap()
+    );
+    assert_eq!(
+        expected,
+        input.member("EpochMember").date_time(Format::EpochSeconds).unwrap()
+    );
+}
+
+fn write_simple_scalar_properties(members: &mut ScopeWriter<'_, '_>) {
+    members.start_el("stringValue").finish().data("string");
+    members.start_el("emptyStringValue").finish();
+    members.start_el("trueBooleanValue").finish().data("true");
+    members.start_el("falseBooleanValue").finish().data("false");
+    members.start_el("byteValue").finish().data("1");
+    members.start_el("shortValue").finish().data("2");
+    members.start_el("integerValue").finish().data("3");
+    members.start_el("longValue").finish().data("4");
+    members.start_el("floatValue").finish().data("5.5");
+    members.start_el("DoubleDribble").finish().data("6.5");
+}
+
+#[test]
+fn aws_query_simple_scalar_properties_response() {
+    // QuerySimpleScalarProperties
+    let body = aws_query_response(
+        "SimpleScalarXmlProperties",
+        Some("https://example.com/"),
+        None,
+        write_simple_scalar_properties,
+    );
+    validate_body(
+        body,
+        r#"<SimpleScalarXmlPropertiesResponse xmlns="https://example.com/">
+            <SimpleScalarXmlPropertiesResult>
+                <stringValue>string</stringValue>
+                <emptyStringValue/>
+                <trueBooleanValue>true</trueBooleanValue>
+                <falseBooleanValue>false</falseBooleanValue>
+                <byteValue>1</byteValue>
+                <shortValue>2</shortValue>
+                <integerValue>3</integerValue>
+                <longValue>4</longValue>
+                <floatValue>5.5</floatValue>
+                <DoubleDribble>6.5</DoubleDribble>
+            </SimpleScalarXmlPropertiesResult>
+        </SimpleScalarXmlPropertiesResponse>"#,
+        MediaType::Xml,
+    )
+    .unwrap();
+}
+
+#[test]
+fn ec2_query_simple_scalar_properties_response() {
+    // Ec2SimpleScalarProperties, with the request ID in EC2's own `requestId` element
+    let body = ec2_query_response(
+        "SimpleScalarXmlProperties",
+        Some("https://example.com/"),
+        Some("requestid"),
+        write_simple_scalar_properties,
+    );
+    validate_body(
+        body,
+        r#"<SimpleScalarXmlPropertiesResponse xmlns="https://example.com/">
+            <stringValue>string</stringValue>
+            <emptyStringValue/>
+            <trueBooleanValue>true</trueBooleanValue>
+            <falseBooleanValue>false</falseBooleanValue>
+            <byteValue>1</byteValue>
+            <shortValue>2</shortValue>
+            <integerValue>3</integerValue>
+            <longValue>4</longValue>
+            <floatValue>5.5</floatValue>
+            <DoubleDribble>6.5</DoubleDribble>
+            <requestId>requestid</requestId>
+        </SimpleScalarXmlPropertiesResponse>"#,
+        MediaType::Xml,
+    )
+    .unwrap();
+}
+
+#[test]
+fn invalid_greeting_errors() {
+    // QueryInvalidGreetingError
+    validate_body(
+        aws_query_error(Fault::Sender, "InvalidGreeting", Some("Hi"), Some("foo-id")),
+        r#"<ErrorResponse>
+            <Error>
+                <Type>Sender</Type>
+                <Code>InvalidGreeting</Code>
+                <Message>Hi</Message>
+            </Error>
+            <RequestId>foo-id</RequestId>
+        </ErrorResponse>"#,
+        MediaType::Xml,
+    )
+    .unwrap();
+
+    // Ec2InvalidGreetingError
+    validate_body(
+        ec2_query_error("InvalidGreeting", Some("Hi"), Some("foo-id")),
+        r#"<Response>
+            <Errors>
+                <Error>
+                    <Code>InvalidGreeting</Code>
+                    <Message>Hi</Message>
+                </Error>
+            </Errors>
+            <RequestID>foo-id</RequestID>
+        </Response>"#,
+        MediaType::Xml,
+    )
+    .unwrap();
+}
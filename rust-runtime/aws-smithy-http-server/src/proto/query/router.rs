@@ -0,0 +1,499 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use std::convert::Infallible;
+use std::fmt;
+use std::future::Future;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use bytes::Bytes;
+use http_body::Body as HttpBody;
+use tower::Layer;
+use tower::Service;
+
+use crate::body::BoxBody;
+use crate::response::IntoResponse;
+use crate::routing::tiny_map::TinyMap;
+use crate::routing::Route;
+use crate::routing::Router;
+use crate::runtime_error::PayloadTooLargeException;
+
+use super::runtime_error::RuntimeError;
+
+use aws_smithy_query::decode::QueryDocument;
+use thiserror::Error;
+
+/// An AWS Query or EC2 Query routing error.
+#[derive(Debug, Error)]
+pub enum Error {
+    /// Relative URI was not "/".
+    #[error("relative URI is not \"/\"")]
+    NotRootUrl,
+    /// Method was not `POST`.
+    #[error("method not POST")]
+    MethodNotAllowed,
+    /// The request had no `Action` parameter. Either the body wasn't form-encoded, or the
+    /// [`QueryActionLayer`] was not applied before routing.
+    #[error("missing the \"Action\" parameter")]
+    MissingAction,
+    /// The `Version` parameter didn't match the version of the service.
+    #[error("unsupported API version")]
+    VersionMismatch,
+    /// Operation not found.
+    #[error("operation not found")]
+    NotFound,
+}
+
+// This constant determines when the `TinyMap` implementation switches from being a `Vec` to a
+// `HashMap`. This is chosen to be 15 as a result of the discussion around
+// https://github.com/awslabs/smithy-rs/pull/1429#issuecomment-1147516546
+const ROUTE_CUTOFF: usize = 15;
+
+/// The `Action` and `Version` parameters of a query request, inserted as a request extension by
+/// [`QueryActionLayer`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryAction {
+    action: String,
+    version: Option<String>,
+}
+
+impl QueryAction {
+    /// Returns the name of the operation being invoked.
+    pub fn action(&self) -> &str {
+        &self.action
+    }
+
+    /// Returns the API version the request was made against.
+    pub fn version(&self) -> Option<&str> {
+        self.version.as_deref()
+    }
+}
+
+/// A [`Router`] supporting the [`AWS Query`] and [`EC2 Query`] protocols.
+///
+/// Query requests name their operation in the form-encoded body, which a synchronous [`Router`]
+/// can't read. The router must therefore be wrapped in a [`QueryActionLayer`], which buffers the
+/// body and exposes its `Action` and `Version` parameters as a [`QueryAction`] extension.
+///
+/// [AWS Query]: https://smithy.io/2.0/aws/protocols/aws-query-protocol.html
+/// [EC2 Query]: https://smithy.io/2.0/aws/protocols/aws-ec2-query-protocol.html
+#[derive(Debug, Clone)]
+pub struct QueryRouter<S> {
+    routes: TinyMap<String, S, ROUTE_CUTOFF>,
+    version: Option<String>,
+}
+
+impl<S> QueryRouter<S> {
+    /// Only routes requests whose `Version` parameter is `version`.
+    pub fn with_version(mut self, version: impl Into<String>) -> Self {
+        self.version = Some(version.into());
+        self
+    }
+
+    /// Applies a [`Layer`] uniformly to all routes.
+    pub fn layer<L>(self, layer: L) -> QueryRouter<L::Service>
+    where
+        L: Layer<S>,
+    {
+        QueryRouter {
+            routes: self
+                .routes
+                .into_iter()
+                .map(|(key, route)| (key, layer.layer(route)))
+                .collect(),
+            version: self.version,
+        }
+    }
+
+    /// Applies type erasure to the inner route using [`Route::new`].
+    pub fn boxed<B>(self) -> QueryRouter<Route<B>>
+    where
+        S: Service<http::Request<B>, Response = http::Response<BoxBody>, Error = Infallible>,
+        S: Send + Clone + 'static,
+        S::Future: Send + 'static,
+    {
+        QueryRouter {
+            routes: self.routes.into_iter().map(|(key, s)| (key, Route::new(s))).collect(),
+            version: self.version,
+        }
+    }
+}
+
+impl<B, S> Router<B> for QueryRouter<S>
+where
+    S: Clone,
+{
+    type Service = S;
+    type Error = Error;
+
+    fn match_route(&self, request: &http::Request<B>) -> Result<S, Self::Error> {
+        // The URI must be root,
+        if request.uri().path() != "/" {
+            return Err(Error::NotRootUrl);
+        }
+
+        // Only `Method::POST` is allowed.
+        if request.method() != http::Method::POST {
+            return Err(Error::MethodNotAllowed);
+        }
+
+        let action = request.extensions().get::<QueryAction>().ok_or(Error::MissingAction)?;
+        if let Some(version) = &self.version {
+            if action.version() != Some(version.as_str()) {
+                return Err(Error::VersionMismatch);
+            }
+        }
+
+        // Lookup in the `TinyMap` for a route for the action.
+        let route = self.routes.get(action.action()).ok_or(Error::NotFound)?;
+        Ok(route.clone())
+    }
+
+    fn allowed_methods(&self, request: &http::Request<B>) -> Vec<http::Method> {
+        // All operations are `POST`s to the root URI.
+        if request.uri().path() == "/" {
+            vec![http::Method::POST]
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+impl<S> FromIterator<(String, S)> for QueryRouter<S> {
+    #[inline]
+    fn from_iter<T: IntoIterator<Item = (String, S)>>(iter: T) -> Self {
+        Self {
+            routes: iter.into_iter().collect(),
+            version: None,
+        }
+    }
+}
+
+/// The default maximum size of the body of a query request, in bytes.
+pub const DEFAULT_BODY_LIMIT: usize = 1024 * 1024;
+
+/// A [`Layer`] that buffers the body of `POST` requests and inserts their `Action` and `Version`
+/// parameters as a [`QueryAction`] extension, for use with [`QueryRouter`].
+///
+/// The buffered body is passed on to the inner service unchanged. Since the body is buffered before
+/// the request is routed, its size is limited here: requests with a body larger than the
+/// [limit](QueryActionLayer::body_limit) are rejected with a `413 Payload Too Large` response, and
+/// bodies that can't be read with a `400 Bad Request` one.
+///
+/// The `Protocol` parameter is used to determine the serialization of these errors.
+pub struct QueryActionLayer<Protocol> {
+    body_limit: usize,
+    _protocol: PhantomData<Protocol>,
+}
+
+impl<P> fmt::Debug for QueryActionLayer<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("QueryActionLayer")
+            .field("body_limit", &self.body_limit)
+            .finish()
+    }
+}
+
+impl<P> Clone for QueryActionLayer<P> {
+    fn clone(&self) -> Self {
+        Self {
+            body_limit: self.body_limit,
+            _protocol: PhantomData,
+        }
+    }
+}
+
+impl<P> Default for QueryActionLayer<P> {
+    fn default() -> Self {
+        Self {
+            body_limit: DEFAULT_BODY_LIMIT,
+            _protocol: PhantomData,
+        }
+    }
+}
+
+impl<P> QueryActionLayer<P> {
+    /// Creates a new [`QueryActionLayer`] limiting bodies to [`DEFAULT_BODY_LIMIT`] bytes.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the maximum size of request bodies, in bytes.
+    pub fn body_limit(mut self, body_limit: usize) -> Self {
+        self.body_limit = body_limit;
+        self
+    }
+}
+
+impl<S, P> Layer<S> for QueryActionLayer<P> {
+    type Service = QueryActionService<S, P>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        QueryActionService {
+            inner,
+            body_limit: self.body_limit,
+            _protocol: PhantomData,
+        }
+    }
+}
+
+/// The [`Service`] produced by [`QueryActionLayer`].
+pub struct QueryActionService<S, Protocol> {
+    inner: S,
+    body_limit: usize,
+    _protocol: PhantomData<Protocol>,
+}
+
+impl<S, P> fmt::Debug for QueryActionService<S, P>
+where
+    S: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("QueryActionService")
+            .field("inner", &self.inner)
+            .field("body_limit", &self.body_limit)
+            .finish()
+    }
+}
+
+impl<S, P> Clone for QueryActionService<S, P>
+where
+    S: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            body_limit: self.body_limit,
+            _protocol: PhantomData,
+        }
+    }
+}
+
+impl<B, S, P> Service<http::Request<B>> for QueryActionService<S, P>
+where
+    B: HttpBody<Data = Bytes> + From<Bytes> + Send + 'static,
+    B::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+    S: Service<http::Request<B>, Response = http::Response<BoxBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    PayloadTooLargeException: IntoResponse<P>,
+    RuntimeError: IntoResponse<P>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: http::Request<B>) -> Self::Future {
+        // The inner service was polled to readiness, so it is the one that must be called.
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        if request.method() != http::Method::POST {
+            return Box::pin(inner.call(request));
+        }
+
+        let body_limit = self.body_limit;
+        Box::pin(async move {
+            let (mut parts, body) = request.into_parts();
+            let content_length = parts
+                .headers
+                .get(http::header::CONTENT_LENGTH)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.parse::<u64>().ok());
+            if matches!(content_length, Some(length) if length > body_limit as u64) {
+                return Ok(IntoResponse::<P>::into_response(PayloadTooLargeException));
+            }
+            let bytes = match hyper::body::to_bytes(http_body::Limited::new(body, body_limit)).await {
+                Ok(bytes) => bytes,
+                Err(err) if err.is::<http_body::LengthLimitError>() => {
+                    return Ok(IntoResponse::<P>::into_response(PayloadTooLargeException));
+                }
+                Err(err) => {
+                    let error = RuntimeError::Serialization(crate::Error::new(err));
+                    return Ok(IntoResponse::<P>::into_response(error));
+                }
+            };
+            // Bodies that aren't form-encoded are left for the router to reject.
+            if let Ok(document) = QueryDocument::parse(&bytes) {
+                if let Some(action) = document.action() {
+                    parts.extensions.insert(QueryAction {
+                        action: action.to_string(),
+                        version: document.version().map(str::to_string),
+                    });
+                }
+            }
+            inner.call(http::Request::from_parts(parts, B::from(bytes))).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::body::empty;
+    use crate::proto::aws_query::AwsQuery;
+    use crate::proto::ec2_query::Ec2Query;
+    use crate::proto::query::runtime_error::tests::body_without_request_id;
+    use crate::{proto::test_helpers::req, routing::Router};
+
+    use http::Method;
+    use pretty_assertions::assert_eq;
+    use tower::ServiceExt;
+
+    fn query_req(body: &'static str) -> http::Request<hyper::Body> {
+        http::Request::builder()
+            .method(Method::POST)
+            .uri("/")
+            .header("content-type", "application/x-www-form-urlencoded")
+            .body(hyper::Body::from(body))
+            .unwrap()
+    }
+
+    async fn extract_action(request: http::Request<hyper::Body>) -> (Option<QueryAction>, String) {
+        let service = tower::service_fn(|request: http::Request<hyper::Body>| async move {
+            let action = request.extensions().get::<QueryAction>().cloned();
+            let body = hyper::body::to_bytes(request.into_body()).await.unwrap();
+            let mut response = http::Response::new(empty());
+            response.extensions_mut().insert((action, body));
+            Ok::<_, Infallible>(response)
+        });
+        let response = QueryActionLayer::<AwsQuery>::new()
+            .layer(service)
+            .oneshot(request)
+            .await
+            .unwrap();
+        let (action, body) = response
+            .extensions()
+            .get::<(Option<QueryAction>, Bytes)>()
+            .unwrap()
+            .clone();
+        (action, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn layer_extracts_action_and_preserves_body() {
+        let (action, body) = extract_action(query_req("Action=GetQueueUrl&Version=2012-11-05&QueueName=q")).await;
+        let action = action.unwrap();
+        assert_eq!("GetQueueUrl", action.action());
+        assert_eq!(Some("2012-11-05"), action.version());
+        assert_eq!("Action=GetQueueUrl&Version=2012-11-05&QueueName=q", body);
+
+        let (action, _) = extract_action(query_req("QueueName=q")).await;
+        assert!(action.is_none());
+    }
+
+    #[tokio::test]
+    async fn layer_rejects_bodies_over_the_limit() {
+        let service = tower::service_fn(|_request: http::Request<hyper::Body>| async move {
+            Ok::<_, Infallible>(http::Response::new(empty()))
+        });
+        let service = QueryActionLayer::<AwsQuery>::new().body_limit(8).layer(service);
+
+        let response = service.clone().oneshot(query_req("Action=A")).await.unwrap();
+        assert_eq!(http::StatusCode::OK, response.status());
+
+        // Declared too large, rejected before the body is read
+        let mut request = query_req("");
+        request
+            .headers_mut()
+            .insert(http::header::CONTENT_LENGTH, "1000".parse().unwrap());
+        let response = service.clone().oneshot(request).await.unwrap();
+        assert_eq!(http::StatusCode::PAYLOAD_TOO_LARGE, response.status());
+
+        let response = service.clone().oneshot(query_req("Action=Long")).await.unwrap();
+        assert_eq!(http::StatusCode::PAYLOAD_TOO_LARGE, response.status());
+        assert_eq!("text/xml", response.headers()["content-type"]);
+        assert_eq!(
+            "<ErrorResponse><Error><Type>Sender</Type><Code>PayloadTooLargeException</Code></Error></ErrorResponse>",
+            body_without_request_id(response, "RequestId").await
+        );
+
+        // Streamed without a length
+        let (mut sender, body) = hyper::Body::channel();
+        let mut request = query_req("");
+        *request.body_mut() = body;
+        let response = tokio::spawn(service.oneshot(request));
+        sender.send_data(Bytes::from("Action=")).await.unwrap();
+        let _ = sender.send_data(Bytes::from("Long")).await;
+        drop(sender);
+        let response = response.await.unwrap().unwrap();
+        assert_eq!(http::StatusCode::PAYLOAD_TOO_LARGE, response.status());
+    }
+
+    #[tokio::test]
+    async fn layer_rejects_unreadable_bodies_in_the_protocol_envelope() {
+        let service = tower::service_fn(|_request: http::Request<hyper::Body>| async move {
+            Ok::<_, Infallible>(http::Response::new(empty()))
+        });
+        let service = QueryActionLayer::<Ec2Query>::new().layer(service);
+
+        let (mut sender, body) = hyper::Body::channel();
+        let mut request = query_req("");
+        *request.body_mut() = body;
+        let response = tokio::spawn(service.oneshot(request));
+        sender.send_data(Bytes::from("Action=")).await.unwrap();
+        sender.abort();
+        let response = response.await.unwrap().unwrap();
+        assert_eq!(http::StatusCode::BAD_REQUEST, response.status());
+        assert_eq!(
+            "<Response><Errors><Error><Code>SerializationException</Code></Error></Errors></Response>",
+            body_without_request_id(response, "RequestID").await
+        );
+    }
+
+    fn with_action(request: http::Request<()>, action: &str, version: Option<&str>) -> http::Request<()> {
+        let mut request = request;
+        request.extensions_mut().insert(QueryAction {
+            action: action.to_string(),
+            version: version.map(str::to_string),
+        });
+        request
+    }
+
+    #[tokio::test]
+    async fn simple_routing() {
+        let router: QueryRouter<_> = vec![("GetQueueUrl".to_string(), ())]
+            .into_iter()
+            .collect::<QueryRouter<_>>()
+            .with_version("2012-11-05");
+
+        // Valid request, should match.
+        router
+            .match_route(&with_action(
+                req(&Method::POST, "/", None),
+                "GetQueueUrl",
+                Some("2012-11-05"),
+            ))
+            .unwrap();
+
+        // No action, should return `MissingAction`.
+        let res = router.match_route(&req(&Method::POST, "/", None));
+        assert_eq!(res.unwrap_err().to_string(), Error::MissingAction.to_string());
+
+        // Wrong version, should return `VersionMismatch`.
+        let res = router.match_route(&with_action(
+            req(&Method::POST, "/", None),
+            "GetQueueUrl",
+            Some("2008-01-01"),
+        ));
+        assert_eq!(res.unwrap_err().to_string(), Error::VersionMismatch.to_string());
+
+        // Unknown action, should return `NotFound`.
+        let res = router.match_route(&with_action(req(&Method::POST, "/", None), "Nope", Some("2012-11-05")));
+        assert_eq!(res.unwrap_err().to_string(), Error::NotFound.to_string());
+
+        // Wrong HTTP method, should return `MethodNotAllowed`.
+        let res = router.match_route(&with_action(req(&Method::GET, "/", None), "GetQueueUrl", None));
+        assert_eq!(res.unwrap_err().to_string(), Error::MethodNotAllowed.to_string());
+
+        // Wrong URI, should return `NotRootUrl`.
+        let res = router.match_route(&req(&Method::POST, "/something", None));
+        assert_eq!(res.unwrap_err().to_string(), Error::NotRootUrl.to_string());
+    }
+}
@@ -0,0 +1,234 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use crate::extension::RuntimeErrorExtension;
+use crate::proto::aws_query::AwsQuery;
+use crate::proto::ec2_query::Ec2Query;
+use crate::response::IntoResponse;
+use crate::runtime_error::{
+    AccessDeniedException, InternalFailureException, PayloadTooLargeException, UnauthorizedException,
+    INVALID_HTTP_RESPONSE_FOR_RUNTIME_ERROR_PANIC_MESSAGE,
+};
+use http::StatusCode;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+
+use super::envelope::{aws_query_error, ec2_query_error, Fault};
+use super::rejection::{RequestRejection, ResponseRejection};
+
+#[derive(Debug)]
+pub enum RuntimeError {
+    Serialization(crate::Error),
+    InternalFailure(crate::Error),
+    NotAcceptable,
+    UnsupportedMediaType,
+    Validation(String),
+}
+
+impl RuntimeError {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Serialization(_) => "SerializationException",
+            Self::InternalFailure(_) => "InternalFailureException",
+            Self::NotAcceptable => "NotAcceptableException",
+            Self::UnsupportedMediaType => "UnsupportedMediaTypeException",
+            Self::Validation(_) => "ValidationException",
+        }
+    }
+
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            Self::Serialization(_) => StatusCode::BAD_REQUEST,
+            Self::InternalFailure(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            Self::NotAcceptable => StatusCode::NOT_ACCEPTABLE,
+            Self::UnsupportedMediaType => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            Self::Validation(_) => StatusCode::BAD_REQUEST,
+        }
+    }
+
+    fn message(&self) -> Option<&str> {
+        match self {
+            Self::Validation(reason) => Some(reason),
+            _ => None,
+        }
+    }
+}
+
+/// Returns a new ID for an error response, since both protocols require one.
+///
+/// Errors raised outside of operations can't be correlated with a request's `ServerRequestId`, so the ID is random.
+/// It's generated without the `uuid` crate, which is only a dependency with the `request-id` feature.
+fn request_id() -> String {
+    let random = || RandomState::new().build_hasher().finish();
+    format!("{:016x}{:016x}", random(), random())
+}
+
+/// Renders an error named `name` in the `awsQuery` format, either a [`RuntimeError`], a routing error or a
+/// protocol-agnostic error raised by a plugin.
+pub(crate) fn aws_query_error_response(
+    name: &'static str,
+    status_code: StatusCode,
+    message: Option<&str>,
+) -> http::Response<crate::body::BoxBody> {
+    let body = aws_query_error(Fault::from_status(status_code), name, message, Some(&request_id()));
+    xml_error_response(name, status_code, body)
+}
+
+/// Renders an error named `name` in the `ec2Query` format, either a [`RuntimeError`], a routing error or a
+/// protocol-agnostic error raised by a plugin.
+pub(crate) fn ec2_query_error_response(
+    name: &'static str,
+    status_code: StatusCode,
+    message: Option<&str>,
+) -> http::Response<crate::body::BoxBody> {
+    let body = ec2_query_error(name, message, Some(&request_id()));
+    xml_error_response(name, status_code, body)
+}
+
+fn xml_error_response(
+    name: &'static str,
+    status_code: StatusCode,
+    body: String,
+) -> http::Response<crate::body::BoxBody> {
+    http::Response::builder()
+        .status(status_code)
+        .header("Content-Type", "text/xml")
+        .extension(RuntimeErrorExtension::new(name.to_string()))
+        .body(crate::body::to_boxed(body))
+        .expect(INVALID_HTTP_RESPONSE_FOR_RUNTIME_ERROR_PANIC_MESSAGE)
+}
+
+impl IntoResponse<AwsQuery> for InternalFailureException {
+    fn into_response(self) -> http::Response<crate::body::BoxBody> {
+        IntoResponse::<AwsQuery>::into_response(RuntimeError::InternalFailure(crate::Error::new(String::new())))
+    }
+}
+
+impl IntoResponse<AwsQuery> for UnauthorizedException {
+    fn into_response(self) -> http::Response<crate::body::BoxBody> {
+        aws_query_error_response(Self::NAME, Self::STATUS_CODE, None)
+    }
+}
+
+impl IntoResponse<AwsQuery> for AccessDeniedException {
+    fn into_response(self) -> http::Response<crate::body::BoxBody> {
+        aws_query_error_response(Self::NAME, Self::STATUS_CODE, None)
+    }
+}
+
+impl IntoResponse<AwsQuery> for PayloadTooLargeException {
+    fn into_response(self) -> http::Response<crate::body::BoxBody> {
+        aws_query_error_response(Self::NAME, Self::STATUS_CODE, None)
+    }
+}
+
+impl IntoResponse<Ec2Query> for InternalFailureException {
+    fn into_response(self) -> http::Response<crate::body::BoxBody> {
+        IntoResponse::<Ec2Query>::into_response(RuntimeError::InternalFailure(crate::Error::new(String::new())))
+    }
+}
+
+impl IntoResponse<Ec2Query> for UnauthorizedException {
+    fn into_response(self) -> http::Response<crate::body::BoxBody> {
+        ec2_query_error_response(Self::NAME, Self::STATUS_CODE, None)
+    }
+}
+
+impl IntoResponse<Ec2Query> for AccessDeniedException {
+    fn into_response(self) -> http::Response<crate::body::BoxBody> {
+        ec2_query_error_response(Self::NAME, Self::STATUS_CODE, None)
+    }
+}
+
+impl IntoResponse<Ec2Query> for PayloadTooLargeException {
+    fn into_response(self) -> http::Response<crate::body::BoxBody> {
+        ec2_query_error_response(Self::NAME, Self::STATUS_CODE, None)
+    }
+}
+
+impl IntoResponse<AwsQuery> for RuntimeError {
+    fn into_response(self) -> http::Response<crate::body::BoxBody> {
+        aws_query_error_response(self.name(), self.status_code(), self.message())
+    }
+}
+
+impl IntoResponse<Ec2Query> for RuntimeError {
+    fn into_response(self) -> http::Response<crate::body::BoxBody> {
+        ec2_query_error_response(self.name(), self.status_code(), self.message())
+    }
+}
+
+impl From<ResponseRejection> for RuntimeError {
+    fn from(err: ResponseRejection) -> Self {
+        Self::Serialization(crate::Error::new(err))
+    }
+}
+
+impl From<RequestRejection> for RuntimeError {
+    fn from(err: RequestRejection) -> Self {
+        match err {
+            RequestRejection::MissingContentType(_reason) => Self::UnsupportedMediaType,
+            RequestRejection::ConstraintViolation(reason) => Self::Validation(reason),
+            _ => Self::Serialization(crate::Error::new(err)),
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::proto::test_helpers::get_body_as_string;
+
+    /// Returns the body of an error response, checking that it has a request ID and removing it for comparison.
+    pub(crate) async fn body_without_request_id(response: http::Response<crate::body::BoxBody>, tag: &str) -> String {
+        let body = get_body_as_string(response.into_body()).await;
+        let (start, end) = (format!("<{tag}>"), format!("</{tag}>"));
+        let (before, rest) = body.split_once(&start).expect("error responses have a request ID");
+        let (request_id, after) = rest.split_once(&end).unwrap();
+        assert_eq!(32, request_id.len(), "{}", body);
+        format!("{before}{after}")
+    }
+
+    #[test]
+    fn request_ids_are_unique() {
+        assert_ne!(request_id(), request_id());
+    }
+
+    #[tokio::test]
+    async fn validation_errors_are_wrapped_in_envelopes() {
+        let response = IntoResponse::<AwsQuery>::into_response(RuntimeError::Validation("bad input".into()));
+        assert_eq!(StatusCode::BAD_REQUEST, response.status());
+        assert_eq!("text/xml", response.headers()["content-type"]);
+        assert_eq!(
+            "<ErrorResponse><Error><Type>Sender</Type><Code>ValidationException</Code>\
+             <Message>bad input</Message></Error></ErrorResponse>",
+            body_without_request_id(response, "RequestId").await
+        );
+
+        let response = IntoResponse::<Ec2Query>::into_response(InternalFailureException);
+        assert_eq!(StatusCode::INTERNAL_SERVER_ERROR, response.status());
+        assert_eq!(
+            "<Response><Errors><Error><Code>InternalFailureException</Code></Error></Errors></Response>",
+            body_without_request_id(response, "RequestID").await
+        );
+    }
+
+    #[tokio::test]
+    async fn plugin_errors_are_wrapped_in_envelopes() {
+        let response = IntoResponse::<AwsQuery>::into_response(UnauthorizedException);
+        assert_eq!(StatusCode::UNAUTHORIZED, response.status());
+        assert_eq!(
+            "<ErrorResponse><Error><Type>Sender</Type><Code>UnauthorizedException</Code></Error></ErrorResponse>",
+            body_without_request_id(response, "RequestId").await
+        );
+
+        let response = IntoResponse::<Ec2Query>::into_response(AccessDeniedException);
+        assert_eq!(StatusCode::FORBIDDEN, response.status());
+        assert_eq!(
+            "<Response><Errors><Error><Code>AccessDeniedException</Code></Error></Errors></Response>",
+            body_without_request_id(response, "RequestID").await
+        );
+    }
+}
@@ -90,6 +90,16 @@ where
             Err(Error::MethodNotAllowed)
         }
     }
+
+    fn allowed_methods(&self, request: &http::Request<B>) -> Vec<http::Method> {
+        let mut methods = Vec::new();
+        for (request_spec, _route) in &self.routes {
+            if request_spec.matches(request) != Match::No && !methods.contains(request_spec.method()) {
+                methods.push(request_spec.method().clone());
+            }
+        }
+        methods
+    }
 }
 
 impl<S> FromIterator<(RequestSpec, S)> for RestRouter<S> {
@@ -270,4 +280,44 @@ mod tests {
             assert_eq!(router.match_route(&req(&method, uri, None)).unwrap(), svc_name);
         }
     }
+
+    #[test]
+    fn allowed_methods_for_uri() {
+        let request_specs: Vec<(RequestSpec, &'static str)> = vec![
+            (
+                RequestSpec::from_parts(
+                    Method::GET,
+                    vec![PathSegment::Literal(String::from("items")), PathSegment::Label],
+                    Vec::new(),
+                ),
+                "GetItem",
+            ),
+            (
+                RequestSpec::from_parts(
+                    Method::PUT,
+                    vec![PathSegment::Literal(String::from("items")), PathSegment::Label],
+                    Vec::new(),
+                ),
+                "PutItem",
+            ),
+            (
+                RequestSpec::from_parts(
+                    Method::POST,
+                    vec![PathSegment::Literal(String::from("items")), PathSegment::Label],
+                    vec![QuerySegment::Key(String::from("restore"))],
+                ),
+                "RestoreItem",
+            ),
+        ];
+        let router: RestRouter<_> = request_specs.into_iter().collect();
+
+        let cases = vec![
+            ("/items/a", vec![Method::GET, Method::PUT]),
+            ("/items/a?restore", vec![Method::POST, Method::GET, Method::PUT]),
+            ("/items", vec![]),
+        ];
+        for (uri, expected) in cases {
+            assert_eq!(router.allowed_methods(&req(&Method::OPTIONS, uri, None)), expected);
+        }
+    }
 }
@@ -34,7 +34,10 @@ use super::rejection::ResponseRejection;
 use super::RestJson1;
 use crate::extension::RuntimeErrorExtension;
 use crate::response::IntoResponse;
+use crate::runtime_error::AccessDeniedException;
 use crate::runtime_error::InternalFailureException;
+use crate::runtime_error::PayloadTooLargeException;
+use crate::runtime_error::UnauthorizedException;
 use crate::runtime_error::INVALID_HTTP_RESPONSE_FOR_RUNTIME_ERROR_PANIC_MESSAGE;
 use http::StatusCode;
 
@@ -86,24 +89,50 @@ impl IntoResponse<RestJson1> for InternalFailureException {
     }
 }
 
-impl IntoResponse<RestJson1> for RuntimeError {
+impl IntoResponse<RestJson1> for UnauthorizedException {
+    fn into_response(self) -> http::Response<crate::body::BoxBody> {
+        error_response(Self::NAME, Self::STATUS_CODE, crate::body::to_boxed("{}"))
+    }
+}
+
+impl IntoResponse<RestJson1> for AccessDeniedException {
+    fn into_response(self) -> http::Response<crate::body::BoxBody> {
+        error_response(Self::NAME, Self::STATUS_CODE, crate::body::to_boxed("{}"))
+    }
+}
+
+impl IntoResponse<RestJson1> for PayloadTooLargeException {
     fn into_response(self) -> http::Response<crate::body::BoxBody> {
-        let res = http::Response::builder()
-            .status(self.status_code())
-            .header("Content-Type", "application/json")
-            .header("X-Amzn-Errortype", self.name())
-            .extension(RuntimeErrorExtension::new(self.name().to_string()));
+        error_response(Self::NAME, Self::STATUS_CODE, crate::body::to_boxed("{}"))
+    }
+}
 
+impl IntoResponse<RestJson1> for RuntimeError {
+    fn into_response(self) -> http::Response<crate::body::BoxBody> {
+        let (name, status_code) = (self.name(), self.status_code());
         let body = match self {
             RuntimeError::Validation(reason) => crate::body::to_boxed(reason),
             _ => crate::body::to_boxed("{}"),
         };
-
-        res.body(body)
-            .expect(INVALID_HTTP_RESPONSE_FOR_RUNTIME_ERROR_PANIC_MESSAGE)
+        error_response(name, status_code, body)
     }
 }
 
+/// Renders an error named `name`, either a [`RuntimeError`] or a protocol-agnostic error raised by a plugin.
+fn error_response(
+    name: &'static str,
+    status_code: StatusCode,
+    body: crate::body::BoxBody,
+) -> http::Response<crate::body::BoxBody> {
+    http::Response::builder()
+        .status(status_code)
+        .header("Content-Type", "application/json")
+        .header("X-Amzn-Errortype", name)
+        .extension(RuntimeErrorExtension::new(name.to_string()))
+        .body(body)
+        .expect(INVALID_HTTP_RESPONSE_FOR_RUNTIME_ERROR_PANIC_MESSAGE)
+}
+
 impl From<ResponseRejection> for RuntimeError {
     fn from(err: ResponseRejection) -> Self {
         Self::Serialization(crate::Error::new(err))
@@ -5,7 +5,10 @@
 
 use crate::proto::rest_xml::RestXml;
 use crate::response::IntoResponse;
+use crate::runtime_error::AccessDeniedException;
 use crate::runtime_error::InternalFailureException;
+use crate::runtime_error::PayloadTooLargeException;
+use crate::runtime_error::UnauthorizedException;
 use crate::{extension::RuntimeErrorExtension, runtime_error::INVALID_HTTP_RESPONSE_FOR_RUNTIME_ERROR_PANIC_MESSAGE};
 use http::StatusCode;
 
@@ -48,20 +51,40 @@ impl IntoResponse<RestXml> for InternalFailureException {
     }
 }
 
-impl IntoResponse<RestXml> for RuntimeError {
+impl IntoResponse<RestXml> for UnauthorizedException {
     fn into_response(self) -> http::Response<crate::body::BoxBody> {
-        let res = http::Response::builder()
-            .status(self.status_code())
-            .header("Content-Type", "application/xml")
-            .extension(RuntimeErrorExtension::new(self.name().to_string()));
+        error_response(Self::NAME, Self::STATUS_CODE)
+    }
+}
+
+impl IntoResponse<RestXml> for AccessDeniedException {
+    fn into_response(self) -> http::Response<crate::body::BoxBody> {
+        error_response(Self::NAME, Self::STATUS_CODE)
+    }
+}
 
-        let body = crate::body::to_boxed("{}");
+impl IntoResponse<RestXml> for PayloadTooLargeException {
+    fn into_response(self) -> http::Response<crate::body::BoxBody> {
+        error_response(Self::NAME, Self::STATUS_CODE)
+    }
+}
 
-        res.body(body)
-            .expect(INVALID_HTTP_RESPONSE_FOR_RUNTIME_ERROR_PANIC_MESSAGE)
+impl IntoResponse<RestXml> for RuntimeError {
+    fn into_response(self) -> http::Response<crate::body::BoxBody> {
+        error_response(self.name(), self.status_code())
     }
 }
 
+/// Renders an error named `name`, either a [`RuntimeError`] or a protocol-agnostic error raised by a plugin.
+fn error_response(name: &'static str, status_code: StatusCode) -> http::Response<crate::body::BoxBody> {
+    http::Response::builder()
+        .status(status_code)
+        .header("Content-Type", "application/xml")
+        .extension(RuntimeErrorExtension::new(name.to_string()))
+        .body(crate::body::to_boxed("{}"))
+        .expect(INVALID_HTTP_RESPONSE_FOR_RUNTIME_ERROR_PANIC_MESSAGE)
+}
+
 impl From<ResponseRejection> for RuntimeError {
     fn from(err: ResponseRejection) -> Self {
         Self::Serialization(crate::Error::new(err))
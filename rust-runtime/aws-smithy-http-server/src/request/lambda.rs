@@ -9,7 +9,10 @@
 use lambda_http::request::RequestContext;
 #[doc(inline)]
 pub use lambda_http::{
-    aws_lambda_events::apigw::{ApiGatewayProxyRequestContext, ApiGatewayV2httpRequestContext},
+    aws_lambda_events::{
+        alb::AlbTargetGroupRequestContext,
+        apigw::{ApiGatewayProxyRequestContext, ApiGatewayV2httpRequestContext},
+    },
     Context,
 };
 use thiserror::Error;
@@ -89,7 +92,8 @@ enum MissingGatewayContextTypeV2 {
 
 /// The [`RequestContext::ApiGatewayV2`] was not found in the [`http::Request`] extensions.
 ///
-/// Use [`LambdaHandler`](crate::routing::LambdaHandler) to ensure it's present and ensure that you're using "ApiGatewayV2".
+/// Use [`LambdaHandler`](crate::routing::LambdaHandler) to ensure it's present and ensure that you're using "ApiGatewayV2"
+/// or a Lambda Function URL, whose events share the "ApiGatewayV2" format.
 #[derive(Debug, Error)]
 #[error("{inner}")]
 pub struct MissingGatewayContextV2 {
@@ -118,3 +122,44 @@ impl<P> FromParts<P> for ApiGatewayV2httpRequestContext {
         }
     }
 }
+
+#[derive(Debug, Error)]
+enum MissingAlbContextType {
+    #[error("`RequestContext` is not present in the `http::Request` extensions - consider using `aws_smithy_http_server::routing::LambdaHandler`")]
+    MissingRequestContext,
+    #[error("`RequestContext::Alb` is not present in the `http::Request` extensions - the request was not sent by an Application Load Balancer")]
+    OriginMismatch,
+}
+
+/// The [`RequestContext::Alb`] was not found in the [`http::Request`] extensions.
+///
+/// Use [`LambdaHandler`](crate::routing::LambdaHandler) to ensure it's present and ensure that the function is the
+/// target of an Application Load Balancer.
+#[derive(Debug, Error)]
+#[error("{inner}")]
+pub struct MissingAlbContext {
+    inner: MissingAlbContextType,
+}
+
+impl<Protocol> IntoResponse<Protocol> for MissingAlbContext {
+    fn into_response(self) -> http::Response<BoxBody> {
+        internal_server_error()
+    }
+}
+
+impl<P> FromParts<P> for AlbTargetGroupRequestContext {
+    type Rejection = MissingAlbContext;
+
+    fn from_parts(parts: &mut http::request::Parts) -> Result<Self, Self::Rejection> {
+        let context = parts.extensions.remove().ok_or(MissingAlbContext {
+            inner: MissingAlbContextType::MissingRequestContext,
+        })?;
+        if let RequestContext::Alb(context) = context {
+            Ok(context)
+        } else {
+            Err(MissingAlbContext {
+                inner: MissingAlbContextType::OriginMismatch,
+            })
+        }
+    }
+}
@@ -0,0 +1,453 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Routing-aware [CORS] handling.
+//!
+//! Enabling CORS on a [`RoutingService`](super::RoutingService) with
+//! [`RoutingService::cors`](super::RoutingService::cors) makes it:
+//!
+//! - answer preflight requests (`OPTIONS` requests with `Origin` and `Access-Control-Request-Method`
+//!   headers) for URIs handled by one of the service's routes, advertising the HTTP methods of all the routes
+//!   matching the URI in `Access-Control-Allow-Methods`. Preflight requests for a method that none of those routes
+//!   accepts, or for headers that aren't allowed, are answered with `403 Forbidden`; and
+//! - add `Access-Control-Allow-Origin` and `Access-Control-Expose-Headers` to the responses of requests from allowed
+//!   origins.
+//!
+//! Preflight requests for URIs that no route handles are routed like any other request, resulting in the protocol's
+//! usual "not found" or "method not allowed" responses.
+//!
+//! Generated services provide a `cors_config` function returning the [`CorsConfig`] described by the service's
+//! [`@cors` trait] and HTTP bindings.
+//!
+//! [CORS]: https://fetch.spec.whatwg.org/#http-cors-protocol
+//! [`@cors` trait]: https://smithy.io/2.0/spec/http-bindings.html#cors-trait
+
+use std::time::Duration;
+
+use http::header::{
+    ACCESS_CONTROL_ALLOW_HEADERS, ACCESS_CONTROL_ALLOW_METHODS, ACCESS_CONTROL_ALLOW_ORIGIN,
+    ACCESS_CONTROL_EXPOSE_HEADERS, ACCESS_CONTROL_MAX_AGE, ACCESS_CONTROL_REQUEST_HEADERS,
+    ACCESS_CONTROL_REQUEST_METHOD, ORIGIN, VARY,
+};
+use http::{HeaderMap, HeaderName, HeaderValue, Method, Request, Response, StatusCode};
+
+use crate::body::{empty, BoxBody};
+
+#[derive(Debug, Clone)]
+enum AllowedOrigins {
+    Any,
+    List(Vec<HeaderValue>),
+}
+
+/// Request headers that browsers may send without them being allowed.
+///
+/// See <https://fetch.spec.whatwg.org/#cors-safelisted-request-header>.
+const SAFELISTED_REQUEST_HEADERS: &[&str] = &["accept", "accept-language", "content-language"];
+
+/// The CORS configuration of a [`RoutingService`](super::RoutingService).
+///
+/// ```
+/// # use std::time::Duration;
+/// # use aws_smithy_http_server::routing::cors::CorsConfig;
+/// # use http::{HeaderName, HeaderValue};
+/// let config = CorsConfig::new()
+///     .allow_origin(HeaderValue::from_static("https://www.example.com"))
+///     .max_age(Duration::from_secs(600))
+///     .allow_header(HeaderName::from_static("content-type"))
+///     .allow_header_prefix("x-amz-meta-")
+///     .expose_header(HeaderName::from_static("x-request-id"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct CorsConfig {
+    allowed_origins: AllowedOrigins,
+    max_age: Option<Duration>,
+    allowed_headers: Vec<HeaderName>,
+    allowed_header_prefixes: Vec<String>,
+    exposed_headers: Vec<HeaderName>,
+}
+
+impl Default for CorsConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CorsConfig {
+    /// Creates a configuration allowing requests from any origin, without any headers other than the
+    /// [CORS-safelisted] ones.
+    ///
+    /// [CORS-safelisted]: https://fetch.spec.whatwg.org/#cors-safelisted-request-header
+    pub fn new() -> Self {
+        Self {
+            allowed_origins: AllowedOrigins::Any,
+            max_age: None,
+            allowed_headers: Vec::new(),
+            allowed_header_prefixes: Vec::new(),
+            exposed_headers: Vec::new(),
+        }
+    }
+
+    /// Allows requests from `origin`.
+    ///
+    /// The first call restricts the allowed origins to those explicitly allowed, unless `origin` is `*`, which allows
+    /// requests from any origin.
+    pub fn allow_origin(mut self, origin: HeaderValue) -> Self {
+        self.allowed_origins = match (self.allowed_origins, origin.as_bytes()) {
+            (_, b"*") => AllowedOrigins::Any,
+            (AllowedOrigins::List(mut origins), _) => {
+                origins.push(origin);
+                AllowedOrigins::List(origins)
+            }
+            (AllowedOrigins::Any, _) => AllowedOrigins::List(vec![origin]),
+        };
+        self
+    }
+
+    /// Sets how long browsers may cache the response to a preflight request.
+    pub fn max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    /// Allows requests to set the `header` header.
+    pub fn allow_header(mut self, header: HeaderName) -> Self {
+        if !self.allowed_headers.contains(&header) {
+            self.allowed_headers.push(header);
+        }
+        self
+    }
+
+    /// Allows requests to set any header whose name starts with `prefix`, e.g. headers bound with
+    /// `@httpPrefixHeaders`.
+    pub fn allow_header_prefix(mut self, prefix: impl Into<String>) -> Self {
+        let prefix = prefix.into().to_ascii_lowercase();
+        if !self.allowed_header_prefixes.contains(&prefix) {
+            self.allowed_header_prefixes.push(prefix);
+        }
+        self
+    }
+
+    /// Allows browsers to expose the `header` response header to scripts.
+    pub fn expose_header(mut self, header: HeaderName) -> Self {
+        if !self.exposed_headers.contains(&header) {
+            self.exposed_headers.push(header);
+        }
+        self
+    }
+
+    /// Returns the `Access-Control-Allow-Origin` value for `request`, or `None` if its origin isn't allowed.
+    fn allow_origin_value<B>(&self, request: &Request<B>) -> Option<HeaderValue> {
+        let origin = request.headers().get(ORIGIN)?;
+        match &self.allowed_origins {
+            AllowedOrigins::Any => Some(HeaderValue::from_static("*")),
+            AllowedOrigins::List(origins) => origins.contains(origin).then(|| origin.clone()),
+        }
+    }
+
+    /// Inserts the `Access-Control-Allow-Origin` header, and `Vary: Origin` if its value depends on the request.
+    fn insert_origin(&self, headers: &mut HeaderMap, allow_origin: HeaderValue) {
+        headers.insert(ACCESS_CONTROL_ALLOW_ORIGIN, allow_origin);
+        if let AllowedOrigins::List(_) = self.allowed_origins {
+            headers.append(VARY, HeaderValue::from_static("origin"));
+        }
+    }
+
+    /// Returns true if requests may set the `name` header, which must be lowercase.
+    fn is_header_allowed(&self, name: &str) -> bool {
+        SAFELISTED_REQUEST_HEADERS.contains(&name)
+            || self.allowed_headers.iter().any(|header| header.as_str() == name)
+            || self
+                .allowed_header_prefixes
+                .iter()
+                .any(|prefix| name.starts_with(prefix.as_str()))
+    }
+
+    /// Returns the response to a preflight request for a URI whose routes accept `allowed_methods`.
+    ///
+    /// Preflight requests from origins that aren't allowed, for methods that aren't in `allowed_methods` or for
+    /// headers that aren't allowed are answered with `403 Forbidden`.
+    pub(crate) fn preflight_response<B>(&self, request: &Request<B>, allowed_methods: &[Method]) -> Response<BoxBody> {
+        let mut response = Response::new(empty());
+        *response.status_mut() = StatusCode::FORBIDDEN;
+        let allow_origin = match self.allow_origin_value(request) {
+            Some(allow_origin) => allow_origin,
+            None => return response,
+        };
+        let requested_method = request
+            .headers()
+            .get(ACCESS_CONTROL_REQUEST_METHOD)
+            .and_then(|method| Method::from_bytes(method.as_bytes()).ok());
+        match requested_method {
+            Some(method) if allowed_methods.contains(&method) => {}
+            _ => return response,
+        }
+        let requested_headers = match requested_headers(request) {
+            Some(headers) if headers.iter().all(|header| self.is_header_allowed(header)) => headers,
+            _ => return response,
+        };
+        *response.status_mut() = StatusCode::NO_CONTENT;
+
+        let headers = response.headers_mut();
+        self.insert_origin(headers, allow_origin);
+        headers.insert(
+            ACCESS_CONTROL_ALLOW_METHODS,
+            join(allowed_methods.iter().map(Method::as_str)),
+        );
+        // Headers allowed by prefix can't be listed up front, so the requested ones are advertised as well
+        let mut allowed_headers: Vec<&str> = self.allowed_headers.iter().map(HeaderName::as_str).collect();
+        for header in &requested_headers {
+            if !allowed_headers.contains(&header.as_str()) {
+                allowed_headers.push(header);
+            }
+        }
+        if !allowed_headers.is_empty() {
+            headers.insert(ACCESS_CONTROL_ALLOW_HEADERS, join(allowed_headers.into_iter()));
+        }
+        if let Some(max_age) = self.max_age {
+            headers.insert(ACCESS_CONTROL_MAX_AGE, max_age.as_secs().into());
+        }
+        response
+    }
+
+    /// Returns the headers to add to the response to `request`, which isn't a preflight request, or `None` if it
+    /// isn't a CORS request from an allowed origin.
+    pub(crate) fn response_headers<B>(&self, request: &Request<B>) -> Option<HeaderMap> {
+        let allow_origin = self.allow_origin_value(request)?;
+        let mut headers = HeaderMap::new();
+        self.insert_origin(&mut headers, allow_origin);
+        if !self.exposed_headers.is_empty() {
+            headers.insert(
+                ACCESS_CONTROL_EXPOSE_HEADERS,
+                join(self.exposed_headers.iter().map(HeaderName::as_str)),
+            );
+        }
+        Some(headers)
+    }
+}
+
+/// Returns true if `request` is a CORS preflight request.
+pub(crate) fn is_preflight<B>(request: &Request<B>) -> bool {
+    request.method() == Method::OPTIONS
+        && request.headers().contains_key(ORIGIN)
+        && request.headers().contains_key(ACCESS_CONTROL_REQUEST_METHOD)
+}
+
+/// Returns the lowercase names listed in the `Access-Control-Request-Headers` headers of `request`, or `None` if they
+/// aren't valid header names.
+fn requested_headers<B>(request: &Request<B>) -> Option<Vec<String>> {
+    let mut names = Vec::new();
+    for value in request.headers().get_all(ACCESS_CONTROL_REQUEST_HEADERS) {
+        for name in value.to_str().ok()?.split(',') {
+            let name = name.trim();
+            if !name.is_empty() {
+                names.push(HeaderName::from_bytes(name.as_bytes()).ok()?.as_str().to_owned());
+            }
+        }
+    }
+    Some(names)
+}
+
+fn join<'a>(values: impl Iterator<Item = &'a str>) -> HeaderValue {
+    let joined = values.collect::<Vec<_>>().join(",");
+    HeaderValue::from_str(&joined).expect("method and header names are valid header values")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn preflight(request_method: &'static str, request_headers: Option<&'static str>) -> Request<()> {
+        let mut request = request(Method::OPTIONS, Some("https://a.com"), Some(request_method));
+        if let Some(request_headers) = request_headers {
+            request.headers_mut().insert(
+                ACCESS_CONTROL_REQUEST_HEADERS,
+                HeaderValue::from_static(request_headers),
+            );
+        }
+        request
+    }
+
+    fn request(method: Method, origin: Option<&'static str>, request_method: Option<&'static str>) -> Request<()> {
+        let mut builder = Request::builder().method(method).uri("/");
+        if let Some(origin) = origin {
+            builder = builder.header(ORIGIN, origin);
+        }
+        if let Some(request_method) = request_method {
+            builder = builder.header(ACCESS_CONTROL_REQUEST_METHOD, request_method);
+        }
+        builder.body(()).unwrap()
+    }
+
+    #[test]
+    fn detects_preflight_requests() {
+        assert!(is_preflight(&request(
+            Method::OPTIONS,
+            Some("https://a.com"),
+            Some("PUT")
+        )));
+        assert!(!is_preflight(&request(Method::OPTIONS, Some("https://a.com"), None)));
+        assert!(!is_preflight(&request(Method::OPTIONS, None, Some("PUT"))));
+        assert!(!is_preflight(&request(Method::PUT, Some("https://a.com"), Some("PUT"))));
+    }
+
+    #[test]
+    fn preflight_response_advertises_methods_and_headers() {
+        let config = CorsConfig::new()
+            .max_age(Duration::from_secs(600))
+            .allow_header(HeaderName::from_static("content-type"))
+            .allow_header(HeaderName::from_static("x-amz-date"));
+        let response = config.preflight_response(&preflight("PUT", Some("Content-Type")), &[Method::GET, Method::PUT]);
+
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        let headers = response.headers();
+        assert_eq!(headers[ACCESS_CONTROL_ALLOW_ORIGIN], "*");
+        assert_eq!(headers[ACCESS_CONTROL_ALLOW_METHODS], "GET,PUT");
+        assert_eq!(headers[ACCESS_CONTROL_ALLOW_HEADERS], "content-type,x-amz-date");
+        assert_eq!(headers[ACCESS_CONTROL_MAX_AGE], "600");
+        assert!(headers.get(VARY).is_none());
+    }
+
+    #[test]
+    fn preflight_requests_are_validated() {
+        let config = CorsConfig::new()
+            .allow_header(HeaderName::from_static("content-type"))
+            .allow_header_prefix("X-Meta-");
+        let methods = [Method::GET, Method::PUT];
+        let status = |request| config.preflight_response(&request, &methods).status();
+
+        assert_eq!(status(preflight("PUT", None)), StatusCode::NO_CONTENT);
+        assert_eq!(status(preflight("DELETE", None)), StatusCode::FORBIDDEN);
+        assert_eq!(
+            status(preflight("PUT", Some("content-type, accept"))),
+            StatusCode::NO_CONTENT
+        );
+        assert_eq!(status(preflight("PUT", Some("x-custom"))), StatusCode::FORBIDDEN);
+        assert_eq!(status(preflight("PUT", Some("content-type,"))), StatusCode::NO_CONTENT);
+        assert_eq!(status(preflight("PUT", Some("not a header"))), StatusCode::FORBIDDEN);
+
+        let response = config.preflight_response(&preflight("PUT", Some("x-meta-color,X-Meta-Size")), &methods);
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        assert_eq!(
+            response.headers()[ACCESS_CONTROL_ALLOW_HEADERS],
+            "content-type,x-meta-color,x-meta-size"
+        );
+    }
+
+    #[test]
+    fn only_allowed_origins_are_echoed() {
+        let config = CorsConfig::new()
+            .allow_origin(HeaderValue::from_static("https://a.com"))
+            .allow_origin(HeaderValue::from_static("https://b.com"))
+            .expose_header(HeaderName::from_static("x-request-id"));
+
+        let headers = config
+            .response_headers(&request(Method::GET, Some("https://b.com"), None))
+            .unwrap();
+        assert_eq!(headers[ACCESS_CONTROL_ALLOW_ORIGIN], "https://b.com");
+        assert_eq!(headers[ACCESS_CONTROL_EXPOSE_HEADERS], "x-request-id");
+        assert_eq!(headers[VARY], "origin");
+
+        assert!(config
+            .response_headers(&request(Method::GET, Some("https://c.com"), None))
+            .is_none());
+        assert!(config.response_headers(&request(Method::GET, None, None)).is_none());
+
+        let response = config.preflight_response(
+            &request(Method::OPTIONS, Some("https://c.com"), Some("GET")),
+            &[Method::GET],
+        );
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        assert!(response.headers().is_empty());
+    }
+
+    #[test]
+    fn wildcard_origin_allows_any_origin() {
+        let config = CorsConfig::new()
+            .allow_origin(HeaderValue::from_static("https://a.com"))
+            .allow_origin(HeaderValue::from_static("*"));
+        let headers = config
+            .response_headers(&request(Method::GET, Some("https://c.com"), None))
+            .unwrap();
+        assert_eq!(headers[ACCESS_CONTROL_ALLOW_ORIGIN], "*");
+    }
+
+    #[tokio::test]
+    async fn routing_service_answers_preflights_for_routed_uris() {
+        use crate::proto::rest::router::RestRouter;
+        use crate::proto::rest_json_1::RestJson1;
+        use crate::routing::request_spec::{PathSegment, RequestSpec};
+        use crate::routing::RoutingService;
+        use std::convert::Infallible;
+        use tower::{service_fn, ServiceExt};
+
+        let handler = service_fn(|_request: Request<()>| async { Ok::<_, Infallible>(Response::new(empty())) });
+        let spec = |method| RequestSpec::from_parts(method, vec![PathSegment::Literal(String::from("items"))], vec![]);
+        let router: RestRouter<_> = [(spec(Method::GET), handler), (spec(Method::PUT), handler)]
+            .into_iter()
+            .collect();
+        let service = RoutingService::<_, RestJson1>::new(router).cors(
+            CorsConfig::new()
+                .allow_origin(HeaderValue::from_static("https://a.com"))
+                .expose_header(HeaderName::from_static("x-request-id")),
+        );
+
+        let preflight = |uri| {
+            let mut request = request(Method::OPTIONS, Some("https://a.com"), Some("PUT"));
+            *request.uri_mut() = http::Uri::from_static(uri);
+            request
+        };
+        let response = service.clone().oneshot(preflight("/items")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        assert_eq!(response.headers()[ACCESS_CONTROL_ALLOW_METHODS], "GET,PUT");
+        assert_eq!(response.headers()[ACCESS_CONTROL_ALLOW_ORIGIN], "https://a.com");
+
+        let response = service.clone().oneshot(preflight("/unknown")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let mut delete = preflight("/items");
+        delete
+            .headers_mut()
+            .insert(ACCESS_CONTROL_REQUEST_METHOD, HeaderValue::from_static("DELETE"));
+        let response = service.clone().oneshot(delete).await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        assert!(response.headers().get(ACCESS_CONTROL_ALLOW_METHODS).is_none());
+
+        let mut get = request(Method::GET, Some("https://a.com"), None);
+        *get.uri_mut() = http::Uri::from_static("/items");
+        let response = service.oneshot(get).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[ACCESS_CONTROL_ALLOW_ORIGIN], "https://a.com");
+        assert_eq!(response.headers()[ACCESS_CONTROL_EXPOSE_HEADERS], "x-request-id");
+    }
+
+    #[tokio::test]
+    async fn routing_service_keeps_headers_set_by_the_handler() {
+        use crate::proto::rest::router::RestRouter;
+        use crate::proto::rest_json_1::RestJson1;
+        use crate::routing::request_spec::{PathSegment, RequestSpec};
+        use crate::routing::RoutingService;
+        use std::convert::Infallible;
+        use tower::{service_fn, ServiceExt};
+
+        let handler = service_fn(|_request: Request<()>| async {
+            let mut response = Response::new(empty());
+            response
+                .headers_mut()
+                .insert(VARY, HeaderValue::from_static("accept-encoding"));
+            Ok::<_, Infallible>(response)
+        });
+        let spec = RequestSpec::from_parts(Method::GET, vec![PathSegment::Literal(String::from("items"))], vec![]);
+        let router: RestRouter<_> = [(spec, handler)].into_iter().collect();
+        let service = RoutingService::<_, RestJson1>::new(router)
+            .cors(CorsConfig::new().allow_origin(HeaderValue::from_static("https://a.com")));
+
+        let mut get = request(Method::GET, Some("https://a.com"), None);
+        *get.uri_mut() = http::Uri::from_static("/items");
+        let response = service.oneshot(get).await.unwrap();
+        let vary: Vec<_> = response.headers().get_all(VARY).iter().collect();
+        assert_eq!(vary, ["accept-encoding", "origin"]);
+        assert_eq!(response.headers()[ACCESS_CONTROL_ALLOW_ORIGIN], "https://a.com");
+    }
+}
@@ -3,21 +3,29 @@
  * SPDX-License-Identifier: Apache-2.0
  */
 
+use bytes::Bytes;
 use http::uri;
+use lambda_http::request::RequestContext;
 use lambda_http::{Request, RequestExt};
 use std::{
-    fmt::Debug,
+    fmt::{Debug, Display},
     task::{Context, Poll},
 };
 use tower::Service;
 
+use crate::body::HttpBody;
+
 type HyperRequest = http::Request<hyper::Body>;
 
 /// A [`Service`] that takes a `lambda_http::Request` and converts
 /// it to `http::Request<hyper::Body>`.
 ///
+/// Requests can come from API Gateway REST and HTTP APIs, Lambda Function URLs and Application Load Balancers.
+/// Responses are buffered when the handler is run with [`lambda_http::run`], and are subject to Lambda's 6 MB
+/// payload limit. Use [`LambdaHandler::run_with_streaming_response`] to stream responses instead.
+///
 /// **This version is only guaranteed to be compatible with
-/// [`lambda_http`](https://docs.rs/lambda_http) ^0.7.0.** Please ensure that your service crate's
+/// [`lambda_http`](https://docs.rs/lambda_http) ^0.8.0.** Please ensure that your service crate's
 /// `Cargo.toml` depends on a compatible version.
 ///
 /// [`Service`]: tower::Service
@@ -30,6 +38,25 @@ impl<S> LambdaHandler<S> {
     pub fn new(service: S) -> Self {
         Self { service }
     }
+
+    /// Starts the Lambda runtime, streaming response bodies back to the client as they are produced.
+    ///
+    /// This lets operations with streaming blob or event stream outputs respond with more than Lambda's 6 MB payload
+    /// limit, and send the first bytes of the response sooner. [Response streaming] is only supported by Lambda
+    /// Function URLs and direct invocations: the function must be configured with the `RESPONSE_STREAM` invoke mode.
+    ///
+    /// [Response streaming]: https://docs.aws.amazon.com/lambda/latest/dg/configuration-response-streaming.html
+    pub async fn run_with_streaming_response<B>(self) -> Result<(), lambda_http::Error>
+    where
+        S: Service<HyperRequest, Response = http::Response<B>>,
+        S::Future: Send,
+        S::Error: Debug + Display,
+        B: HttpBody + Unpin + Send + 'static,
+        B::Data: Into<Bytes> + Send,
+        B::Error: Into<lambda_http::Error> + Send + Debug,
+    {
+        lambda_http::run_with_streaming_response(self).await
+    }
 }
 
 impl<S> Service<Request> for LambdaHandler<S>
@@ -53,39 +80,28 @@ where
 /// Converts a `lambda_http::Request` into a `http::Request<hyper::Body>`
 /// Issue: <https://github.com/awslabs/smithy-rs/issues/1125>
 ///
-/// While converting the event the [API Gateway Stage] portion of the URI
-/// is removed from the uri that gets returned as a new `http::Request`.
+/// While converting API Gateway events the [API Gateway Stage] portion of the URI
+/// is removed from the uri that gets returned as a new `http::Request`. Lambda Function URL
+/// and Application Load Balancer events have no stage, so their URI is left as is.
 ///
 /// [API Gateway Stage]: https://docs.aws.amazon.com/apigateway/latest/developerguide/http-api-stages.html
 fn convert_event(request: Request) -> HyperRequest {
-    let raw_path: &str = request.extensions().raw_http_path();
-    let path: &str = request.uri().path();
+    let (mut parts, body) = request.into_parts();
 
-    let (parts, body) = if !raw_path.is_empty() && raw_path != path {
-        let mut path = raw_path.to_owned(); // Clone only when we need to strip out the stage.
-        let (mut parts, body) = request.into_parts();
-
-        let uri_parts: uri::Parts = parts.uri.into();
-        let path_and_query = uri_parts
-            .path_and_query
-            .expect("request URI does not have `PathAndQuery`");
-
-        if let Some(query) = path_and_query.query() {
+    let has_stage = !matches!(parts.request_context_ref(), Some(RequestContext::Alb(_)));
+    let raw_path = parts.raw_http_path();
+    if has_stage && !raw_path.is_empty() && raw_path != parts.uri.path() {
+        let mut path = raw_path.to_owned();
+        if let Some(query) = parts.uri.query() {
             path.push('?');
             path.push_str(query);
         }
 
-        parts.uri = uri::Uri::builder()
-            .authority(uri_parts.authority.expect("request URI does not have authority set"))
-            .scheme(uri_parts.scheme.expect("request URI does not have scheme set"))
-            .path_and_query(path)
-            .build()
-            .expect("unable to construct new URI");
-
-        (parts, body)
-    } else {
-        request.into_parts()
-    };
+        // Events without a `Host` header have a URI without scheme and authority.
+        let mut uri_parts = uri::Parts::from(parts.uri.clone());
+        uri_parts.path_and_query = Some(path.parse().expect("raw HTTP path is not a valid URI path"));
+        parts.uri = uri::Uri::from_parts(uri_parts).expect("unable to construct new URI");
+    }
 
     let body = match body {
         lambda_http::Body::Empty => hyper::Body::empty(),
@@ -125,4 +141,234 @@ mod tests {
 
         assert_eq!(request.uri().path(), "/resources/1")
     }
+
+    #[test]
+    fn raw_http_path_without_host() {
+        let event = http::Request::builder()
+            .uri("/prod/resources/1?color=red")
+            .body(())
+            .expect("unable to build Request");
+        let (parts, _) = event.into_parts();
+
+        let event =
+            lambda_http::Request::from_parts(parts, lambda_http::Body::Empty).with_raw_http_path("/resources/1");
+        let request = convert_event(event);
+
+        assert_eq!(request.uri(), "/resources/1?color=red")
+    }
+
+    #[test]
+    fn function_url_event() {
+        let event = lambda_http::request::from_str(fixtures::FUNCTION_URL_EVENT).expect("invalid event");
+        let request = convert_event(event);
+
+        assert_eq!(http::Method::POST, request.method());
+        assert_eq!(
+            "https://abcdefg.lambda-url.us-east-1.on.aws/reports/1?format=csv",
+            request.uri()
+        );
+        assert!(matches!(
+            request.request_context_ref(),
+            Some(RequestContext::ApiGatewayV2(_))
+        ));
+    }
+
+    #[test]
+    fn alb_event() {
+        let event = lambda_http::request::from_str(fixtures::ALB_EVENT).expect("invalid event");
+        let request = convert_event(event);
+
+        assert_eq!(http::Method::GET, request.method());
+        assert_eq!("http://alb.example.com/reports/1?format=csv", request.uri());
+        assert!(matches!(request.request_context_ref(), Some(RequestContext::Alb(_))));
+    }
+
+    /// A stand-in for the [Lambda runtime API] that serves a single event and records the function's responses.
+    ///
+    /// [Lambda runtime API]: https://docs.aws.amazon.com/lambda/latest/dg/runtimes-api.html
+    mod runtime_api {
+        use std::convert::Infallible;
+        use std::net::SocketAddr;
+        use std::sync::atomic::{AtomicBool, Ordering};
+        use std::sync::Arc;
+
+        use bytes::Bytes;
+        use hyper::service::{make_service_fn, service_fn};
+        use hyper::{Body, Response, Server};
+        use once_cell::sync::Lazy;
+        use tokio::sync::{mpsc, Mutex, MutexGuard};
+
+        /// The runtime reads its configuration from the environment, which is shared by all tests.
+        static ENVIRONMENT: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+        /// A request made by the function to the runtime API, other than polling for the next event.
+        pub(super) struct Invocation {
+            pub(super) path: String,
+            pub(super) headers: http::HeaderMap,
+            pub(super) body: Bytes,
+        }
+
+        pub(super) struct RuntimeApi {
+            pub(super) invocations: mpsc::UnboundedReceiver<Invocation>,
+            _environment: MutexGuard<'static, ()>,
+        }
+
+        /// Starts the runtime API and points the Lambda runtime at it until the returned value is dropped.
+        pub(super) async fn start(event: &'static str) -> RuntimeApi {
+            let environment = ENVIRONMENT.lock().await;
+            let (invocations_tx, invocations) = mpsc::unbounded_channel();
+            let served = Arc::new(AtomicBool::new(false));
+            let make_service = make_service_fn(move |_| {
+                let invocations_tx = invocations_tx.clone();
+                let served = served.clone();
+                async move {
+                    Ok::<_, Infallible>(service_fn(move |request: http::Request<Body>| {
+                        let invocations_tx = invocations_tx.clone();
+                        let served = served.clone();
+                        async move {
+                            if request.uri().path() == "/2018-06-01/runtime/invocation/next" {
+                                if served.swap(true, Ordering::SeqCst) {
+                                    // There's only one event: keep the runtime waiting for the next.
+                                    std::future::pending::<()>().await;
+                                }
+                                let response = Response::builder()
+                                    .header("lambda-runtime-aws-request-id", "request-1")
+                                    .header("lambda-runtime-deadline-ms", "4102444800000")
+                                    .header(
+                                        "lambda-runtime-invoked-function-arn",
+                                        "arn:aws:lambda:us-east-1:123456789012:function:reports",
+                                    )
+                                    .body(Body::from(event))
+                                    .unwrap();
+                                return Ok::<_, hyper::Error>(response);
+                            }
+
+                            let (parts, body) = request.into_parts();
+                            let body = hyper::body::to_bytes(body).await?;
+                            let _ = invocations_tx.send(Invocation {
+                                path: parts.uri.path().to_owned(),
+                                headers: parts.headers,
+                                body,
+                            });
+                            Ok(Response::builder().status(202).body(Body::empty()).unwrap())
+                        }
+                    }))
+                }
+            });
+            let server = Server::bind(&SocketAddr::from(([127, 0, 0, 1], 0))).serve(make_service);
+            std::env::set_var("AWS_LAMBDA_RUNTIME_API", server.local_addr().to_string());
+            std::env::set_var("AWS_LAMBDA_FUNCTION_NAME", "reports");
+            std::env::set_var("AWS_LAMBDA_FUNCTION_MEMORY_SIZE", "128");
+            std::env::set_var("AWS_LAMBDA_FUNCTION_VERSION", "$LATEST");
+            tokio::spawn(server);
+
+            RuntimeApi {
+                invocations,
+                _environment: environment,
+            }
+        }
+    }
+
+    fn reports_service() -> impl Service<
+        HyperRequest,
+        Response = http::Response<crate::body::BoxBody>,
+        Error = std::convert::Infallible,
+        Future = impl Send,
+    > + Clone {
+        tower::service_fn(|request: HyperRequest| async move {
+            let chunks = vec![
+                Ok::<_, std::convert::Infallible>(format!("report for {}\n", request.uri().path())),
+                Ok("a,b\n".to_owned()),
+                Ok("1,2\n".to_owned()),
+            ];
+            let body = hyper::Body::wrap_stream(futures_util::stream::iter(chunks));
+            let response = http::Response::builder()
+                .header("content-type", "text/csv")
+                .body(crate::body::boxed(body))
+                .unwrap();
+            Ok(response)
+        })
+    }
+
+    #[tokio::test]
+    async fn buffered_response() {
+        let mut runtime_api = runtime_api::start(fixtures::FUNCTION_URL_EVENT).await;
+        let invocation = tokio::select! {
+            result = lambda_http::run(LambdaHandler::new(reports_service())) => panic!("runtime stopped: {result:?}"),
+            invocation = runtime_api.invocations.recv() => invocation.unwrap(),
+        };
+
+        assert_eq!("/2018-06-01/runtime/invocation/request-1/response", invocation.path);
+        let body = std::str::from_utf8(&invocation.body).unwrap();
+        assert!(body.contains(r#""statusCode":200"#), "{body}");
+        assert!(body.contains(r#""body":"report for /reports/1\na,b\n1,2\n""#), "{body}");
+    }
+
+    #[tokio::test]
+    async fn streaming_response() {
+        let mut runtime_api = runtime_api::start(fixtures::FUNCTION_URL_EVENT).await;
+        let invocation = tokio::select! {
+            result = LambdaHandler::new(reports_service()).run_with_streaming_response() => {
+                panic!("runtime stopped: {result:?}")
+            }
+            invocation = runtime_api.invocations.recv() => invocation.unwrap(),
+        };
+
+        assert_eq!("/2018-06-01/runtime/invocation/request-1/response", invocation.path);
+        assert_eq!("streaming", invocation.headers["lambda-runtime-function-response-mode"]);
+        let body = std::str::from_utf8(&invocation.body).unwrap();
+        let (prelude, body) = body.split_once(&"\0".repeat(8)).expect("missing prelude delimiter");
+        assert!(prelude.contains(r#""statusCode":200"#), "{prelude}");
+        assert!(prelude.contains(r#""content-type":"text/csv""#), "{prelude}");
+        assert_eq!("report for /reports/1\na,b\n1,2\n", body);
+    }
+
+    mod fixtures {
+        pub(super) const FUNCTION_URL_EVENT: &str = r#"{
+            "version": "2.0",
+            "routeKey": "$default",
+            "rawPath": "/reports/1",
+            "rawQueryString": "format=csv",
+            "headers": {
+                "host": "abcdefg.lambda-url.us-east-1.on.aws",
+                "x-forwarded-proto": "https"
+            },
+            "queryStringParameters": { "format": "csv" },
+            "requestContext": {
+                "accountId": "anonymous",
+                "apiId": "abcdefg",
+                "domainName": "abcdefg.lambda-url.us-east-1.on.aws",
+                "domainPrefix": "abcdefg",
+                "http": {
+                    "method": "POST",
+                    "path": "/reports/1",
+                    "protocol": "HTTP/1.1",
+                    "sourceIp": "192.0.2.1",
+                    "userAgent": "curl/8.0.1"
+                },
+                "requestId": "request-1",
+                "routeKey": "$default",
+                "stage": "$default",
+                "time": "18/Oct/2026:12:00:00 +0000",
+                "timeEpoch": 1792324800000
+            },
+            "isBase64Encoded": false
+        }"#;
+
+        pub(super) const ALB_EVENT: &str = r#"{
+            "requestContext": {
+                "elb": {
+                    "targetGroupArn": "arn:aws:elasticloadbalancing:us-east-1:123456789012:targetgroup/reports/abcdefg"
+                }
+            },
+            "httpMethod": "GET",
+            "path": "/reports/1",
+            "queryStringParameters": { "format": "csv" },
+            "headers": {
+                "host": "alb.example.com",
+                "x-forwarded-proto": "http"
+            },
+            "isBase64Encoded": false
+        }"#;
+    }
 }
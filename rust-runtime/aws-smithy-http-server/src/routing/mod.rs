@@ -7,6 +7,7 @@
 //!
 //! [Smithy specification]: https://awslabs.github.io/smithy/1.0/spec/core/http-traits.html
 
+pub mod cors;
 mod into_make_service;
 mod into_make_service_with_connect_info;
 #[cfg(feature = "aws-lambda")]
@@ -26,6 +27,7 @@ use std::{
     future::{ready, Future, Ready},
     marker::PhantomData,
     pin::Pin,
+    sync::Arc,
     task::{Context, Poll},
 };
 
@@ -34,7 +36,7 @@ use futures_util::{
     future::{Either, MapOk},
     TryFutureExt,
 };
-use http::Response;
+use http::{HeaderMap, Response};
 use http_body::Body as HttpBody;
 use tower::{util::Oneshot, Service, ServiceExt};
 use tracing::debug;
@@ -45,6 +47,8 @@ use crate::{
     response::IntoResponse,
 };
 
+use self::cors::CorsConfig;
+
 #[cfg(feature = "aws-lambda")]
 #[cfg_attr(docsrs, doc(cfg(feature = "aws-lambda")))]
 pub use self::lambda_handler::LambdaHandler;
@@ -72,6 +76,13 @@ pub trait Router<B> {
 
     /// Matches a [`http::Request`] to a target [`Service`].
     fn match_route(&self, request: &http::Request<B>) -> Result<Self::Service, Self::Error>;
+
+    /// Returns the HTTP methods of the routes matching the URI of a [`http::Request`], regardless of its method.
+    ///
+    /// This is used to answer [CORS](cors) preflight requests. An empty list means no route matches the URI.
+    fn allowed_methods(&self, _request: &http::Request<B>) -> Vec<http::Method> {
+        Vec::new()
+    }
 }
 
 /// A [`Service`] using the [`Router`] `R` to redirect messages to specific routes.
@@ -79,6 +90,7 @@ pub trait Router<B> {
 /// The `Protocol` parameter is used to determine the serialization of errors.
 pub struct RoutingService<R, Protocol> {
     router: R,
+    cors: Option<Arc<CorsConfig>>,
     _protocol: PhantomData<Protocol>,
 }
 
@@ -89,6 +101,7 @@ where
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("RoutingService")
             .field("router", &self.router)
+            .field("cors", &self.cors)
             .field("_protocol", &self._protocol)
             .finish()
     }
@@ -101,6 +114,7 @@ where
     fn clone(&self) -> Self {
         Self {
             router: self.router.clone(),
+            cors: self.cors.clone(),
             _protocol: PhantomData,
         }
     }
@@ -111,10 +125,17 @@ impl<R, P> RoutingService<R, P> {
     pub fn new(router: R) -> Self {
         Self {
             router,
+            cors: None,
             _protocol: PhantomData,
         }
     }
 
+    /// Enables [CORS](cors) handling with the given configuration.
+    pub fn cors(mut self, config: CorsConfig) -> Self {
+        self.cors = Some(Arc::new(config));
+        self
+    }
+
     /// Maps a [`Router`] using a closure.
     pub fn map<RNew, F>(self, f: F) -> RoutingService<RNew, P>
     where
@@ -122,6 +143,7 @@ impl<R, P> RoutingService<R, P> {
     {
         RoutingService {
             router: f(self.router),
+            cors: self.cors,
             _protocol: PhantomData,
         }
     }
@@ -135,7 +157,9 @@ type EitherOneshotReady<S, B> = Either<
 pin_project_lite::pin_project! {
     pub struct RoutingFuture<S, B> where S: Service<http::Request<B>> {
         #[pin]
-        inner: EitherOneshotReady<S, B>
+        inner: EitherOneshotReady<S, B>,
+        // Headers added to the response, e.g. by CORS handling.
+        extra_headers: Option<HeaderMap>,
     }
 }
 
@@ -152,6 +176,7 @@ where
     {
         Self {
             inner: Either::Left(future.map_ok(|x| x.map(boxed))),
+            extra_headers: None,
         }
     }
 
@@ -159,8 +184,14 @@ where
     pub(super) fn from_response(response: http::Response<BoxBody>) -> Self {
         Self {
             inner: Either::Right(ready(Ok(response))),
+            extra_headers: None,
         }
     }
+
+    fn with_extra_headers(mut self, extra_headers: Option<HeaderMap>) -> Self {
+        self.extra_headers = extra_headers;
+        self
+    }
 }
 
 impl<S, B> Future for RoutingFuture<S, B>
@@ -170,7 +201,23 @@ where
     type Output = Result<http::Response<BoxBody>, S::Error>;
 
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
-        self.project().inner.poll(cx)
+        let this = self.project();
+        this.inner.poll(cx).map_ok(|mut response| {
+            if let Some(extra_headers) = this.extra_headers.take() {
+                // Append rather than `extend`, which would replace headers the handler set, such as `Vary`.
+                let headers = response.headers_mut();
+                let mut last_name = None;
+                for (name, value) in extra_headers {
+                    if let Some(name) = name {
+                        last_name = Some(name);
+                    }
+                    if let Some(name) = &last_name {
+                        headers.append(name, value);
+                    }
+                }
+            }
+            response
+        })
     }
 }
 
@@ -191,7 +238,19 @@ where
     }
 
     fn call(&mut self, req: http::Request<B>) -> Self::Future {
-        match self.router.match_route(&req) {
+        let mut cors_headers = None;
+        if let Some(cors) = &self.cors {
+            if cors::is_preflight(&req) {
+                let allowed_methods = self.router.allowed_methods(&req);
+                // Preflight requests for URIs without routes are routed as usual.
+                if !allowed_methods.is_empty() {
+                    return RoutingFuture::from_response(cors.preflight_response(&req, &allowed_methods));
+                }
+            }
+            cors_headers = cors.response_headers(&req);
+        }
+
+        let future = match self.router.match_route(&req) {
             // Successfully routed, use the routes `Service::call`.
             Ok(ok) => RoutingFuture::from_oneshot(ok.oneshot(req)),
             // Failed to route, use the `R::Error`s `IntoResponse<P>`.
@@ -199,6 +258,7 @@ where
                 debug!(%error, "failed to route");
                 RoutingFuture::from_response(error.into_response())
             }
-        }
+        };
+        future.with_extra_headers(cors_headers)
     }
 }
@@ -161,6 +161,10 @@ impl RequestSpec {
         self.uri_spec.path_and_query.path_segments.0.len() + self.uri_spec.path_and_query.query_segments.0.len()
     }
 
+    pub(crate) fn method(&self) -> &http::Method {
+        &self.method
+    }
+
     pub(crate) fn matches<B>(&self, req: &Request<B>) -> Match {
         if let Some(_host_prefix) = &self.uri_spec.host_prefix {
             todo!("Look at host prefix");
@@ -3,6 +3,8 @@
  * SPDX-License-Identifier: Apache-2.0
  */
 
+use http::StatusCode;
+
 /// A _protocol-agnostic_ type representing an internal framework error. As of writing, this can only
 /// occur upon failure to extract an [`crate::extension::Extension`] from the request.
 /// This type is converted into protocol-specific error variants. For example, in the
@@ -10,4 +12,34 @@
 /// [`crate::proto::rest_json_1::runtime_error::RuntimeError::InternalFailure`] variant.
 pub struct InternalFailureException;
 
+/// A _protocol-agnostic_ type representing a request that does not carry valid credentials for any of the
+/// operation's auth schemes. It is rendered like the protocol-specific `RuntimeError`s, with a `401 Unauthorized`
+/// status code, but isn't one of their variants.
+pub struct UnauthorizedException;
+
+impl UnauthorizedException {
+    pub(crate) const NAME: &'static str = "UnauthorizedException";
+    pub(crate) const STATUS_CODE: StatusCode = StatusCode::UNAUTHORIZED;
+}
+
+/// A _protocol-agnostic_ type representing a request whose authenticated principal is not allowed to invoke the
+/// operation. It is rendered like the protocol-specific `RuntimeError`s, with a `403 Forbidden` status code, but
+/// isn't one of their variants.
+pub struct AccessDeniedException;
+
+impl AccessDeniedException {
+    pub(crate) const NAME: &'static str = "AccessDeniedException";
+    pub(crate) const STATUS_CODE: StatusCode = StatusCode::FORBIDDEN;
+}
+
+/// A _protocol-agnostic_ type representing a request whose body is larger than the configured limit. It is rendered
+/// like the protocol-specific `RuntimeError`s, with a `413 Payload Too Large` status code, but isn't one of their
+/// variants.
+pub struct PayloadTooLargeException;
+
+impl PayloadTooLargeException {
+    pub(crate) const NAME: &'static str = "PayloadTooLargeException";
+    pub(crate) const STATUS_CODE: StatusCode = StatusCode::PAYLOAD_TOO_LARGE;
+}
+
 pub const INVALID_HTTP_RESPONSE_FOR_RUNTIME_ERROR_PANIC_MESSAGE: &str = "invalid HTTP response for `RuntimeError`; please file a bug report under https://github.com/awslabs/smithy-rs/issues";
@@ -0,0 +1,214 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use std::future::Future;
+use std::io;
+use std::net::{SocketAddr, TcpListener as StdTcpListener};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use hyper::server::accept::Accept;
+use hyper::server::conn::{AddrIncoming, AddrStream};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tokio::sync::watch;
+
+#[cfg(feature = "tls")]
+pub(crate) use self::tls::TlsIncoming;
+
+/// A connection accepted by a [`Server`](super::Server).
+pub(crate) enum Connection {
+    Plain(AddrStream),
+    #[cfg(feature = "tls")]
+    Tls(Box<tokio_rustls::server::TlsStream<AddrStream>>),
+}
+
+impl Connection {
+    pub(crate) fn remote_addr(&self) -> SocketAddr {
+        match self {
+            Connection::Plain(stream) => stream.remote_addr(),
+            #[cfg(feature = "tls")]
+            Connection::Tls(stream) => stream.get_ref().0.remote_addr(),
+        }
+    }
+}
+
+macro_rules! delegate {
+    ($self:ident, $stream:ident => $call:expr) => {
+        match $self.get_mut() {
+            Connection::Plain($stream) => {
+                let $stream = Pin::new($stream);
+                $call
+            }
+            #[cfg(feature = "tls")]
+            Connection::Tls($stream) => {
+                let $stream = Pin::new($stream.as_mut());
+                $call
+            }
+        }
+    };
+}
+
+impl AsyncRead for Connection {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        delegate!(self, stream => stream.poll_read(cx, buf))
+    }
+}
+
+impl AsyncWrite for Connection {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        delegate!(self, stream => stream.poll_write(cx, buf))
+    }
+
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[io::IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        delegate!(self, stream => stream.poll_write_vectored(cx, bufs))
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        match self {
+            Connection::Plain(stream) => stream.is_write_vectored(),
+            #[cfg(feature = "tls")]
+            Connection::Tls(stream) => stream.is_write_vectored(),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        delegate!(self, stream => stream.poll_flush(cx))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        delegate!(self, stream => stream.poll_shutdown(cx))
+    }
+}
+
+/// Accepts [`Connection`]s from a listener.
+pub(crate) enum Incoming {
+    Plain(AddrIncoming),
+    #[cfg(feature = "tls")]
+    Tls(Box<TlsIncoming>),
+}
+
+impl Incoming {
+    /// Registers `listener` with the Tokio runtime. Must be called from within the runtime.
+    pub(crate) fn new(listener: StdTcpListener, tcp_nodelay: bool) -> io::Result<Self> {
+        listener.set_nonblocking(true)?;
+        let listener = tokio::net::TcpListener::from_std(listener)?;
+        let mut incoming =
+            AddrIncoming::from_listener(listener).map_err(|err| io::Error::new(io::ErrorKind::Other, err))?;
+        incoming.set_nodelay(tcp_nodelay);
+        Ok(Incoming::Plain(incoming))
+    }
+
+    /// Performs a TLS handshake on every accepted connection.
+    #[cfg(feature = "tls")]
+    pub(crate) fn with_tls(self, acceptor: watch::Receiver<tokio_rustls::TlsAcceptor>) -> Self {
+        match self {
+            Incoming::Plain(incoming) => Incoming::Tls(Box::new(TlsIncoming::new(incoming, acceptor))),
+            tls @ Incoming::Tls(_) => tls,
+        }
+    }
+}
+
+impl Accept for Incoming {
+    type Conn = Connection;
+    type Error = io::Error;
+
+    fn poll_accept(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Result<Self::Conn, Self::Error>>> {
+        match self.get_mut() {
+            Incoming::Plain(incoming) => Pin::new(incoming)
+                .poll_accept(cx)
+                .map(|accepted| accepted.map(|result| result.map(Connection::Plain))),
+            #[cfg(feature = "tls")]
+            Incoming::Tls(incoming) => Pin::new(incoming.as_mut())
+                .poll_accept(cx)
+                .map(|accepted| accepted.map(|result| result.map(|stream| Connection::Tls(Box::new(stream))))),
+        }
+    }
+}
+
+/// Spawns connection tasks onto the Tokio runtime, cancelling them once the [`Server`](super::Server) stops.
+#[derive(Clone)]
+pub(crate) struct ConnectionExecutor {
+    closed: watch::Receiver<()>,
+}
+
+impl ConnectionExecutor {
+    /// Creates an executor whose tasks are cancelled when the sender of `closed` is dropped.
+    pub(crate) fn new(closed: watch::Receiver<()>) -> Self {
+        Self { closed }
+    }
+}
+
+impl<F> hyper::rt::Executor<F> for ConnectionExecutor
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    fn execute(&self, future: F) {
+        let mut closed = self.closed.clone();
+        tokio::spawn(async move {
+            tokio::select! {
+                _ = future => {}
+                _ = closed.changed() => {}
+            }
+        });
+    }
+}
+
+#[cfg(feature = "tls")]
+mod tls {
+    use std::io;
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+
+    use futures_util::{ready, Stream};
+    use hyper::server::accept::Accept;
+    use hyper::server::conn::{AddrIncoming, AddrStream};
+    use tls_listener::{Error as TlsListenerError, TlsListener};
+    use tokio::sync::watch;
+    use tokio_rustls::server::TlsStream;
+    use tokio_rustls::TlsAcceptor;
+
+    /// Accepts TLS connections, picking up reloaded configuration and ignoring failed handshakes.
+    pub(crate) struct TlsIncoming {
+        inner: TlsListener<AddrIncoming, TlsAcceptor>,
+        acceptor: watch::Receiver<TlsAcceptor>,
+    }
+
+    impl TlsIncoming {
+        pub(crate) fn new(incoming: AddrIncoming, acceptor: watch::Receiver<TlsAcceptor>) -> Self {
+            let inner = TlsListener::new(acceptor.borrow().clone(), incoming);
+            Self { inner, acceptor }
+        }
+    }
+
+    impl Accept for TlsIncoming {
+        type Conn = TlsStream<AddrStream>;
+        type Error = io::Error;
+
+        fn poll_accept(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Result<Self::Conn, Self::Error>>> {
+            let this = self.get_mut();
+            if this.acceptor.has_changed().unwrap_or(false) {
+                let acceptor = this.acceptor.borrow_and_update().clone();
+                this.inner.replace_acceptor(acceptor);
+            }
+
+            loop {
+                match ready!(Pin::new(&mut this.inner).poll_next(cx)) {
+                    Some(Ok(stream)) => return Poll::Ready(Some(Ok(stream))),
+                    Some(Err(TlsListenerError::ListenerError(err))) => return Poll::Ready(Some(Err(err))),
+                    Some(Err(TlsListenerError::TlsAcceptError(err))) => {
+                        // Returning handshake errors to Hyper would stop the server.
+                        tracing::debug!(error = %err, "TLS handshake failed");
+                    }
+                    None => return Poll::Ready(None),
+                }
+            }
+        }
+    }
+}
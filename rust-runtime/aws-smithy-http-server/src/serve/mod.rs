@@ -0,0 +1,556 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! A [`Server`] that runs a service on one or more listeners, taking care of graceful shutdown, TLS and
+//! HTTP/1 and HTTP/2 connection settings.
+//!
+//! Every request handled by the [`Server`] carries the address of the peer in a
+//! [`ConnectInfo<SocketAddr>`](crate::request::connect_info::ConnectInfo) extension.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use aws_smithy_http_server::body::{boxed, Body};
+//! use aws_smithy_http_server::serve::Server;
+//! use std::convert::Infallible;
+//! use std::time::Duration;
+//!
+//! # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let app = tower::service_fn(|_request: http::Request<Body>| async {
+//!     Ok::<_, Infallible>(http::Response::new(boxed(Body::empty())))
+//! });
+//!
+//! // Runs until SIGTERM or SIGINT is received, then waits up to 20 seconds for in-flight requests to complete.
+//! Server::new()
+//!     .bind(([0, 0, 0, 0], 8080).into())?
+//!     .graceful_shutdown_timeout(Duration::from_secs(20))
+//!     .http2_max_concurrent_streams(128)
+//!     .serve(app)
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fmt;
+use std::future::Future;
+use std::io;
+use std::net::{SocketAddr, TcpListener as StdTcpListener};
+use std::pin::Pin;
+use std::time::Duration;
+
+use http_body::Body as HttpBody;
+use hyper::service::make_service_fn;
+use thiserror::Error;
+use tokio::sync::watch;
+use tokio::task::{JoinError, JoinSet};
+use tower::Service;
+use tower_http::add_extension::AddExtension;
+
+use crate::body::Body;
+use crate::error::BoxError;
+use crate::request::connect_info::ConnectInfo;
+
+use self::connection::{Connection, ConnectionExecutor, Incoming};
+
+mod connection;
+#[cfg(feature = "tls")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tls")))]
+pub mod tls;
+
+#[cfg(feature = "tls")]
+use self::tls::{TlsConfig, TlsConfigError};
+
+const DEFAULT_GRACEFUL_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(30);
+
+type ShutdownSignal = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// Errors that can occur while running a [`Server`].
+#[non_exhaustive]
+#[derive(Debug, Error)]
+pub enum ServeError {
+    /// No listener was bound or provided.
+    #[error("the server has no listeners to accept connections from")]
+    NoListeners,
+    /// A listener could not be registered with the Tokio runtime.
+    #[error("failed to set up listener")]
+    Listener(#[source] io::Error),
+    /// The handlers for the termination signals could not be installed.
+    #[error("failed to install termination signal handlers")]
+    Signal(#[source] io::Error),
+    /// The TLS configuration could not be loaded.
+    #[cfg(feature = "tls")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tls")))]
+    #[error("failed to load TLS configuration")]
+    Tls(#[from] TlsConfigError),
+    /// A listener failed to accept connections.
+    #[error("server error")]
+    Server(#[source] hyper::Error),
+    /// Connections were still open once the graceful shutdown timeout elapsed, and were closed.
+    #[error("connections were still open {0:?} after shutdown was requested")]
+    DrainTimeout(Duration),
+}
+
+/// HTTP/1 and HTTP/2 connection settings, applied to every listener.
+#[derive(Clone, Copy, Debug)]
+struct HttpConfig {
+    http1_keep_alive: bool,
+    http1_header_read_timeout: Option<Duration>,
+    http2_only: bool,
+    http2_keep_alive_interval: Option<Duration>,
+    http2_keep_alive_timeout: Option<Duration>,
+    http2_max_concurrent_streams: Option<u32>,
+    http2_initial_stream_window_size: Option<u32>,
+    http2_initial_connection_window_size: Option<u32>,
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        Self {
+            http1_keep_alive: true,
+            http1_header_read_timeout: None,
+            http2_only: false,
+            http2_keep_alive_interval: None,
+            http2_keep_alive_timeout: None,
+            http2_max_concurrent_streams: None,
+            http2_initial_stream_window_size: None,
+            http2_initial_connection_window_size: None,
+        }
+    }
+}
+
+impl HttpConfig {
+    fn apply<I, E>(&self, mut builder: hyper::server::Builder<I, E>) -> hyper::server::Builder<I, E> {
+        builder = builder
+            .http1_keepalive(self.http1_keep_alive)
+            .http2_only(self.http2_only)
+            .http2_keep_alive_interval(self.http2_keep_alive_interval);
+        if let Some(timeout) = self.http1_header_read_timeout {
+            builder = builder.http1_header_read_timeout(timeout);
+        }
+        if let Some(timeout) = self.http2_keep_alive_timeout {
+            builder = builder.http2_keep_alive_timeout(timeout);
+        }
+        if let Some(max) = self.http2_max_concurrent_streams {
+            builder = builder.http2_max_concurrent_streams(max);
+        }
+        if let Some(size) = self.http2_initial_stream_window_size {
+            builder = builder.http2_initial_stream_window_size(size);
+        }
+        if let Some(size) = self.http2_initial_connection_window_size {
+            builder = builder.http2_initial_connection_window_size(size);
+        }
+        builder
+    }
+}
+
+/// Runs a service on one or more listeners until a shutdown signal is received.
+///
+/// By default, the server shuts down when the process receives SIGTERM or SIGINT (Ctrl-C on Windows). It then stops
+/// accepting connections and waits up to the [graceful shutdown timeout](Server::graceful_shutdown_timeout) for
+/// in-flight requests to complete before closing the remaining connections.
+///
+/// See the [module documentation](self) for an example.
+pub struct Server {
+    listeners: Vec<StdTcpListener>,
+    tcp_nodelay: bool,
+    http: HttpConfig,
+    graceful_shutdown_timeout: Duration,
+    shutdown_signal: Option<ShutdownSignal>,
+    #[cfg(feature = "tls")]
+    tls: Option<TlsConfig>,
+}
+
+impl fmt::Debug for Server {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut debug = f.debug_struct("Server");
+        debug
+            .field("listeners", &self.listeners)
+            .field("tcp_nodelay", &self.tcp_nodelay)
+            .field("http", &self.http)
+            .field("graceful_shutdown_timeout", &self.graceful_shutdown_timeout)
+            .field("shutdown_signal", &self.shutdown_signal.as_ref().map(|_| "..."));
+        #[cfg(feature = "tls")]
+        debug.field("tls", &self.tls);
+        debug.finish()
+    }
+}
+
+impl Default for Server {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Server {
+    /// Creates a server with no listeners.
+    pub fn new() -> Self {
+        Self {
+            listeners: Vec::new(),
+            tcp_nodelay: false,
+            http: HttpConfig::default(),
+            graceful_shutdown_timeout: DEFAULT_GRACEFUL_SHUTDOWN_TIMEOUT,
+            shutdown_signal: None,
+            #[cfg(feature = "tls")]
+            tls: None,
+        }
+    }
+
+    /// Binds a listener to `addr`.
+    ///
+    /// Can be called multiple times to accept connections on several addresses.
+    pub fn bind(mut self, addr: SocketAddr) -> io::Result<Self> {
+        self.listeners.push(StdTcpListener::bind(addr)?);
+        Ok(self)
+    }
+
+    /// Accepts connections from an already bound listener.
+    ///
+    /// Sockets that need options the server doesn't set itself, for example `SO_REUSEPORT` to share a port between
+    /// worker processes, can be configured with a crate such as `socket2` and converted into a listener once they're
+    /// bound and listening.
+    pub fn listener(mut self, listener: StdTcpListener) -> Self {
+        self.listeners.push(listener);
+        self
+    }
+
+    /// Returns the local addresses of the listeners.
+    ///
+    /// This is useful to find out which port was assigned when binding to port 0.
+    pub fn local_addrs(&self) -> io::Result<Vec<SocketAddr>> {
+        self.listeners.iter().map(StdTcpListener::local_addr).collect()
+    }
+
+    /// Sets whether `TCP_NODELAY` is set on accepted connections. Defaults to `false`.
+    pub fn tcp_nodelay(mut self, enabled: bool) -> Self {
+        self.tcp_nodelay = enabled;
+        self
+    }
+
+    /// Sets whether HTTP/1 connections are kept alive between requests. Defaults to `true`.
+    pub fn http1_keep_alive(mut self, enabled: bool) -> Self {
+        self.http.http1_keep_alive = enabled;
+        self
+    }
+
+    /// Sets how long to wait for a client to send the headers of an HTTP/1 request before closing the connection.
+    pub fn http1_header_read_timeout(mut self, timeout: Duration) -> Self {
+        self.http.http1_header_read_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets whether only HTTP/2 connections are accepted. Defaults to `false`.
+    pub fn http2_only(mut self, enabled: bool) -> Self {
+        self.http.http2_only = enabled;
+        self
+    }
+
+    /// Sets the interval at which HTTP/2 PING frames are sent to keep connections alive.
+    ///
+    /// Keep-alive PINGs are disabled by default.
+    pub fn http2_keep_alive_interval(mut self, interval: Duration) -> Self {
+        self.http.http2_keep_alive_interval = Some(interval);
+        self
+    }
+
+    /// Sets how long to wait for a keep-alive PING to be acknowledged before closing the HTTP/2 connection.
+    ///
+    /// Has no effect unless [`http2_keep_alive_interval`](Server::http2_keep_alive_interval) is set.
+    pub fn http2_keep_alive_timeout(mut self, timeout: Duration) -> Self {
+        self.http.http2_keep_alive_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the maximum number of concurrent streams a client may open on an HTTP/2 connection.
+    pub fn http2_max_concurrent_streams(mut self, max: u32) -> Self {
+        self.http.http2_max_concurrent_streams = Some(max);
+        self
+    }
+
+    /// Sets the initial HTTP/2 flow control window size of each stream, in bytes.
+    pub fn http2_initial_stream_window_size(mut self, size: u32) -> Self {
+        self.http.http2_initial_stream_window_size = Some(size);
+        self
+    }
+
+    /// Sets the initial HTTP/2 flow control window size of each connection, in bytes.
+    pub fn http2_initial_connection_window_size(mut self, size: u32) -> Self {
+        self.http.http2_initial_connection_window_size = Some(size);
+        self
+    }
+
+    /// Sets how long in-flight requests are given to complete once shutdown is requested. Defaults to 30 seconds.
+    pub fn graceful_shutdown_timeout(mut self, timeout: Duration) -> Self {
+        self.graceful_shutdown_timeout = timeout;
+        self
+    }
+
+    /// Shuts the server down when `signal` completes, instead of when SIGTERM or SIGINT is received.
+    pub fn shutdown_signal(mut self, signal: impl Future<Output = ()> + Send + 'static) -> Self {
+        self.shutdown_signal = Some(Box::pin(signal));
+        self
+    }
+
+    /// Serves TLS connections on every listener.
+    #[cfg(feature = "tls")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tls")))]
+    pub fn tls(mut self, config: TlsConfig) -> Self {
+        self.tls = Some(config);
+        self
+    }
+
+    /// Runs `service` until shutdown is requested and connections have been drained.
+    ///
+    /// Returns [`ServeError::DrainTimeout`] if connections had to be closed because they were still open when the
+    /// graceful shutdown timeout elapsed.
+    pub async fn serve<S, B>(self, service: S) -> Result<(), ServeError>
+    where
+        S: Service<http::Request<Body>, Response = http::Response<B>> + Clone + Send + 'static,
+        S::Future: Send + 'static,
+        S::Error: Into<BoxError>,
+        B: HttpBody + Send + 'static,
+        B::Data: Send,
+        B::Error: Into<BoxError>,
+    {
+        if self.listeners.is_empty() {
+            return Err(ServeError::NoListeners);
+        }
+        let shutdown_signal = match self.shutdown_signal {
+            Some(signal) => signal,
+            None => termination_signal().map_err(ServeError::Signal)?,
+        };
+
+        #[cfg(feature = "tls")]
+        let tls = match &self.tls {
+            Some(config) => Some(tls::Reloader::spawn(config.clone(), self.http.http2_only)?),
+            None => None,
+        };
+
+        // Dropping the sender closes any connection still open, so that nothing outlives `serve`.
+        let (_close_connections, close_connections_rx) = watch::channel(());
+        let (drain_tx, drain_rx) = watch::channel(());
+        let mut servers = JoinSet::new();
+        for listener in self.listeners {
+            let incoming = Incoming::new(listener, self.tcp_nodelay).map_err(ServeError::Listener)?;
+            #[cfg(feature = "tls")]
+            let incoming = match &tls {
+                Some(reloader) => incoming.with_tls(reloader.acceptor()),
+                None => incoming,
+            };
+
+            let service = service.clone();
+            let make_service = make_service_fn(move |connection: &Connection| {
+                let service = AddExtension::new(service.clone(), ConnectInfo(connection.remote_addr()));
+                async move { Ok::<_, std::convert::Infallible>(service) }
+            });
+            let mut drain_rx = drain_rx.clone();
+            let server = self
+                .http
+                .apply(hyper::Server::builder(incoming))
+                .executor(ConnectionExecutor::new(close_connections_rx.clone()))
+                .serve(make_service)
+                .with_graceful_shutdown(async move {
+                    let _ = drain_rx.changed().await;
+                });
+            servers.spawn(server);
+        }
+
+        let mut result = tokio::select! {
+            _ = shutdown_signal => Ok(()),
+            Some(joined) = servers.join_next() => server_result(joined),
+        };
+
+        tracing::info!("shutting down server, draining open connections");
+        let _ = drain_tx.send(());
+        let drain = async {
+            while let Some(joined) = servers.join_next().await {
+                let joined = server_result(joined);
+                if result.is_ok() {
+                    result = joined;
+                }
+            }
+        };
+        if tokio::time::timeout(self.graceful_shutdown_timeout, drain)
+            .await
+            .is_err()
+        {
+            tracing::warn!(
+                timeout = ?self.graceful_shutdown_timeout,
+                "connections were not drained before the graceful shutdown timeout, closing them"
+            );
+            servers.abort_all();
+            if result.is_ok() {
+                result = Err(ServeError::DrainTimeout(self.graceful_shutdown_timeout));
+            }
+        }
+        result
+    }
+}
+
+fn server_result(joined: Result<Result<(), hyper::Error>, JoinError>) -> Result<(), ServeError> {
+    match joined {
+        Ok(result) => result.map_err(ServeError::Server),
+        Err(err) if err.is_panic() => std::panic::resume_unwind(err.into_panic()),
+        Err(_) => Ok(()),
+    }
+}
+
+/// Returns a future that completes when the process is asked to terminate.
+fn termination_signal() -> io::Result<ShutdownSignal> {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        let mut terminate = signal(SignalKind::terminate())?;
+        let mut interrupt = signal(SignalKind::interrupt())?;
+        Ok(Box::pin(async move {
+            tokio::select! {
+                _ = terminate.recv() => tracing::info!("received SIGTERM"),
+                _ = interrupt.recv() => tracing::info!("received SIGINT"),
+            }
+        }))
+    }
+
+    #[cfg(not(unix))]
+    {
+        Ok(Box::pin(async {
+            match tokio::signal::ctrl_c().await {
+                Ok(()) => tracing::info!("received Ctrl-C"),
+                Err(err) => {
+                    tracing::error!(error = %err, "failed to listen for Ctrl-C, the server must be stopped externally");
+                    std::future::pending::<()>().await
+                }
+            }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+    use std::net::SocketAddr;
+    use std::time::Duration;
+
+    use hyper::{Client, Uri};
+    use tokio::sync::{mpsc, oneshot};
+
+    use super::*;
+    use crate::body::{boxed, BoxBody};
+
+    async fn echo_peer(request: http::Request<Body>) -> Result<http::Response<BoxBody>, Infallible> {
+        let ConnectInfo(peer) = request.extensions().get::<ConnectInfo<SocketAddr>>().unwrap().clone();
+        Ok(http::Response::new(boxed(Body::from(peer.ip().to_string()))))
+    }
+
+    fn uri(addr: SocketAddr, path: &str) -> Uri {
+        format!("http://{addr}{path}").parse().unwrap()
+    }
+
+    #[tokio::test]
+    async fn serves_requests_and_shuts_down_gracefully() {
+        let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+        let server = Server::new()
+            .bind(([127, 0, 0, 1], 0).into())
+            .unwrap()
+            .bind(([127, 0, 0, 1], 0).into())
+            .unwrap()
+            .shutdown_signal(async {
+                let _ = shutdown_rx.await;
+            });
+        let addrs = server.local_addrs().unwrap();
+        let server = tokio::spawn(server.serve(tower::service_fn(echo_peer)));
+
+        for addr in addrs {
+            let response = Client::new().get(uri(addr, "/")).await.unwrap();
+            let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+            assert_eq!("127.0.0.1", body);
+        }
+
+        shutdown_tx.send(()).unwrap();
+        server.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn http2_only() {
+        let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+        let server = Server::new()
+            .bind(([127, 0, 0, 1], 0).into())
+            .unwrap()
+            .http2_only(true)
+            .http2_max_concurrent_streams(10)
+            .shutdown_signal(async {
+                let _ = shutdown_rx.await;
+            });
+        let addr = server.local_addrs().unwrap()[0];
+        let server = tokio::spawn(server.serve(tower::service_fn(echo_peer)));
+
+        let client = Client::builder().http2_only(true).build_http::<Body>();
+        let response = client.get(uri(addr, "/")).await.unwrap();
+        assert_eq!(http::Version::HTTP_2, response.version());
+        assert!(Client::new().get(uri(addr, "/")).await.is_err());
+
+        shutdown_tx.send(()).unwrap();
+        server.await.unwrap().unwrap();
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn closes_connections_after_graceful_shutdown_timeout() {
+        // Requests announce that they've reached the service, and then never complete
+        let (received_tx, mut received_rx) = mpsc::unbounded_channel();
+        let never_responds = tower::service_fn(move |_request: http::Request<Body>| {
+            let _ = received_tx.send(());
+            std::future::pending::<Result<http::Response<BoxBody>, Infallible>>()
+        });
+
+        let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+        let server = Server::new()
+            .bind(([127, 0, 0, 1], 0).into())
+            .unwrap()
+            .graceful_shutdown_timeout(Duration::from_millis(100))
+            .shutdown_signal(async {
+                let _ = shutdown_rx.await;
+            });
+        let addr = server.local_addrs().unwrap()[0];
+        let server = tokio::spawn(server.serve(never_responds));
+
+        let request = tokio::spawn(Client::new().get(uri(addr, "/")));
+        received_rx.recv().await.unwrap();
+        shutdown_tx.send(()).unwrap();
+
+        // Time is paused, so the timeout elapses as soon as the server is only waiting on the connection
+        let result = server.await.unwrap();
+        assert!(
+            matches!(result, Err(ServeError::DrainTimeout(timeout)) if timeout == Duration::from_millis(100)),
+            "{result:?}"
+        );
+        assert!(request.await.unwrap().is_err());
+    }
+
+    #[tokio::test]
+    async fn serves_from_socket() {
+        let socket = socket2::Socket::new(socket2::Domain::IPV4, socket2::Type::STREAM, None).unwrap();
+        socket.set_reuse_address(true).unwrap();
+        socket.bind(&SocketAddr::from(([127, 0, 0, 1], 0)).into()).unwrap();
+        socket.listen(128).unwrap();
+
+        let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+        let server = Server::new().listener(socket.into()).shutdown_signal(async {
+            let _ = shutdown_rx.await;
+        });
+        let addr = server.local_addrs().unwrap()[0];
+        let server = tokio::spawn(server.serve(tower::service_fn(echo_peer)));
+
+        let response = Client::new().get(uri(addr, "/")).await.unwrap();
+        assert_eq!(http::StatusCode::OK, response.status());
+
+        shutdown_tx.send(()).unwrap();
+        server.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn requires_a_listener() {
+        let result = Server::new().serve(tower::service_fn(echo_peer)).await;
+        assert!(matches!(result, Err(ServeError::NoListeners)));
+    }
+}
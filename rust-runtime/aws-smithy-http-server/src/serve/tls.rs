@@ -0,0 +1,255 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! TLS configuration for a [`Server`](super::Server).
+
+use std::fs::File;
+use std::io::{self, BufReader};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use thiserror::Error;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use tokio_rustls::rustls::{Certificate, PrivateKey, ServerConfig};
+use tokio_rustls::TlsAcceptor;
+
+const DEFAULT_RELOAD_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Errors that can occur while loading a [`TlsConfig`].
+#[non_exhaustive]
+#[derive(Debug, Error)]
+pub enum TlsConfigError {
+    /// The certificate chain could not be read.
+    #[error("failed to read certificate chain from `{}`", .0.display())]
+    ReadCertificates(PathBuf, #[source] io::Error),
+    /// The certificate chain file contained no certificates.
+    #[error("no certificates found in `{}`", .0.display())]
+    NoCertificates(PathBuf),
+    /// The private key could not be read.
+    #[error("failed to read private key from `{}`", .0.display())]
+    ReadPrivateKey(PathBuf, #[source] io::Error),
+    /// The private key file contained no PKCS#8 or RSA private key.
+    #[error("no PKCS#8 or RSA private key found in `{}`", .0.display())]
+    NoPrivateKey(PathBuf),
+    /// The certificate chain and private key were rejected by rustls.
+    #[error("invalid certificate chain or private key")]
+    Rustls(#[from] tokio_rustls::rustls::Error),
+}
+
+/// A certificate chain and private key loaded from PEM files, which are periodically re-read so that certificates
+/// can be rotated without restarting the server.
+///
+/// If the files can't be loaded when reloading, an error is logged and the previous certificate stays in use.
+#[derive(Clone, Debug)]
+pub struct TlsConfig {
+    cert_path: PathBuf,
+    key_path: PathBuf,
+    reload_interval: Duration,
+}
+
+impl TlsConfig {
+    /// Creates a configuration from a PEM-encoded certificate chain and a PEM-encoded PKCS#8 or RSA private key.
+    pub fn from_pem_files(cert_path: impl Into<PathBuf>, key_path: impl Into<PathBuf>) -> Self {
+        Self {
+            cert_path: cert_path.into(),
+            key_path: key_path.into(),
+            reload_interval: DEFAULT_RELOAD_INTERVAL,
+        }
+    }
+
+    /// Sets how often the certificate chain and private key are re-read from disk. Defaults to one hour.
+    pub fn reload_interval(mut self, interval: Duration) -> Self {
+        self.reload_interval = interval;
+        self
+    }
+
+    /// Loads the rustls [`ServerConfig`] from disk.
+    pub fn load(&self) -> Result<ServerConfig, TlsConfigError> {
+        let cert_chain = read_certificates(&self.cert_path)?;
+        let key = read_private_key(&self.key_path)?;
+        Ok(ServerConfig::builder()
+            .with_safe_defaults()
+            .with_no_client_auth()
+            .with_single_cert(cert_chain, key)?)
+    }
+
+    fn acceptor(&self, http2_only: bool) -> Result<TlsAcceptor, TlsConfigError> {
+        let mut config = self.load()?;
+        config.alpn_protocols = if http2_only {
+            vec![b"h2".to_vec()]
+        } else {
+            vec![b"h2".to_vec(), b"http/1.1".to_vec()]
+        };
+        Ok(TlsAcceptor::from(Arc::new(config)))
+    }
+}
+
+fn read_certificates(path: &Path) -> Result<Vec<Certificate>, TlsConfigError> {
+    let read_error = |err| TlsConfigError::ReadCertificates(path.to_owned(), err);
+    let mut reader = BufReader::new(File::open(path).map_err(read_error)?);
+    let certificates = rustls_pemfile::certs(&mut reader).map_err(read_error)?;
+    if certificates.is_empty() {
+        return Err(TlsConfigError::NoCertificates(path.to_owned()));
+    }
+    Ok(certificates.into_iter().map(Certificate).collect())
+}
+
+fn read_private_key(path: &Path) -> Result<PrivateKey, TlsConfigError> {
+    let read_error = |err| TlsConfigError::ReadPrivateKey(path.to_owned(), err);
+    let pem = std::fs::read(path).map_err(read_error)?;
+    let mut keys = rustls_pemfile::pkcs8_private_keys(&mut pem.as_slice()).map_err(read_error)?;
+    if keys.is_empty() {
+        keys = rustls_pemfile::rsa_private_keys(&mut pem.as_slice()).map_err(read_error)?;
+    }
+    keys.into_iter()
+        .next()
+        .map(PrivateKey)
+        .ok_or_else(|| TlsConfigError::NoPrivateKey(path.to_owned()))
+}
+
+/// Periodically reloads a [`TlsConfig`], publishing a new [`TlsAcceptor`] each time.
+pub(crate) struct Reloader {
+    acceptor: watch::Receiver<TlsAcceptor>,
+    task: JoinHandle<()>,
+}
+
+impl Reloader {
+    /// Loads `config` and starts reloading it in the background. Must be called from within the Tokio runtime.
+    pub(crate) fn spawn(config: TlsConfig, http2_only: bool) -> Result<Self, TlsConfigError> {
+        let (acceptor_tx, acceptor) = watch::channel(config.acceptor(http2_only)?);
+        let task = tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(config.reload_interval).await;
+                match config.acceptor(http2_only) {
+                    Ok(acceptor) => {
+                        tracing::debug!("reloaded TLS configuration");
+                        if acceptor_tx.send(acceptor).is_err() {
+                            break;
+                        }
+                    }
+                    Err(err) => {
+                        tracing::error!(error = %err, "failed to reload TLS configuration, keeping the current one")
+                    }
+                }
+            }
+        });
+        Ok(Self { acceptor, task })
+    }
+
+    /// Returns a receiver of the most recently loaded acceptor.
+    pub(crate) fn acceptor(&self) -> watch::Receiver<TlsAcceptor> {
+        self.acceptor.clone()
+    }
+}
+
+impl Drop for Reloader {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::SocketAddr;
+    use std::time::Duration;
+
+    use hyper::{Body, Client};
+    use hyper_rustls::HttpsConnectorBuilder;
+    use tokio::sync::oneshot;
+    use tokio_rustls::rustls::{ClientConfig, RootCertStore};
+
+    use super::*;
+    use crate::body::boxed;
+    use crate::serve::Server;
+
+    fn certificate() -> rcgen::Certificate {
+        rcgen::Certificate::from_params(rcgen::CertificateParams::new(vec!["localhost".to_string()])).unwrap()
+    }
+
+    /// Writes the certificate and its key, replacing each file atomically so that a reload never reads a partial file.
+    fn write_certificate(dir: &Path, certificate: &rcgen::Certificate) {
+        let write_atomic = |name: &str, contents: String| {
+            let temp = dir.join(format!(".{name}.tmp"));
+            std::fs::write(&temp, contents).unwrap();
+            std::fs::rename(temp, dir.join(name)).unwrap();
+        };
+        write_atomic("key.pem", certificate.serialize_private_key_pem());
+        write_atomic("cert.pem", certificate.serialize_pem().unwrap());
+    }
+
+    async fn get(trusted: &rcgen::Certificate, addr: SocketAddr) -> Result<String, hyper::Error> {
+        let mut roots = RootCertStore::empty();
+        roots.add_parsable_certificates(&[trusted.serialize_der().unwrap()]);
+        let config = ClientConfig::builder()
+            .with_safe_defaults()
+            .with_root_certificates(roots)
+            .with_no_client_auth();
+        let connector = HttpsConnectorBuilder::new()
+            .with_tls_config(config)
+            .https_only()
+            .enable_http1()
+            .enable_http2()
+            .build();
+        let uri = format!("https://localhost:{}/", addr.port()).parse().unwrap();
+        let response = Client::builder().build::<_, Body>(connector).get(uri).await?;
+        let body = hyper::body::to_bytes(response.into_body()).await?;
+        Ok(String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[test]
+    fn missing_private_key() {
+        let dir = tempfile::tempdir().unwrap();
+        write_certificate(dir.path(), &certificate());
+        std::fs::write(dir.path().join("key.pem"), "").unwrap();
+
+        let config = TlsConfig::from_pem_files(dir.path().join("cert.pem"), dir.path().join("key.pem"));
+        assert!(matches!(config.load(), Err(TlsConfigError::NoPrivateKey(_))));
+    }
+
+    #[tokio::test]
+    async fn reloads_certificates_from_disk() {
+        let dir = tempfile::tempdir().unwrap();
+        let first = certificate();
+        write_certificate(dir.path(), &first);
+
+        let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+        let config = TlsConfig::from_pem_files(dir.path().join("cert.pem"), dir.path().join("key.pem"))
+            .reload_interval(Duration::from_millis(50));
+        let server = Server::new()
+            .bind(([127, 0, 0, 1], 0).into())
+            .unwrap()
+            .tls(config)
+            .shutdown_signal(async {
+                let _ = shutdown_rx.await;
+            });
+        let addr = server.local_addrs().unwrap()[0];
+        let app = tower::service_fn(|_request: http::Request<Body>| async {
+            Ok::<_, std::convert::Infallible>(http::Response::new(boxed(Body::from("hello"))))
+        });
+        let server = tokio::spawn(server.serve(app));
+
+        assert_eq!("hello", get(&first, addr).await.unwrap());
+
+        let second = certificate();
+        write_certificate(dir.path(), &second);
+        // A reload may run between writing the key and the certificate, so poll until the new pair is served.
+        tokio::time::timeout(Duration::from_secs(30), async {
+            while get(&second, addr).await.is_err() {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+            }
+        })
+        .await
+        .expect("the new certificate should be served");
+
+        // The failed handshake doesn't stop the server.
+        assert!(get(&first, addr).await.is_err());
+        assert_eq!("hello", get(&second, addr).await.unwrap());
+
+        shutdown_tx.send(()).unwrap();
+        server.await.unwrap().unwrap();
+    }
+}
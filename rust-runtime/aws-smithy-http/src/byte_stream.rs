@@ -123,6 +123,8 @@
 
 use crate::body::SdkBody;
 use crate::byte_stream::error::Error;
+#[cfg(any(feature = "rt-tokio", feature = "blocking-fs"))]
+use crate::byte_stream::error::ErrorKind;
 use bytes::Buf;
 use bytes::Bytes;
 use bytes_utils::SegmentedBuf;
@@ -132,16 +134,63 @@ use std::io::IoSlice;
 use std::pin::Pin;
 use std::task::{Context, Poll};
 
+#[cfg(feature = "blocking-fs")]
+mod blocking_fs;
 #[cfg(feature = "rt-tokio")]
 mod bytestream_util;
-#[cfg(feature = "rt-tokio")]
-pub use bytestream_util::Length;
 
 pub mod error;
 
+#[cfg(feature = "futures-io")]
+mod futures_adapters;
+
+#[cfg(feature = "blocking-fs")]
+pub use self::blocking_fs::BlockingFsBuilder;
 #[cfg(feature = "rt-tokio")]
 pub use self::bytestream_util::FsBuilder;
 
+#[cfg(feature = "rt-tokio")]
+mod partition;
+#[cfg(feature = "rt-tokio")]
+pub use self::partition::{Part, Partitions};
+
+/// The length (in bytes) to read. Determines whether or not a short read counts as an error.
+#[cfg(any(feature = "rt-tokio", feature = "blocking-fs"))]
+#[derive(Debug)]
+pub enum Length {
+    /// Read this number of bytes exactly. Returns an error if the file is smaller than expected.
+    Exact(u64),
+    /// Read up to this number of bytes. May read less than the specified amount if the file
+    /// is smaller than expected.
+    UpTo(u64),
+}
+
+/// Resolves the offset and number of bytes to read from a file of `file_length` bytes
+#[cfg(any(feature = "rt-tokio", feature = "blocking-fs"))]
+fn read_range(
+    offset: Option<u64>,
+    length: Option<&Length>,
+    file_length: u64,
+) -> Result<(u64, u64), Error> {
+    // By default, read files from their start
+    let offset = offset.unwrap_or(0);
+    if offset > file_length {
+        return Err(ErrorKind::OffsetLargerThanFileSize.into());
+    }
+
+    let length = match length {
+        Some(Length::Exact(length)) => {
+            if *length > file_length - offset {
+                return Err(ErrorKind::LengthLargerThanFileSizeMinusReadOffset.into());
+            }
+            *length
+        }
+        Some(Length::UpTo(length)) => *length,
+        None => file_length - offset,
+    };
+    Ok((offset, length))
+}
+
 pin_project! {
     /// Stream of binary data
     ///
@@ -375,6 +424,86 @@ impl ByteStream {
         FsBuilder::new().file(file).build().await
     }
 
+    /// Returns a [`BlockingFsBuilder`](crate::byte_stream::BlockingFsBuilder), allowing you to build
+    /// a `ByteStream` from a file without depending on Tokio.
+    ///
+    /// The file is read on a blocking thread pool, so the resulting `ByteStream` can be used with
+    /// `async-std`, `smol` or any other async runtime.
+    #[cfg(feature = "blocking-fs")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "blocking-fs")))]
+    pub fn read_from_blocking() -> BlockingFsBuilder {
+        BlockingFsBuilder::new()
+    }
+
+    /// Create a retryable ByteStream that streams data from the filesystem without depending on Tokio
+    ///
+    /// This is equivalent to [`ByteStream::from_path`](ByteStream::from_path), except that the file
+    /// is read on a blocking thread pool, so it can be used with any async runtime.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use aws_smithy_http::byte_stream::ByteStream;
+    /// # #[cfg(feature = "blocking-fs")]
+    /// async fn make_bytestream() -> ByteStream {
+    ///     ByteStream::from_path_blocking("docs/rows.csv").await.expect("file should be readable")
+    /// }
+    /// ```
+    #[cfg(feature = "blocking-fs")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "blocking-fs")))]
+    pub async fn from_path_blocking(path: impl AsRef<std::path::Path>) -> Result<Self, Error> {
+        BlockingFsBuilder::new().path(path).build().await
+    }
+
+    /// Create a ByteStream that reads from a [`futures_io::AsyncRead`]
+    ///
+    /// NOTE: This will NOT result in a retryable ByteStream. To retry requests with a file body,
+    /// use [`ByteStream::from_path_blocking`](ByteStream::from_path_blocking) instead.
+    #[cfg(feature = "futures-io")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "futures-io")))]
+    pub fn from_futures_async_read(
+        reader: impl futures_io::AsyncRead + Send + Sync + 'static,
+    ) -> Self {
+        futures_adapters::from_async_read(reader)
+    }
+
+    /// Convert this `ByteStream` into a struct that implements [`futures_io::AsyncBufRead`]
+    /// (and therefore [`futures_io::AsyncRead`]).
+    ///
+    /// This is the `futures` counterpart of `into_async_read`, for use with runtimes like
+    /// `async-std` and `smol`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use aws_smithy_http::byte_stream::ByteStream;
+    /// use futures_lite::io::AsyncReadExt;
+    ///
+    /// # async fn dox(my_bytestream: ByteStream) -> std::io::Result<()> {
+    /// let mut contents = String::new();
+    /// my_bytestream.into_futures_async_read().read_to_string(&mut contents).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "futures-io")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "futures-io")))]
+    pub fn into_futures_async_read(self) -> impl futures_io::AsyncBufRead {
+        futures_adapters::FuturesAsyncRead::new(self)
+    }
+
+    /// Write the contents of this `ByteStream` into a [`futures_io::AsyncWrite`], returning the number
+    /// of bytes written
+    ///
+    /// Data is written chunk by chunk as it is received, so the stream is never held in memory in
+    /// full. The writer is flushed once the stream has been written.
+    #[cfg(feature = "futures-io")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "futures-io")))]
+    pub async fn copy_to_futures_async_write<W>(self, writer: &mut W) -> Result<u64, Error>
+    where
+        W: futures_io::AsyncWrite + Unpin + ?Sized,
+    {
+        futures_adapters::copy_to_async_write(self, writer).await
+    }
+
     #[cfg(feature = "rt-tokio")]
     /// Convert this `ByteStream` into a struct that implements [`AsyncRead`](tokio::io::AsyncRead).
     ///
@@ -0,0 +1,312 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! File-backed bodies that don't depend on an async runtime
+//!
+//! File I/O is performed on the [`blocking`] thread pool, which is the same approach `async-std`
+//! and `smol` take for their file system APIs. The resulting bodies can be polled from any
+//! executor.
+
+use crate::body::SdkBody;
+use crate::byte_stream::{error::Error, read_range, ByteStream, Length};
+use blocking::{unblock, Task};
+use bytes::Bytes;
+use http::HeaderMap;
+use http_body::{Body, SizeHint};
+use std::fs::File;
+use std::future::Future;
+use std::io::{self, Read, Seek};
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+// Matches the default buffer size of `FsBuilder`
+const DEFAULT_BUFFER_SIZE: usize = 4096;
+
+/// Builder for creating [`ByteStreams`](ByteStream) from a path without depending on an async runtime.
+///
+/// This offers the same options as [`FsBuilder`](crate::byte_stream::FsBuilder), but reads the file
+/// on a blocking thread pool rather than with Tokio, so it works with `async-std`, `smol`, or any
+/// other executor.
+///
+/// The resulting `ByteStream` is retryable: if the request fails, the file will be reopened and
+/// read again. The contents of the file MUST NOT change during retries.
+///
+/// Example usage:
+/// ```no_run
+/// # #[cfg(feature = "blocking-fs")]
+/// # {
+/// use aws_smithy_http::byte_stream::{ByteStream, Length};
+///
+/// async fn bytestream_from_file() -> ByteStream {
+///     ByteStream::read_from_blocking()
+///         .path("docs/some-large-file.csv")
+///         .buffer_size(32_784)
+///         .length(Length::UpTo(123_456))
+///         .build()
+///         .await
+///         .expect("valid path")
+/// }
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct BlockingFsBuilder {
+    path: Option<PathBuf>,
+    length: Option<Length>,
+    buffer_size: usize,
+    offset: Option<u64>,
+}
+
+impl Default for BlockingFsBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BlockingFsBuilder {
+    /// Create a new [`BlockingFsBuilder`] (using a default read buffer of 4096 bytes).
+    ///
+    /// You must then call [`path`](BlockingFsBuilder::path) to specify what to read from.
+    pub fn new() -> Self {
+        BlockingFsBuilder {
+            path: None,
+            length: None,
+            buffer_size: DEFAULT_BUFFER_SIZE,
+            offset: None,
+        }
+    }
+
+    /// Sets the path to read from.
+    pub fn path(mut self, path: impl AsRef<std::path::Path>) -> Self {
+        self.path = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Specify the length to read (in bytes).
+    ///
+    /// By pre-specifying the length, this API skips an additional call to retrieve the size from file-system metadata.
+    pub fn length(mut self, length: Length) -> Self {
+        self.length = Some(length);
+        self
+    }
+
+    /// Specify the size of the buffer used to read the file (in bytes).
+    pub fn buffer_size(mut self, buffer_size: usize) -> Self {
+        self.buffer_size = buffer_size;
+        self
+    }
+
+    /// Specify the offset to start reading from (in bytes)
+    pub fn offset(mut self, offset: u64) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+
+    /// Returns a [`ByteStream`](ByteStream) from this builder.
+    pub async fn build(self) -> Result<ByteStream, Error> {
+        let path = self
+            .path
+            .expect("BlockingFsBuilder constructed without a path");
+        let file_length = {
+            let path = path.clone();
+            unblock(move || std::fs::metadata(path)).await?.len()
+        };
+        let (offset, length) = read_range(self.offset, self.length.as_ref(), file_length)?;
+        let buffer_size = self.buffer_size.max(1);
+
+        let body_loader = move || {
+            SdkBody::from_dyn(http_body::combinators::BoxBody::new(BlockingPathBody {
+                state: State::Unloaded(path.clone()),
+                length,
+                remaining: length,
+                buffer_size,
+                offset,
+            }))
+        };
+        Ok(ByteStream::new(SdkBody::retryable(body_loader)))
+    }
+}
+
+struct BlockingPathBody {
+    state: State,
+    // The number of bytes to read
+    length: u64,
+    // The number of bytes left to read
+    remaining: u64,
+    buffer_size: usize,
+    // The byte-offset to start reading from
+    offset: u64,
+}
+
+enum State {
+    Unloaded(PathBuf),
+    Loading(Task<io::Result<File>>),
+    Idle(File),
+    Reading(Task<(File, io::Result<Vec<u8>>)>),
+    Done,
+}
+
+impl Body for BlockingPathBody {
+    type Data = Bytes;
+    type Error = Box<dyn std::error::Error + Send + Sync + 'static>;
+
+    fn poll_data(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Self::Data, Self::Error>>> {
+        loop {
+            match std::mem::replace(&mut self.state, State::Done) {
+                State::Unloaded(path) => {
+                    let offset = self.offset;
+                    self.state = State::Loading(unblock(move || {
+                        let mut file = File::open(path)?;
+                        if offset != 0 {
+                            file.seek(io::SeekFrom::Start(offset))?;
+                        }
+                        Ok(file)
+                    }));
+                }
+                State::Loading(mut task) => match Pin::new(&mut task).poll(cx) {
+                    Poll::Pending => {
+                        self.state = State::Loading(task);
+                        return Poll::Pending;
+                    }
+                    Poll::Ready(Ok(file)) => self.state = State::Idle(file),
+                    Poll::Ready(Err(err)) => return Poll::Ready(Some(Err(err.into()))),
+                },
+                State::Idle(file) => {
+                    if self.remaining == 0 {
+                        return Poll::Ready(None);
+                    }
+                    let to_read = self.remaining.min(self.buffer_size as u64);
+                    self.state = State::Reading(unblock(move || {
+                        let mut buf = Vec::with_capacity(to_read as usize);
+                        let result = (&file).take(to_read).read_to_end(&mut buf).map(|_| buf);
+                        (file, result)
+                    }));
+                }
+                State::Reading(mut task) => {
+                    let (file, result) = match Pin::new(&mut task).poll(cx) {
+                        Poll::Pending => {
+                            self.state = State::Reading(task);
+                            return Poll::Pending;
+                        }
+                        Poll::Ready(output) => output,
+                    };
+                    let buf = match result {
+                        Ok(buf) => buf,
+                        Err(err) => return Poll::Ready(Some(Err(err.into()))),
+                    };
+                    // The file is shorter than expected, which is allowed with `Length::UpTo`
+                    if buf.is_empty() {
+                        return Poll::Ready(None);
+                    }
+                    self.remaining -= buf.len() as u64;
+                    self.state = State::Idle(file);
+                    return Poll::Ready(Some(Ok(Bytes::from(buf))));
+                }
+                State::Done => return Poll::Ready(None),
+            }
+        }
+    }
+
+    fn poll_trailers(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+    ) -> Poll<Result<Option<HeaderMap>, Self::Error>> {
+        Poll::Ready(Ok(None))
+    }
+
+    fn is_end_stream(&self) -> bool {
+        // fast path end-stream for empty streams
+        self.length == 0
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        SizeHint::with_exact(self.length)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::BlockingFsBuilder;
+    use crate::byte_stream::Length;
+    use futures_lite::future::block_on;
+    use http_body::Body;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    fn file_with_lines(lines: usize) -> (NamedTempFile, String) {
+        let mut file = NamedTempFile::new().unwrap();
+        let mut contents = String::new();
+        for i in 0..lines {
+            contents.push_str(&format!("Line {:04}\n", i));
+        }
+        file.write_all(contents.as_bytes()).unwrap();
+        file.flush().unwrap();
+        (file, contents)
+    }
+
+    #[test]
+    fn reads_file_without_a_runtime() {
+        let (file, contents) = file_with_lines(1000);
+        let stream = block_on(
+            BlockingFsBuilder::new()
+                .path(&file)
+                .buffer_size(1000)
+                .build(),
+        )
+        .unwrap();
+        let body = stream.into_inner();
+        assert_eq!(Some(contents.len() as u64), body.size_hint().exact());
+
+        // retryable bodies can be read more than once
+        let retry = body.try_clone().expect("retryable");
+        for body in [body, retry] {
+            let data = block_on(crate::byte_stream::ByteStream::new(body).collect())
+                .unwrap()
+                .into_bytes();
+            assert_eq!(contents.as_bytes(), &data[..]);
+        }
+    }
+
+    #[test]
+    fn offset_and_length() {
+        let (file, contents) = file_with_lines(10);
+        let read = |builder: BlockingFsBuilder| {
+            block_on(async { builder.build().await?.collect().await })
+                .map(|data| String::from_utf8(data.to_vec()).unwrap())
+        };
+
+        assert_eq!(
+            &contents[10..30],
+            read(
+                BlockingFsBuilder::new()
+                    .path(&file)
+                    .offset(10)
+                    .length(Length::Exact(20))
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            &contents[90..],
+            read(
+                BlockingFsBuilder::new()
+                    .path(&file)
+                    .offset(90)
+                    .length(Length::UpTo(9000))
+            )
+            .unwrap()
+        );
+        read(
+            BlockingFsBuilder::new()
+                .path(&file)
+                .length(Length::Exact(9000)),
+        )
+        .expect_err("longer than the file");
+        read(BlockingFsBuilder::new().path(&file).offset(9000))
+            .expect_err("past the end of the file");
+    }
+}
@@ -4,7 +4,9 @@
  */
 
 use crate::body::SdkBody;
-use crate::byte_stream::{error::Error, error::ErrorKind, ByteStream};
+use crate::byte_stream::{
+    error::Error, error::ErrorKind, read_range, ByteStream, Length, Partitions,
+};
 use bytes::Bytes;
 use futures_core::ready;
 use http::HeaderMap;
@@ -99,16 +101,6 @@ impl Default for FsBuilder {
     }
 }
 
-/// The length (in bytes) to read. Determines whether or not a short read counts as an error.
-#[allow(missing_debug_implementations)]
-pub enum Length {
-    /// Read this number of bytes exactly. Returns an error if the file is smaller than expected.
-    Exact(u64),
-    /// Read up to this number of bytes. May read less than the specified amount if the file
-    /// is smaller than expected.
-    UpTo(u64),
-}
-
 impl FsBuilder {
     /// Create a new [`FsBuilder`] (using a default read buffer of 4096 bytes).
     ///
@@ -176,24 +168,10 @@ impl FsBuilder {
         };
 
         let buffer_size = self.buffer_size;
-        let offset = self.offset.unwrap_or(DEFAULT_OFFSET);
         // Checking the file length like this does have a cost, but the benefit is that we can
         // notify users when file/chunk is smaller than expected.
         let file_length = self.get_file_size().await?;
-        if offset > file_length {
-            return Err(ErrorKind::OffsetLargerThanFileSize.into());
-        }
-
-        let length = match self.length {
-            Some(Length::Exact(length)) => {
-                if length > file_length - offset {
-                    return Err(ErrorKind::LengthLargerThanFileSizeMinusReadOffset.into());
-                }
-                length
-            }
-            Some(Length::UpTo(length)) => length,
-            None => file_length - offset,
-        };
+        let (offset, length) = read_range(self.offset, self.length.as_ref(), file_length)?;
 
         if let Some(path) = self.path {
             let body_loader = move || {
@@ -224,6 +202,45 @@ impl FsBuilder {
         }
     }
 
+    /// Splits the selected range of the file into parts of `part_size` bytes, returning
+    /// [`Partitions`](crate::byte_stream::Partitions) from which a [`ByteStream`] can be taken for each part.
+    ///
+    /// The file is opened once, and the parts share the file handle. Each part has an exact
+    /// length and is retryable, regardless of whether this builder was given a
+    /// [`path`](FsBuilder::path) or a [`file`](FsBuilder::file). When used in conjunction with
+    /// [`offset`](FsBuilder::offset) and [`length`](FsBuilder::length), only that range of the
+    /// file is partitioned. [`Length::UpTo`] is clamped to the end of the file.
+    ///
+    /// Returns an error if `part_size` is zero.
+    pub async fn partition(self, part_size: u64) -> Result<Partitions, Error> {
+        if self.path.is_some() && self.file.is_some() {
+            panic!("The 'file' and 'path' options on an FsBuilder are mutually exclusive but both were set. Please set only one")
+        };
+
+        if part_size == 0 {
+            return Err(ErrorKind::PartSizeIsZero.into());
+        }
+
+        let file_length = self.get_file_size().await?;
+        let (offset, length) = read_range(self.offset, self.length.as_ref(), file_length)?;
+        // Unlike `build`, parts must have an exact length, so don't read past the end of the file
+        let length = length.min(file_length - offset);
+
+        let file = match (self.path, self.file) {
+            (Some(path), _) => File::open(path).await?,
+            (None, Some(file)) => file,
+            (None, None) => panic!("FsBuilder constructed without a file or a path"),
+        };
+
+        Ok(Partitions::new(
+            file.into_std().await,
+            offset,
+            length,
+            part_size,
+            self.buffer_size,
+        ))
+    }
+
     async fn get_file_size(&self) -> Result<u64, Error> {
         Ok(match self.path.as_ref() {
             Some(path) => tokio::fs::metadata(path).await,
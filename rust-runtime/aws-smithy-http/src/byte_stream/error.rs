@@ -11,10 +11,12 @@ use std::io::{Error as IoError, ErrorKind as IoErrorKind};
 
 #[derive(Debug)]
 pub(super) enum ErrorKind {
-    #[cfg(feature = "rt-tokio")]
+    #[cfg(any(feature = "rt-tokio", feature = "blocking-fs"))]
     OffsetLargerThanFileSize,
-    #[cfg(feature = "rt-tokio")]
+    #[cfg(any(feature = "rt-tokio", feature = "blocking-fs"))]
     LengthLargerThanFileSizeMinusReadOffset,
+    #[cfg(feature = "rt-tokio")]
+    PartSizeIsZero,
     IoError(IoError),
     StreamingError(Box<dyn StdError + Send + Sync + 'static>),
 }
@@ -46,16 +48,18 @@ impl From<IoError> for Error {
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self.kind {
-            #[cfg(feature = "rt-tokio")]
+            #[cfg(any(feature = "rt-tokio", feature = "blocking-fs"))]
             ErrorKind::OffsetLargerThanFileSize => write!(
                 f,
                 "offset must be less than or equal to file size but was greater than"
             ),
-            #[cfg(feature = "rt-tokio")]
+            #[cfg(any(feature = "rt-tokio", feature = "blocking-fs"))]
             ErrorKind::LengthLargerThanFileSizeMinusReadOffset => write!(
                 f,
                 "`Length::Exact` was larger than file size minus read offset"
             ),
+            #[cfg(feature = "rt-tokio")]
+            ErrorKind::PartSizeIsZero => write!(f, "part size must be greater than zero"),
             ErrorKind::IoError(_) => write!(f, "IO error"),
             ErrorKind::StreamingError(_) => write!(f, "streaming error"),
         }
@@ -67,9 +71,11 @@ impl StdError for Error {
         match &self.kind {
             ErrorKind::IoError(err) => Some(err as _),
             ErrorKind::StreamingError(err) => Some(err.as_ref() as _),
-            #[cfg(feature = "rt-tokio")]
+            #[cfg(any(feature = "rt-tokio", feature = "blocking-fs"))]
             ErrorKind::OffsetLargerThanFileSize
             | ErrorKind::LengthLargerThanFileSizeMinusReadOffset => None,
+            #[cfg(feature = "rt-tokio")]
+            ErrorKind::PartSizeIsZero => None,
         }
     }
 }
@@ -0,0 +1,208 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Adapters between [`ByteStream`] and the `futures::io` traits
+
+use crate::body::SdkBody;
+use crate::byte_stream::{error::Error, ByteStream};
+use bytes::{Buf, Bytes};
+use futures_core::{ready, Stream};
+use futures_io::{AsyncBufRead, AsyncRead, AsyncWrite};
+use http::HeaderMap;
+use http_body::Body;
+use pin_project_lite::pin_project;
+use std::future::poll_fn;
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+// 4KB corresponds to the default buffer size used when reading files
+const DEFAULT_BUFFER_SIZE: usize = 4096;
+
+pin_project! {
+    /// Implements [`AsyncBufRead`] and [`AsyncRead`] for a [`ByteStream`]
+    pub(super) struct FuturesAsyncRead {
+        #[pin]
+        stream: ByteStream,
+        chunk: Bytes,
+    }
+}
+
+impl FuturesAsyncRead {
+    pub(super) fn new(stream: ByteStream) -> Self {
+        Self {
+            stream,
+            chunk: Bytes::new(),
+        }
+    }
+}
+
+impl AsyncBufRead for FuturesAsyncRead {
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
+        let this = self.project();
+        let mut stream = this.stream;
+        let chunk = this.chunk;
+        while !chunk.has_remaining() {
+            match ready!(stream.as_mut().poll_next(cx)) {
+                Some(Ok(next)) => *chunk = next,
+                Some(Err(err)) => return Poll::Ready(Err(err.into())),
+                None => break,
+            }
+        }
+        let chunk: &Bytes = chunk;
+        Poll::Ready(Ok(chunk.chunk()))
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        self.project().chunk.advance(amt)
+    }
+}
+
+impl AsyncRead for FuturesAsyncRead {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let available = ready!(self.as_mut().poll_fill_buf(cx))?;
+        let len = available.len().min(buf.len());
+        buf[..len].copy_from_slice(&available[..len]);
+        self.consume(len);
+        Poll::Ready(Ok(len))
+    }
+}
+
+pin_project! {
+    /// An HTTP body that reads from an [`AsyncRead`]
+    struct AsyncReadBody<R> {
+        #[pin]
+        reader: R,
+        buf: Vec<u8>,
+        done: bool,
+    }
+}
+
+impl<R: AsyncRead> Body for AsyncReadBody<R> {
+    type Data = Bytes;
+    type Error = Box<dyn std::error::Error + Send + Sync + 'static>;
+
+    fn poll_data(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Self::Data, Self::Error>>> {
+        let this = self.project();
+        if *this.done {
+            return Poll::Ready(None);
+        }
+        match ready!(this.reader.poll_read(cx, this.buf)) {
+            Ok(0) => {
+                *this.done = true;
+                Poll::Ready(None)
+            }
+            Ok(len) => Poll::Ready(Some(Ok(Bytes::copy_from_slice(&this.buf[..len])))),
+            Err(err) => Poll::Ready(Some(Err(err.into()))),
+        }
+    }
+
+    fn poll_trailers(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+    ) -> Poll<Result<Option<HeaderMap>, Self::Error>> {
+        Poll::Ready(Ok(None))
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.done
+    }
+}
+
+pub(super) fn from_async_read(reader: impl AsyncRead + Send + Sync + 'static) -> ByteStream {
+    ByteStream::new(SdkBody::from_dyn(http_body::combinators::BoxBody::new(
+        AsyncReadBody {
+            reader,
+            buf: vec![0; DEFAULT_BUFFER_SIZE],
+            done: false,
+        },
+    )))
+}
+
+pub(super) async fn copy_to_async_write<W>(stream: ByteStream, writer: &mut W) -> Result<u64, Error>
+where
+    W: AsyncWrite + Unpin + ?Sized,
+{
+    let mut stream = stream;
+    let mut written = 0;
+    while let Some(chunk) = poll_fn(|cx| Pin::new(&mut stream).poll_next(cx)).await {
+        let mut chunk = chunk?;
+        while chunk.has_remaining() {
+            let len = poll_fn(|cx| Pin::new(&mut *writer).poll_write(cx, chunk.chunk())).await?;
+            if len == 0 {
+                return Err(io::Error::from(io::ErrorKind::WriteZero).into());
+            }
+            chunk.advance(len);
+            written += len as u64;
+        }
+    }
+    poll_fn(|cx| Pin::new(&mut *writer).poll_flush(cx)).await?;
+    Ok(written)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::body::SdkBody;
+    use crate::byte_stream::ByteStream;
+    use bytes::Bytes;
+    use futures_lite::future::block_on;
+    use futures_lite::io::{AsyncBufReadExt, AsyncReadExt, Cursor};
+
+    fn chunked(chunks: &[&'static str]) -> ByteStream {
+        let chunks: Vec<Result<_, std::io::Error>> = chunks
+            .iter()
+            .map(|chunk| Ok(Bytes::from_static(chunk.as_bytes())))
+            .collect();
+        ByteStream::new(SdkBody::from(hyper::Body::wrap_stream(
+            futures_util::stream::iter(chunks),
+        )))
+    }
+
+    #[test]
+    fn into_futures_async_read() {
+        let mut out = String::new();
+        block_on(
+            chunked(&["hello ", "", "world\nsecond", " line\n"])
+                .into_futures_async_read()
+                .read_to_string(&mut out),
+        )
+        .unwrap();
+        assert_eq!("hello world\nsecond line\n", out);
+
+        let reader = chunked(&["a\nb", "c\n"]).into_futures_async_read();
+        let lines: Vec<String> = block_on(futures_lite::StreamExt::collect::<Vec<_>>(
+            Box::pin(reader).lines(),
+        ))
+        .into_iter()
+        .collect::<Result<_, _>>()
+        .unwrap();
+        assert_eq!(vec!["a", "bc"], lines);
+    }
+
+    #[test]
+    fn from_futures_async_read() {
+        let contents = "x".repeat(10_000);
+        let stream =
+            ByteStream::from_futures_async_read(Cursor::new(contents.clone().into_bytes()));
+        let data = block_on(stream.collect()).unwrap().into_bytes();
+        assert_eq!(contents.as_bytes(), &data[..]);
+    }
+
+    #[test]
+    fn copy_to_futures_async_write() {
+        let mut out = Cursor::new(Vec::new());
+        let written =
+            block_on(chunked(&["hello ", "world"]).copy_to_futures_async_write(&mut out)).unwrap();
+        assert_eq!(11, written);
+        assert_eq!(b"hello world", &out.into_inner()[..]);
+    }
+}
@@ -0,0 +1,514 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Splitting a file into fixed-size parts, e.g. for multipart uploads.
+
+use crate::body::SdkBody;
+use crate::byte_stream::ByteStream;
+use bytes::Bytes;
+use futures_core::ready;
+use http::HeaderMap;
+use http_body::{Body, SizeHint};
+use std::fmt;
+use std::future::Future;
+use std::io;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tokio::task::JoinHandle;
+
+/// A file that has been split into parts of a fixed size.
+///
+/// Created with [`FsBuilder::partition`](crate::byte_stream::FsBuilder::partition). The file is
+/// opened once and shared by every part; each part reads its own byte range with positional reads,
+/// so parts can be streamed concurrently and retried independently.
+///
+/// Every part except the last is exactly `part_size` bytes long. The last part holds whatever is
+/// left over.
+///
+/// Example usage:
+/// ```no_run
+/// # #[cfg(feature = "rt-tokio")]
+/// # async fn upload_parts() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+/// use aws_smithy_http::byte_stream::ByteStream;
+///
+/// let partitions = ByteStream::read_from()
+///     .path("docs/some-large-file.csv")
+///     .partition(8 * 1024 * 1024)
+///     .await?;
+///
+/// // Upload at most four parts at a time
+/// let sizes = partitions
+///     .try_for_each_concurrent(4, |part| async move {
+///         let length = part.length();
+///         let _body = part.into_body();
+///         // ... send the body as part `index + 1` of a multipart upload ...
+///         Ok::<_, aws_smithy_http::byte_stream::error::Error>(length)
+///     })
+///     .await?;
+/// # let _ = sizes;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct Partitions {
+    file: Arc<std::fs::File>,
+    offset: u64,
+    length: u64,
+    part_size: u64,
+    buffer_size: usize,
+}
+
+impl fmt::Debug for Partitions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Partitions")
+            .field("offset", &self.offset)
+            .field("length", &self.length)
+            .field("part_size", &self.part_size)
+            .field("part_count", &self.len())
+            .finish()
+    }
+}
+
+impl Partitions {
+    pub(super) fn new(
+        file: std::fs::File,
+        offset: u64,
+        length: u64,
+        part_size: u64,
+        buffer_size: usize,
+    ) -> Self {
+        debug_assert!(part_size > 0, "part size is validated by the caller");
+        Self {
+            file: Arc::new(file),
+            offset,
+            length,
+            part_size,
+            buffer_size,
+        }
+    }
+
+    /// The number of parts.
+    pub fn len(&self) -> usize {
+        (self.length / self.part_size + u64::from(self.length % self.part_size > 0)) as usize
+    }
+
+    /// Returns true if there is nothing to read, i.e. the partitioned range is empty.
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    /// The size of every part except (possibly) the last one.
+    pub fn part_size(&self) -> u64 {
+        self.part_size
+    }
+
+    /// The total number of bytes across all parts.
+    pub fn total_length(&self) -> u64 {
+        self.length
+    }
+
+    /// Returns the part at `index` (starting from zero), or `None` if `index` is out of range.
+    pub fn part(&self, index: usize) -> Option<Part> {
+        if index >= self.len() {
+            return None;
+        }
+        let relative_offset = index as u64 * self.part_size;
+        let length = self.part_size.min(self.length - relative_offset);
+        let offset = self.offset + relative_offset;
+
+        let file = self.file.clone();
+        let buffer_size = self.buffer_size;
+        let body = SdkBody::retryable(move || {
+            SdkBody::from_dyn(http_body::combinators::BoxBody::new(PartBody::new(
+                file.clone(),
+                offset,
+                length,
+                buffer_size,
+            )))
+        });
+
+        Some(Part {
+            index,
+            offset,
+            length,
+            body: ByteStream::new(body),
+        })
+    }
+
+    /// Returns an iterator over every part, in order.
+    pub fn iter(&self) -> impl Iterator<Item = Part> + '_ {
+        (0..self.len()).filter_map(move |index| self.part(index))
+    }
+
+    /// Runs `f` on every part, with at most `limit` futures in flight at once.
+    ///
+    /// The outputs are returned in part order. If any future fails, no new parts are started
+    /// and the first error is returned once the futures already in flight have been dropped.
+    ///
+    /// The futures are polled by the caller's task rather than spawned, so this works with any
+    /// executor. Spawn inside `f` to spread the work across threads.
+    pub async fn try_for_each_concurrent<F, Fut, T, E>(
+        &self,
+        limit: usize,
+        mut f: F,
+    ) -> Result<Vec<T>, E>
+    where
+        F: FnMut(Part) -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        let limit = limit.max(1);
+        let mut parts = self.iter();
+        let mut in_flight: Vec<(usize, Pin<Box<Fut>>)> = Vec::with_capacity(limit);
+        let mut outputs: Vec<Option<T>> = (0..self.len()).map(|_| None).collect();
+
+        loop {
+            while in_flight.len() < limit {
+                match parts.next() {
+                    Some(part) => {
+                        let index = part.index();
+                        in_flight.push((index, Box::pin(f(part))));
+                    }
+                    None => break,
+                }
+            }
+            if in_flight.is_empty() {
+                break;
+            }
+
+            let (position, output) = std::future::poll_fn(|cx| {
+                for (position, (_, future)) in in_flight.iter_mut().enumerate() {
+                    if let Poll::Ready(output) = future.as_mut().poll(cx) {
+                        return Poll::Ready((position, output));
+                    }
+                }
+                Poll::Pending
+            })
+            .await;
+            let (index, _) = in_flight.swap_remove(position);
+            outputs[index] = Some(output?);
+        }
+
+        Ok(outputs
+            .into_iter()
+            .map(|output| output.expect("every part was processed"))
+            .collect())
+    }
+}
+
+/// One part of a [`Partitions`].
+#[derive(Debug)]
+pub struct Part {
+    index: usize,
+    offset: u64,
+    length: u64,
+    body: ByteStream,
+}
+
+impl Part {
+    /// The index of this part, starting from zero.
+    ///
+    /// Note that S3 multipart upload part numbers start from one.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// The byte-offset of this part within the file.
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// The exact length of this part in bytes.
+    pub fn length(&self) -> u64 {
+        self.length
+    }
+
+    /// Returns a reference to the body of this part.
+    pub fn body(&self) -> &ByteStream {
+        &self.body
+    }
+
+    /// Consumes this part, returning its body.
+    ///
+    /// The body is retryable and has an exact size hint matching [`length`](Part::length).
+    pub fn into_body(self) -> ByteStream {
+        self.body
+    }
+}
+
+/// Body that reads a byte range of a shared file using positional reads on a blocking thread.
+struct PartBody {
+    file: Arc<std::fs::File>,
+    // The position of the next read
+    position: u64,
+    // The number of bytes left to read
+    remaining: u64,
+    buffer_size: usize,
+    pending: Option<JoinHandle<io::Result<Bytes>>>,
+}
+
+impl PartBody {
+    fn new(file: Arc<std::fs::File>, offset: u64, length: u64, buffer_size: usize) -> Self {
+        Self {
+            file,
+            position: offset,
+            remaining: length,
+            buffer_size: buffer_size.max(1),
+            pending: None,
+        }
+    }
+}
+
+impl Body for PartBody {
+    type Data = Bytes;
+    type Error = Box<dyn std::error::Error + Send + Sync + 'static>;
+
+    fn poll_data(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Self::Data, Self::Error>>> {
+        if self.remaining == 0 {
+            return Poll::Ready(None);
+        }
+
+        if self.pending.is_none() {
+            let file = self.file.clone();
+            let position = self.position;
+            let to_read = (self.buffer_size as u64).min(self.remaining) as usize;
+            self.pending = Some(tokio::task::spawn_blocking(move || {
+                let mut buf = vec![0; to_read];
+                let read = read_at(&file, &mut buf, position)?;
+                buf.truncate(read);
+                Ok(Bytes::from(buf))
+            }));
+        }
+
+        let result = ready!(Pin::new(self.pending.as_mut().unwrap()).poll(cx));
+        self.pending = None;
+        match result {
+            Ok(Ok(bytes)) if bytes.is_empty() => Poll::Ready(Some(Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "file ended before the part was fully read",
+            )
+            .into()))),
+            Ok(Ok(bytes)) => {
+                self.position += bytes.len() as u64;
+                self.remaining -= bytes.len() as u64;
+                Poll::Ready(Some(Ok(bytes)))
+            }
+            Ok(Err(err)) => Poll::Ready(Some(Err(err.into()))),
+            Err(join_err) => Poll::Ready(Some(Err(join_err.into()))),
+        }
+    }
+
+    fn poll_trailers(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+    ) -> Poll<Result<Option<HeaderMap>, Self::Error>> {
+        Poll::Ready(Ok(None))
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.remaining == 0
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        SizeHint::with_exact(self.remaining)
+    }
+}
+
+#[cfg(unix)]
+fn read_at(file: &std::fs::File, buf: &mut [u8], offset: u64) -> io::Result<usize> {
+    use std::os::unix::fs::FileExt;
+    file.read_at(buf, offset)
+}
+
+#[cfg(windows)]
+fn read_at(file: &std::fs::File, buf: &mut [u8], offset: u64) -> io::Result<usize> {
+    use std::os::windows::fs::FileExt;
+    file.seek_read(buf, offset)
+}
+
+#[cfg(not(any(unix, windows)))]
+fn read_at(_file: &std::fs::File, _buf: &mut [u8], _offset: u64) -> io::Result<usize> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "positional reads are not supported on this platform",
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::byte_stream::{ByteStream, Length};
+    use http_body::Body;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    fn file_with_lines(count: usize) -> (NamedTempFile, String) {
+        let mut file = NamedTempFile::new().unwrap();
+        let mut contents = String::new();
+        for i in 0..count {
+            let line = format!("Line {:04}\n", i);
+            file.write_all(line.as_bytes()).unwrap();
+            contents.push_str(&line);
+        }
+        file.flush().expect("flushing is OK");
+        (file, contents)
+    }
+
+    #[tokio::test]
+    async fn partitions_cover_the_whole_file() {
+        let (file, contents) = file_with_lines(1000);
+        let file_size = contents.len() as u64;
+        let part_size = 1024;
+
+        let partitions = ByteStream::read_from()
+            .path(&file)
+            .buffer_size(100)
+            .partition(part_size)
+            .await
+            .unwrap();
+        assert_eq!(partitions.total_length(), file_size);
+        assert_eq!(partitions.len(), 10);
+        assert!(partitions.part(10).is_none());
+
+        let mut collected = Vec::new();
+        for (index, part) in partitions.iter().enumerate() {
+            assert_eq!(part.index(), index);
+            assert_eq!(part.offset(), index as u64 * part_size);
+            let expected_length = if index == 9 {
+                file_size - 9 * part_size
+            } else {
+                part_size
+            };
+            assert_eq!(part.length(), expected_length);
+
+            let body = part.into_body().into_inner();
+            assert_eq!(body.size_hint().exact(), Some(expected_length));
+            let data = ByteStream::new(body).collect().await.unwrap().into_bytes();
+            assert_eq!(data.len() as u64, expected_length);
+            collected.extend_from_slice(&data);
+        }
+        assert_eq!(String::from_utf8(collected).unwrap(), contents);
+    }
+
+    #[tokio::test]
+    async fn file_based_parts_are_retryable() {
+        let (file, contents) = file_with_lines(100);
+        let partitions = ByteStream::read_from()
+            .file(tokio::fs::File::open(file.path()).await.unwrap())
+            .offset(10)
+            .length(Length::Exact(20))
+            .partition(7)
+            .await
+            .unwrap();
+        assert_eq!(partitions.len(), 3);
+
+        let part = partitions.part(2).unwrap();
+        assert_eq!(part.length(), 6);
+        let body = part.into_body().into_inner();
+        let retry = body.try_clone().expect("parts are retryable");
+
+        let first = ByteStream::new(body).collect().await.unwrap().into_bytes();
+        let second = ByteStream::new(retry).collect().await.unwrap().into_bytes();
+        assert_eq!(first, second);
+        assert_eq!(&first[..], &contents.as_bytes()[24..30]);
+    }
+
+    #[tokio::test]
+    async fn length_up_to_is_clamped_to_the_end_of_the_file() {
+        let (file, contents) = file_with_lines(3);
+        let partitions = ByteStream::read_from()
+            .path(&file)
+            .length(Length::UpTo(9000))
+            .partition(10)
+            .await
+            .unwrap();
+        assert_eq!(partitions.total_length(), contents.len() as u64);
+        assert_eq!(partitions.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn zero_part_size_is_an_error() {
+        // The part size is validated before the file is touched
+        assert_eq!(
+            ByteStream::read_from()
+                .path("/this/file/does/not/exist")
+                .partition(0)
+                .await
+                .unwrap_err()
+                .to_string(),
+            "part size must be greater than zero"
+        );
+    }
+
+    #[tokio::test]
+    async fn empty_files_have_no_parts() {
+        let file = NamedTempFile::new().unwrap();
+        let partitions = ByteStream::read_from()
+            .path(&file)
+            .partition(10)
+            .await
+            .unwrap();
+        assert!(partitions.is_empty());
+        assert_eq!(partitions.len(), 0);
+        assert!(partitions.iter().next().is_none());
+    }
+
+    #[tokio::test]
+    async fn try_for_each_concurrent_respects_limit_and_order() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        let (file, contents) = file_with_lines(500);
+        let partitions = ByteStream::read_from()
+            .path(&file)
+            .partition(333)
+            .await
+            .unwrap();
+
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let max_in_flight = Arc::new(AtomicUsize::new(0));
+        let parts = partitions
+            .try_for_each_concurrent(3, |part| {
+                let in_flight = in_flight.clone();
+                let max_in_flight = max_in_flight.clone();
+                async move {
+                    let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                    max_in_flight.fetch_max(now, Ordering::SeqCst);
+                    let data = part.into_body().collect().await?.into_bytes();
+                    in_flight.fetch_sub(1, Ordering::SeqCst);
+                    Ok::<_, crate::byte_stream::error::Error>(data)
+                }
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(parts.len(), partitions.len());
+        assert!(max_in_flight.load(Ordering::SeqCst) <= 3);
+        assert_eq!(String::from_utf8(parts.concat()).unwrap(), contents);
+    }
+
+    #[tokio::test]
+    async fn try_for_each_concurrent_stops_on_error() {
+        let (file, _) = file_with_lines(100);
+        let partitions = ByteStream::read_from()
+            .path(&file)
+            .partition(100)
+            .await
+            .unwrap();
+
+        let result = partitions
+            .try_for_each_concurrent(2, |part| async move {
+                if part.index() == 1 {
+                    Err(part.index())
+                } else {
+                    Ok(())
+                }
+            })
+            .await;
+        assert_eq!(result, Err(1));
+    }
+}
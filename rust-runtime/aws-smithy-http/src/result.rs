@@ -11,7 +11,7 @@ use std::fmt::{Debug, Display, Formatter};
 
 use aws_smithy_types::error::metadata::{ProvideErrorMetadata, EMPTY_ERROR_METADATA};
 use aws_smithy_types::error::ErrorMetadata;
-use aws_smithy_types::retry::ErrorKind;
+use aws_smithy_types::retry::{AttemptHistory, ErrorKind, ProvideAttemptHistory};
 
 use crate::connection::ConnectionMetadata;
 use crate::operation;
@@ -58,7 +58,7 @@ pub mod builders {
 
                 #[doc = "Builds the error context."]
                 pub fn build(self) -> $errorName {
-                    $errorName { source: self.source.expect("source is required") }
+                    $errorName { source: self.source.expect("source is required"), attempts: None }
                 }
             }
         };
@@ -119,6 +119,7 @@ pub mod builders {
             ResponseError {
                 source: self.source.expect("source is required"),
                 raw: self.raw.expect("a raw response is required"),
+                attempts: None,
             }
         }
     }
@@ -174,15 +175,16 @@ pub mod builders {
             ServiceError {
                 source: self.source.expect("source is required"),
                 raw: self.raw.expect("a raw response is required"),
+                attempts: None,
             }
         }
     }
 }
 
 /// Error context for [`SdkError::ConstructionFailure`]
-#[derive(Debug)]
 pub struct ConstructionFailure {
     source: BoxError,
+    attempts: Option<AttemptHistory>,
 }
 
 impl ConstructionFailure {
@@ -193,9 +195,9 @@ impl ConstructionFailure {
 }
 
 /// Error context for [`SdkError::TimeoutError`]
-#[derive(Debug)]
 pub struct TimeoutError {
     source: BoxError,
+    attempts: Option<AttemptHistory>,
 }
 
 impl TimeoutError {
@@ -206,9 +208,9 @@ impl TimeoutError {
 }
 
 /// Error context for [`SdkError::DispatchFailure`]
-#[derive(Debug)]
 pub struct DispatchFailure {
     source: ConnectorError,
+    attempts: Option<AttemptHistory>,
 }
 
 impl DispatchFailure {
@@ -244,12 +246,12 @@ impl DispatchFailure {
 }
 
 /// Error context for [`SdkError::ResponseError`]
-#[derive(Debug)]
 pub struct ResponseError<R> {
     /// Error encountered while parsing the response
     source: BoxError,
     /// Raw response that was available
     raw: R,
+    attempts: Option<AttemptHistory>,
 }
 
 impl<R> ResponseError<R> {
@@ -270,12 +272,12 @@ impl<R> ResponseError<R> {
 }
 
 /// Error context for [`SdkError::ServiceError`]
-#[derive(Debug)]
 pub struct ServiceError<E, R> {
     /// Modeled service error
     source: E,
     /// Raw response from the service
     raw: R,
+    attempts: Option<AttemptHistory>,
 }
 
 impl<E, R> ServiceError<E, R> {
@@ -305,6 +307,26 @@ impl<E, R> ServiceError<E, R> {
     }
 }
 
+// The attempt history is left out of the `Debug` output of the error contexts. It's available
+// through `ProvideAttemptHistory`, and is summarized by the `Display` impl of `SdkError`.
+macro_rules! debug_without_attempts {
+    ($name:ident<$($generic:ident),*> { $($field:ident),* }) => {
+        impl<$($generic: Debug),*> Debug for $name<$($generic),*> {
+            fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+                f.debug_struct(stringify!($name))
+                    $(.field(stringify!($field), &self.$field))*
+                    .finish()
+            }
+        }
+    };
+}
+
+debug_without_attempts!(ConstructionFailure<> { source });
+debug_without_attempts!(TimeoutError<> { source });
+debug_without_attempts!(DispatchFailure<> { source });
+debug_without_attempts!(ResponseError<R> { source, raw });
+debug_without_attempts!(ServiceError<E, R> { source, raw });
+
 /// Constructs the unhandled variant of a code generated error.
 ///
 /// This trait exists so that [`SdkError::into_service_error`] can be infallible.
@@ -348,6 +370,7 @@ impl<E, R> SdkError<E, R> {
     pub fn construction_failure(source: impl Into<BoxError>) -> Self {
         Self::ConstructionFailure(ConstructionFailure {
             source: source.into(),
+            attempts: None,
         })
     }
 
@@ -355,12 +378,16 @@ impl<E, R> SdkError<E, R> {
     pub fn timeout_error(source: impl Into<BoxError>) -> Self {
         Self::TimeoutError(TimeoutError {
             source: source.into(),
+            attempts: None,
         })
     }
 
     /// Construct a `SdkError` for a dispatch failure with a [`ConnectorError`]
     pub fn dispatch_failure(source: ConnectorError) -> Self {
-        Self::DispatchFailure(DispatchFailure { source })
+        Self::DispatchFailure(DispatchFailure {
+            source,
+            attempts: None,
+        })
     }
 
     /// Construct a `SdkError` for a response error
@@ -368,12 +395,17 @@ impl<E, R> SdkError<E, R> {
         Self::ResponseError(ResponseError {
             source: source.into(),
             raw,
+            attempts: None,
         })
     }
 
     /// Construct a `SdkError` for a service failure
     pub fn service_error(source: E, raw: R) -> Self {
-        Self::ServiceError(ServiceError { source, raw })
+        Self::ServiceError(ServiceError {
+            source,
+            raw,
+            attempts: None,
+        })
     }
 
     /// Returns the underlying service error `E` if there is one
@@ -445,6 +477,35 @@ impl<E, R> SdkError<E, R> {
         }
     }
 
+    /// Attaches the history of the request attempts that led to this error.
+    pub fn with_attempt_history(mut self, history: AttemptHistory) -> Self {
+        *self.attempts_mut() = Some(history);
+        self
+    }
+
+    /// Returns the history of the request attempts that led to this error, if it was recorded.
+    ///
+    /// When there was more than one attempt, the history is summarized in the error's `Display` output.
+    pub fn attempt_history(&self) -> Option<&AttemptHistory> {
+        match self {
+            Self::ConstructionFailure(context) => context.attempts.as_ref(),
+            Self::TimeoutError(context) => context.attempts.as_ref(),
+            Self::DispatchFailure(context) => context.attempts.as_ref(),
+            Self::ResponseError(context) => context.attempts.as_ref(),
+            Self::ServiceError(context) => context.attempts.as_ref(),
+        }
+    }
+
+    fn attempts_mut(&mut self) -> &mut Option<AttemptHistory> {
+        match self {
+            Self::ConstructionFailure(context) => &mut context.attempts,
+            Self::TimeoutError(context) => &mut context.attempts,
+            Self::DispatchFailure(context) => &mut context.attempts,
+            Self::ResponseError(context) => &mut context.attempts,
+            Self::ServiceError(context) => &mut context.attempts,
+        }
+    }
+
     /// Maps the service error type in `SdkError::ServiceError`
     #[doc(hidden)]
     pub fn map_service_error<E2>(self, map: impl FnOnce(E) -> E2) -> SdkError<E2, R> {
@@ -452,6 +513,7 @@ impl<E, R> SdkError<E, R> {
             Self::ServiceError(context) => SdkError::<E2, R>::ServiceError(ServiceError {
                 source: map(context.source),
                 raw: context.raw,
+                attempts: context.attempts,
             }),
             Self::ConstructionFailure(context) => SdkError::<E2, R>::ConstructionFailure(context),
             Self::DispatchFailure(context) => SdkError::<E2, R>::DispatchFailure(context),
@@ -469,10 +531,21 @@ impl<E, R> Display for SdkError<E, R> {
             SdkError::DispatchFailure(_) => write!(f, "dispatch failure"),
             SdkError::ResponseError(_) => write!(f, "response error"),
             SdkError::ServiceError(_) => write!(f, "service error"),
+        }?;
+        // A single attempt's outcome is already described by the error itself
+        match self.attempt_history() {
+            Some(history) if history.len() > 1 => write!(f, " ({history})"),
+            _ => Ok(()),
         }
     }
 }
 
+impl<E, R> ProvideAttemptHistory for SdkError<E, R> {
+    fn attempt_history(&self) -> Option<&AttemptHistory> {
+        SdkError::attempt_history(self)
+    }
+}
+
 impl<E, R> Error for SdkError<E, R>
 where
     E: Error + 'static,
@@ -8,6 +8,7 @@ use aws_smithy_types::Number;
 use ErrorKind::*;
 
 pub mod error;
+pub mod streaming;
 pub mod token;
 
 pub use token::{EscapeError, EscapedStr, Offset, Token};
@@ -15,10 +15,12 @@ pub(in crate::deserialize) enum DeserializeErrorKind {
         message: Cow<'static, str>,
         source: Option<Box<dyn StdError + Send + Sync + 'static>>,
     },
+    DepthLimitExceeded(usize),
     ExpectedLiteral(String),
     InvalidEscape(char),
     InvalidNumber,
     InvalidUtf8,
+    StringLimitExceeded(usize),
     UnescapeFailed(EscapeError),
     UnexpectedControlCharacter(u8),
     UnexpectedEos,
@@ -78,10 +80,12 @@ impl StdError for DeserializeError {
                 ..
             } => Some(source.as_ref()),
             Custom { source: None, .. }
+            | DepthLimitExceeded(_)
             | ExpectedLiteral(_)
             | InvalidEscape(_)
             | InvalidNumber
             | InvalidUtf8
+            | StringLimitExceeded(_)
             | UnexpectedControlCharacter(_)
             | UnexpectedToken(..)
             | UnexpectedEos => None,
@@ -97,10 +101,16 @@ impl fmt::Display for DeserializeError {
         }
         match &self.kind {
             Custom { message, .. } => write!(f, "failed to parse JSON: {message}"),
+            DepthLimitExceeded(limit) => {
+                write!(f, "nesting exceeds the maximum depth of {limit}")
+            }
             ExpectedLiteral(literal) => write!(f, "expected literal: {literal}"),
             InvalidEscape(escape) => write!(f, "invalid JSON escape: \\{escape}"),
             InvalidNumber => write!(f, "invalid number"),
             InvalidUtf8 => write!(f, "invalid UTF-8 codepoint in JSON stream"),
+            StringLimitExceeded(limit) => {
+                write!(f, "string exceeds the maximum length of {limit} bytes")
+            }
             UnescapeFailed(_) => write!(f, "failed to unescape JSON string"),
             UnexpectedControlCharacter(value) => write!(
                 f,
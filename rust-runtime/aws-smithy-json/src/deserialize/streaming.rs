@@ -0,0 +1,547 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Resumable JSON tokenization for documents that arrive in chunks.
+//!
+//! [`json_token_iter`](crate::deserialize::json_token_iter) requires the complete document up front.
+//! [`JsonTokenizer`] instead accepts chunks of the document as they arrive (for example, from
+//! polling an HTTP body), and yields each token as soon as all of its bytes are available.
+//! Bytes are discarded once they've been tokenized, so only the token currently being read is
+//! kept in memory.
+//!
+//! ```
+//! use aws_smithy_json::deserialize::streaming::{JsonTokenizer, Next};
+//! use aws_smithy_json::deserialize::Token;
+//! use bytes::Bytes;
+//!
+//! let mut tokenizer = JsonTokenizer::new();
+//! let mut chunks = vec![Bytes::from_static(b"{\"Count\": 1"), Bytes::from_static(b"2}")].into_iter();
+//! let mut tokens = Vec::new();
+//! loop {
+//!     match tokenizer.next_token().unwrap() {
+//!         Next::Token(token) => tokens.push(format!("{:?}", token)),
+//!         Next::NeedMoreInput => match chunks.next() {
+//!             Some(chunk) => tokenizer.push(chunk),
+//!             None => tokenizer.finish(),
+//!         },
+//!         Next::Done => break,
+//!     }
+//! }
+//! assert_eq!(4, tokens.len());
+//! ```
+
+use crate::deserialize::error::{DeserializeError as Error, DeserializeErrorKind as ErrorKind};
+use crate::deserialize::{JsonTokenIterator, Offset, State, Token};
+use bytes::{Buf, Bytes, BytesMut};
+
+/// Numbers and literals longer than this can't be valid, so bounded tokenizers reject them
+/// rather than buffering them indefinitely.
+const MAX_SCALAR_LENGTH: usize = 256;
+
+/// Memory limits for a [`JsonTokenizer`].
+///
+/// By default, there are no limits.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TokenizerLimits {
+    max_depth: Option<usize>,
+    max_string_length: Option<usize>,
+}
+
+impl TokenizerLimits {
+    /// Creates limits that don't restrict the document.
+    pub fn unlimited() -> Self {
+        Self::default()
+    }
+
+    /// Sets the maximum nesting depth of objects and arrays.
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = Some(max_depth);
+        self
+    }
+
+    /// Sets the maximum length, in escaped bytes, of string values and object keys.
+    ///
+    /// This bounds how much of the document the tokenizer buffers while waiting for a token
+    /// to complete.
+    pub fn max_string_length(mut self, max_string_length: usize) -> Self {
+        self.max_string_length = Some(max_string_length);
+        self
+    }
+}
+
+/// The result of asking a [`JsonTokenizer`] for its next token.
+#[derive(Debug, PartialEq)]
+pub enum Next<'a> {
+    /// A complete token.
+    Token(Token<'a>),
+    /// The buffered input ends partway through a token. Call [`JsonTokenizer::push`] with the
+    /// next chunk, or [`JsonTokenizer::finish`] if there is none.
+    NeedMoreInput,
+    /// All input has been tokenized.
+    Done,
+}
+
+/// A resumable JSON tokenizer that is fed the document one chunk at a time.
+///
+/// The tokens yielded are the same as those of [`json_token_iter`](crate::deserialize::json_token_iter)
+/// for the concatenated chunks, and their [`Offset`]s are relative to the start of the document
+/// rather than the current chunk. Tokens borrow from the tokenizer, so they must be converted
+/// into owned values before asking for the next one.
+#[derive(Debug)]
+pub struct JsonTokenizer {
+    buffer: BytesMut,
+    /// Offset of `buffer[0]` within the document
+    base: usize,
+    /// Number of bytes at the front of `buffer` that have already been tokenized
+    consumed: usize,
+    state_stack: Vec<State>,
+    scan: Scan,
+    limits: TokenizerLimits,
+    finished: bool,
+    failed: bool,
+}
+
+/// Progress finding the end of the token at the front of the unconsumed input.
+///
+/// This is kept between chunks so that a token split across many chunks is only scanned once,
+/// and the (comparatively expensive) tokenizer only runs once the whole token is available.
+#[derive(Debug, Default)]
+struct Scan {
+    /// Number of unconsumed bytes that have been scanned
+    scanned: usize,
+    /// Position of the token's first byte within the unconsumed bytes, once it has been seen
+    start: Option<usize>,
+    /// Whether the previous byte of a string started an escape sequence
+    escaped: bool,
+    /// Number of bytes of a `\u` escape sequence that remain to be skipped
+    skip: usize,
+    /// Whether the end of the token has been seen
+    complete: bool,
+}
+
+impl Default for JsonTokenizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl JsonTokenizer {
+    /// Creates a tokenizer with no memory limits.
+    pub fn new() -> Self {
+        Self::with_limits(TokenizerLimits::unlimited())
+    }
+
+    /// Creates a tokenizer that fails once the document exceeds the given `limits`.
+    pub fn with_limits(limits: TokenizerLimits) -> Self {
+        Self {
+            buffer: BytesMut::new(),
+            base: 0,
+            consumed: 0,
+            state_stack: vec![State::Initial],
+            scan: Scan::default(),
+            limits,
+            finished: false,
+            failed: false,
+        }
+    }
+
+    /// Appends the next chunk of the document.
+    pub fn push(&mut self, chunk: Bytes) {
+        debug_assert!(!self.finished, "chunks must not be pushed after `finish`");
+        self.discard_consumed();
+        self.buffer.extend_from_slice(&chunk);
+    }
+
+    /// Marks the end of the document, after which incomplete tokens are errors.
+    pub fn finish(&mut self) {
+        self.finished = true;
+    }
+
+    /// Returns the offset within the document of the first byte that hasn't been tokenized yet.
+    pub fn offset(&self) -> Offset {
+        Offset(self.base + self.consumed)
+    }
+
+    fn discard_consumed(&mut self) {
+        self.buffer.advance(self.consumed);
+        self.base += self.consumed;
+        self.consumed = 0;
+    }
+
+    /// Returns the next token, or what the tokenizer needs to make progress.
+    ///
+    /// Once an error has been returned, all further calls return [`Next::Done`].
+    pub fn next_token(&mut self) -> Result<Next<'_>, Error> {
+        if self.failed {
+            return Ok(Next::Done);
+        }
+        self.discard_consumed();
+
+        let error = if !self.scan_token() && !self.finished {
+            self.pending_limit_error()
+        } else {
+            // Tokenizing can only modify the top of the state stack before it runs out of input,
+            // so that's all that needs to be restored if the token turns out to be incomplete
+            let top = *self
+                .state_stack
+                .last()
+                .expect("the initial state is never popped");
+            let mut iter = JsonTokenIterator {
+                input: &self.buffer[..],
+                index: self.consumed,
+                state_stack: std::mem::take(&mut self.state_stack),
+            };
+            let result = iter.next();
+            let index = iter.index;
+            self.state_stack = iter.state_stack;
+
+            match result {
+                Some(Ok(token)) => match self.limit_error(&token) {
+                    Some(error) => Some(error),
+                    None => {
+                        self.consumed = index;
+                        self.scan = Scan::default();
+                        return Ok(Next::Token(rebase(token, self.base)));
+                    }
+                },
+                Some(Err(error))
+                    if matches!(error.kind, ErrorKind::UnexpectedEos) && !self.finished =>
+                {
+                    *self
+                        .state_stack
+                        .last_mut()
+                        .expect("the initial state is never popped") = top;
+                    self.pending_limit_error()
+                }
+                Some(Err(error)) => Some(error),
+                None if self.state_stack == [State::Initial] => return Ok(Next::Done),
+                None => Some(Error::new(
+                    ErrorKind::UnexpectedEos,
+                    Some(self.buffer.len()),
+                )),
+            }
+        };
+        match error {
+            Some(mut error) => {
+                self.failed = true;
+                error.offset = error.offset.map(|offset| offset + self.base);
+                Err(error)
+            }
+            None => Ok(Next::NeedMoreInput),
+        }
+    }
+
+    /// Scans the unconsumed bytes that haven't been scanned yet, and returns whether they contain
+    /// a complete token.
+    ///
+    /// Whitespace before the token is discarded. Numbers and literals are only complete once
+    /// the byte following them has arrived, since the next chunk might otherwise continue them.
+    fn scan_token(&mut self) -> bool {
+        let scan = &mut self.scan;
+        while !scan.complete && self.consumed + scan.scanned < self.buffer.len() {
+            let byte = self.buffer[self.consumed + scan.scanned];
+            scan.scanned += 1;
+            match scan.start.map(|start| self.buffer[self.consumed + start]) {
+                None => match byte {
+                    // There is no need to keep whitespace around
+                    b' ' | b'\t' | b'\r' | b'\n' if scan.scanned == 1 => {
+                        self.consumed += 1;
+                        scan.scanned = 0;
+                    }
+                    b' ' | b'\t' | b'\r' | b'\n' | b',' | b':' => {}
+                    _ => {
+                        scan.start = Some(scan.scanned - 1);
+                        scan.complete = matches!(byte, b'{' | b'[' | b'}' | b']');
+                    }
+                },
+                Some(b'"') if scan.skip > 0 => scan.skip -= 1,
+                Some(b'"') if scan.escaped => {
+                    scan.escaped = false;
+                    if byte == b'u' {
+                        scan.skip = 4;
+                    }
+                }
+                Some(b'"') => match byte {
+                    b'\\' => scan.escaped = true,
+                    b'"' => scan.complete = true,
+                    _ => {}
+                },
+                Some(_) => {
+                    scan.complete = !matches!(
+                        byte,
+                        b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'+' | b'-' | b'.'
+                    )
+                }
+            }
+        }
+        scan.complete
+    }
+
+    /// Returns an error if the complete `token` exceeds the limits.
+    fn limit_error(&self, token: &Token<'_>) -> Option<Error> {
+        let offset = token.offset().0;
+        if let Some(max_depth) = self.limits.max_depth {
+            // The bottom of the stack is the initial state, which doesn't count towards the depth
+            if self.state_stack.len() - 1 > max_depth {
+                return Some(Error::new(
+                    ErrorKind::DepthLimitExceeded(max_depth),
+                    Some(offset),
+                ));
+            }
+        }
+        if let Some(max_string_length) = self.limits.max_string_length {
+            let length = match token {
+                Token::ValueString { value, .. } | Token::ObjectKey { key: value, .. } => {
+                    value.as_escaped_str().len()
+                }
+                _ => 0,
+            };
+            if length > max_string_length {
+                return Some(Error::new(
+                    ErrorKind::StringLimitExceeded(max_string_length),
+                    Some(offset),
+                ));
+            }
+        }
+        None
+    }
+
+    /// Returns an error if buffering more input for the incomplete token would exceed the limits.
+    fn pending_limit_error(&self) -> Option<Error> {
+        let max_string_length = self.limits.max_string_length?;
+        let start = self.scan.start?;
+        let length = self.scan.scanned - start;
+        let kind = match self.buffer[self.consumed + start] {
+            // Account for the opening quote
+            b'"' if length > max_string_length + 1 => {
+                ErrorKind::StringLimitExceeded(max_string_length)
+            }
+            b'"' => return None,
+            _ if length > MAX_SCALAR_LENGTH => ErrorKind::InvalidNumber,
+            _ => return None,
+        };
+        Some(Error::new(kind, Some(self.consumed + start)))
+    }
+}
+
+/// Converts the offset of a token from being relative to the buffer to the document.
+fn rebase(token: Token<'_>, base: usize) -> Token<'_> {
+    let rebase = |offset: Offset| Offset(offset.0 + base);
+    match token {
+        Token::StartArray { offset } => Token::StartArray {
+            offset: rebase(offset),
+        },
+        Token::EndArray { offset } => Token::EndArray {
+            offset: rebase(offset),
+        },
+        Token::ObjectKey { offset, key } => Token::ObjectKey {
+            offset: rebase(offset),
+            key,
+        },
+        Token::StartObject { offset } => Token::StartObject {
+            offset: rebase(offset),
+        },
+        Token::EndObject { offset } => Token::EndObject {
+            offset: rebase(offset),
+        },
+        Token::ValueBool { offset, value } => Token::ValueBool {
+            offset: rebase(offset),
+            value,
+        },
+        Token::ValueNull { offset } => Token::ValueNull {
+            offset: rebase(offset),
+        },
+        Token::ValueNumber { offset, value } => Token::ValueNumber {
+            offset: rebase(offset),
+            value,
+        },
+        Token::ValueString { offset, value } => Token::ValueString {
+            offset: rebase(offset),
+            value,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::deserialize::json_token_iter;
+    use proptest::prelude::*;
+
+    /// Tokenizes `chunks`, rendering each token with `Debug` so they outlive the tokenizer.
+    fn tokenize(chunks: &[&[u8]], limits: TokenizerLimits) -> Result<Vec<String>, Error> {
+        let mut tokenizer = JsonTokenizer::with_limits(limits);
+        let mut chunks = chunks.iter();
+        let mut tokens = Vec::new();
+        loop {
+            match tokenizer.next_token()? {
+                Next::Token(token) => tokens.push(format!("{:?}", token)),
+                Next::NeedMoreInput => match chunks.next() {
+                    Some(chunk) => tokenizer.push(Bytes::copy_from_slice(chunk)),
+                    None => tokenizer.finish(),
+                },
+                Next::Done => return Ok(tokens),
+            }
+        }
+    }
+
+    fn expected(document: &[u8]) -> Vec<String> {
+        json_token_iter(document)
+            .map(|token| format!("{:?}", token.unwrap()))
+            .collect()
+    }
+
+    const DOCUMENT: &[u8] = r#" {"Items": [{"name": {"S": "café \"quoted\""}, "count": {"N": "-12.5e3"}},
+        {"flag": true, "none": null, "list": [1, 22, 333, false]}], "Count": 2, "ScannedCount": 1234567} "#.as_bytes();
+
+    #[test]
+    fn matches_complete_document_at_every_split() {
+        let expected = expected(DOCUMENT);
+        for split in 0..=DOCUMENT.len() {
+            let (first, second) = DOCUMENT.split_at(split);
+            assert_eq!(
+                expected,
+                tokenize(&[first, second], TokenizerLimits::unlimited()).unwrap(),
+                "split at {split}"
+            );
+        }
+    }
+
+    #[test]
+    fn byte_at_a_time() {
+        let chunks: Vec<&[u8]> = DOCUMENT.chunks(1).collect();
+        assert_eq!(
+            expected(DOCUMENT),
+            tokenize(&chunks, TokenizerLimits::unlimited()).unwrap()
+        );
+    }
+
+    #[test]
+    fn tokens_are_yielded_before_the_document_completes() {
+        let mut tokenizer = JsonTokenizer::new();
+        tokenizer.push(Bytes::from_static(b"[12"));
+        assert!(matches!(
+            tokenizer.next_token().unwrap(),
+            Next::Token(Token::StartArray { offset: Offset(0) })
+        ));
+        // The number might continue in the next chunk
+        assert_eq!(Next::NeedMoreInput, tokenizer.next_token().unwrap());
+        tokenizer.push(Bytes::from_static(b"3, \"abc"));
+        assert!(matches!(
+            tokenizer.next_token().unwrap(),
+            Next::Token(Token::ValueNumber {
+                offset: Offset(1),
+                ..
+            })
+        ));
+        assert_eq!(Next::NeedMoreInput, tokenizer.next_token().unwrap());
+        tokenizer.push(Bytes::from_static(b"\"]"));
+        assert!(matches!(
+            tokenizer.next_token().unwrap(),
+            Next::Token(Token::ValueString {
+                offset: Offset(6),
+                ..
+            })
+        ));
+        assert_eq!(Offset(11), tokenizer.offset());
+    }
+
+    #[test]
+    fn errors_have_document_offsets() {
+        let err = tokenize(&[b"[1, 2, ", b"tru"], TokenizerLimits::unlimited()).unwrap_err();
+        assert_eq!(Some(10), err.offset);
+        assert!(matches!(err.kind, ErrorKind::UnexpectedEos));
+
+        let err = tokenize(&[b"[1, 2, ", b"x]"], TokenizerLimits::unlimited()).unwrap_err();
+        assert_eq!(Some(7), err.offset);
+        assert!(matches!(err.kind, ErrorKind::UnexpectedToken('x', _)));
+
+        let err = tokenize(&[b"{\"a\": 1"], TokenizerLimits::unlimited()).unwrap_err();
+        assert!(matches!(err.kind, ErrorKind::UnexpectedEos));
+    }
+
+    #[test]
+    fn depth_limit() {
+        let limits = TokenizerLimits::unlimited().max_depth(2);
+        assert!(tokenize(&[b"[[1]]"], limits).is_ok());
+        let err = tokenize(&[b"[[", b"[1]]]"], limits).unwrap_err();
+        assert_eq!(Some(2), err.offset);
+        assert!(matches!(err.kind, ErrorKind::DepthLimitExceeded(2)));
+    }
+
+    #[test]
+    fn string_length_limit() {
+        let limits = TokenizerLimits::unlimited().max_string_length(4);
+        assert!(tokenize(&[br#"{"abcd": "ef"}"#], limits).is_ok());
+
+        let err = tokenize(&[br#"{"abcde": "ef"}"#], limits).unwrap_err();
+        assert_eq!(Some(1), err.offset);
+        assert!(matches!(err.kind, ErrorKind::StringLimitExceeded(4)));
+
+        // An incomplete string is rejected as soon as it's too long, without waiting for the rest
+        let mut tokenizer = JsonTokenizer::with_limits(limits);
+        tokenizer.push(Bytes::from_static(b"[\"abcdef"));
+        assert!(matches!(tokenizer.next_token(), Ok(Next::Token(_))));
+        let err = tokenizer.next_token().unwrap_err();
+        assert_eq!(Some(1), err.offset);
+        assert_eq!(Next::Done, tokenizer.next_token().unwrap());
+    }
+
+    #[test]
+    fn partial_tokens_are_not_rescanned() {
+        let mut tokenizer = JsonTokenizer::new();
+        tokenizer.push(Bytes::from_static(b"[  \"ab"));
+        assert!(matches!(tokenizer.next_token(), Ok(Next::Token(_))));
+        assert_eq!(Next::NeedMoreInput, tokenizer.next_token().unwrap());
+        assert_eq!(Some(0), tokenizer.scan.start);
+        assert_eq!(3, tokenizer.scan.scanned);
+        assert_eq!(
+            [State::Initial, State::ArrayFirstValueOrEnd],
+            &tokenizer.state_stack[..]
+        );
+
+        tokenizer.push(Bytes::from_static(b"\\u0022cd"));
+        assert_eq!(Next::NeedMoreInput, tokenizer.next_token().unwrap());
+        assert_eq!(11, tokenizer.scan.scanned);
+        assert!(!tokenizer.scan.complete);
+
+        tokenizer.push(Bytes::from_static(b"\"]"));
+        assert!(matches!(
+            tokenizer.next_token().unwrap(),
+            Next::Token(Token::ValueString {
+                offset: Offset(3),
+                ..
+            })
+        ));
+        assert_eq!(0, tokenizer.scan.scanned);
+    }
+
+    #[test]
+    fn whitespace_is_not_buffered() {
+        let mut tokenizer = JsonTokenizer::new();
+        tokenizer.push(Bytes::from_static(b"[1]      "));
+        while let Next::Token(_) = tokenizer.next_token().unwrap() {}
+        assert_eq!(Offset(9), tokenizer.offset());
+        tokenizer.finish();
+        assert_eq!(Next::Done, tokenizer.next_token().unwrap());
+    }
+
+    proptest! {
+        #[test]
+        fn arbitrary_chunking(splits in proptest::collection::vec(0..DOCUMENT.len(), 0..8)) {
+            let mut splits = splits;
+            splits.sort_unstable();
+            splits.dedup();
+            let mut chunks = Vec::new();
+            let mut start = 0;
+            for split in splits {
+                chunks.push(&DOCUMENT[start..split]);
+                start = split;
+            }
+            chunks.push(&DOCUMENT[start..]);
+            prop_assert_eq!(expected(DOCUMENT), tokenize(&chunks, TokenizerLimits::unlimited()).unwrap());
+        }
+    }
+}
@@ -0,0 +1,439 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Deserialization of `application/x-www-form-urlencoded` AWS Query and EC2 Query requests.
+//!
+//! [`QueryDocument`] is the inverse of [`QueryWriter`](crate::QueryWriter): a request body is
+//! parsed into a set of parameters, and [`QueryValueReader`]s are used to navigate it by prefix.
+
+use aws_smithy_types::date_time::Format;
+use aws_smithy_types::primitive::Parse;
+use aws_smithy_types::{base64, DateTime};
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug)]
+enum QueryDecodeErrorKind {
+    InvalidEncoding {
+        param: String,
+    },
+    InvalidValue {
+        param: String,
+        expected: &'static str,
+    },
+    Custom(Cow<'static, str>),
+}
+
+/// Failed to decode a query request.
+#[derive(Debug)]
+pub struct QueryDecodeError {
+    kind: QueryDecodeErrorKind,
+}
+
+impl QueryDecodeError {
+    fn invalid_value(param: &str, expected: &'static str) -> Self {
+        Self {
+            kind: QueryDecodeErrorKind::InvalidValue {
+                param: param.into(),
+                expected,
+            },
+        }
+    }
+
+    /// Creates a custom decode error with the given message.
+    pub fn custom(msg: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            kind: QueryDecodeErrorKind::Custom(msg.into()),
+        }
+    }
+}
+
+impl fmt::Display for QueryDecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            QueryDecodeErrorKind::InvalidEncoding { param } => {
+                write!(
+                    f,
+                    "query parameter `{}` is not valid percent-encoded UTF-8",
+                    param
+                )
+            }
+            QueryDecodeErrorKind::InvalidValue { param, expected } => {
+                write!(f, "query parameter `{}` is not a valid {}", param, expected)
+            }
+            QueryDecodeErrorKind::Custom(msg) => write!(f, "error parsing query: {}", msg),
+        }
+    }
+}
+
+impl Error for QueryDecodeError {}
+
+/// A parsed query request body.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct QueryDocument {
+    params: BTreeMap<String, String>,
+}
+
+impl QueryDocument {
+    /// Parses an `application/x-www-form-urlencoded` request body.
+    ///
+    /// If a parameter is repeated, the last value wins.
+    pub fn parse(input: &[u8]) -> Result<Self, QueryDecodeError> {
+        let mut params = BTreeMap::new();
+        for pair in input.split(|b| *b == b'&').filter(|pair| !pair.is_empty()) {
+            let (name, value) = match pair.iter().position(|b| *b == b'=') {
+                Some(idx) => (&pair[..idx], &pair[idx + 1..]),
+                None => (pair, &[][..]),
+            };
+            params.insert(decode_component(name)?, decode_component(value)?);
+        }
+        Ok(Self { params })
+    }
+
+    /// Returns the `Action` parameter, which names the operation being invoked.
+    pub fn action(&self) -> Option<&str> {
+        self.params.get("Action").map(String::as_str)
+    }
+
+    /// Returns the `Version` parameter, which names the API version of the service.
+    pub fn version(&self) -> Option<&str> {
+        self.params.get("Version").map(String::as_str)
+    }
+
+    /// Returns a reader for the top-level members of the operation input.
+    pub fn reader(&self) -> QueryValueReader<'_> {
+        QueryValueReader {
+            doc: self,
+            prefix: Cow::Borrowed(""),
+        }
+    }
+
+    fn has_prefix(&self, prefix: &str) -> bool {
+        if self.params.contains_key(prefix) {
+            return true;
+        }
+        let nested = format!("{}.", prefix);
+        self.params
+            .range(nested.clone()..)
+            .next()
+            .map(|(name, _)| name.starts_with(&nested))
+            .unwrap_or(false)
+    }
+}
+
+fn decode_component(input: &[u8]) -> Result<String, QueryDecodeError> {
+    let input = String::from_utf8_lossy(input).replace('+', " ");
+    urlencoding::decode(&input)
+        .map(Cow::into_owned)
+        .map_err(|_| QueryDecodeError {
+            kind: QueryDecodeErrorKind::InvalidEncoding { param: input },
+        })
+}
+
+/// Reads the value at a given prefix of a [`QueryDocument`].
+///
+/// Scalar accessors return `Ok(None)` when the parameter isn't present.
+#[derive(Debug, Clone)]
+pub struct QueryValueReader<'a> {
+    doc: &'a QueryDocument,
+    prefix: Cow<'a, str>,
+}
+
+impl<'a> QueryValueReader<'a> {
+    /// Returns a reader for the member named `name`.
+    ///
+    /// `name` should already account for any `@xmlName` (or `@ec2QueryName`) override.
+    pub fn member(&self, name: &str) -> QueryValueReader<'a> {
+        self.nested(name)
+    }
+
+    fn nested(&self, name: impl fmt::Display) -> QueryValueReader<'a> {
+        let prefix = if self.prefix.is_empty() {
+            name.to_string()
+        } else {
+            format!("{}.{}", self.prefix, name)
+        };
+        QueryValueReader {
+            doc: self.doc,
+            prefix: Cow::Owned(prefix),
+        }
+    }
+
+    /// Returns `true` if this value, or any value nested under it, is present.
+    pub fn is_present(&self) -> bool {
+        self.doc.has_prefix(&self.prefix)
+    }
+
+    /// Reads a string.
+    pub fn string(&self) -> Option<&'a str> {
+        self.doc
+            .params
+            .get(self.prefix.as_ref())
+            .map(String::as_str)
+    }
+
+    /// Reads a boolean.
+    pub fn boolean(&self) -> Result<Option<bool>, QueryDecodeError> {
+        self.parse("boolean", |value| match value {
+            "true" => Some(true),
+            "false" => Some(false),
+            _ => None,
+        })
+    }
+
+    /// Reads a number, accepting `NaN`, `Infinity` and `-Infinity` for floating point types.
+    pub fn number<T: Parse>(&self) -> Result<Option<T>, QueryDecodeError> {
+        self.parse("number", |value| T::parse_smithy_primitive(value).ok())
+    }
+
+    /// Reads a date-time in the given `format`.
+    pub fn date_time(&self, format: Format) -> Result<Option<DateTime>, QueryDecodeError> {
+        self.parse("timestamp", |value| DateTime::from_str(value, format).ok())
+    }
+
+    /// Reads a base64-encoded blob.
+    pub fn blob(&self) -> Result<Option<Vec<u8>>, QueryDecodeError> {
+        self.parse("base64-encoded blob", |value| base64::decode(value).ok())
+    }
+
+    fn parse<T>(
+        &self,
+        expected: &'static str,
+        parse: impl FnOnce(&str) -> Option<T>,
+    ) -> Result<Option<T>, QueryDecodeError> {
+        match self.string() {
+            Some(value) => parse(value)
+                .map(Some)
+                .ok_or_else(|| QueryDecodeError::invalid_value(&self.prefix, expected)),
+            None => Ok(None),
+        }
+    }
+
+    /// Reads a list, returning a reader for each entry in order.
+    ///
+    /// The arguments match those given to [`QueryValueWriter::start_list`](crate::QueryValueWriter::start_list).
+    /// An empty list is serialized as the bare parameter name, so `Some(vec![])` is returned for it.
+    pub fn list(
+        &self,
+        flat: bool,
+        member_override: Option<&str>,
+    ) -> Option<Vec<QueryValueReader<'a>>> {
+        if !self.is_present() {
+            return None;
+        }
+        let container = match (flat, member_override) {
+            (true, _) => self.clone(),
+            (false, Some(member)) => self.nested(member),
+            (false, None) => self.nested("member"),
+        };
+        Some(
+            (1..)
+                .map(|index| container.nested(index))
+                .take_while(QueryValueReader::is_present)
+                .collect(),
+        )
+    }
+
+    /// Reads a map, returning each key with a reader for its value.
+    ///
+    /// The arguments match those given to [`QueryValueWriter::start_map`](crate::QueryValueWriter::start_map).
+    pub fn map(
+        &self,
+        flat: bool,
+        key_name: &str,
+        value_name: &str,
+    ) -> Result<Option<Vec<(&'a str, QueryValueReader<'a>)>>, QueryDecodeError> {
+        if !self.is_present() {
+            return Ok(None);
+        }
+        let container = if flat {
+            self.clone()
+        } else {
+            self.nested("entry")
+        };
+        let mut entries = Vec::new();
+        for index in 1.. {
+            let entry = container.nested(index);
+            if !entry.is_present() {
+                break;
+            }
+            let key = entry.nested(key_name);
+            let key = key.string().ok_or_else(|| {
+                QueryDecodeError::custom(format!("map entry `{}` is missing its key", entry.prefix))
+            })?;
+            entries.push((key, entry.nested(value_name)));
+        }
+        Ok(Some(entries))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::QueryWriter;
+    use aws_smithy_types::Number;
+
+    #[test]
+    fn action_and_version() {
+        let doc = QueryDocument::parse(b"Action=Some%20Action&Version=1.0").unwrap();
+        assert_eq!(Some("Some Action"), doc.action());
+        assert_eq!(Some("1.0"), doc.version());
+        assert_eq!(None, QueryDocument::parse(b"").unwrap().action());
+    }
+
+    #[test]
+    fn form_encoding() {
+        let doc = QueryDocument::parse(b"a=one+two&b=%2B%26%3D&c").unwrap();
+        assert_eq!(Some("one two"), doc.reader().member("a").string());
+        assert_eq!(Some("+&="), doc.reader().member("b").string());
+        assert_eq!(Some(""), doc.reader().member("c").string());
+        assert!(QueryDocument::parse(b"a=%FF").is_err());
+    }
+
+    #[test]
+    fn scalars() {
+        let doc = QueryDocument::parse(
+            b"Bool=true&Int=-5&Float=NaN&Ts=2015-02-20T06%3A23%3A48Z&Blob=dmFsdWU%3D&Bad=nope",
+        )
+        .unwrap();
+        let reader = doc.reader();
+        assert_eq!(Some(true), reader.member("Bool").boolean().unwrap());
+        assert_eq!(Some(-5), reader.member("Int").number::<i32>().unwrap());
+        assert!(reader
+            .member("Float")
+            .number::<f64>()
+            .unwrap()
+            .unwrap()
+            .is_nan());
+        assert_eq!(
+            Some(DateTime::from_secs(1424413428)),
+            reader.member("Ts").date_time(Format::DateTime).unwrap()
+        );
+        assert_eq!(
+            Some(b"value".to_vec()),
+            reader.member("Blob").blob().unwrap()
+        );
+        assert_eq!(None, reader.member("Missing").boolean().unwrap());
+        assert_eq!(
+            "query parameter `Bad` is not a valid number",
+            reader
+                .member("Bad")
+                .number::<i32>()
+                .unwrap_err()
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn nested_structures() {
+        let doc = QueryDocument::parse(b"Outer.Inner.Value=x&Outerish=y").unwrap();
+        let outer = doc.reader().member("Outer");
+        assert!(outer.is_present());
+        assert_eq!(Some("x"), outer.member("Inner").member("Value").string());
+        assert!(!outer.member("Other").is_present());
+        assert!(!doc.reader().member("Out").is_present());
+    }
+
+    fn strings(list: Option<Vec<QueryValueReader<'_>>>) -> Option<Vec<&str>> {
+        list.map(|list| list.iter().map(|v| v.string().unwrap()).collect())
+    }
+
+    #[test]
+    fn lists() {
+        let doc = QueryDocument::parse(
+            b"List.member.1=a&List.member.2=b&Flat.1=c&Renamed.item.1=d&Empty=&Gap.member.2=e",
+        )
+        .unwrap();
+        let reader = doc.reader();
+        assert_eq!(
+            Some(vec!["a", "b"]),
+            strings(reader.member("List").list(false, None))
+        );
+        assert_eq!(
+            Some(vec!["c"]),
+            strings(reader.member("Flat").list(true, None))
+        );
+        assert_eq!(
+            Some(vec!["d"]),
+            strings(reader.member("Renamed").list(false, Some("item")))
+        );
+        assert_eq!(
+            Some(vec![]),
+            strings(reader.member("Empty").list(false, None))
+        );
+        assert_eq!(
+            Some(vec![]),
+            strings(reader.member("Gap").list(false, None))
+        );
+        assert_eq!(None, strings(reader.member("Missing").list(false, None)));
+    }
+
+    #[test]
+    fn maps() {
+        let doc = QueryDocument::parse(
+            b"Map.entry.1.key=k1&Map.entry.1.value=v1&Map.entry.2.key=k2&Map.entry.2.value=v2\
+              &Flat.1.Name=k&Flat.1.Value=v&Broken.entry.1.value=v",
+        )
+        .unwrap();
+        let reader = doc.reader();
+        let map = reader
+            .member("Map")
+            .map(false, "key", "value")
+            .unwrap()
+            .unwrap();
+        let map: Vec<_> = map.iter().map(|(k, v)| (*k, v.string().unwrap())).collect();
+        assert_eq!(vec![("k1", "v1"), ("k2", "v2")], map);
+
+        let flat = reader
+            .member("Flat")
+            .map(true, "Name", "Value")
+            .unwrap()
+            .unwrap();
+        assert_eq!("k", flat[0].0);
+        assert_eq!(Some("v"), flat[0].1.string());
+
+        assert!(reader.member("Broken").map(false, "key", "value").is_err());
+        assert!(reader
+            .member("Missing")
+            .map(false, "key", "value")
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn round_trips_query_writer() {
+        let mut out = String::new();
+        let mut writer = QueryWriter::new(&mut out, "SomeAction", "1.0");
+        let mut list = writer.prefix("Tags").start_list(false, Some("Tag"));
+        let mut tag = list.entry();
+        tag.prefix("Key").string("a b&c");
+        tag.prefix("Count").number(Number::PosInt(3));
+        list.finish();
+        let mut map = writer.prefix("Attributes").start_map(true, "Name", "Value");
+        map.entry("Policy").string("{}");
+        map.finish();
+        writer.finish();
+
+        let doc = QueryDocument::parse(out.as_bytes()).unwrap();
+        assert_eq!(Some("SomeAction"), doc.action());
+        let tags = doc
+            .reader()
+            .member("Tags")
+            .list(false, Some("Tag"))
+            .unwrap();
+        assert_eq!(Some("a b&c"), tags[0].member("Key").string());
+        assert_eq!(Some(3), tags[0].member("Count").number::<i8>().unwrap());
+        let attributes = doc
+            .reader()
+            .member("Attributes")
+            .map(true, "Name", "Value")
+            .unwrap()
+            .unwrap();
+        assert_eq!("Policy", attributes[0].0);
+        assert_eq!(Some("{}"), attributes[0].1.string());
+    }
+}
@@ -13,6 +13,8 @@
 
 //! Abstractions for the Smithy AWS Query protocol
 
+pub mod decode;
+
 use aws_smithy_types::date_time::{DateTimeFormatError, Format};
 use aws_smithy_types::primitive::Encoder;
 use aws_smithy_types::{DateTime, Number};
@@ -34,6 +34,7 @@ pub mod wrappers;
 use crate::client::orchestrator::{HttpRequest, HttpResponse, OrchestratorError};
 use aws_smithy_http::result::SdkError;
 use aws_smithy_types::config_bag::ConfigBag;
+use aws_smithy_types::retry::AttemptHistory;
 use aws_smithy_types::type_erasure::{TypeErasedBox, TypeErasedError};
 use phase::Phase;
 use std::fmt::Debug;
@@ -62,6 +63,7 @@ pub struct InterceptorContext<I = Input, O = Output, E = Error> {
     phase: Phase,
     tainted: bool,
     request_checkpoint: Option<HttpRequest>,
+    attempt_history: AttemptHistory,
 }
 
 impl InterceptorContext<Input, Output, Error> {
@@ -75,6 +77,7 @@ impl InterceptorContext<Input, Output, Error> {
             phase: Phase::BeforeSerialization,
             tainted: false,
             request_checkpoint: None,
+            attempt_history: AttemptHistory::new(),
         }
     }
 }
@@ -104,11 +107,19 @@ impl<I, O, E: Debug> InterceptorContext<I, O, E> {
             output_or_error,
             response,
             phase,
+            attempt_history,
             ..
         } = self;
         output_or_error
             .expect("output_or_error must always be set before finalize is called.")
-            .map_err(|error| OrchestratorError::into_sdk_error(error, &phase, response))
+            .map_err(|error| {
+                let error = OrchestratorError::into_sdk_error(error, &phase, response);
+                if attempt_history.is_empty() {
+                    error
+                } else {
+                    error.with_attempt_history(attempt_history)
+                }
+            })
     }
 
     /// Retrieve the input for the operation being invoked.
@@ -178,6 +189,17 @@ impl<I, O, E: Debug> InterceptorContext<I, O, E> {
         self.output_or_error.as_mut()
     }
 
+    /// Returns the history of the request attempts made so far.
+    pub fn attempt_history(&self) -> &AttemptHistory {
+        &self.attempt_history
+    }
+
+    /// Returns a mutable reference to the history of the request attempts made so far.
+    #[doc(hidden)]
+    pub fn attempt_history_mut(&mut self) -> &mut AttemptHistory {
+        &mut self.attempt_history
+    }
+
     /// Advance to the Serialization phase.
     #[doc(hidden)]
     pub fn enter_serialization_phase(&mut self) {
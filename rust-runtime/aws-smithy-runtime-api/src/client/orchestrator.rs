@@ -62,6 +62,12 @@ impl Connection for Box<dyn Connection> {
     }
 }
 
+impl Connection for Arc<dyn Connection> {
+    fn call(&self, request: HttpRequest) -> BoxFuture<HttpResponse> {
+        (**self).call(request)
+    }
+}
+
 #[derive(Debug)]
 pub struct EndpointResolverParams(TypeErasedBox);
 
@@ -215,15 +221,26 @@ pub trait ConfigBagAccessors {
     {
         &**self
             .config_bag()
-            .get::<Box<dyn Connection>>()
+            .get::<Arc<dyn Connection>>()
             .expect("missing connector")
     }
 
+    /// Returns a shared handle to the connection, e.g. to wrap it in another [`Connection`].
+    fn shared_connection(&self) -> Arc<dyn Connection>
+    where
+        Self: Gettable,
+    {
+        self.config_bag()
+            .get::<Arc<dyn Connection>>()
+            .expect("missing connector")
+            .clone()
+    }
+
     fn set_connection(&mut self, connection: impl Connection + 'static)
     where
         Self: Settable,
     {
-        self.put::<Box<dyn Connection>>(Box::new(connection));
+        self.put::<Arc<dyn Connection>>(Arc::new(connection));
     }
 
     fn http_auth_schemes(&self) -> &HttpAuthSchemes
@@ -5,12 +5,12 @@
 
 use crate::client::interceptors::InterceptorContext;
 use crate::client::orchestrator::BoxError;
-use aws_smithy_types::config_bag::ConfigBag;
+use aws_smithy_types::config_bag::{ConfigBag, Storable, StoreReplace};
 use std::fmt::Debug;
 use std::time::Duration;
 use tracing::trace;
 
-pub use aws_smithy_types::retry::ErrorKind;
+pub use aws_smithy_types::retry::{ErrorKind, RetryReason};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 /// An answer to the question "should I make a request attempt?"
@@ -40,11 +40,39 @@ pub trait RetryStrategy: Send + Sync + Debug {
     ) -> Result<ShouldAttempt, BoxError>;
 }
 
+/// Marks an operation as safe to send more than once concurrently, because it's `@readonly` or
+/// `@idempotent`.
+///
+/// Generated operations put this marker in their config bag. It enables request hedging.
 #[non_exhaustive]
-#[derive(Clone, Eq, PartialEq, Debug)]
-pub enum RetryReason {
-    Error(ErrorKind),
-    Explicit(Duration),
+#[derive(Clone, Copy, Debug, Default)]
+pub struct IdempotentOperation;
+
+impl IdempotentOperation {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Storable for IdempotentOperation {
+    type Storer = StoreReplace<Self>;
+}
+
+/// Marks an operation as `@readonly`, so that its responses may be reused.
+///
+/// Generated operations put this marker in their config bag. It enables response caching.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ReadonlyOperation;
+
+impl ReadonlyOperation {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Storable for ReadonlyOperation {
+    type Storer = StoreReplace<Self>;
 }
 
 /// Classifies what kind of retry is needed for a given an [`InterceptorContext`].
@@ -53,6 +81,18 @@ pub trait ClassifyRetry: Send + Sync + Debug {
     /// `Some(RetryKind)` if the error should be retried; Otherwise returns `None`.
     fn classify_retry(&self, ctx: &InterceptorContext) -> Option<RetryReason>;
 
+    /// Run this classifier against an error with access to the request's config, e.g. its
+    /// [request time](crate::client::orchestrator::ConfigBagAccessors::request_time).
+    ///
+    /// Defaults to [`ClassifyRetry::classify_retry`]. The orchestrator always calls this method.
+    fn classify_retry_with_config(
+        &self,
+        ctx: &InterceptorContext,
+        _cfg: &ConfigBag,
+    ) -> Option<RetryReason> {
+        self.classify_retry(ctx)
+    }
+
     /// The name that this classifier should report for debugging purposes.
     fn name(&self) -> &'static str;
 }
@@ -79,13 +119,14 @@ impl RetryClassifiers {
 
     // TODO(https://github.com/awslabs/smithy-rs/issues/2632) make a map function so users can front-run or second-guess the classifier's decision
     // pub fn map_classifiers(mut self, fun: Fn() -> RetryClassifiers)
-}
 
-impl ClassifyRetry for RetryClassifiers {
-    fn classify_retry(&self, ctx: &InterceptorContext) -> Option<RetryReason> {
+    fn classify_with(
+        &self,
+        classify: impl Fn(&dyn ClassifyRetry) -> Option<RetryReason>,
+    ) -> Option<RetryReason> {
         // return the first non-None result
         self.inner.iter().find_map(|cr| {
-            let maybe_reason = cr.classify_retry(ctx);
+            let maybe_reason = classify(cr.as_ref());
 
             match maybe_reason.as_ref() {
                 Some(reason) => trace!(
@@ -99,6 +140,20 @@ impl ClassifyRetry for RetryClassifiers {
             maybe_reason
         })
     }
+}
+
+impl ClassifyRetry for RetryClassifiers {
+    fn classify_retry(&self, ctx: &InterceptorContext) -> Option<RetryReason> {
+        self.classify_with(|cr| cr.classify_retry(ctx))
+    }
+
+    fn classify_retry_with_config(
+        &self,
+        ctx: &InterceptorContext,
+        cfg: &ConfigBag,
+    ) -> Option<RetryReason> {
+        self.classify_with(|cr| cr.classify_retry_with_config(ctx, cfg))
+    }
 
     fn name(&self) -> &'static str {
         "Collection of Classifiers"
@@ -19,6 +19,10 @@ use aws_smithy_types::config_bag::ConfigBag;
 use http::header::HeaderName;
 use http::HeaderValue;
 
+mod digest;
+
+pub use digest::{DigestAuthCache, DigestChallengeClassifier, DigestChallengeInterceptor};
+
 /// Destination for the API key
 #[derive(Copy, Clone, Debug)]
 pub enum ApiKeyLocation {
@@ -219,6 +223,10 @@ impl HttpRequestSigner for BearerAuthSigner {
 }
 
 /// Auth implementation for Smithy's `@httpDigestAuth` auth scheme
+///
+/// Challenges are cached in the [`DigestAuthCache`] found in the config bag by the
+/// [`DigestChallengeInterceptor`], and answered by the retry strategy when the
+/// [`DigestChallengeClassifier`] is registered. Without a cache, requests are sent unsigned.
 #[derive(Debug, Default)]
 pub struct DigestAuthScheme {
     signer: DigestAuthSigner,
@@ -256,14 +264,15 @@ struct DigestAuthSigner;
 impl HttpRequestSigner for DigestAuthSigner {
     fn sign_request(
         &self,
-        _request: &mut HttpRequest,
-        _identity: &Identity,
+        request: &mut HttpRequest,
+        identity: &Identity,
         _auth_scheme_endpoint_config: AuthSchemeEndpointConfig<'_>,
-        _config_bag: &ConfigBag,
+        config_bag: &ConfigBag,
     ) -> Result<(), BoxError> {
-        unimplemented!(
-            "support for signing with Smithy's `@httpDigestAuth` auth scheme is not implemented yet"
-        )
+        let login = identity
+            .data::<Login>()
+            .ok_or("HTTP digest auth requires a `Login` identity")?;
+        digest::sign(request, login, config_bag)
     }
 }
 
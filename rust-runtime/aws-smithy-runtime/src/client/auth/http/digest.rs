@@ -0,0 +1,573 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Support for [RFC 7616](https://www.rfc-editor.org/rfc/rfc7616) HTTP digest auth.
+//!
+//! Digest auth is challenge based: the first request to an endpoint is sent without credentials,
+//! and the server responds with a `401` carrying a `WWW-Authenticate: Digest ...` challenge.
+//! The [`DigestChallengeInterceptor`] caches the challenge per endpoint in a [`DigestAuthCache`],
+//! the [`DigestChallengeClassifier`] asks the retry strategy to retry the request once, and
+//! subsequent requests to that endpoint are signed up front using the cached nonce.
+//!
+//! Since the challenge is answered by retrying the request, the retry strategy must allow at least
+//! two attempts.
+
+use aws_smithy_runtime_api::client::identity::http::Login;
+use aws_smithy_runtime_api::client::interceptors::{
+    BeforeDeserializationInterceptorContextMut, Interceptor, InterceptorContext,
+};
+use aws_smithy_runtime_api::client::orchestrator::{BoxError, HttpRequest};
+use aws_smithy_runtime_api::client::retries::ClassifyRetry;
+use aws_smithy_types::config_bag::{ConfigBag, Storable, StoreReplace};
+use aws_smithy_types::endpoint::Endpoint;
+use aws_smithy_types::retry::RetryReason;
+use http::{HeaderValue, StatusCode};
+use md5::Md5;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Cache of the most recent digest auth challenge received from each endpoint
+///
+/// Clones of a `DigestAuthCache` share the same underlying cache. It should be created
+/// once per client so that nonces can be reused across operations.
+#[derive(Clone, Default)]
+pub struct DigestAuthCache {
+    challenges: Arc<Mutex<HashMap<String, Challenge>>>,
+}
+
+impl DigestAuthCache {
+    /// Creates a new, empty `DigestAuthCache`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn insert(&self, endpoint: &str, challenge: Challenge) {
+        self.challenges
+            .lock()
+            .unwrap()
+            .insert(endpoint.to_string(), challenge);
+    }
+
+    /// Returns the challenge for the given endpoint with its nonce count incremented
+    fn next(&self, endpoint: &str) -> Option<(Challenge, u32)> {
+        let mut challenges = self.challenges.lock().unwrap();
+        let challenge = challenges.get_mut(endpoint)?;
+        challenge.nonce_count += 1;
+        Some((challenge.clone(), challenge.nonce_count))
+    }
+}
+
+impl fmt::Debug for DigestAuthCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DigestAuthCache")
+            .field("endpoints", &self.challenges.lock().unwrap().len())
+            .finish()
+    }
+}
+
+impl Storable for DigestAuthCache {
+    type Storer = StoreReplace<Self>;
+}
+
+/// Marks that the current operation has already received a challenge
+#[derive(Debug)]
+struct ChallengeReceived;
+
+impl Storable for ChallengeReceived {
+    type Storer = StoreReplace<Self>;
+}
+
+/// Marks a `401` response whose challenge should be answered by retrying the request
+#[derive(Clone, Copy, Debug)]
+struct RetryableChallenge;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Algorithm {
+    Md5,
+    Md5Sess,
+    Sha256,
+    Sha256Sess,
+}
+
+impl Algorithm {
+    fn from_str(value: &str) -> Option<Self> {
+        match value.to_ascii_uppercase().as_str() {
+            "MD5" => Some(Self::Md5),
+            "MD5-SESS" => Some(Self::Md5Sess),
+            "SHA-256" => Some(Self::Sha256),
+            "SHA-256-SESS" => Some(Self::Sha256Sess),
+            _ => None,
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Md5 => "MD5",
+            Self::Md5Sess => "MD5-sess",
+            Self::Sha256 => "SHA-256",
+            Self::Sha256Sess => "SHA-256-sess",
+        }
+    }
+
+    fn is_session(&self) -> bool {
+        matches!(self, Self::Md5Sess | Self::Sha256Sess)
+    }
+
+    fn hash(&self, data: &str) -> String {
+        match self {
+            Self::Md5 | Self::Md5Sess => hex::encode(Md5::digest(data.as_bytes())),
+            Self::Sha256 | Self::Sha256Sess => hex::encode(Sha256::digest(data.as_bytes())),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Challenge {
+    realm: String,
+    nonce: String,
+    opaque: Option<String>,
+    algorithm: Algorithm,
+    qop_auth: bool,
+    nonce_count: u32,
+}
+
+impl Challenge {
+    /// Parses a `WWW-Authenticate` header value, returning `None` if it isn't a supported digest challenge
+    fn parse(header: &str) -> Option<Self> {
+        let (scheme, params) = header.trim().split_once(' ')?;
+        if !scheme.eq_ignore_ascii_case("digest") {
+            return None;
+        }
+        let params = parse_params(params);
+        let algorithm = match params.get("algorithm") {
+            Some(algorithm) => Algorithm::from_str(algorithm)?,
+            None => Algorithm::Md5,
+        };
+        let qop_auth = match params.get("qop") {
+            Some(qop) => {
+                let qop_auth = qop.split(',').any(|qop| qop.trim() == "auth");
+                // Only `auth` is supported; `auth-int` alone would require hashing the body
+                if !qop_auth {
+                    return None;
+                }
+                qop_auth
+            }
+            None => false,
+        };
+        Some(Self {
+            realm: params.get("realm")?.clone(),
+            nonce: params.get("nonce")?.clone(),
+            opaque: params.get("opaque").cloned(),
+            algorithm,
+            qop_auth,
+            nonce_count: 0,
+        })
+    }
+}
+
+/// Parses comma-separated `key=value` and `key="quoted value"` auth parameters
+fn parse_params(input: &str) -> HashMap<String, String> {
+    let mut params = HashMap::new();
+    let mut rest = input.trim();
+    while !rest.is_empty() {
+        let (key, after_key) = match rest.split_once('=') {
+            Some(split) => split,
+            None => break,
+        };
+        let key = key.trim().to_ascii_lowercase();
+        let after_key = after_key.trim_start();
+        let (value, remaining) = if let Some(quoted) = after_key.strip_prefix('"') {
+            let mut value = String::new();
+            let mut chars = quoted.char_indices();
+            let mut end = quoted.len();
+            while let Some((idx, c)) = chars.next() {
+                match c {
+                    '\\' => {
+                        if let Some((_, escaped)) = chars.next() {
+                            value.push(escaped);
+                        }
+                    }
+                    '"' => {
+                        end = idx + 1;
+                        break;
+                    }
+                    _ => value.push(c),
+                }
+            }
+            (value, &quoted[end..])
+        } else {
+            let end = after_key.find(',').unwrap_or(after_key.len());
+            (after_key[..end].trim().to_string(), &after_key[end..])
+        };
+        params.insert(key, value);
+        rest = remaining.trim_start().trim_start_matches(',').trim_start();
+    }
+    params
+}
+
+fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn generate_cnonce() -> String {
+    let bytes: Vec<u8> = (0..16).map(|_| fastrand::u8(..)).collect();
+    hex::encode(bytes)
+}
+
+fn authorization_header(
+    challenge: &Challenge,
+    nonce_count: u32,
+    cnonce: &str,
+    login: &Login,
+    method: &str,
+    uri: &str,
+) -> String {
+    let algorithm = challenge.algorithm;
+    let mut ha1 = algorithm.hash(&format!(
+        "{}:{}:{}",
+        login.user(),
+        challenge.realm,
+        login.password()
+    ));
+    if algorithm.is_session() {
+        ha1 = algorithm.hash(&format!("{}:{}:{}", ha1, challenge.nonce, cnonce));
+    }
+    let ha2 = algorithm.hash(&format!("{}:{}", method, uri));
+    let nc = format!("{:08x}", nonce_count);
+    let response = if challenge.qop_auth {
+        algorithm.hash(&format!(
+            "{}:{}:{}:{}:auth:{}",
+            ha1, challenge.nonce, nc, cnonce, ha2
+        ))
+    } else {
+        algorithm.hash(&format!("{}:{}:{}", ha1, challenge.nonce, ha2))
+    };
+
+    let mut header = format!(
+        "Digest username={}, realm={}, uri={}, algorithm={}, nonce={}",
+        quote(login.user()),
+        quote(&challenge.realm),
+        quote(uri),
+        algorithm.as_str(),
+        quote(&challenge.nonce),
+    );
+    if challenge.qop_auth {
+        header.push_str(&format!(", nc={}, cnonce={}, qop=auth", nc, quote(cnonce)));
+    }
+    header.push_str(&format!(", response={}", quote(&response)));
+    if let Some(opaque) = &challenge.opaque {
+        header.push_str(&format!(", opaque={}", quote(opaque)));
+    }
+    header
+}
+
+/// Signs the request if a challenge has been cached for its endpoint
+///
+/// Requests to an endpoint that hasn't challenged yet are sent unsigned.
+pub(super) fn sign(
+    request: &mut HttpRequest,
+    login: &Login,
+    config_bag: &ConfigBag,
+) -> Result<(), BoxError> {
+    let (cache, endpoint) = match (
+        config_bag.load::<DigestAuthCache>(),
+        config_bag.get::<Endpoint>(),
+    ) {
+        (Some(cache), Some(endpoint)) => (cache, endpoint),
+        _ => return Ok(()),
+    };
+    let (challenge, nonce_count) = match cache.next(endpoint.url()) {
+        Some(next) => next,
+        None => return Ok(()),
+    };
+    let uri = request
+        .uri()
+        .path_and_query()
+        .map(|path| path.as_str())
+        .unwrap_or("/")
+        .to_string();
+    let header = authorization_header(
+        &challenge,
+        nonce_count,
+        &generate_cnonce(),
+        login,
+        request.method().as_str(),
+        &uri,
+    );
+    request.headers_mut().insert(
+        http::header::AUTHORIZATION,
+        HeaderValue::try_from(header).map_err(|_| {
+            "digest auth login contains characters that can't be included in a HTTP header"
+        })?,
+    );
+    Ok(())
+}
+
+/// An interceptor caching the digest auth challenges of `401` responses in the [`DigestAuthCache`].
+///
+/// Only the first challenge received by an operation is answered, so that bad credentials fail fast.
+/// The [`DigestChallengeClassifier`] must be registered for the challenge to be answered.
+#[non_exhaustive]
+#[derive(Debug, Default)]
+pub struct DigestChallengeInterceptor;
+
+impl DigestChallengeInterceptor {
+    /// Creates a new `DigestChallengeInterceptor`.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Interceptor for DigestChallengeInterceptor {
+    fn modify_before_deserialization(
+        &self,
+        context: &mut BeforeDeserializationInterceptorContextMut<'_>,
+        cfg: &mut ConfigBag,
+    ) -> Result<(), BoxError> {
+        if cfg.load::<ChallengeReceived>().is_some() {
+            return Ok(());
+        }
+        let (cache, endpoint) = match (cfg.load::<DigestAuthCache>(), cfg.get::<Endpoint>()) {
+            (Some(cache), Some(endpoint)) => (cache.clone(), endpoint.url().to_string()),
+            _ => return Ok(()),
+        };
+        let response = context.response_mut();
+        if response.status() != StatusCode::UNAUTHORIZED {
+            return Ok(());
+        }
+        let challenge = response
+            .headers()
+            .get_all(http::header::WWW_AUTHENTICATE)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .filter_map(Challenge::parse)
+            // Prefer the strongest algorithm when the server offers several
+            .max_by_key(|challenge| {
+                matches!(
+                    challenge.algorithm,
+                    Algorithm::Sha256 | Algorithm::Sha256Sess
+                )
+            });
+        if let Some(challenge) = challenge {
+            tracing::debug!(endpoint = %endpoint, "received digest auth challenge");
+            cache.insert(&endpoint, challenge);
+            response.extensions_mut().insert(RetryableChallenge);
+            cfg.interceptor_state().store_put(ChallengeReceived);
+        }
+        Ok(())
+    }
+}
+
+/// A retry classifier retrying requests rejected with a digest auth challenge, immediately.
+///
+/// Challenges are only retried once they've been cached by the [`DigestChallengeInterceptor`].
+#[non_exhaustive]
+#[derive(Debug, Default)]
+pub struct DigestChallengeClassifier;
+
+impl DigestChallengeClassifier {
+    /// Creates a new `DigestChallengeClassifier`.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl ClassifyRetry for DigestChallengeClassifier {
+    fn classify_retry(&self, ctx: &InterceptorContext) -> Option<RetryReason> {
+        ctx.response()
+            .and_then(|response| response.extensions().get::<RetryableChallenge>())
+            .map(|_| RetryReason::Explicit(Duration::ZERO))
+    }
+
+    fn name(&self) -> &'static str {
+        "Digest Auth Challenge"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aws_smithy_http::body::SdkBody;
+    use aws_smithy_runtime_api::client::orchestrator::HttpResponse;
+    use aws_smithy_types::config_bag::Layer;
+    use aws_smithy_types::type_erasure::TypedBox;
+
+    #[test]
+    fn parse_challenge() {
+        let challenge = Challenge::parse(
+            r#"Digest realm="http-auth@example.org", qop="auth, auth-int", algorithm=SHA-256, nonce="7ypf/xlj9XXwfDPEoM4URrv/xwf94BcCAzFZH4GiTo0v", opaque="FQhe/qaU925kfnzjCev0ciny7QMkPqMAFRtzCUYo5tdS""#,
+        )
+        .unwrap();
+        assert_eq!("http-auth@example.org", challenge.realm);
+        assert_eq!(
+            "7ypf/xlj9XXwfDPEoM4URrv/xwf94BcCAzFZH4GiTo0v",
+            challenge.nonce
+        );
+        assert_eq!(Algorithm::Sha256, challenge.algorithm);
+        assert!(challenge.qop_auth);
+
+        assert!(Challenge::parse(r#"Basic realm="example""#).is_none());
+        assert!(Challenge::parse(r#"Digest realm="example", nonce="n", qop="auth-int""#).is_none());
+        assert!(
+            Challenge::parse(r#"Digest realm="example", nonce="n", algorithm=SHA-512"#).is_none()
+        );
+    }
+
+    #[test]
+    fn parse_escaped_params() {
+        let params = parse_params(r#"realm="a \"quoted\", realm", stale=false"#);
+        assert_eq!(r#"a "quoted", realm"#, params["realm"]);
+        assert_eq!("false", params["stale"]);
+    }
+
+    // Test vectors from RFC 7616 section 3.9.1
+    fn rfc_challenge(algorithm: Algorithm) -> Challenge {
+        Challenge {
+            realm: "http-auth@example.org".into(),
+            nonce: "7ypf/xlj9XXwfDPEoM4URrv/xwf94BcCAzFZH4GiTo0v".into(),
+            opaque: Some("FQhe/qaU925kfnzjCev0ciny7QMkPqMAFRtzCUYo5tdS".into()),
+            algorithm,
+            qop_auth: true,
+            nonce_count: 0,
+        }
+    }
+
+    fn rfc_header(algorithm: Algorithm) -> String {
+        authorization_header(
+            &rfc_challenge(algorithm),
+            1,
+            "f2/wE4q74E6zIJEtWaHKaf5wv/H5QzzpXusqGemxURZJ",
+            &Login::new("Mufasa", "Circle of Life", None),
+            "GET",
+            "/dir/index.html",
+        )
+    }
+
+    #[test]
+    fn rfc_7616_md5() {
+        let header = rfc_header(Algorithm::Md5);
+        assert!(header.starts_with(r#"Digest username="Mufasa", realm="http-auth@example.org", uri="/dir/index.html", algorithm=MD5"#));
+        assert!(header.contains("nc=00000001"));
+        assert!(header.contains("qop=auth"));
+        assert!(header.contains(r#"response="8ca523f5e9506fed4657c9700eebdbec""#));
+        assert!(header.ends_with(r#"opaque="FQhe/qaU925kfnzjCev0ciny7QMkPqMAFRtzCUYo5tdS""#));
+    }
+
+    #[test]
+    fn rfc_7616_sha256() {
+        let header = rfc_header(Algorithm::Sha256);
+        assert!(header.contains("algorithm=SHA-256"));
+        assert!(header.contains(
+            r#"response="753927fa0e85d155564e2e272a28d1802ca10daf4496794697cf8db5856cb6c1""#
+        ));
+    }
+
+    fn cfg_with_cache(cache: &DigestAuthCache) -> ConfigBag {
+        let mut layer = Layer::new("test");
+        layer.store_put(cache.clone());
+        let mut cfg = ConfigBag::of_layers(vec![layer]);
+        cfg.interceptor_state()
+            .put(Endpoint::builder().url("https://example.com").build());
+        cfg
+    }
+
+    fn request() -> HttpRequest {
+        http::Request::builder()
+            .uri("https://example.com/dir/index.html?a=b")
+            .body(SdkBody::empty())
+            .unwrap()
+    }
+
+    #[test]
+    fn unsigned_until_challenged() {
+        let cache = DigestAuthCache::new();
+        let cfg = cfg_with_cache(&cache);
+        let login = Login::new("Mufasa", "Circle of Life", None);
+        let mut request = request();
+        sign(&mut request, &login, &cfg).unwrap();
+        assert!(request.headers().get("authorization").is_none());
+
+        cache.insert("https://example.com", rfc_challenge(Algorithm::Md5));
+        sign(&mut request, &login, &cfg).unwrap();
+        let header = request.headers()["authorization"].to_str().unwrap();
+        assert!(header.contains(r#"uri="/dir/index.html?a=b""#));
+        assert!(header.contains("nc=00000001"));
+
+        // The nonce count increments each time the cached nonce is used
+        sign(&mut request, &login, &cfg).unwrap();
+        let header = request.headers()["authorization"].to_str().unwrap();
+        assert!(header.contains("nc=00000002"));
+    }
+
+    fn challenge_response() -> HttpResponse {
+        http::Response::builder()
+            .status(401)
+            .header(
+                "www-authenticate",
+                r#"Digest realm="a", nonce="md5", algorithm=MD5, qop="auth""#,
+            )
+            .header(
+                "www-authenticate",
+                r#"Digest realm="a", nonce="sha", algorithm=SHA-256, qop="auth""#,
+            )
+            .body(SdkBody::empty())
+            .unwrap()
+    }
+
+    /// Runs an attempt answered with `response` through the interceptor, then classifies it.
+    fn classify(response: HttpResponse, cfg: &mut ConfigBag) -> Option<RetryReason> {
+        let mut ctx = InterceptorContext::new(TypedBox::new("doesnt-matter").erase());
+        ctx.enter_serialization_phase();
+        let _ = ctx.take_input();
+        ctx.set_request(request());
+        ctx.enter_before_transmit_phase();
+        ctx.enter_transmit_phase();
+        let _ = ctx.take_request();
+        ctx.set_response(response);
+        ctx.enter_before_deserialization_phase();
+        DigestChallengeInterceptor::new()
+            .modify_before_deserialization(&mut (&mut ctx).into(), cfg)
+            .unwrap();
+        DigestChallengeClassifier::new().classify_retry(&ctx)
+    }
+
+    #[test]
+    fn challenge_is_retried_once() {
+        let cache = DigestAuthCache::new();
+        let mut cfg = cfg_with_cache(&cache);
+
+        assert_eq!(
+            Some(RetryReason::Explicit(Duration::ZERO)),
+            classify(challenge_response(), &mut cfg)
+        );
+        let (challenge, _) = cache.next("https://example.com").unwrap();
+        assert_eq!("sha", challenge.nonce);
+        assert_eq!(Algorithm::Sha256, challenge.algorithm);
+
+        // A second challenge means the credentials were rejected
+        assert_eq!(None, classify(challenge_response(), &mut cfg));
+    }
+
+    #[test]
+    fn other_responses_are_not_retried() {
+        let cache = DigestAuthCache::new();
+        let mut cfg = cfg_with_cache(&cache);
+        let basic_challenge = http::Response::builder()
+            .status(401)
+            .header("www-authenticate", r#"Basic realm="a""#)
+            .body(SdkBody::empty())
+            .unwrap();
+        assert_eq!(None, classify(basic_challenge, &mut cfg));
+        let ok = http::Response::builder()
+            .status(200)
+            .body(SdkBody::empty())
+            .unwrap();
+        assert_eq!(None, classify(ok, &mut cfg));
+        assert!(cache.next("https://example.com").is_none());
+    }
+}
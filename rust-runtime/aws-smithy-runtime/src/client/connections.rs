@@ -6,6 +6,9 @@
 #[cfg(feature = "test-util")]
 pub mod test_connection;
 
+#[cfg(all(feature = "connector-wasi", target_family = "wasm", target_os = "wasi"))]
+pub mod wasi;
+
 pub mod adapter {
     use aws_smithy_client::erase::DynConnector;
     use aws_smithy_runtime_api::client::orchestrator::{
@@ -5,6 +5,8 @@
 
 //! Module with client connectors useful for testing.
 
+pub mod event_stream;
+
 use aws_smithy_async::rt::sleep::AsyncSleep;
 use aws_smithy_http::body::SdkBody;
 use aws_smithy_http::result::ConnectorError;
@@ -0,0 +1,489 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Test connection that responds with a scripted event stream.
+//!
+//! [`EventStreamConnection`] responds to a single request with the messages of an
+//! [`EventStreamScript`], encoded with `aws-smithy-eventstream`. The script can also pause
+//! between messages and drop the connection mid-stream. The messages the client sends in the
+//! request body are decoded as they arrive so that tests can make assertions about them.
+//!
+//! ```no_run
+//! use aws_smithy_runtime::client::connections::test_connection::event_stream::{
+//!     EventStreamConnection, EventStreamScript,
+//! };
+//! use std::time::Duration;
+//!
+//! # async fn docs() {
+//! let connection = EventStreamConnection::new(
+//!     EventStreamScript::new()
+//!         .event("TranscriptEvent", "application/json", r#"{"Transcript":{"Results":[]}}"#)
+//!         .delay(Duration::from_millis(100))
+//!         .exception("BadRequestException", "application/json", r#"{"Message":"bad"}"#),
+//! );
+//! // ... configure a client with `connection` and send a streaming request ...
+//! connection.wait_for_request_body().await;
+//! for message in connection.messages() {
+//!     println!("{:?}", message.headers());
+//! }
+//! # }
+//! ```
+//!
+//! This is the orchestrator counterpart of `aws_smithy_client::test_connection::event_stream`,
+//! which provides the same connection for middleware clients.
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use aws_smithy_eventstream::frame::{
+    DecodedFrame, Header, HeaderValue, Message, MessageFrameDecoder,
+};
+use aws_smithy_http::body::SdkBody;
+use aws_smithy_http::result::ConnectorError;
+use aws_smithy_runtime_api::client::orchestrator::{
+    BoxFuture, Connection, HttpRequest, HttpResponse,
+};
+use bytes::{Bytes, BytesMut};
+use http::header::CONTENT_TYPE;
+use http_body::Body;
+use tokio::sync::watch;
+
+const EVENT_STREAM_CONTENT_TYPE: &str = "application/vnd.amazon.eventstream";
+
+#[derive(Debug)]
+enum Action {
+    Message(Message),
+    Delay(Duration),
+    Disconnect,
+}
+
+/// A scripted event stream response
+///
+/// Actions are performed in the order they were added. If the script doesn't end with a
+/// [`disconnect`](EventStreamScript::disconnect), the response body ends cleanly after the
+/// last message.
+#[derive(Debug, Default)]
+pub struct EventStreamScript {
+    actions: Vec<Action>,
+}
+
+impl EventStreamScript {
+    /// Creates an empty script
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sends a modeled event with the given `event_type` and payload
+    pub fn event(
+        self,
+        event_type: &'static str,
+        content_type: &'static str,
+        payload: impl Into<Bytes>,
+    ) -> Self {
+        self.message(
+            Message::new(payload)
+                .add_header(string_header(":message-type", "event"))
+                .add_header(string_header(":event-type", event_type))
+                .add_header(string_header(":content-type", content_type)),
+        )
+    }
+
+    /// Sends a modeled exception with the given `exception_type` and payload
+    pub fn exception(
+        self,
+        exception_type: &'static str,
+        content_type: &'static str,
+        payload: impl Into<Bytes>,
+    ) -> Self {
+        self.message(
+            Message::new(payload)
+                .add_header(string_header(":message-type", "exception"))
+                .add_header(string_header(":exception-type", exception_type))
+                .add_header(string_header(":content-type", content_type)),
+        )
+    }
+
+    /// Sends an unmodeled error with the given error code and message
+    pub fn error(self, code: &'static str, message: &'static str) -> Self {
+        self.message(
+            Message::new(Bytes::new())
+                .add_header(string_header(":message-type", "error"))
+                .add_header(string_header(":error-code", code))
+                .add_header(string_header(":error-message", message)),
+        )
+    }
+
+    /// Sends an arbitrary message
+    pub fn message(mut self, message: Message) -> Self {
+        self.actions.push(Action::Message(message));
+        self
+    }
+
+    /// Waits for `duration` before performing the next action
+    pub fn delay(mut self, duration: Duration) -> Self {
+        self.actions.push(Action::Delay(duration));
+        self
+    }
+
+    /// Aborts the response body, simulating a dropped connection
+    ///
+    /// Any actions added after this one are ignored.
+    pub fn disconnect(mut self) -> Self {
+        self.actions.push(Action::Disconnect);
+        self
+    }
+}
+
+fn string_header(name: &'static str, value: &'static str) -> Header {
+    Header::new(name, HeaderValue::String(value.into()))
+}
+
+#[derive(Debug, Default)]
+struct Captured {
+    request: Option<http::Request<()>>,
+    messages: Vec<Message>,
+    error: Option<String>,
+}
+
+/// Test connection that responds to a single request with a scripted event stream
+///
+/// See the [module documentation](self) for an example.
+#[derive(Clone, Debug)]
+pub struct EventStreamConnection {
+    script: Arc<Mutex<Option<EventStreamScript>>>,
+    status: u16,
+    captured: Arc<Mutex<Captured>>,
+    request_done: watch::Receiver<bool>,
+    request_done_sender: Arc<Mutex<Option<watch::Sender<bool>>>>,
+}
+
+impl EventStreamConnection {
+    /// Creates a connection that responds with a 200 status and the given script
+    pub fn new(script: EventStreamScript) -> Self {
+        Self::with_status(200, script)
+    }
+
+    /// Creates a connection that responds with the given status and script
+    pub fn with_status(status: u16, script: EventStreamScript) -> Self {
+        let (sender, receiver) = watch::channel(false);
+        Self {
+            script: Arc::new(Mutex::new(Some(script))),
+            status,
+            captured: Default::default(),
+            request_done: receiver,
+            request_done_sender: Arc::new(Mutex::new(Some(sender))),
+        }
+    }
+
+    /// Waits until the client has finished sending the request body
+    ///
+    /// This will wait forever if the client never finishes the request, so tests of streams that
+    /// fail part way through should use [`messages`](Self::messages) instead.
+    pub async fn wait_for_request_body(&self) {
+        let mut done = self.request_done.clone();
+        while !*done.borrow() {
+            if done.changed().await.is_err() {
+                return;
+            }
+        }
+    }
+
+    /// Returns the request that was sent, without its body
+    ///
+    /// Panics if no request has been sent yet.
+    pub fn request(&self) -> http::Request<()> {
+        let captured = self.captured.lock().unwrap();
+        let request = captured.request.as_ref().expect("no request was sent");
+        let mut copy = http::Request::builder()
+            .method(request.method())
+            .uri(request.uri())
+            .version(request.version())
+            .body(())
+            .unwrap();
+        *copy.headers_mut() = request.headers().clone();
+        copy
+    }
+
+    /// Returns the messages that have been decoded from the request body so far
+    ///
+    /// For signed event streams, these are the signed wrapper messages. Use
+    /// [`unwrap_signed_message`] to get the event inside of them.
+    pub fn messages(&self) -> Vec<Message> {
+        self.captured.lock().unwrap().messages.clone()
+    }
+
+    /// Returns the events that have been sent so far with their signing wrappers removed
+    ///
+    /// The empty signed message that ends a signed event stream is skipped.
+    pub fn unsigned_messages(&self) -> Vec<Message> {
+        self.messages()
+            .iter()
+            .filter_map(|message| unwrap_signed_message(message).expect("valid inner message"))
+            .collect()
+    }
+
+    /// Returns an error if reading or decoding the request body failed
+    pub fn request_body_error(&self) -> Option<String> {
+        self.captured.lock().unwrap().error.clone()
+    }
+
+    /// Captures `request` and responds with the script
+    ///
+    /// The response is returned immediately; its body is played out in the background. This
+    /// must be called from within a Tokio runtime.
+    fn respond(&self, request: HttpRequest) -> Result<HttpResponse, ConnectorError> {
+        let script = self.script.lock().unwrap().take().ok_or_else(|| {
+            ConnectorError::other(
+                "EventStreamConnection only responds to a single request".into(),
+                None,
+            )
+        })?;
+
+        let (parts, body) = request.into_parts();
+        self.captured.lock().unwrap().request = Some(http::Request::from_parts(parts, ()));
+        tokio::spawn(capture_body(
+            body,
+            self.captured.clone(),
+            self.request_done_sender.lock().unwrap().take(),
+        ));
+
+        let (sender, response_body) = hyper::Body::channel();
+        tokio::spawn(play(script.actions, sender));
+        Ok(http::Response::builder()
+            .status(self.status)
+            .header(CONTENT_TYPE, EVENT_STREAM_CONTENT_TYPE)
+            .body(SdkBody::from(response_body))
+            .expect("valid response"))
+    }
+}
+
+/// Returns the event inside of a signed wrapper message
+///
+/// Returns `Ok(None)` for the empty signed message that ends an event stream, and a copy of
+/// `message` if it isn't signed.
+pub fn unwrap_signed_message(
+    message: &Message,
+) -> Result<Option<Message>, aws_smithy_eventstream::error::Error> {
+    let signed = message
+        .headers()
+        .iter()
+        .any(|header| header.name().as_str() == ":chunk-signature");
+    if !signed {
+        Ok(Some(message.clone()))
+    } else if message.payload().is_empty() {
+        Ok(None)
+    } else {
+        Message::read_from(message.payload().as_ref()).map(Some)
+    }
+}
+
+async fn capture_body(
+    mut body: SdkBody,
+    captured: Arc<Mutex<Captured>>,
+    done: Option<watch::Sender<bool>>,
+) {
+    let mut decoder = MessageFrameDecoder::new();
+    let mut buffer = BytesMut::new();
+    'read: while let Some(data) = body.data().await {
+        match data {
+            Ok(data) => buffer.extend_from_slice(&data),
+            Err(err) => {
+                captured.lock().unwrap().error = Some(err.to_string());
+                break;
+            }
+        }
+        loop {
+            match decoder.decode_frame(&mut buffer) {
+                Ok(DecodedFrame::Complete(message)) => {
+                    captured.lock().unwrap().messages.push(message)
+                }
+                Ok(DecodedFrame::Incomplete) => break,
+                Err(err) => {
+                    captured.lock().unwrap().error = Some(err.to_string());
+                    break 'read;
+                }
+            }
+        }
+    }
+    if let Some(done) = done {
+        let _ = done.send(true);
+    }
+}
+
+async fn play(actions: Vec<Action>, mut sender: hyper::body::Sender) {
+    for action in actions {
+        match action {
+            Action::Message(message) => {
+                let mut data = Vec::new();
+                message.write_to(&mut data).expect("valid message");
+                if sender.send_data(Bytes::from(data)).await.is_err() {
+                    // the client stopped reading the response
+                    return;
+                }
+            }
+            Action::Delay(duration) => tokio::time::sleep(duration).await,
+            Action::Disconnect => {
+                sender.abort();
+                return;
+            }
+        }
+    }
+}
+
+impl Connection for EventStreamConnection {
+    fn call(&self, request: HttpRequest) -> BoxFuture<HttpResponse> {
+        let response = self.respond(request).map_err(Into::into);
+        Box::pin(async move { response })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{unwrap_signed_message, EventStreamConnection, EventStreamScript};
+    use aws_smithy_eventstream::frame::{
+        DecodedFrame, Header, HeaderValue, Message, MessageFrameDecoder,
+    };
+    use aws_smithy_http::body::SdkBody;
+    use aws_smithy_runtime_api::client::orchestrator::Connection;
+    use bytes::{Bytes, BytesMut};
+    use http_body::Body;
+    use std::time::Duration;
+
+    fn encode(message: &Message) -> Bytes {
+        let mut data = Vec::new();
+        message.write_to(&mut data).unwrap();
+        data.into()
+    }
+
+    fn signed(inner: Option<&Message>) -> Message {
+        Message::new(inner.map(encode).unwrap_or_default())
+            .add_header(Header::new(
+                ":chunk-signature",
+                HeaderValue::ByteArray(Bytes::from_static(b"signature")),
+            ))
+            .add_header(Header::new(
+                ":date",
+                HeaderValue::Timestamp(aws_smithy_types::DateTime::from_secs(0)),
+            ))
+    }
+
+    fn header<'a>(message: &'a Message, name: &str) -> &'a HeaderValue {
+        message
+            .headers()
+            .iter()
+            .find(|header| header.name().as_str() == name)
+            .unwrap()
+            .value()
+    }
+
+    #[tokio::test]
+    async fn scripted_response_and_captured_request() {
+        let connection = EventStreamConnection::new(
+            EventStreamScript::new()
+                .event("Greeting", "text/plain", "hello")
+                .delay(Duration::from_millis(10))
+                .exception("BadRequest", "application/json", "{}")
+                .error("Throttled", "slow down"),
+        );
+
+        let audio = Message::new(&b"audio"[..]);
+        let (mut request_sender, request_body) = hyper::Body::channel();
+        let mut response = connection
+            .call(
+                http::Request::builder()
+                    .uri("https://example.com/stream")
+                    .header("authorization", "Signature=abc")
+                    .body(SdkBody::from(request_body))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let request_data = [encode(&signed(Some(&audio))), encode(&signed(None))].concat();
+        // split the frames across writes
+        request_sender
+            .send_data(Bytes::copy_from_slice(&request_data[..7]))
+            .await
+            .unwrap();
+        request_sender
+            .send_data(Bytes::copy_from_slice(&request_data[7..]))
+            .await
+            .unwrap();
+        drop(request_sender);
+
+        let mut data = BytesMut::new();
+        while let Some(chunk) = response.body_mut().data().await {
+            data.extend_from_slice(&chunk.unwrap());
+        }
+        let mut decoder = MessageFrameDecoder::new();
+        let mut messages = Vec::new();
+        while let DecodedFrame::Complete(message) = decoder.decode_frame(&mut data).unwrap() {
+            messages.push(message);
+        }
+        assert_eq!(3, messages.len());
+        assert_eq!(
+            "Greeting",
+            header(&messages[0], ":event-type")
+                .as_string()
+                .unwrap()
+                .as_str()
+        );
+        assert_eq!(b"hello", messages[0].payload().as_ref());
+        assert_eq!(
+            "BadRequest",
+            header(&messages[1], ":exception-type")
+                .as_string()
+                .unwrap()
+                .as_str()
+        );
+        assert_eq!(
+            "Throttled",
+            header(&messages[2], ":error-code")
+                .as_string()
+                .unwrap()
+                .as_str()
+        );
+
+        connection.wait_for_request_body().await;
+        assert_eq!(
+            "Signature=abc",
+            connection.request().headers()["authorization"]
+        );
+        assert_eq!(2, connection.messages().len());
+        assert_eq!(vec![audio], connection.unsigned_messages());
+        assert_eq!(None, connection.request_body_error());
+    }
+
+    #[tokio::test]
+    async fn disconnect() {
+        let connection = EventStreamConnection::new(
+            EventStreamScript::new()
+                .event("Greeting", "text/plain", "hello")
+                .disconnect()
+                .event("Ignored", "text/plain", "ignored"),
+        );
+        let mut response = connection
+            .call(http::Request::new(SdkBody::empty()))
+            .await
+            .unwrap();
+        assert!(response.body_mut().data().await.unwrap().is_ok());
+        assert!(response.body_mut().data().await.unwrap().is_err());
+
+        connection
+            .call(http::Request::new(SdkBody::empty()))
+            .await
+            .expect_err("single use");
+    }
+
+    #[test]
+    fn unwrap_signed() {
+        let inner = Message::new(&b"inner"[..]);
+        assert_eq!(
+            Some(inner.clone()),
+            unwrap_signed_message(&signed(Some(&inner))).unwrap()
+        );
+        assert_eq!(None, unwrap_signed_message(&signed(None)).unwrap());
+        assert_eq!(Some(inner.clone()), unwrap_signed_message(&inner).unwrap());
+    }
+}
@@ -0,0 +1,271 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! A [`Connection`] for WebAssembly components that sends requests through the WASI 0.2 HTTP
+//! interface (`wasi:http/outgoing-handler`).
+//!
+//! This is used together with [`WasiSleep`](aws_smithy_async::rt::sleep::WasiSleep) and
+//! [`WasiTimeSource`](aws_smithy_async::time::WasiTimeSource), which are enabled by the `rt-wasi`
+//! feature of `aws-smithy-async`.
+
+use aws_smithy_http::body::SdkBody;
+use aws_smithy_http::result::ConnectorError;
+use aws_smithy_runtime_api::client::orchestrator::{
+    BoxError, BoxFuture, Connection, HttpRequest, HttpResponse,
+};
+use bytes::{Bytes, BytesMut};
+use std::future::poll_fn;
+use std::time::Duration;
+use wasi::http::outgoing_handler;
+use wasi::http::types::{
+    ErrorCode, Fields, IncomingBody, IncomingResponse, Method, OutgoingBody, OutgoingRequest,
+    RequestOptions, Scheme,
+};
+use wasi::io::poll::Pollable;
+use wasi::io::streams::StreamError;
+
+// `blocking-write-and-flush` accepts at most 4096 bytes per call
+const WRITE_CHUNK_SIZE: usize = 4096;
+const READ_CHUNK_SIZE: u64 = 64 * 1024;
+
+/// [`Connection`] that sends requests with the host's `wasi:http/outgoing-handler`
+///
+/// The host is responsible for establishing connections and TLS. Timeouts configured here are
+/// passed to the host as request options; hosts that don't support them will reject the request.
+///
+/// Bodies are fully buffered in memory: the request body is collected before it's written to the
+/// host, and the response body is read to the end before the response is returned. This
+/// connection isn't suitable for payloads that don't fit in memory.
+///
+/// WASI doesn't provide a way to register a waker with the host, so while waiting for the
+/// response, the connection blocks in `wasi:io/poll`. Other tasks on the same executor don't run
+/// in the meantime.
+#[derive(Clone, Debug, Default)]
+pub struct WasiHttpConnection {
+    connect_timeout: Option<Duration>,
+    first_byte_timeout: Option<Duration>,
+    between_bytes_timeout: Option<Duration>,
+}
+
+impl WasiHttpConnection {
+    /// Creates a new `WasiHttpConnection` that uses the host's default timeouts
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the timeout for establishing a connection
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the timeout for receiving the first byte of the response
+    pub fn first_byte_timeout(mut self, timeout: Duration) -> Self {
+        self.first_byte_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the timeout between bytes of the response
+    pub fn between_bytes_timeout(mut self, timeout: Duration) -> Self {
+        self.between_bytes_timeout = Some(timeout);
+        self
+    }
+
+    fn request_options(&self) -> Result<Option<RequestOptions>, ConnectorError> {
+        if self.connect_timeout.is_none()
+            && self.first_byte_timeout.is_none()
+            && self.between_bytes_timeout.is_none()
+        {
+            return Ok(None);
+        }
+        let options = RequestOptions::new();
+        let unsupported = |name: &str| {
+            ConnectorError::user(format!("the host does not support setting the {}", name).into())
+        };
+        options
+            .set_connect_timeout(self.connect_timeout.map(nanos))
+            .map_err(|_| unsupported("connect timeout"))?;
+        options
+            .set_first_byte_timeout(self.first_byte_timeout.map(nanos))
+            .map_err(|_| unsupported("first byte timeout"))?;
+        options
+            .set_between_bytes_timeout(self.between_bytes_timeout.map(nanos))
+            .map_err(|_| unsupported("between bytes timeout"))?;
+        Ok(Some(options))
+    }
+
+    async fn send(self, request: HttpRequest) -> Result<HttpResponse, ConnectorError> {
+        let (parts, body) = request.into_parts();
+        let body = collect_body(body)
+            .await
+            .map_err(|err| ConnectorError::other(err, None))?;
+
+        let outgoing = outgoing_request(&parts)?;
+        let outgoing_body = outgoing
+            .body()
+            .map_err(|_| ConnectorError::other("request body was already taken".into(), None))?;
+        let future_response =
+            outgoing_handler::handle(outgoing, self.request_options()?).map_err(connector_error)?;
+        write_body(outgoing_body, &body)?;
+
+        let response = loop {
+            match future_response.get() {
+                Some(Ok(Ok(response))) => break response,
+                Some(Ok(Err(err))) => return Err(connector_error(err)),
+                Some(Err(_)) => {
+                    return Err(ConnectorError::other(
+                        "the response was already taken".into(),
+                        None,
+                    ))
+                }
+                None => block_until_ready(&future_response.subscribe()),
+            }
+        };
+        into_response(response)
+    }
+}
+
+impl Connection for WasiHttpConnection {
+    fn call(&self, request: HttpRequest) -> BoxFuture<HttpResponse> {
+        let connection = self.clone();
+        Box::pin(async move { connection.send(request).await.map_err(BoxError::from) })
+    }
+}
+
+fn nanos(duration: Duration) -> u64 {
+    u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX)
+}
+
+/// Blocks until `pollable` is ready
+///
+/// WASI doesn't provide a way to register a waker with the host, so rather than asking the
+/// executor to poll again in a loop, this waits in `wasi:io/poll` until the host is ready.
+fn block_until_ready(pollable: &Pollable) {
+    wasi::io::poll::poll(&[pollable]);
+}
+
+async fn collect_body(mut body: SdkBody) -> Result<Bytes, BoxError> {
+    let mut out = BytesMut::new();
+    while let Some(chunk) =
+        poll_fn(|cx| http_body::Body::poll_data(std::pin::Pin::new(&mut body), cx)).await
+    {
+        out.extend_from_slice(&chunk?);
+    }
+    Ok(out.freeze())
+}
+
+fn outgoing_request(parts: &http::request::Parts) -> Result<OutgoingRequest, ConnectorError> {
+    let headers: Vec<(String, Vec<u8>)> = parts
+        .headers
+        .iter()
+        .map(|(name, value)| (name.as_str().to_owned(), value.as_bytes().to_vec()))
+        .collect();
+    let headers = Fields::from_list(&headers).map_err(|err| ConnectorError::user(err.into()))?;
+    let request = OutgoingRequest::new(headers);
+
+    let invalid = |what: &str| ConnectorError::user(format!("invalid request {}", what).into());
+    request
+        .set_method(&method(&parts.method))
+        .map_err(|_| invalid("method"))?;
+    let uri = &parts.uri;
+    let scheme = match uri.scheme_str() {
+        Some("http") => Some(Scheme::Http),
+        Some("https") => Some(Scheme::Https),
+        Some(other) => Some(Scheme::Other(other.to_owned())),
+        None => None,
+    };
+    request
+        .set_scheme(scheme.as_ref())
+        .map_err(|_| invalid("scheme"))?;
+    request
+        .set_authority(uri.authority().map(|authority| authority.as_str()))
+        .map_err(|_| invalid("authority"))?;
+    request
+        .set_path_with_query(uri.path_and_query().map(|path| path.as_str()))
+        .map_err(|_| invalid("path"))?;
+    Ok(request)
+}
+
+fn method(method: &http::Method) -> Method {
+    match *method {
+        http::Method::GET => Method::Get,
+        http::Method::HEAD => Method::Head,
+        http::Method::POST => Method::Post,
+        http::Method::PUT => Method::Put,
+        http::Method::DELETE => Method::Delete,
+        http::Method::CONNECT => Method::Connect,
+        http::Method::OPTIONS => Method::Options,
+        http::Method::TRACE => Method::Trace,
+        http::Method::PATCH => Method::Patch,
+        ref other => Method::Other(other.as_str().to_owned()),
+    }
+}
+
+fn write_body(outgoing_body: OutgoingBody, body: &[u8]) -> Result<(), ConnectorError> {
+    {
+        let stream = outgoing_body
+            .write()
+            .map_err(|_| ConnectorError::other("request body was already written".into(), None))?;
+        for chunk in body.chunks(WRITE_CHUNK_SIZE) {
+            stream
+                .blocking_write_and_flush(chunk)
+                .map_err(|err| ConnectorError::io(err.into()))?;
+        }
+        // the stream must be dropped before the body is finished
+    }
+    OutgoingBody::finish(outgoing_body, None).map_err(connector_error)
+}
+
+fn into_response(response: IncomingResponse) -> Result<HttpResponse, ConnectorError> {
+    let mut builder = http::Response::builder().status(response.status());
+    for (name, value) in response.headers().entries() {
+        builder = builder.header(name, value);
+    }
+
+    let incoming_body = response
+        .consume()
+        .map_err(|_| ConnectorError::other("response body was already taken".into(), None))?;
+    let body = read_body(&incoming_body)?;
+    drop(IncomingBody::finish(incoming_body));
+
+    builder
+        .body(SdkBody::from(body))
+        .map_err(|err| ConnectorError::other(err.into(), None))
+}
+
+fn read_body(incoming_body: &IncomingBody) -> Result<Bytes, ConnectorError> {
+    let stream = incoming_body
+        .stream()
+        .map_err(|_| ConnectorError::other("response body was already read".into(), None))?;
+    let mut out = BytesMut::new();
+    loop {
+        match stream.read(READ_CHUNK_SIZE) {
+            Ok(chunk) if chunk.is_empty() => block_until_ready(&stream.subscribe()),
+            Ok(chunk) => out.extend_from_slice(&chunk),
+            Err(StreamError::Closed) => break,
+            Err(err) => return Err(ConnectorError::io(err.into())),
+        }
+    }
+    Ok(out.freeze())
+}
+
+fn connector_error(err: ErrorCode) -> ConnectorError {
+    match err {
+        ErrorCode::DnsTimeout
+        | ErrorCode::ConnectionTimeout
+        | ErrorCode::ConnectionReadTimeout
+        | ErrorCode::ConnectionWriteTimeout
+        | ErrorCode::HttpResponseTimeout => ConnectorError::timeout(err.into()),
+        ErrorCode::DnsError(_)
+        | ErrorCode::DestinationNotFound
+        | ErrorCode::DestinationUnavailable
+        | ErrorCode::DestinationIpUnroutable
+        | ErrorCode::ConnectionRefused
+        | ErrorCode::ConnectionTerminated
+        | ErrorCode::ConnectionLimitReached
+        | ErrorCode::HttpResponseIncomplete => ConnectorError::io(err.into()),
+        err => ConnectorError::other(err.into(), None),
+    }
+}
@@ -9,6 +9,9 @@
 use self::auth::orchestrate_auth;
 use crate::client::orchestrator::endpoints::orchestrate_endpoint;
 use crate::client::orchestrator::http::read_body;
+use crate::client::orchestrator::interceptors::response_cache::ServedFromCache;
+use crate::client::retries::classifier::store_attempt_classification;
+use crate::client::retries::hedging;
 use crate::client::timeout::{MaybeTimeout, ProvideMaybeTimeoutConfig, TimeoutKind};
 use aws_smithy_async::rt::sleep::AsyncSleep;
 use aws_smithy_http::body::SdkBody;
@@ -20,13 +23,16 @@ use aws_smithy_runtime_api::client::orchestrator::{
     BoxError, ConfigBagAccessors, HttpResponse, LoadedRequestBody, OrchestratorError,
 };
 use aws_smithy_runtime_api::client::request_attempts::RequestAttempts;
-use aws_smithy_runtime_api::client::retries::ShouldAttempt;
+use aws_smithy_runtime_api::client::retries::{RetryReason, ShouldAttempt};
 use aws_smithy_runtime_api::client::runtime_plugin::RuntimePlugins;
 use aws_smithy_types::config_bag::ConfigBag;
+use aws_smithy_types::endpoint::Endpoint;
+use aws_smithy_types::retry::RequestAttempt;
 use std::mem;
+use std::time::SystemTime;
 use tracing::{debug, debug_span, instrument, Instrument};
 
-mod auth;
+pub(crate) mod auth;
 /// Defines types that implement a trait for endpoint resolution
 pub mod endpoints;
 mod http;
@@ -94,17 +100,24 @@ pub async fn invoke_with_stop_point(
         return Err(SdkError::construction_failure(err));
     }
     let operation_timeout_config = cfg.maybe_timeout_config(TimeoutKind::Operation);
-    async {
+    let result = async {
         // If running the pre-execution interceptors failed, then we skip running the op and run the
         // final interceptors instead.
         if !ctx.is_failed() {
             try_op(&mut ctx, cfg, &interceptors, stop_point).await;
         }
         finally_op(&mut ctx, cfg, &interceptors).await;
-        Ok(ctx)
+        Ok(())
     }
     .maybe_timeout_with_config(operation_timeout_config)
-    .await
+    .await;
+    match result {
+        Ok(()) => Ok(ctx),
+        Err(err) if !ctx.attempt_history().is_empty() => {
+            Err(err.with_attempt_history(ctx.attempt_history().clone()))
+        }
+        Err(err) => Err(err),
+    }
 }
 
 /// Apply configuration is responsible for apply runtime plugins to the config bag, as well as running
@@ -196,6 +209,7 @@ async fn try_op(
         }
         // Track which attempt we're currently on.
         cfg.interceptor_state().put::<RequestAttempts>(i.into());
+        let attempt_start_time = now(cfg);
         let attempt_timeout_config = cfg.maybe_timeout_config(TimeoutKind::OperationAttempt);
         let maybe_timeout = async {
             try_attempt(ctx, cfg, interceptors, stop_point).await;
@@ -208,6 +222,8 @@ async fn try_op(
 
         // We continue when encountering a timeout error. The retry classifier will decide what to do with it.
         continue_on_err!([ctx] => maybe_timeout);
+        let retry_reason = store_attempt_classification(ctx, cfg);
+        record_attempt(ctx, cfg, attempt_start_time, retry_reason);
 
         let retry_strategy = cfg.retry_strategy();
 
@@ -231,6 +247,9 @@ async fn try_op(
                 let sleep_impl = halt_on_err!([ctx] => cfg.sleep_impl().ok_or(OrchestratorError::other(
                     "the retry strategy requested a delay before sending the next request, but no 'async sleep' implementation was set"
                 )));
+                if let Some(attempt) = ctx.attempt_history_mut().last_mut() {
+                    attempt.set_backoff(Some(delay));
+                }
                 sleep_impl.sleep(delay).await;
                 continue;
             }
@@ -238,6 +257,48 @@ async fn try_op(
     }
 }
 
+/// Response headers that carry the request ID, in the order they're checked.
+const REQUEST_ID_HEADERS: &[&str] = &["x-amzn-requestid", "x-amz-request-id", "x-request-id"];
+
+fn now(cfg: &ConfigBag) -> SystemTime {
+    cfg.request_time()
+        .map(|time_source| time_source.now())
+        .unwrap_or_else(SystemTime::now)
+}
+
+/// Adds the outcome of the attempt that just finished to the context's attempt history.
+///
+/// Attempts answered from the response cache didn't reach the service, so they aren't recorded.
+fn record_attempt(
+    ctx: &mut InterceptorContext,
+    cfg: &ConfigBag,
+    start_time: SystemTime,
+    retry_reason: Option<RetryReason>,
+) {
+    if cfg.load::<ServedFromCache>().is_some() {
+        return;
+    }
+    let duration = now(cfg).duration_since(start_time).unwrap_or_default();
+    let mut attempt = RequestAttempt::new(start_time, duration);
+    if let Some(endpoint) = cfg.get::<Endpoint>() {
+        attempt = attempt.with_endpoint(endpoint.url());
+    }
+    if let Some(response) = ctx.response() {
+        attempt = attempt.with_status(response.status().as_u16());
+        let request_id = REQUEST_ID_HEADERS
+            .iter()
+            .find_map(|name| response.headers().get(*name))
+            .and_then(|value| value.to_str().ok());
+        if let Some(request_id) = request_id {
+            attempt = attempt.with_request_id(request_id);
+        }
+    }
+    if let Some(retry_reason) = retry_reason {
+        attempt = attempt.with_retry_reason(retry_reason);
+    }
+    ctx.attempt_history_mut().push(attempt);
+}
+
 #[instrument(skip_all)]
 async fn try_attempt(
     ctx: &mut InterceptorContext,
@@ -266,7 +327,7 @@ async fn try_attempt(
     ctx.enter_transmit_phase();
     let call_result = halt_on_err!([ctx] => {
         let request = ctx.take_request().expect("set during serialization");
-        cfg.connection().call(request).await.map_err(|err| {
+        hedging::call(cfg, cfg.connection(), request).await.map_err(|err| {
             match err.downcast() {
                 Ok(connector_error) => OrchestratorError::connector(*connector_error),
                 Err(box_err) => OrchestratorError::other(box_err)
@@ -1077,4 +1138,92 @@ mod tests {
             .read_after_execution_called
             .load(Ordering::Relaxed));
     }
+
+    #[test]
+    fn attempts_are_recorded_and_attached_to_errors() {
+        use super::record_attempt;
+        use crate::client::orchestrator::interceptors::response_cache::ServedFromCache;
+        use crate::client::retries::classifier::{
+            classify_attempt, store_attempt_classification, HttpStatusCodeClassifier,
+        };
+        use aws_smithy_async::time::StaticTimeSource;
+        use aws_smithy_http::result::SdkError;
+        use aws_smithy_runtime_api::client::interceptors::InterceptorContext;
+        use aws_smithy_runtime_api::client::retries::{ErrorKind, RetryClassifiers, RetryReason};
+        use aws_smithy_types::endpoint::Endpoint;
+        use aws_smithy_types::error::display::DisplayErrorContext;
+        use std::time::{Duration, SystemTime};
+
+        let start_time = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+        let mut cfg = ConfigBag::base();
+        cfg.interceptor_state()
+            .set_request_time(StaticTimeSource::new(start_time));
+        cfg.interceptor_state().set_retry_classifiers(
+            RetryClassifiers::new().with_classifier(HttpStatusCodeClassifier::default()),
+        );
+        cfg.interceptor_state()
+            .put(Endpoint::builder().url("https://example.com").build());
+
+        let mut ctx = InterceptorContext::new(TypedBox::new(()).erase());
+        ctx.enter_serialization_phase();
+        let _ = ctx.take_input();
+        ctx.set_request(http::Request::new(SdkBody::empty()));
+        ctx.enter_before_transmit_phase();
+        ctx.enter_transmit_phase();
+        let _ = ctx.take_request();
+        ctx.set_response(
+            http::Response::builder()
+                .status(503)
+                .header("x-amzn-requestid", "request-1")
+                .body(SdkBody::empty())
+                .unwrap(),
+        );
+        ctx.set_output_or_error(Err(OrchestratorError::other("unavailable")));
+        let retry_reason = store_attempt_classification(&ctx, &mut cfg);
+        record_attempt(&mut ctx, &cfg, start_time, retry_reason);
+        ctx.attempt_history_mut()
+            .last_mut()
+            .unwrap()
+            .set_backoff(Some(Duration::from_millis(50)));
+
+        // The retry strategy reuses the classification rather than running the classifiers again
+        cfg.interceptor_state()
+            .set_retry_classifiers(RetryClassifiers::new());
+        let retry_reason = classify_attempt(&ctx, &cfg);
+        assert_eq!(
+            Some(RetryReason::Error(ErrorKind::TransientError)),
+            retry_reason
+        );
+        record_attempt(&mut ctx, &cfg, start_time, retry_reason);
+
+        // Responses from the cache never reached the service
+        cfg.interceptor_state().store_put(ServedFromCache);
+        record_attempt(&mut ctx, &cfg, start_time, None);
+
+        let attempts = ctx.attempt_history().attempts();
+        assert_eq!(2, attempts.len());
+        assert_eq!(start_time, attempts[0].start_time());
+        assert_eq!(Some("https://example.com"), attempts[0].endpoint());
+        assert_eq!(Some(503), attempts[0].status());
+        assert_eq!(Some("request-1"), attempts[0].request_id());
+        assert_eq!(
+            Some(&RetryReason::Error(ErrorKind::TransientError)),
+            attempts[0].retry_reason()
+        );
+        assert_eq!(Some(Duration::from_millis(50)), attempts[0].backoff());
+        assert_eq!(None, attempts[1].backoff());
+
+        let err: SdkError<_, _> = ctx.finalize().expect_err("failed");
+        assert_eq!(2, err.attempt_history().unwrap().len());
+        // Every attempt is summarized when the error is reported, along with the last one's cause
+        let message = DisplayErrorContext(&err).to_string();
+        assert!(
+            message.starts_with(
+                "response error (2 attempts: #1 HTTP 503 after 0ns (request ID: request-1), \
+                 retryable TransientError, backed off 50ms; #2 "
+            ),
+            "{message}"
+        );
+        assert!(message.contains("unavailable"), "{message}");
+    }
 }
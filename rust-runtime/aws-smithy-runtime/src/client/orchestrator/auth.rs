@@ -5,7 +5,7 @@
 
 use aws_smithy_runtime_api::client::auth::{AuthSchemeEndpointConfig, AuthSchemeId};
 use aws_smithy_runtime_api::client::interceptors::InterceptorContext;
-use aws_smithy_runtime_api::client::orchestrator::{BoxError, ConfigBagAccessors};
+use aws_smithy_runtime_api::client::orchestrator::{BoxError, ConfigBagAccessors, HttpRequest};
 use aws_smithy_types::config_bag::ConfigBag;
 use aws_smithy_types::endpoint::Endpoint;
 use aws_smithy_types::Document;
@@ -62,6 +62,17 @@ impl StdError for AuthOrchestrationError {}
 pub(super) async fn orchestrate_auth(
     ctx: &mut InterceptorContext,
     cfg: &ConfigBag,
+) -> Result<(), BoxError> {
+    let request = ctx.request_mut().expect("set during serialization");
+    sign_request(request, cfg).await
+}
+
+/// Signs `request` with the first of the operation's auth options that has an identity resolver.
+///
+/// This is also used to sign hedged requests again, so that each is signed at the time it's sent.
+pub(crate) async fn sign_request(
+    request: &mut HttpRequest,
+    cfg: &ConfigBag,
 ) -> Result<(), BoxError> {
     let params = cfg.auth_option_resolver_params();
     let auth_options = cfg.auth_option_resolver().resolve_auth_options(params)?;
@@ -85,7 +96,6 @@ pub(super) async fn orchestrate_auth(
                     extract_endpoint_auth_scheme_config(endpoint, scheme_id)?;
 
                 let identity = identity_resolver.resolve_identity(cfg).await?;
-                let request = ctx.request_mut().expect("set during serialization");
                 request_signer.sign_request(
                     request,
                     &identity,
@@ -3,6 +3,7 @@
  * SPDX-License-Identifier: Apache-2.0
  */
 
+pub mod response_cache;
 mod service_clock_skew;
 
-pub use service_clock_skew::{ServiceClockSkew, ServiceClockSkewInterceptor};
+pub use service_clock_skew::{ClockSkewCache, ServiceClockSkew, ServiceClockSkewInterceptor};
@@ -0,0 +1,997 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Caching of responses to read-only operations.
+//!
+//! The [`ResponseCacheInterceptor`] stores successful responses in a [`ResponseCacheStore`], keyed by
+//! the serialized request and the identity it's sent with. While a stored response is fresh, the
+//! request is neither signed nor sent, and the stored response is deserialized instead. Once it's
+//! stale, the request is sent with an `If-None-Match` header if the stored response had an `ETag`,
+//! and a `304 Not Modified` response is answered with the stored response.
+//!
+//! How long a response stays fresh is determined by the `max-age` directive of its `Cache-Control`
+//! header, falling back to the interceptor's default TTL, if any. Responses with `Cache-Control: no-store`
+//! are never stored, and neither are responses with neither a freshness lifetime nor an `ETag`.
+//!
+//! Only the responses of operations marked with [`ReadonlyOperation`] are cached, and only when
+//! the identity used to sign the request has a key, either from the interceptor's
+//! [identity key](ResponseCacheInterceptor::identity_key), an [`IdentityCacheKey`] in the config
+//! bag, or one of the defaults. Keys never contain secrets in plaintext. Requests with streaming
+//! bodies are never cached.
+//!
+//! Attempts answered from the cache have a [`ServedFromCache`] marker in their config bag.
+
+use aws_smithy_http::body::SdkBody;
+use aws_smithy_runtime_api::client::auth::{
+    AuthSchemeEndpointConfig, AuthSchemeId, HttpAuthScheme, HttpAuthSchemes, HttpRequestSigner,
+};
+use aws_smithy_runtime_api::client::identity::{Identity, IdentityResolver, IdentityResolvers};
+use aws_smithy_runtime_api::client::interceptors::{
+    AfterDeserializationInterceptorContextRef, BeforeDeserializationInterceptorContextMut,
+    BeforeTransmitInterceptorContextMut, BeforeTransmitInterceptorContextRef, BoxError,
+    Interceptor,
+};
+use aws_smithy_runtime_api::client::orchestrator::{
+    BoxFuture, ConfigBagAccessors, Connection, HttpRequest, HttpResponse,
+};
+use aws_smithy_runtime_api::client::retries::ReadonlyOperation;
+use aws_smithy_types::config_bag::{ConfigBag, Storable, StoreReplace};
+use bytes::Bytes;
+use http::header::{CACHE_CONTROL, ETAG, IF_NONE_MATCH};
+use http::{HeaderMap, HeaderValue, StatusCode};
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+mod store;
+
+pub use store::{InMemoryResponseCache, ResponseCacheStore};
+
+/// Identifies a request whose response can be cached.
+///
+/// Two requests have the same key if they're sent with the same identity, and have the same method,
+/// URI, target (for protocols that route on the `X-Amz-Target` header) and body.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct CacheKey {
+    identity: String,
+    method: String,
+    uri: String,
+    target: Option<HeaderValue>,
+    body: Bytes,
+}
+
+impl fmt::Debug for CacheKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CacheKey")
+            .field("identity", &"** redacted **")
+            .field("method", &self.method)
+            .field("uri", &self.uri)
+            .field("target", &self.target)
+            .field("body", &self.body)
+            .finish()
+    }
+}
+
+impl CacheKey {
+    fn from_request(request: &HttpRequest, identity: String) -> Option<Self> {
+        Some(Self {
+            identity,
+            method: request.method().to_string(),
+            uri: request.uri().to_string(),
+            target: request.headers().get("x-amz-target").cloned(),
+            body: Bytes::copy_from_slice(request.body().bytes()?),
+        })
+    }
+}
+
+/// A response stored in a [`ResponseCacheStore`].
+#[derive(Clone, Debug)]
+pub struct CachedResponse {
+    status: StatusCode,
+    headers: HeaderMap,
+    body: Bytes,
+    stored_at: SystemTime,
+    ttl: Duration,
+}
+
+impl CachedResponse {
+    /// The time at which this response was stored, or last revalidated.
+    pub fn stored_at(&self) -> SystemTime {
+        self.stored_at
+    }
+
+    /// How long after [`stored_at`](Self::stored_at) this response may be reused without revalidating it.
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// Returns true if this response may be reused at `now` without revalidating it.
+    pub fn is_fresh(&self, now: SystemTime) -> bool {
+        match now.duration_since(self.stored_at) {
+            Ok(age) => age < self.ttl,
+            // The clock went backwards; err on the side of revalidating
+            Err(_) => false,
+        }
+    }
+
+    fn etag(&self) -> Option<&HeaderValue> {
+        self.headers.get(ETAG)
+    }
+
+    fn to_response(&self) -> HttpResponse {
+        let mut response = http::Response::new(SdkBody::from(self.body.clone()));
+        *response.status_mut() = self.status;
+        *response.headers_mut() = self.headers.clone();
+        response
+    }
+}
+
+/// Marks an attempt whose response was served from a cache rather than received from the service.
+///
+/// Interceptors measuring the service, e.g. its clock skew, should ignore such responses.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ServedFromCache;
+
+impl Storable for ServedFromCache {
+    type Storer = StoreReplace<Self>;
+}
+
+/// The `Cache-Control` directives relevant to a client-side cache.
+#[derive(Debug, Default, PartialEq)]
+struct CacheControl {
+    no_store: bool,
+    no_cache: bool,
+    max_age: Option<Duration>,
+}
+
+impl CacheControl {
+    fn from_headers(headers: &HeaderMap) -> Self {
+        let mut cache_control = Self::default();
+        let directives = headers
+            .get_all(CACHE_CONTROL)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .map(|directive| directive.trim().to_ascii_lowercase());
+        for directive in directives {
+            match directive.split_once('=') {
+                Some(("max-age", seconds)) => {
+                    cache_control.max_age = seconds
+                        .trim_matches('"')
+                        .parse()
+                        .ok()
+                        .map(Duration::from_secs)
+                }
+                None if directive == "no-store" => cache_control.no_store = true,
+                None if directive == "no-cache" => cache_control.no_cache = true,
+                _ => {}
+            }
+        }
+        cache_control
+    }
+}
+
+/// What the cache decided for the current attempt, when its request was signed.
+///
+/// It's carried to the connection in the request's extensions, and kept in the config bag for the
+/// rest of the attempt.
+#[derive(Clone, Debug)]
+enum CacheState {
+    /// Nothing usable is stored; store the response if possible.
+    Miss(CacheKey),
+    /// A stale response with an `ETag` is stored and the request is conditional.
+    Revalidating(CacheKey, CachedResponse),
+    /// A fresh response is stored and will be used instead of signing and sending the request.
+    Hit(CachedResponse),
+}
+
+type IdentityKeyFn = dyn Fn(&Identity) -> Option<String> + Send + Sync;
+
+/// Turns identities of a type the Smithy runtime doesn't define into part of a cache key.
+///
+/// Auth schemes with their own identity types put this in the config bag, so that responses to
+/// requests signed with those identities are cached without configuring the interceptor's
+/// [identity key](ResponseCacheInterceptor::identity_key). The key mustn't contain secrets in
+/// plaintext, since it's kept alongside the cached response.
+#[derive(Clone)]
+pub struct IdentityCacheKey(Arc<IdentityKeyFn>);
+
+impl IdentityCacheKey {
+    /// Creates an `IdentityCacheKey` from a function returning the key of the identities it knows.
+    pub fn new(identity_key: impl Fn(&Identity) -> Option<String> + Send + Sync + 'static) -> Self {
+        Self(Arc::new(identity_key))
+    }
+
+    /// Returns the key of `identity`, or `None` if it isn't an identity this knows.
+    pub fn key(&self, identity: &Identity) -> Option<String> {
+        (self.0)(identity)
+    }
+}
+
+impl fmt::Debug for IdentityCacheKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("IdentityCacheKey").finish_non_exhaustive()
+    }
+}
+
+impl Storable for IdentityCacheKey {
+    type Storer = StoreReplace<Self>;
+}
+
+/// Returns a key for the identity types defined by the Smithy runtime.
+///
+/// Secrets are hashed so that they aren't kept in plaintext for as long as the cache lives.
+fn default_identity_key(identity: &Identity) -> Option<String> {
+    #[cfg(feature = "http-auth")]
+    {
+        use aws_smithy_runtime_api::client::identity::http::{Login, Token};
+        use sha2::{Digest, Sha256};
+        let hash = |secret: &str| hex::encode(Sha256::digest(secret.as_bytes()));
+        if let Some(token) = identity.data::<Token>() {
+            return Some(format!("token:{}", hash(token.token())));
+        }
+        if let Some(login) = identity.data::<Login>() {
+            return Some(format!("login:{}:{}", login.user(), hash(login.password())));
+        }
+    }
+    #[cfg(feature = "anonymous-auth")]
+    {
+        use crate::client::identity::anonymous::AnonymousIdentity;
+        if identity.data::<AnonymousIdentity>().is_some() {
+            return Some("anonymous".into());
+        }
+    }
+    let _ = identity;
+    None
+}
+
+/// An interceptor that caches the responses of read-only operations.
+///
+/// See the [module docs](self) for how responses are cached and reused.
+#[derive(Clone)]
+pub struct ResponseCacheInterceptor {
+    store: Arc<dyn ResponseCacheStore>,
+    default_ttl: Option<Duration>,
+    identity_key: Option<Arc<IdentityKeyFn>>,
+}
+
+impl fmt::Debug for ResponseCacheInterceptor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ResponseCacheInterceptor")
+            .field("store", &self.store)
+            .field("default_ttl", &self.default_ttl)
+            .finish()
+    }
+}
+
+impl ResponseCacheInterceptor {
+    /// Creates an interceptor that caches responses in `store`.
+    pub fn new(store: impl ResponseCacheStore + 'static) -> Self {
+        Self {
+            store: Arc::new(store),
+            default_ttl: None,
+            identity_key: None,
+        }
+    }
+
+    /// Creates an interceptor that caches up to `capacity` responses in memory, evicting the least
+    /// recently used ones first.
+    pub fn in_memory(capacity: usize) -> Self {
+        Self::new(InMemoryResponseCache::new(capacity))
+    }
+
+    /// Sets how long responses without a `Cache-Control: max-age` directive stay fresh.
+    ///
+    /// Without a default TTL, such responses are only stored if they have an `ETag`, and are
+    /// revalidated every time they're reused.
+    pub fn default_ttl(mut self, default_ttl: Duration) -> Self {
+        self.default_ttl = Some(default_ttl);
+        self
+    }
+
+    /// Sets how the identity a request is signed with is turned into part of its cache key.
+    ///
+    /// Responses are only shared between requests whose identities have the same key, and the
+    /// responses to requests whose identity has no key aren't cached at all. By default, the
+    /// [`IdentityCacheKey`] in the config bag is used, falling back to keys for the HTTP auth and
+    /// anonymous identities. For example, to share responses between all credentials with the
+    /// same access key ID:
+    ///
+    /// ```ignore
+    /// let interceptor = ResponseCacheInterceptor::in_memory(100).identity_key(|identity| {
+    ///     let credentials = identity.data::<Credentials>()?;
+    ///     Some(credentials.access_key_id().to_string())
+    /// });
+    /// ```
+    ///
+    /// The key mustn't contain secrets in plaintext, since it's kept alongside the cached response.
+    pub fn identity_key(
+        mut self,
+        identity_key: impl Fn(&Identity) -> Option<String> + Send + Sync + 'static,
+    ) -> Self {
+        self.identity_key = Some(Arc::new(identity_key));
+        self
+    }
+
+    fn key_for(&self, identity: &Identity, cfg: &ConfigBag) -> Option<String> {
+        if let Some(identity_key) = &self.identity_key {
+            return identity_key(identity);
+        }
+        cfg.load::<IdentityCacheKey>()
+            .and_then(|identity_key| identity_key.key(identity))
+            .or_else(|| default_identity_key(identity))
+    }
+
+    /// Returns how long a response with the given headers stays fresh, or `None` if it mustn't be stored.
+    fn ttl(&self, headers: &HeaderMap) -> Option<Duration> {
+        let cache_control = CacheControl::from_headers(headers);
+        if cache_control.no_store {
+            return None;
+        }
+        if cache_control.no_cache {
+            return headers.contains_key(ETAG).then_some(Duration::ZERO);
+        }
+        cache_control
+            .max_age
+            .or(self.default_ttl)
+            .or_else(|| headers.contains_key(ETAG).then_some(Duration::ZERO))
+    }
+
+    /// Looks up the response to `request`, making it conditional if a stale response is stored.
+    fn lookup(
+        &self,
+        request: &mut HttpRequest,
+        identity: &Identity,
+        cfg: &ConfigBag,
+    ) -> Option<CacheState> {
+        let identity = match self.key_for(identity, cfg) {
+            Some(identity) => identity,
+            None => {
+                tracing::trace!("not caching the response to a request with an unknown identity");
+                return None;
+            }
+        };
+        let key = match CacheKey::from_request(request, identity) {
+            Some(key) => key,
+            None => {
+                tracing::trace!("not caching the response to a request with a streaming body");
+                return None;
+            }
+        };
+
+        Some(match self.store.get(&key) {
+            Some(cached) if cached.is_fresh(now(cfg)) => {
+                tracing::debug!("using cached response");
+                CacheState::Hit(cached)
+            }
+            Some(cached) if cached.etag().is_some() => {
+                tracing::debug!("revalidating cached response");
+                let etag = cached.etag().expect("checked above").clone();
+                request.headers_mut().insert(IF_NONE_MATCH, etag);
+                CacheState::Revalidating(key, cached)
+            }
+            _ => CacheState::Miss(key),
+        })
+    }
+}
+
+fn now(cfg: &ConfigBag) -> SystemTime {
+    cfg.request_time()
+        .map(|time_source| time_source.now())
+        .unwrap_or_else(SystemTime::now)
+}
+
+/// Wraps an auth scheme so that requests with a fresh cached response aren't signed.
+#[derive(Debug)]
+struct CachingAuthScheme {
+    scheme_id: AuthSchemeId,
+    schemes: HttpAuthSchemes,
+    cache: ResponseCacheInterceptor,
+}
+
+impl CachingAuthScheme {
+    fn inner(&self) -> &dyn HttpAuthScheme {
+        self.schemes
+            .scheme(self.scheme_id)
+            .expect("only configured auth schemes are wrapped")
+    }
+}
+
+impl HttpAuthScheme for CachingAuthScheme {
+    fn scheme_id(&self) -> AuthSchemeId {
+        self.scheme_id
+    }
+
+    fn identity_resolver<'a>(
+        &self,
+        identity_resolvers: &'a IdentityResolvers,
+    ) -> Option<&'a dyn IdentityResolver> {
+        self.inner().identity_resolver(identity_resolvers)
+    }
+
+    fn request_signer(&self) -> &dyn HttpRequestSigner {
+        self
+    }
+}
+
+impl HttpRequestSigner for CachingAuthScheme {
+    fn sign_request(
+        &self,
+        request: &mut HttpRequest,
+        identity: &Identity,
+        auth_scheme_endpoint_config: AuthSchemeEndpointConfig<'_>,
+        config_bag: &ConfigBag,
+    ) -> Result<(), BoxError> {
+        request.extensions_mut().remove::<CacheState>();
+        match self.cache.lookup(request, identity, config_bag) {
+            // The cached response replaces the request, so there's no need to sign it
+            Some(hit @ CacheState::Hit(_)) => {
+                request.extensions_mut().insert(hit);
+                return Ok(());
+            }
+            Some(state) => {
+                request.extensions_mut().insert(state);
+            }
+            None => {}
+        }
+        self.inner().request_signer().sign_request(
+            request,
+            identity,
+            auth_scheme_endpoint_config,
+            config_bag,
+        )
+    }
+}
+
+/// Wraps a connection so that requests with a fresh cached response aren't sent.
+#[derive(Debug)]
+struct CachingConnection {
+    inner: Arc<dyn Connection>,
+}
+
+impl Connection for CachingConnection {
+    fn call(&self, request: HttpRequest) -> BoxFuture<HttpResponse> {
+        match request.extensions().get::<CacheState>() {
+            Some(CacheState::Hit(cached)) => {
+                let response = cached.to_response();
+                Box::pin(async move { Ok(response) })
+            }
+            _ => self.inner.call(request),
+        }
+    }
+}
+
+impl Interceptor for ResponseCacheInterceptor {
+    fn modify_before_retry_loop(
+        &self,
+        _context: &mut BeforeTransmitInterceptorContextMut<'_>,
+        cfg: &mut ConfigBag,
+    ) -> Result<(), BoxError> {
+        if cfg.load::<ReadonlyOperation>().is_none() {
+            tracing::trace!("not caching the response to an operation that isn't read-only");
+            return Ok(());
+        }
+
+        let auth_options = cfg
+            .auth_option_resolver()
+            .resolve_auth_options(cfg.auth_option_resolver_params())?
+            .to_vec();
+        let schemes = cfg.http_auth_schemes().clone();
+        let mut caching_schemes = HttpAuthSchemes::builder();
+        for scheme_id in auth_options {
+            if schemes.scheme(scheme_id).is_some() {
+                caching_schemes = caching_schemes.auth_scheme(
+                    scheme_id,
+                    CachingAuthScheme {
+                        scheme_id,
+                        schemes: schemes.clone(),
+                        cache: self.clone(),
+                    },
+                );
+            }
+        }
+        let connection = CachingConnection {
+            inner: cfg.shared_connection(),
+        };
+
+        let state = cfg.interceptor_state();
+        state.set_http_auth_schemes(caching_schemes.build());
+        state.set_connection(connection);
+        Ok(())
+    }
+
+    fn read_before_transmit(
+        &self,
+        context: &BeforeTransmitInterceptorContextRef<'_>,
+        cfg: &mut ConfigBag,
+    ) -> Result<(), BoxError> {
+        let state = context.request().extensions().get::<CacheState>().cloned();
+        let hit = matches!(state, Some(CacheState::Hit(_)));
+        let layer = cfg.interceptor_state();
+        match state {
+            Some(state) => layer.put(state),
+            None => layer.unset::<CacheState>(),
+        };
+        layer.store_or_unset(hit.then_some(ServedFromCache));
+        Ok(())
+    }
+
+    fn modify_before_deserialization(
+        &self,
+        context: &mut BeforeDeserializationInterceptorContextMut<'_>,
+        cfg: &mut ConfigBag,
+    ) -> Result<(), BoxError> {
+        let (key, cached) = match cfg.get::<CacheState>() {
+            Some(CacheState::Revalidating(key, cached))
+                if context.response().status() == StatusCode::NOT_MODIFIED =>
+            {
+                (key.clone(), cached.clone())
+            }
+            _ => return Ok(()),
+        };
+
+        tracing::debug!("cached response is still valid");
+        // A `304` may update the freshness of the stored response
+        let ttl = self.ttl(context.response().headers()).unwrap_or(cached.ttl);
+        let refreshed = CachedResponse {
+            stored_at: now(cfg),
+            ttl,
+            ..cached
+        };
+        *context.response_mut() = refreshed.to_response();
+        self.store.put(key, refreshed.clone());
+        cfg.interceptor_state()
+            .put(CacheState::Hit(refreshed))
+            .store_put(ServedFromCache);
+        Ok(())
+    }
+
+    fn read_after_deserialization(
+        &self,
+        context: &AfterDeserializationInterceptorContextRef<'_>,
+        cfg: &mut ConfigBag,
+    ) -> Result<(), BoxError> {
+        let key = match cfg.get::<CacheState>() {
+            Some(CacheState::Miss(key)) | Some(CacheState::Revalidating(key, _)) => key.clone(),
+            _ => return Ok(()),
+        };
+        let response = context.response();
+        if context.output_or_error().is_err() || !response.status().is_success() {
+            return Ok(());
+        }
+        let (ttl, body) = match (self.ttl(response.headers()), response.body().bytes()) {
+            (Some(ttl), Some(body)) => (ttl, body),
+            _ => return Ok(()),
+        };
+
+        tracing::debug!(ttl = ?ttl, "caching response");
+        self.store.put(
+            key,
+            CachedResponse {
+                status: response.status(),
+                headers: response.headers().clone(),
+                body: Bytes::copy_from_slice(body),
+                stored_at: now(cfg),
+                ttl,
+            },
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aws_smithy_runtime_api::client::auth::option_resolver::StaticAuthOptionResolver;
+    use aws_smithy_runtime_api::client::auth::AuthOptionResolverParams;
+    use aws_smithy_runtime_api::client::interceptors::InterceptorContext;
+    use aws_smithy_runtime_api::client::orchestrator::OrchestratorError;
+    use aws_smithy_types::config_bag::Layer;
+    use aws_smithy_types::type_erasure::TypedBox;
+    use http::header::AUTHORIZATION;
+    use std::sync::Mutex;
+
+    const TEST_SCHEME_ID: AuthSchemeId = AuthSchemeId::new("test-scheme");
+
+    #[derive(Debug)]
+    struct TestAuthScheme;
+
+    impl HttpAuthScheme for TestAuthScheme {
+        fn scheme_id(&self) -> AuthSchemeId {
+            TEST_SCHEME_ID
+        }
+
+        fn identity_resolver<'a>(
+            &self,
+            identity_resolvers: &'a IdentityResolvers,
+        ) -> Option<&'a dyn IdentityResolver> {
+            identity_resolvers.identity_resolver(TEST_SCHEME_ID)
+        }
+
+        fn request_signer(&self) -> &dyn HttpRequestSigner {
+            self
+        }
+    }
+
+    impl HttpRequestSigner for TestAuthScheme {
+        fn sign_request(
+            &self,
+            request: &mut HttpRequest,
+            identity: &Identity,
+            _auth_scheme_endpoint_config: AuthSchemeEndpointConfig<'_>,
+            _config_bag: &ConfigBag,
+        ) -> Result<(), BoxError> {
+            let user = *identity.data::<&'static str>().unwrap();
+            request
+                .headers_mut()
+                .insert(AUTHORIZATION, HeaderValue::from_static(user));
+            Ok(())
+        }
+    }
+
+    /// Answers a request with a canned response, panicking if there's none.
+    #[derive(Clone, Debug, Default)]
+    struct CannedConnection {
+        response: Arc<Mutex<Option<HttpResponse>>>,
+    }
+
+    impl Connection for CannedConnection {
+        fn call(&self, _request: HttpRequest) -> BoxFuture<HttpResponse> {
+            let response = self
+                .response
+                .lock()
+                .unwrap()
+                .take()
+                .expect("request shouldn't be sent");
+            Box::pin(async move { Ok(response) })
+        }
+    }
+
+    fn interceptor() -> ResponseCacheInterceptor {
+        ResponseCacheInterceptor::in_memory(10)
+            .identity_key(|identity| identity.data::<&'static str>().map(|user| user.to_string()))
+    }
+
+    struct Attempt {
+        ctx: InterceptorContext,
+        cfg: ConfigBag,
+        connection: CannedConnection,
+    }
+
+    impl Attempt {
+        fn new(interceptor: &ResponseCacheInterceptor) -> Self {
+            Self::with_identity(interceptor, "alice", true)
+        }
+
+        fn with_identity(
+            interceptor: &ResponseCacheInterceptor,
+            user: &'static str,
+            readonly: bool,
+        ) -> Self {
+            let mut ctx = InterceptorContext::new(TypedBox::new("input").erase());
+            ctx.enter_serialization_phase();
+            let _ = ctx.take_input();
+            ctx.set_request(
+                http::Request::builder()
+                    .uri("https://example.com/parameters")
+                    .body(SdkBody::from("{\"Name\":\"param\"}"))
+                    .unwrap(),
+            );
+            ctx.enter_before_transmit_phase();
+
+            let connection = CannedConnection::default();
+            let mut layer = Layer::new("test");
+            layer.set_auth_option_resolver_params(AuthOptionResolverParams::new("doesntmatter"));
+            layer.set_auth_option_resolver(StaticAuthOptionResolver::new(vec![TEST_SCHEME_ID]));
+            layer.set_http_auth_schemes(
+                HttpAuthSchemes::builder()
+                    .auth_scheme(TEST_SCHEME_ID, TestAuthScheme)
+                    .build(),
+            );
+            layer.set_connection(connection.clone());
+            if readonly {
+                layer.store_put(ReadonlyOperation::new());
+            }
+            let mut cfg = ConfigBag::base();
+            cfg.push_layer(layer);
+
+            interceptor
+                .modify_before_retry_loop(&mut (&mut ctx).into(), &mut cfg)
+                .unwrap();
+            cfg.http_auth_schemes()
+                .scheme(TEST_SCHEME_ID)
+                .unwrap()
+                .request_signer()
+                .sign_request(
+                    ctx.request_mut().unwrap(),
+                    &Identity::new(user, None),
+                    AuthSchemeEndpointConfig::empty(),
+                    &cfg,
+                )
+                .unwrap();
+            interceptor
+                .read_before_transmit(&(&ctx).into(), &mut cfg)
+                .unwrap();
+            Self {
+                ctx,
+                cfg,
+                connection,
+            }
+        }
+
+        fn sent_request(&self) -> &HttpRequest {
+            self.ctx.request().unwrap()
+        }
+
+        fn is_signed(&self) -> bool {
+            self.sent_request().headers().contains_key(AUTHORIZATION)
+        }
+
+        fn is_served_from_cache(&self) -> bool {
+            self.cfg.load::<ServedFromCache>().is_some()
+        }
+
+        async fn respond(
+            self,
+            interceptor: &ResponseCacheInterceptor,
+            response: HttpResponse,
+        ) -> (StatusCode, Option<Bytes>) {
+            *self.connection.response.lock().unwrap() = Some(response);
+            self.complete(interceptor).await
+        }
+
+        /// Completes the attempt, without a response to send if the request is sent.
+        async fn complete(
+            mut self,
+            interceptor: &ResponseCacheInterceptor,
+        ) -> (StatusCode, Option<Bytes>) {
+            self.ctx.enter_transmit_phase();
+            let request = self.ctx.take_request().unwrap();
+            let response = self.cfg.connection().call(request).await.unwrap();
+            self.ctx.set_response(response);
+            self.ctx.enter_before_deserialization_phase();
+            interceptor
+                .modify_before_deserialization(&mut (&mut self.ctx).into(), &mut self.cfg)
+                .unwrap();
+            self.ctx.enter_deserialization_phase();
+            let output = if self.ctx.response().unwrap().status().is_success() {
+                Ok(TypedBox::new("output").erase())
+            } else {
+                Err(OrchestratorError::other("error"))
+            };
+            self.ctx.set_output_or_error(output);
+            self.ctx.enter_after_deserialization_phase();
+            interceptor
+                .read_after_deserialization(&(&self.ctx).into(), &mut self.cfg)
+                .unwrap();
+            let response = self.ctx.response().unwrap();
+            (
+                response.status(),
+                response.body().bytes().map(Bytes::copy_from_slice),
+            )
+        }
+    }
+
+    fn response(
+        status: u16,
+        headers: &[(&'static str, &'static str)],
+        body: &'static str,
+    ) -> HttpResponse {
+        let mut builder = http::Response::builder().status(status);
+        for (name, value) in headers {
+            builder = builder.header(*name, *value);
+        }
+        builder.body(SdkBody::from(body)).unwrap()
+    }
+
+    #[tokio::test]
+    async fn fresh_responses_are_reused_without_signing_or_sending_the_request() {
+        let interceptor = interceptor();
+        let first = Attempt::new(&interceptor);
+        assert!(first.is_signed());
+        assert!(!first.is_served_from_cache());
+        first
+            .respond(
+                &interceptor,
+                response(200, &[("cache-control", "max-age=60")], "value"),
+            )
+            .await;
+
+        let second = Attempt::new(&interceptor);
+        assert!(!second.is_signed());
+        assert!(second.is_served_from_cache());
+        // The canned connection has no response, so the request mustn't be sent
+        let reused = second.complete(&interceptor).await;
+        assert_eq!(reused, (StatusCode::OK, Some(Bytes::from("value"))));
+    }
+
+    #[tokio::test]
+    async fn responses_are_not_shared_between_identities() {
+        let interceptor = interceptor();
+        Attempt::new(&interceptor)
+            .respond(
+                &interceptor,
+                response(200, &[("cache-control", "max-age=60")], "alice's value"),
+            )
+            .await;
+
+        let bob = Attempt::with_identity(&interceptor, "bob", true);
+        assert!(bob.is_signed());
+        assert!(!bob.is_served_from_cache());
+
+        // Identities without a key are never cached
+        let interceptor = interceptor.identity_key(|_| None);
+        Attempt::new(&interceptor)
+            .respond(
+                &interceptor,
+                response(200, &[("cache-control", "max-age=60")], "value"),
+            )
+            .await;
+        assert!(!Attempt::new(&interceptor).is_served_from_cache());
+    }
+
+    #[tokio::test]
+    async fn only_readonly_operations_are_cached() {
+        let interceptor = interceptor();
+        Attempt::with_identity(&interceptor, "alice", false)
+            .respond(
+                &interceptor,
+                response(200, &[("cache-control", "max-age=60")], "value"),
+            )
+            .await;
+        assert!(!Attempt::new(&interceptor).is_served_from_cache());
+
+        Attempt::new(&interceptor)
+            .respond(
+                &interceptor,
+                response(200, &[("cache-control", "max-age=60")], "value"),
+            )
+            .await;
+        let mutation = Attempt::with_identity(&interceptor, "alice", false);
+        assert!(mutation.is_signed());
+        assert!(!mutation.is_served_from_cache());
+    }
+
+    #[tokio::test]
+    async fn stale_responses_are_revalidated_with_their_etag() {
+        let interceptor = interceptor();
+        Attempt::new(&interceptor)
+            .respond(
+                &interceptor,
+                response(
+                    200,
+                    &[("cache-control", "no-cache"), ("etag", "\"v1\"")],
+                    "value",
+                ),
+            )
+            .await;
+
+        let second = Attempt::new(&interceptor);
+        assert!(second.is_signed());
+        assert_eq!(second.sent_request().headers()[IF_NONE_MATCH], "\"v1\"");
+        let revalidated = second.respond(&interceptor, response(304, &[], "")).await;
+        assert_eq!(revalidated, (StatusCode::OK, Some(Bytes::from("value"))));
+
+        // A changed response replaces the stored one
+        let third = Attempt::new(&interceptor);
+        third
+            .respond(
+                &interceptor,
+                response(200, &[("etag", "\"v2\"")], "new value"),
+            )
+            .await;
+        let fourth = Attempt::new(&interceptor);
+        assert_eq!(fourth.sent_request().headers()[IF_NONE_MATCH], "\"v2\"");
+    }
+
+    #[tokio::test]
+    async fn uncacheable_responses_are_not_stored() {
+        let interceptor = interceptor().default_ttl(Duration::from_secs(60));
+        Attempt::new(&interceptor)
+            .respond(
+                &interceptor,
+                response(200, &[("cache-control", "no-store")], ""),
+            )
+            .await;
+        Attempt::new(&interceptor)
+            .respond(&interceptor, response(404, &[], ""))
+            .await;
+        assert!(!Attempt::new(&interceptor).is_served_from_cache());
+
+        // Without `Cache-Control` the default TTL applies
+        Attempt::new(&interceptor)
+            .respond(&interceptor, response(200, &[], "value"))
+            .await;
+        assert!(Attempt::new(&interceptor).is_served_from_cache());
+    }
+
+    #[tokio::test]
+    async fn responses_without_freshness_or_etag_are_not_stored() {
+        let interceptor = interceptor();
+        Attempt::new(&interceptor)
+            .respond(&interceptor, response(200, &[], "value"))
+            .await;
+        let attempt = Attempt::new(&interceptor);
+        assert!(!attempt.is_served_from_cache());
+        assert!(attempt
+            .sent_request()
+            .headers()
+            .get(IF_NONE_MATCH)
+            .is_none());
+    }
+
+    #[cfg(feature = "http-auth")]
+    #[test]
+    fn default_identity_keys_hash_secrets() {
+        use aws_smithy_runtime_api::client::identity::http::{Login, Token};
+        let interceptor = ResponseCacheInterceptor::in_memory(10);
+        let cfg = ConfigBag::base();
+        let key = |identity| interceptor.key_for(&identity, &cfg).unwrap();
+
+        let token = key(Identity::new(Token::new("secret-token", None), None));
+        assert!(!token.contains("secret-token"), "{}", token);
+        assert_eq!(
+            token,
+            key(Identity::new(Token::new("secret-token", None), None))
+        );
+        assert_ne!(
+            token,
+            key(Identity::new(Token::new("other-token", None), None))
+        );
+
+        let login = key(Identity::new(Login::new("alice", "hunter2", None), None));
+        assert!(!login.contains("hunter2"), "{}", login);
+        assert_ne!(
+            login,
+            key(Identity::new(Login::new("alice", "hunter3", None), None))
+        );
+    }
+
+    #[test]
+    fn identity_cache_key_in_the_config_bag_is_used() {
+        let mut layer = Layer::new("test");
+        layer.store_put(IdentityCacheKey::new(|identity| {
+            identity
+                .data::<&'static str>()
+                .map(|user| format!("user:{}", user))
+        }));
+        let mut cfg = ConfigBag::base();
+        cfg.push_layer(layer);
+        let alice = Identity::new("alice", None);
+
+        let interceptor = ResponseCacheInterceptor::in_memory(10);
+        assert_eq!(
+            Some("user:alice".to_string()),
+            interceptor.key_for(&alice, &cfg)
+        );
+        assert_eq!(None, interceptor.key_for(&alice, &ConfigBag::base()));
+
+        // The interceptor's own identity key takes precedence
+        let interceptor = interceptor.identity_key(|_| Some("everyone".into()));
+        assert_eq!(
+            Some("everyone".to_string()),
+            interceptor.key_for(&alice, &cfg)
+        );
+    }
+
+    #[test]
+    fn parse_cache_control() {
+        let mut headers = HeaderMap::new();
+        headers.append(
+            CACHE_CONTROL,
+            HeaderValue::from_static("public, Max-Age=30"),
+        );
+        headers.append(CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+        assert_eq!(
+            CacheControl::from_headers(&headers),
+            CacheControl {
+                no_store: false,
+                no_cache: true,
+                max_age: Some(Duration::from_secs(30)),
+            }
+        );
+    }
+}
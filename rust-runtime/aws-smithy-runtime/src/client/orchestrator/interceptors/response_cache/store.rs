@@ -0,0 +1,131 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use super::{CacheKey, CachedResponse};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Debug;
+use std::sync::Mutex;
+
+/// Storage for the responses cached by a [`ResponseCacheInterceptor`](super::ResponseCacheInterceptor).
+///
+/// Implementations are free to evict responses at any time, e.g. to bound their size.
+pub trait ResponseCacheStore: Send + Sync + Debug {
+    /// Returns the response stored for `key`, if any.
+    fn get(&self, key: &CacheKey) -> Option<CachedResponse>;
+
+    /// Stores `response` for `key`, replacing any previously stored response.
+    fn put(&self, key: CacheKey, response: CachedResponse);
+}
+
+/// A [`ResponseCacheStore`] that keeps a bounded number of responses in memory, evicting the
+/// least recently used ones first.
+#[derive(Debug)]
+pub struct InMemoryResponseCache {
+    capacity: usize,
+    inner: Mutex<Lru>,
+}
+
+#[derive(Debug, Default)]
+struct Lru {
+    /// Incremented on every access, to order entries by recency.
+    clock: u64,
+    entries: HashMap<CacheKey, (CachedResponse, u64)>,
+    /// Keys by the time they were last accessed.
+    recency: BTreeMap<u64, CacheKey>,
+}
+
+impl Lru {
+    fn touch(&mut self, key: &CacheKey) -> Option<&CachedResponse> {
+        self.clock += 1;
+        let (response, last_used) = self.entries.get_mut(key)?;
+        self.recency.remove(last_used);
+        *last_used = self.clock;
+        self.recency.insert(self.clock, key.clone());
+        Some(response)
+    }
+}
+
+impl InMemoryResponseCache {
+    /// Creates a cache holding up to `capacity` responses.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            inner: Default::default(),
+        }
+    }
+}
+
+impl ResponseCacheStore for InMemoryResponseCache {
+    fn get(&self, key: &CacheKey) -> Option<CachedResponse> {
+        self.inner.lock().unwrap().touch(key).cloned()
+    }
+
+    fn put(&self, key: CacheKey, response: CachedResponse) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut lru = self.inner.lock().unwrap();
+        if lru.entries.contains_key(&key) {
+            lru.entries.get_mut(&key).expect("checked above").0 = response;
+            lru.touch(&key);
+            return;
+        }
+        if lru.entries.len() >= self.capacity {
+            if let Some((_, evicted)) = lru.recency.pop_first() {
+                lru.entries.remove(&evicted);
+            }
+        }
+        lru.clock += 1;
+        let clock = lru.clock;
+        lru.recency.insert(clock, key.clone());
+        lru.entries.insert(key, (response, clock));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aws_smithy_http::body::SdkBody;
+    use bytes::Bytes;
+    use std::time::{Duration, SystemTime};
+
+    fn key(uri: &str) -> CacheKey {
+        CacheKey::from_request(
+            &http::Request::builder()
+                .uri(uri)
+                .body(SdkBody::empty())
+                .unwrap(),
+            "identity".into(),
+        )
+        .unwrap()
+    }
+
+    fn response() -> CachedResponse {
+        CachedResponse {
+            status: http::StatusCode::OK,
+            headers: Default::default(),
+            body: Bytes::new(),
+            stored_at: SystemTime::UNIX_EPOCH,
+            ttl: Duration::ZERO,
+        }
+    }
+
+    #[test]
+    fn least_recently_used_responses_are_evicted() {
+        let cache = InMemoryResponseCache::new(2);
+        cache.put(key("/a"), response());
+        cache.put(key("/b"), response());
+        assert!(cache.get(&key("/a")).is_some());
+
+        cache.put(key("/c"), response());
+        assert!(cache.get(&key("/a")).is_some());
+        assert!(cache.get(&key("/b")).is_none());
+        assert!(cache.get(&key("/c")).is_some());
+
+        // Replacing a response doesn't evict anything
+        cache.put(key("/a"), response());
+        assert!(cache.get(&key("/c")).is_some());
+    }
+}
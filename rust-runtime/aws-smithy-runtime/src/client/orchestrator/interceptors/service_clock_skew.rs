@@ -3,49 +3,120 @@
  * SPDX-License-Identifier: Apache-2.0
  */
 
+use crate::client::orchestrator::interceptors::response_cache::ServedFromCache;
 use aws_smithy_runtime_api::client::interceptors::{
-    BeforeDeserializationInterceptorContextMut, BoxError, Interceptor,
+    BeforeDeserializationInterceptorContextMut, BeforeTransmitInterceptorContextMut, BoxError,
+    Interceptor,
 };
-use aws_smithy_types::config_bag::ConfigBag;
+use aws_smithy_runtime_api::client::orchestrator::ConfigBagAccessors;
+use aws_smithy_types::config_bag::{ConfigBag, Storable, StoreReplace};
 use aws_smithy_types::date_time::Format;
 use aws_smithy_types::DateTime;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, SystemTime};
 
-#[derive(Debug, Clone)]
+/// The estimated difference between the service's clock and the client's clock.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
 #[non_exhaustive]
 pub struct ServiceClockSkew {
-    inner: Duration,
+    skew: Duration,
+    service_is_behind: bool,
 }
 
 impl ServiceClockSkew {
-    fn new(inner: Duration) -> Self {
-        Self { inner }
+    fn new(time_sent: DateTime, time_received: DateTime) -> Self {
+        let skew = time_sent.as_secs_f64() - time_received.as_secs_f64();
+        Self {
+            skew: Duration::from_secs_f64(skew.abs()),
+            service_is_behind: skew < 0.0,
+        }
+    }
+
+    /// Creates a skew of `skew`, with the service's clock ahead of the client's unless
+    /// `service_is_behind` is true.
+    #[cfg(feature = "test-util")]
+    pub fn for_tests(skew: Duration, service_is_behind: bool) -> Self {
+        Self {
+            skew,
+            service_is_behind,
+        }
     }
 
+    /// The magnitude of the skew.
     pub fn skew(&self) -> Duration {
-        self.inner
+        self.skew
+    }
+
+    /// Returns true if the service's clock is behind the client's clock.
+    pub fn service_is_behind(&self) -> bool {
+        self.service_is_behind
+    }
+
+    /// Converts a time read from the client's clock into the service's estimated time.
+    pub fn apply(&self, time: SystemTime) -> SystemTime {
+        if self.service_is_behind {
+            time.checked_sub(self.skew).unwrap_or(time)
+        } else {
+            time.checked_add(self.skew).unwrap_or(time)
+        }
     }
 }
 
 impl From<ServiceClockSkew> for Duration {
     fn from(skew: ServiceClockSkew) -> Duration {
-        skew.inner
+        skew.skew
+    }
+}
+
+/// Clock skews measured against each endpoint a client has sent requests to.
+///
+/// A cache is shared by every operation of a client, so that a skew measured by one request
+/// is used when signing the next request sent to the same endpoint.
+#[derive(Debug, Clone, Default)]
+pub struct ClockSkewCache {
+    skews: Arc<Mutex<HashMap<String, ServiceClockSkew>>>,
+}
+
+impl ClockSkewCache {
+    /// Creates an empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the last skew measured for `endpoint`, if any.
+    pub fn get(&self, endpoint: &str) -> Option<ServiceClockSkew> {
+        self.skews.lock().unwrap().get(endpoint).copied()
+    }
+
+    fn insert(&self, endpoint: String, skew: ServiceClockSkew) {
+        self.skews.lock().unwrap().insert(endpoint, skew);
     }
 }
 
+impl Storable for ClockSkewCache {
+    type Storer = StoreReplace<ClockSkewCache>;
+}
+
+/// The endpoint the current attempt was sent to, used as the key into the [`ClockSkewCache`].
+#[derive(Debug, Clone)]
+struct SkewedEndpoint(String);
+
 #[derive(Debug, Default)]
 #[non_exhaustive]
-pub struct ServiceClockSkewInterceptor {}
+pub struct ServiceClockSkewInterceptor {
+    cache: ClockSkewCache,
+}
 
 impl ServiceClockSkewInterceptor {
     pub fn new() -> Self {
         Self::default()
     }
-}
 
-fn calculate_skew(time_sent: DateTime, time_received: DateTime) -> Duration {
-    let skew = (time_sent.as_secs_f64() - time_received.as_secs_f64()).max(0.0);
-    Duration::from_secs_f64(skew)
+    /// Creates an interceptor that remembers skews in the given `cache`.
+    pub fn with_cache(cache: ClockSkewCache) -> Self {
+        Self { cache }
+    }
 }
 
 fn extract_time_sent_from_response(
@@ -61,12 +132,32 @@ fn extract_time_sent_from_response(
 }
 
 impl Interceptor for ServiceClockSkewInterceptor {
+    fn modify_before_signing(
+        &self,
+        ctx: &mut BeforeTransmitInterceptorContextMut<'_>,
+        cfg: &mut ConfigBag,
+    ) -> Result<(), BoxError> {
+        let endpoint = match ctx.request().uri().authority() {
+            Some(authority) => authority.as_str().to_owned(),
+            None => return Ok(()),
+        };
+        if let Some(skew) = self.cache.get(&endpoint) {
+            cfg.interceptor_state().put(skew);
+        }
+        cfg.interceptor_state().put(SkewedEndpoint(endpoint));
+        Ok(())
+    }
+
     fn modify_before_deserialization(
         &self,
         ctx: &mut BeforeDeserializationInterceptorContextMut<'_>,
         cfg: &mut ConfigBag,
     ) -> Result<(), BoxError> {
-        let time_received = DateTime::from(SystemTime::now());
+        // A cached response says nothing about the service's clock as of this attempt
+        if cfg.load::<ServedFromCache>().is_some() {
+            return Ok(());
+        }
+        let time_received = DateTime::from(cfg.request_time().unwrap_or_default().now());
         let time_sent = match extract_time_sent_from_response(ctx) {
             Ok(time_sent) => time_sent,
             Err(e) => {
@@ -77,8 +168,168 @@ impl Interceptor for ServiceClockSkewInterceptor {
                 return Ok(());
             }
         };
-        let skew = ServiceClockSkew::new(calculate_skew(time_sent, time_received));
+        let skew = ServiceClockSkew::new(time_sent, time_received);
+        if let Some(SkewedEndpoint(endpoint)) = cfg.get::<SkewedEndpoint>().cloned() {
+            self.cache.insert(endpoint, skew);
+        }
         cfg.interceptor_state().put(skew);
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{ClockSkewCache, ServiceClockSkew, ServiceClockSkewInterceptor};
+    use crate::client::orchestrator::interceptors::response_cache::ServedFromCache;
+    use aws_smithy_http::body::SdkBody;
+    use aws_smithy_runtime_api::client::interceptors::{
+        BeforeDeserializationInterceptorContextMut, BeforeTransmitInterceptorContextMut,
+        Interceptor, InterceptorContext,
+    };
+    use aws_smithy_runtime_api::client::orchestrator::ConfigBagAccessors;
+    use aws_smithy_types::config_bag::{ConfigBag, Layer};
+    use aws_smithy_types::type_erasure::TypeErasedBox;
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    // Sun, 09 Sep 2001 01:46:40 GMT
+    const REQUEST_TIME: u64 = 1_000_000_000;
+
+    fn config_bag() -> ConfigBag {
+        let mut layer = Layer::new("test");
+        layer.set_request_time(aws_smithy_async::time::StaticTimeSource::new(
+            UNIX_EPOCH + Duration::from_secs(REQUEST_TIME),
+        ));
+        ConfigBag::of_layers(vec![layer])
+    }
+
+    fn attempt(
+        interceptor: &ServiceClockSkewInterceptor,
+        cfg: &mut ConfigBag,
+        uri: &str,
+        date: &str,
+    ) {
+        let mut ctx = InterceptorContext::new(TypeErasedBox::doesnt_matter());
+        ctx.enter_serialization_phase();
+        ctx.set_request(
+            http::Request::builder()
+                .uri(uri)
+                .body(SdkBody::empty())
+                .unwrap(),
+        );
+        let _ = ctx.take_input();
+        ctx.enter_before_transmit_phase();
+        let mut before_signing = BeforeTransmitInterceptorContextMut::from(&mut ctx);
+        interceptor
+            .modify_before_signing(&mut before_signing, cfg)
+            .unwrap();
+
+        ctx.enter_transmit_phase();
+        let _ = ctx.take_request();
+        ctx.set_response(
+            http::Response::builder()
+                .header("date", date)
+                .body(SdkBody::empty())
+                .unwrap(),
+        );
+        ctx.enter_before_deserialization_phase();
+        let mut before_deser = BeforeDeserializationInterceptorContextMut::from(&mut ctx);
+        interceptor
+            .modify_before_deserialization(&mut before_deser, cfg)
+            .unwrap();
+    }
+
+    #[test]
+    fn apply_skew() {
+        let now = UNIX_EPOCH + Duration::from_secs(REQUEST_TIME);
+        let ahead = ServiceClockSkew {
+            skew: Duration::from_secs(600),
+            service_is_behind: false,
+        };
+        let behind = ServiceClockSkew {
+            skew: Duration::from_secs(600),
+            service_is_behind: true,
+        };
+        assert_eq!(now + Duration::from_secs(600), ahead.apply(now));
+        assert_eq!(now - Duration::from_secs(600), behind.apply(now));
+        assert_eq!(now, ServiceClockSkew::default().apply(now));
+    }
+
+    #[test]
+    fn measures_skew_against_request_time() {
+        let interceptor = ServiceClockSkewInterceptor::new();
+        let mut cfg = config_bag();
+        attempt(
+            &interceptor,
+            &mut cfg,
+            "https://example.com/",
+            "Sun, 09 Sep 2001 01:36:40 GMT",
+        );
+
+        let skew = cfg.get::<ServiceClockSkew>().unwrap();
+        assert_eq!(Duration::from_secs(600), skew.skew());
+        assert!(skew.service_is_behind());
+    }
+
+    #[test]
+    fn skew_is_remembered_per_endpoint() {
+        let cache = ClockSkewCache::new();
+        let interceptor = ServiceClockSkewInterceptor::with_cache(cache.clone());
+        attempt(
+            &interceptor,
+            &mut config_bag(),
+            "https://example.com/",
+            "Sun, 09 Sep 2001 01:56:40 GMT",
+        );
+
+        let skew = cache.get("example.com").unwrap();
+        assert_eq!(Duration::from_secs(600), skew.skew());
+        assert!(!skew.service_is_behind());
+        assert_eq!(None, cache.get("other.example.com"));
+
+        // The next request to the same endpoint starts out with the remembered skew
+        let mut cfg = config_bag();
+        let mut ctx = InterceptorContext::new(TypeErasedBox::doesnt_matter());
+        ctx.enter_serialization_phase();
+        ctx.set_request(
+            http::Request::builder()
+                .uri("https://example.com/")
+                .body(SdkBody::empty())
+                .unwrap(),
+        );
+        let _ = ctx.take_input();
+        ctx.enter_before_transmit_phase();
+        let mut before_signing = BeforeTransmitInterceptorContextMut::from(&mut ctx);
+        interceptor
+            .modify_before_signing(&mut before_signing, &mut cfg)
+            .unwrap();
+        let request_time = cfg.request_time().unwrap().now();
+        assert_eq!(
+            SystemTime::UNIX_EPOCH + Duration::from_secs(REQUEST_TIME + 600),
+            cfg.get::<ServiceClockSkew>().unwrap().apply(request_time)
+        );
+    }
+
+    #[test]
+    fn cached_responses_are_ignored() {
+        let cache = ClockSkewCache::new();
+        let interceptor = ServiceClockSkewInterceptor::with_cache(cache.clone());
+        let mut cfg = config_bag();
+        cfg.interceptor_state().store_put(ServedFromCache);
+        attempt(
+            &interceptor,
+            &mut cfg,
+            "https://example.com/",
+            "Sun, 09 Sep 2001 01:36:40 GMT",
+        );
+        assert!(cfg.get::<ServiceClockSkew>().is_none());
+        assert_eq!(None, cache.get("example.com"));
+    }
+
+    #[test]
+    fn missing_date_header_is_ignored() {
+        let interceptor = ServiceClockSkewInterceptor::new();
+        let mut cfg = config_bag();
+        attempt(&interceptor, &mut cfg, "https://example.com/", "not a date");
+        assert!(cfg.get::<ServiceClockSkew>().is_none());
+    }
+}
@@ -4,4 +4,5 @@
  */
 
 pub mod classifier;
+pub mod hedging;
 pub mod strategy;
@@ -5,12 +5,52 @@
 
 use aws_smithy_runtime_api::client::interceptors::InterceptorContext;
 use aws_smithy_runtime_api::client::orchestrator::OrchestratorError;
-use aws_smithy_runtime_api::client::retries::{ClassifyRetry, RetryReason};
+use aws_smithy_runtime_api::client::retries::{ClassifyRetry, RetryClassifiers, RetryReason};
+use aws_smithy_types::config_bag::{ConfigBag, Storable, StoreReplace};
 use aws_smithy_types::retry::{ErrorKind, ProvideErrorKind};
 use std::borrow::Cow;
 use std::error::Error as StdError;
 use std::marker::PhantomData;
 
+/// The retry classification of the current request attempt.
+///
+/// The orchestrator classifies each attempt once, so that the retry strategy and the attempt
+/// history agree without running the classifiers twice.
+#[derive(Clone, Debug)]
+struct AttemptClassification(Option<RetryReason>);
+
+impl Storable for AttemptClassification {
+    type Storer = StoreReplace<Self>;
+}
+
+/// Runs the retry classifiers against the attempt in `ctx`, and stores the result for
+/// [`classify_attempt`].
+pub(crate) fn store_attempt_classification(
+    ctx: &InterceptorContext,
+    cfg: &mut ConfigBag,
+) -> Option<RetryReason> {
+    let retry_reason = match ctx.output_or_error() {
+        Some(Err(_)) => cfg
+            .get::<RetryClassifiers>()
+            .and_then(|classifiers| classifiers.classify_retry_with_config(ctx, cfg)),
+        _ => None,
+    };
+    cfg.interceptor_state()
+        .store_put(AttemptClassification(retry_reason.clone()));
+    retry_reason
+}
+
+/// Returns the retry classification of the attempt in `ctx`, only running the retry classifiers
+/// if the orchestrator hasn't already.
+pub(crate) fn classify_attempt(ctx: &InterceptorContext, cfg: &ConfigBag) -> Option<RetryReason> {
+    match cfg.load::<AttemptClassification>() {
+        Some(AttemptClassification(retry_reason)) => retry_reason.clone(),
+        None => cfg
+            .get::<RetryClassifiers>()
+            .and_then(|classifiers| classifiers.classify_retry_with_config(ctx, cfg)),
+    }
+}
+
 /// A retry classifier for checking if an error is modeled as retryable.
 #[derive(Debug, Default)]
 pub struct ModeledAsRetryableClassifier<E> {
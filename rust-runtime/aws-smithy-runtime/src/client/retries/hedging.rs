@@ -0,0 +1,566 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Request hedging, to reduce tail latency.
+//!
+//! When hedging is enabled with a [`HedgingRuntimePlugin`], requests for operations marked with
+//! [`IdempotentOperation`] that take longer than a percentile of recently observed latencies are
+//! sent a second time. Whichever response arrives first is used and the other request is cancelled.
+//! The hedged request is signed again when it's sent, so that it isn't a replay of the original.
+//!
+//! Each hedged request spends tokens from the
+//! [`StandardTokenBucket`](crate::client::runtime_plugin::standard_token_bucket::StandardTokenBucketRuntimePlugin)
+//! if one is configured, the same way retries do, so that hedging stops when the service is
+//! struggling. Requests with bodies that can't be cloned are never hedged.
+
+use crate::client::orchestrator::auth::sign_request;
+use crate::client::runtime_plugin::standard_token_bucket::StandardTokenBucket;
+use aws_smithy_async::rt::sleep::AsyncSleep;
+use aws_smithy_runtime_api::client::orchestrator::{
+    BoxError, ConfigBagAccessors, Connection, HttpRequest, HttpResponse,
+};
+use aws_smithy_runtime_api::client::retries::IdempotentOperation;
+use aws_smithy_runtime_api::client::runtime_plugin::RuntimePlugin;
+use aws_smithy_types::config_bag::{ConfigBag, FrozenLayer, Layer, Storable, StoreReplace};
+use std::collections::VecDeque;
+use std::future::{poll_fn, Future};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::Poll;
+use std::time::{Duration, SystemTime};
+use tracing::debug;
+
+const DEFAULT_PERCENTILE: f64 = 0.95;
+const DEFAULT_INITIAL_DELAY: Duration = Duration::from_millis(100);
+const DEFAULT_MIN_DELAY: Duration = Duration::from_millis(1);
+const DEFAULT_MIN_SAMPLES: usize = 20;
+const DEFAULT_WINDOW_SIZE: usize = 1000;
+
+/// Configuration for request hedging.
+#[derive(Clone, Debug)]
+pub struct HedgingConfig {
+    percentile: f64,
+    initial_delay: Duration,
+    min_delay: Duration,
+    min_samples: usize,
+    window_size: usize,
+}
+
+impl Default for HedgingConfig {
+    fn default() -> Self {
+        Self {
+            percentile: DEFAULT_PERCENTILE,
+            initial_delay: DEFAULT_INITIAL_DELAY,
+            min_delay: DEFAULT_MIN_DELAY,
+            min_samples: DEFAULT_MIN_SAMPLES,
+            window_size: DEFAULT_WINDOW_SIZE,
+        }
+    }
+}
+
+impl HedgingConfig {
+    /// Creates a configuration hedging requests slower than the 95th percentile of the last 1000
+    /// latencies observed.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the latency percentile, between `0.0` and `1.0`, after which a request is hedged.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `percentile` isn't between `0.0` and `1.0`.
+    pub fn percentile(mut self, percentile: f64) -> Self {
+        assert!(
+            (0.0..=1.0).contains(&percentile),
+            "the hedging percentile must be between 0.0 and 1.0"
+        );
+        self.percentile = percentile;
+        self
+    }
+
+    /// Sets the delay after which requests are hedged until enough latencies have been observed
+    /// to compute the percentile.
+    pub fn initial_delay(mut self, initial_delay: Duration) -> Self {
+        self.initial_delay = initial_delay;
+        self
+    }
+
+    /// Sets the minimum delay after which a request is hedged, regardless of observed latencies.
+    pub fn min_delay(mut self, min_delay: Duration) -> Self {
+        self.min_delay = min_delay;
+        self
+    }
+
+    /// Sets how many latencies must be observed before the percentile is used.
+    pub fn min_samples(mut self, min_samples: usize) -> Self {
+        self.min_samples = min_samples;
+        self
+    }
+
+    /// Sets how many of the most recent latencies the percentile is computed from.
+    pub fn window_size(mut self, window_size: usize) -> Self {
+        self.window_size = window_size.max(1);
+        self
+    }
+}
+
+/// The most recently observed latencies.
+#[derive(Debug, Default)]
+struct LatencyWindow {
+    latencies: VecDeque<Duration>,
+}
+
+impl LatencyWindow {
+    fn record(&mut self, latency: Duration, window_size: usize) {
+        if self.latencies.len() >= window_size {
+            self.latencies.pop_front();
+        }
+        self.latencies.push_back(latency);
+    }
+
+    fn percentile(&self, percentile: f64) -> Option<Duration> {
+        if self.latencies.is_empty() {
+            return None;
+        }
+        let mut sorted: Vec<_> = self.latencies.iter().copied().collect();
+        sorted.sort_unstable();
+        let rank = (percentile * sorted.len() as f64).ceil() as usize;
+        Some(sorted[rank.clamp(1, sorted.len()) - 1])
+    }
+}
+
+/// Hedging configuration and latency history, shared by all the requests made with a config bag.
+#[derive(Clone, Debug)]
+struct Hedging {
+    config: HedgingConfig,
+    latencies: Arc<Mutex<LatencyWindow>>,
+}
+
+impl Storable for Hedging {
+    type Storer = StoreReplace<Self>;
+}
+
+impl Hedging {
+    fn delay(&self) -> Duration {
+        let latencies = self.latencies.lock().unwrap();
+        if latencies.latencies.len() < self.config.min_samples {
+            return self.config.initial_delay;
+        }
+        latencies
+            .percentile(self.config.percentile)
+            .unwrap_or(self.config.initial_delay)
+            .max(self.config.min_delay)
+    }
+
+    fn record(&self, latency: Duration) {
+        self.latencies
+            .lock()
+            .unwrap()
+            .record(latency, self.config.window_size);
+    }
+}
+
+/// A [`RuntimePlugin`] enabling request hedging.
+///
+/// Latencies are tracked per plugin, so registering a plugin per operation hedges each operation
+/// based on its own latency distribution.
+#[derive(Debug)]
+pub struct HedgingRuntimePlugin {
+    hedging: Hedging,
+}
+
+impl HedgingRuntimePlugin {
+    pub fn new(config: HedgingConfig) -> Self {
+        Self {
+            hedging: Hedging {
+                config,
+                latencies: Default::default(),
+            },
+        }
+    }
+}
+
+impl RuntimePlugin for HedgingRuntimePlugin {
+    fn config(&self) -> Option<FrozenLayer> {
+        let mut cfg = Layer::new("hedging");
+        cfg.store_put(self.hedging.clone());
+        Some(cfg.freeze())
+    }
+}
+
+fn try_clone(request: &HttpRequest) -> Option<HttpRequest> {
+    let body = request.body().try_clone()?;
+    let mut cloned = http::Request::builder()
+        .method(request.method().clone())
+        .uri(request.uri().clone())
+        .version(request.version())
+        .body(body)
+        .expect("valid request parts");
+    *cloned.headers_mut() = request.headers().clone();
+    Some(cloned)
+}
+
+type CallFuture = Pin<Box<dyn Future<Output = Result<HttpResponse, BoxError>> + Send>>;
+
+fn now(cfg: &ConfigBag) -> SystemTime {
+    cfg.request_time().unwrap_or_default().now()
+}
+
+fn elapsed_since(cfg: &ConfigBag, start: SystemTime) -> Duration {
+    now(cfg).duration_since(start).unwrap_or_default()
+}
+
+/// Calls `connection` with `request`, hedging the request if it's enabled for the operation.
+pub(crate) async fn call(
+    cfg: &ConfigBag,
+    connection: &dyn Connection,
+    request: HttpRequest,
+) -> Result<HttpResponse, BoxError> {
+    let (hedging, sleep_impl) = match (
+        cfg.get::<Hedging>(),
+        cfg.get::<IdempotentOperation>(),
+        cfg.sleep_impl(),
+    ) {
+        (Some(hedging), Some(_), Some(sleep_impl)) => (hedging, sleep_impl),
+        _ => return connection.call(request).await,
+    };
+    let mut hedge_request = match try_clone(&request) {
+        Some(hedge_request) => hedge_request,
+        None => return connection.call(request).await,
+    };
+
+    let start = now(cfg);
+    let mut original: CallFuture = connection.call(request);
+    let mut delay = sleep_impl.sleep(hedging.delay());
+    let original_first = poll_fn(|cx| {
+        if let Poll::Ready(result) = original.as_mut().poll(cx) {
+            return Poll::Ready(Some(result));
+        }
+        Pin::new(&mut delay).poll(cx).map(|_| None)
+    })
+    .await;
+    if let Some(result) = original_first {
+        hedging.record(elapsed_since(cfg, start));
+        return result;
+    }
+
+    if let Some(token_bucket) = cfg.get::<StandardTokenBucket>() {
+        match token_bucket.acquire_hedge() {
+            // Hedged requests are extra load on the service, so their tokens are spent for good
+            Some(permit) => permit.forget(),
+            None => {
+                debug!("no tokens are available for hedging the request; waiting for the original request");
+                let result = original.await;
+                hedging.record(elapsed_since(cfg, start));
+                return result;
+            }
+        }
+    }
+
+    debug!("request is slower than expected; hedging it");
+    if let Err(err) = sign_request(&mut hedge_request, cfg).await {
+        debug!(error = %err, "failed to sign the hedged request; waiting for the original request");
+        let result = original.await;
+        hedging.record(elapsed_since(cfg, start));
+        return result;
+    }
+    let mut hedge: CallFuture = connection.call(hedge_request);
+    let mut original_done = false;
+    let mut hedge_done = false;
+    // The first successful response wins, and the other request is cancelled by dropping it. If a
+    // request fails, the other one is awaited; if both fail, the last error is returned. Either
+    // way, the latency of the original request is recorded: if the hedge wins, the original took
+    // at least as long as it has been in flight, and recording the hedge's latency instead would
+    // drag the percentile down and hedge more and more requests.
+    poll_fn(|cx| {
+        if !original_done {
+            if let Poll::Ready(result) = original.as_mut().poll(cx) {
+                original_done = true;
+                if result.is_ok() || hedge_done {
+                    hedging.record(elapsed_since(cfg, start));
+                    return Poll::Ready(result);
+                }
+            }
+        }
+        if !hedge_done {
+            if let Poll::Ready(result) = hedge.as_mut().poll(cx) {
+                hedge_done = true;
+                if result.is_ok() || original_done {
+                    hedging.record(elapsed_since(cfg, start));
+                    return Poll::Ready(result);
+                }
+            }
+        }
+        Poll::Pending
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aws_smithy_async::rt::sleep::{SharedAsyncSleep, TokioSleep};
+    use aws_smithy_async::time::TimeSource;
+    use aws_smithy_http::body::SdkBody;
+    use aws_smithy_runtime_api::client::auth::option_resolver::StaticAuthOptionResolver;
+    use aws_smithy_runtime_api::client::auth::{
+        AuthOptionResolverParams, AuthSchemeEndpointConfig, AuthSchemeId, HttpAuthScheme,
+        HttpAuthSchemes, HttpRequestSigner,
+    };
+    use aws_smithy_runtime_api::client::identity::{Identity, IdentityResolver, IdentityResolvers};
+    use aws_smithy_runtime_api::client::orchestrator::Future as IdentityFuture;
+    use aws_smithy_types::endpoint::Endpoint;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    const TEST_SCHEME_ID: AuthSchemeId = AuthSchemeId::new("test-scheme");
+
+    /// Signs each request with a new signature number.
+    #[derive(Debug, Default)]
+    struct TestAuthScheme {
+        signatures: AtomicUsize,
+    }
+
+    impl HttpAuthScheme for TestAuthScheme {
+        fn scheme_id(&self) -> AuthSchemeId {
+            TEST_SCHEME_ID
+        }
+
+        fn identity_resolver<'a>(
+            &self,
+            identity_resolvers: &'a IdentityResolvers,
+        ) -> Option<&'a dyn IdentityResolver> {
+            identity_resolvers.identity_resolver(TEST_SCHEME_ID)
+        }
+
+        fn request_signer(&self) -> &dyn HttpRequestSigner {
+            self
+        }
+    }
+
+    impl HttpRequestSigner for TestAuthScheme {
+        fn sign_request(
+            &self,
+            request: &mut HttpRequest,
+            _identity: &Identity,
+            _auth_scheme_endpoint_config: AuthSchemeEndpointConfig<'_>,
+            _config_bag: &ConfigBag,
+        ) -> Result<(), BoxError> {
+            let signature = self.signatures.fetch_add(1, Ordering::SeqCst);
+            request
+                .headers_mut()
+                .insert("signature", signature.to_string().parse().unwrap());
+            Ok(())
+        }
+    }
+
+    #[derive(Debug)]
+    struct TestIdentityResolver;
+
+    impl IdentityResolver for TestIdentityResolver {
+        fn resolve_identity(&self, _config_bag: &ConfigBag) -> IdentityFuture<Identity> {
+            IdentityFuture::ready(Ok(Identity::new("identity", None)))
+        }
+    }
+
+    /// A time source following tokio's (paused) clock.
+    #[derive(Debug)]
+    struct TokioTime {
+        start: tokio::time::Instant,
+    }
+
+    impl TimeSource for TokioTime {
+        fn now(&self) -> SystemTime {
+            SystemTime::UNIX_EPOCH + self.start.elapsed()
+        }
+    }
+
+    /// A connection whose first response takes `first_latency`, and the others `latency`.
+    #[derive(Debug)]
+    struct SlowConnection {
+        signatures: Arc<Mutex<Vec<String>>>,
+        first_latency: Duration,
+        latency: Duration,
+    }
+
+    impl SlowConnection {
+        fn new(first_latency: Duration, latency: Duration) -> Self {
+            Self {
+                signatures: Default::default(),
+                first_latency,
+                latency,
+            }
+        }
+
+        /// The signatures of the requests sent, in the order they were sent.
+        fn signatures(&self) -> Vec<String> {
+            self.signatures.lock().unwrap().clone()
+        }
+    }
+
+    impl Connection for SlowConnection {
+        fn call(&self, request: HttpRequest) -> CallFuture {
+            let mut signatures = self.signatures.lock().unwrap();
+            let call = signatures.len();
+            signatures.push(
+                request
+                    .headers()
+                    .get("signature")
+                    .map(|signature| signature.to_str().unwrap().to_owned())
+                    .unwrap_or_default(),
+            );
+            let latency = if call == 0 {
+                self.first_latency
+            } else {
+                self.latency
+            };
+            let sleep = TokioSleep::new().sleep(latency);
+            Box::pin(async move {
+                sleep.await;
+                Ok(http::Response::builder()
+                    .status(200)
+                    .body(SdkBody::from(format!("response {call}")))
+                    .unwrap())
+            })
+        }
+    }
+
+    fn cfg(idempotent: bool, token_bucket: Option<StandardTokenBucket>) -> ConfigBag {
+        let mut layer = Layer::new("test");
+        let plugin = HedgingRuntimePlugin::new(
+            HedgingConfig::new().initial_delay(Duration::from_millis(20)),
+        );
+        layer.store_put(plugin.hedging);
+        layer.set_sleep_impl(Some(SharedAsyncSleep::new(TokioSleep::new())));
+        layer.set_request_time(TokioTime {
+            start: tokio::time::Instant::now(),
+        });
+        layer.set_auth_option_resolver_params(AuthOptionResolverParams::new("doesntmatter"));
+        layer.set_auth_option_resolver(StaticAuthOptionResolver::new(vec![TEST_SCHEME_ID]));
+        layer.set_identity_resolvers(
+            IdentityResolvers::builder()
+                .identity_resolver(TEST_SCHEME_ID, TestIdentityResolver)
+                .build(),
+        );
+        layer.set_http_auth_schemes(
+            HttpAuthSchemes::builder()
+                .auth_scheme(TEST_SCHEME_ID, TestAuthScheme::default())
+                .build(),
+        );
+        layer.put(Endpoint::builder().url("https://example.com").build());
+        if idempotent {
+            layer.store_put(IdempotentOperation::new());
+        }
+        if let Some(token_bucket) = token_bucket {
+            layer.store_put(token_bucket);
+        }
+        ConfigBag::of_layers(vec![layer])
+    }
+
+    /// A request as signed by the orchestrator before it's sent.
+    async fn request(cfg: &ConfigBag) -> HttpRequest {
+        let mut request = http::Request::builder()
+            .uri("https://example.com")
+            .body(SdkBody::from("body"))
+            .unwrap();
+        sign_request(&mut request, cfg).await.unwrap();
+        request
+    }
+
+    fn body(response: &HttpResponse) -> &[u8] {
+        response.body().bytes().unwrap()
+    }
+
+    fn recorded_latencies(cfg: &ConfigBag) -> Vec<Duration> {
+        let hedging = cfg.get::<Hedging>().unwrap();
+        let latencies = hedging.latencies.lock().unwrap();
+        latencies.latencies.iter().copied().collect()
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn slow_requests_are_hedged_and_signed_again() {
+        let cfg = cfg(true, None);
+        let connection = SlowConnection::new(Duration::from_secs(10), Duration::from_millis(1));
+        let response = call(&cfg, &connection, request(&cfg).await).await.unwrap();
+        assert_eq!(body(&response), b"response 1");
+        assert_eq!(connection.signatures(), vec!["0", "1"]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn the_original_latency_is_recorded_when_the_hedge_wins() {
+        let cfg = cfg(true, None);
+        let connection = SlowConnection::new(Duration::from_secs(10), Duration::from_millis(1));
+        call(&cfg, &connection, request(&cfg).await).await.unwrap();
+        // The original request was in flight for the hedging delay and the hedge's latency
+        assert_eq!(recorded_latencies(&cfg), vec![Duration::from_millis(21)]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn fast_requests_are_not_hedged() {
+        let cfg = cfg(true, None);
+        let connection = SlowConnection::new(Duration::from_millis(1), Duration::from_millis(1));
+        let response = call(&cfg, &connection, request(&cfg).await).await.unwrap();
+        assert_eq!(body(&response), b"response 0");
+        assert_eq!(connection.signatures(), vec!["0"]);
+        assert_eq!(recorded_latencies(&cfg), vec![Duration::from_millis(1)]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn non_idempotent_operations_are_not_hedged() {
+        let cfg = cfg(false, None);
+        let connection = SlowConnection::new(Duration::from_millis(50), Duration::from_millis(1));
+        let response = call(&cfg, &connection, request(&cfg).await).await.unwrap();
+        assert_eq!(body(&response), b"response 0");
+        assert_eq!(connection.signatures(), vec!["0"]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn hedging_is_limited_by_the_token_bucket() {
+        let token_bucket = StandardTokenBucket::new(5);
+        let cfg = cfg(true, Some(token_bucket.clone()));
+        let connection = SlowConnection::new(Duration::from_millis(50), Duration::from_millis(50));
+
+        // The first slow request spends the bucket's only hedge
+        call(&cfg, &connection, request(&cfg).await).await.unwrap();
+        assert_eq!(connection.signatures().len(), 2);
+
+        let response = call(&cfg, &connection, request(&cfg).await).await.unwrap();
+        assert_eq!(body(&response), b"response 2");
+        assert_eq!(connection.signatures().len(), 3);
+    }
+
+    #[test]
+    fn percentile_of_recent_latencies() {
+        let mut window = LatencyWindow::default();
+        assert_eq!(window.percentile(0.5), None);
+        for millis in 1..=10 {
+            window.record(Duration::from_millis(millis), 10);
+        }
+        assert_eq!(window.percentile(0.5), Some(Duration::from_millis(5)));
+        assert_eq!(window.percentile(0.95), Some(Duration::from_millis(10)));
+        assert_eq!(window.percentile(0.0), Some(Duration::from_millis(1)));
+
+        // Old latencies are dropped
+        window.record(Duration::from_millis(100), 10);
+        assert_eq!(window.percentile(0.0), Some(Duration::from_millis(2)));
+        assert_eq!(window.percentile(1.0), Some(Duration::from_millis(100)));
+    }
+
+    #[test]
+    fn delay_uses_initial_delay_until_enough_samples() {
+        let hedging = HedgingRuntimePlugin::new(
+            HedgingConfig::new()
+                .initial_delay(Duration::from_millis(100))
+                .min_samples(2)
+                .percentile(0.5),
+        )
+        .hedging;
+        assert_eq!(hedging.delay(), Duration::from_millis(100));
+        hedging.record(Duration::from_millis(10));
+        assert_eq!(hedging.delay(), Duration::from_millis(100));
+        hedging.record(Duration::from_millis(20));
+        assert_eq!(hedging.delay(), Duration::from_millis(10));
+    }
+}
@@ -3,12 +3,11 @@
  * SPDX-License-Identifier: Apache-2.0
  */
 
+use crate::client::retries::classifier::classify_attempt;
 use aws_smithy_runtime_api::client::interceptors::InterceptorContext;
 use aws_smithy_runtime_api::client::orchestrator::BoxError;
 use aws_smithy_runtime_api::client::request_attempts::RequestAttempts;
-use aws_smithy_runtime_api::client::retries::{
-    ClassifyRetry, RetryClassifiers, RetryReason, RetryStrategy, ShouldAttempt,
-};
+use aws_smithy_runtime_api::client::retries::{RetryReason, RetryStrategy, ShouldAttempt};
 use aws_smithy_types::config_bag::ConfigBag;
 use std::time::Duration;
 
@@ -65,10 +64,7 @@ impl RetryStrategy for FixedDelayRetryStrategy {
             return Ok(ShouldAttempt::No);
         }
 
-        let retry_classifiers = cfg
-            .get::<RetryClassifiers>()
-            .expect("a retry classifier is set");
-        let retry_reason = retry_classifiers.classify_retry(ctx);
+        let retry_reason = classify_attempt(ctx, cfg);
 
         let backoff = match retry_reason {
             Some(RetryReason::Explicit(_)) => self.fixed_delay,
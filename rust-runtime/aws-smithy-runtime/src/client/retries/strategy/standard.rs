@@ -3,16 +3,15 @@
  * SPDX-License-Identifier: Apache-2.0
  */
 
+use crate::client::retries::classifier::classify_attempt;
 use crate::client::retries::strategy::standard::ReleaseResult::{
     APermitWasReleased, NoPermitWasReleased,
 };
 use crate::client::runtime_plugin::standard_token_bucket::StandardTokenBucket;
 use aws_smithy_runtime_api::client::interceptors::InterceptorContext;
-use aws_smithy_runtime_api::client::orchestrator::{BoxError, ConfigBagAccessors};
+use aws_smithy_runtime_api::client::orchestrator::BoxError;
 use aws_smithy_runtime_api::client::request_attempts::RequestAttempts;
-use aws_smithy_runtime_api::client::retries::{
-    ClassifyRetry, RetryReason, RetryStrategy, ShouldAttempt,
-};
+use aws_smithy_runtime_api::client::retries::{RetryReason, RetryStrategy, ShouldAttempt};
 use aws_smithy_types::config_bag::ConfigBag;
 use aws_smithy_types::retry::RetryConfig;
 use std::sync::Mutex;
@@ -146,8 +145,7 @@ impl RetryStrategy for StandardRetryStrategy {
         }
 
         // Run the classifiers against the context to determine if we should retry
-        let retry_classifiers = cfg.retry_classifiers();
-        let retry_reason = retry_classifiers.classify_retry(ctx);
+        let retry_reason = classify_attempt(ctx, cfg);
 
         // Calculate the appropriate backoff time.
         let backoff = match retry_reason {
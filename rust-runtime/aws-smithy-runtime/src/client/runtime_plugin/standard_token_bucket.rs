@@ -86,6 +86,14 @@ impl StandardTokenBucket {
             .ok()
     }
 
+    /// Acquires the permits for sending a hedged request, which cost as much as a retry.
+    pub(crate) fn acquire_hedge(&self) -> Option<OwnedSemaphorePermit> {
+        self.semaphore
+            .clone()
+            .try_acquire_many_owned(self.retry_cost)
+            .ok()
+    }
+
     pub(crate) fn regenerate_a_token(&self) {
         if self.semaphore.available_permits() < (self.max_permits) {
             trace!("adding {PERMIT_REGENERATION_AMOUNT} back into the bucket");
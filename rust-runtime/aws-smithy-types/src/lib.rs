@@ -23,6 +23,8 @@ pub mod endpoint;
 pub mod error;
 pub mod primitive;
 pub mod retry;
+#[cfg(all(aws_sdk_unstable, feature = "serde-serialize"))]
+pub mod sensitive;
 pub mod timeout;
 
 //TODO(enableNewSmithyRuntimeLaunch): Unhide this module when switching to the orchestrator
@@ -10,6 +10,10 @@ use std::fmt;
 use std::str::FromStr;
 use std::time::Duration;
 
+mod history;
+
+pub use history::{AttemptHistory, ProvideAttemptHistory, RequestAttempt};
+
 const VALID_RETRY_MODES: &[RetryMode] = &[RetryMode::Standard];
 
 /// Type of error that occurred when making a request.
@@ -53,6 +57,17 @@ pub trait ProvideErrorKind {
     fn code(&self) -> Option<&str>;
 }
 
+/// The reason a retry classifier gave for retrying a request attempt.
+#[non_exhaustive]
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum RetryReason {
+    /// Retry because of an error of the given kind.
+    Error(ErrorKind),
+
+    /// Retry after the given delay, as requested by the response.
+    Explicit(Duration),
+}
+
 /// `RetryKind` describes how a request MAY be retried for a given response
 ///
 /// A `RetryKind` describes how a response MAY be retried; it does not mandate retry behavior.
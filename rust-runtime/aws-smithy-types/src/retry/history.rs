@@ -0,0 +1,228 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use super::RetryReason;
+use std::fmt;
+use std::time::{Duration, SystemTime};
+
+/// A record of a single request attempt made while invoking an operation.
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq)]
+pub struct RequestAttempt {
+    start_time: SystemTime,
+    duration: Duration,
+    endpoint: Option<String>,
+    status: Option<u16>,
+    request_id: Option<String>,
+    retry_reason: Option<RetryReason>,
+    backoff: Option<Duration>,
+}
+
+impl RequestAttempt {
+    /// Creates a record of an attempt that started at `start_time` and took `duration`.
+    pub fn new(start_time: SystemTime, duration: Duration) -> Self {
+        Self {
+            start_time,
+            duration,
+            endpoint: None,
+            status: None,
+            request_id: None,
+            retry_reason: None,
+            backoff: None,
+        }
+    }
+
+    /// Sets the endpoint the attempt was sent to.
+    pub fn with_endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.endpoint = Some(endpoint.into());
+        self
+    }
+
+    /// Sets the HTTP status code of the response to the attempt.
+    pub fn with_status(mut self, status: u16) -> Self {
+        self.status = Some(status);
+        self
+    }
+
+    /// Sets the request ID returned by the service for the attempt.
+    pub fn with_request_id(mut self, request_id: impl Into<String>) -> Self {
+        self.request_id = Some(request_id.into());
+        self
+    }
+
+    /// Sets the reason the outcome of the attempt was considered retryable.
+    pub fn with_retry_reason(mut self, retry_reason: RetryReason) -> Self {
+        self.retry_reason = Some(retry_reason);
+        self
+    }
+
+    /// Sets the delay before the next attempt was made.
+    pub fn with_backoff(mut self, backoff: Duration) -> Self {
+        self.backoff = Some(backoff);
+        self
+    }
+
+    /// Sets the delay before the next attempt was made.
+    pub fn set_backoff(&mut self, backoff: Option<Duration>) -> &mut Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Returns the time at which the attempt started.
+    pub fn start_time(&self) -> SystemTime {
+        self.start_time
+    }
+
+    /// Returns how long the attempt took.
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    /// Returns the endpoint the attempt was sent to, if the endpoint was resolved.
+    pub fn endpoint(&self) -> Option<&str> {
+        self.endpoint.as_deref()
+    }
+
+    /// Returns the HTTP status code of the response, if a response was received.
+    pub fn status(&self) -> Option<u16> {
+        self.status
+    }
+
+    /// Returns the request ID returned by the service, if there was one.
+    pub fn request_id(&self) -> Option<&str> {
+        self.request_id.as_deref()
+    }
+
+    /// Returns the reason the outcome of the attempt was considered retryable, if it was.
+    pub fn retry_reason(&self) -> Option<&RetryReason> {
+        self.retry_reason.as_ref()
+    }
+
+    /// Returns the delay before the next attempt, if another attempt was made after a delay.
+    pub fn backoff(&self) -> Option<Duration> {
+        self.backoff
+    }
+}
+
+impl fmt::Display for RequestAttempt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.status {
+            Some(status) => write!(f, "HTTP {status}")?,
+            None => write!(f, "no response")?,
+        }
+        write!(f, " after {:?}", self.duration)?;
+        if let Some(request_id) = &self.request_id {
+            write!(f, " (request ID: {request_id})")?;
+        }
+        match &self.retry_reason {
+            Some(RetryReason::Error(kind)) => write!(f, ", retryable {kind:?}")?,
+            Some(RetryReason::Explicit(delay)) => write!(f, ", retry requested after {delay:?}")?,
+            None => {}
+        }
+        if let Some(backoff) = self.backoff {
+            write!(f, ", backed off {backoff:?}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The request attempts made while invoking an operation, in the order they were made.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AttemptHistory {
+    attempts: Vec<RequestAttempt>,
+}
+
+impl AttemptHistory {
+    /// Creates an empty history.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records an attempt.
+    pub fn push(&mut self, attempt: RequestAttempt) {
+        self.attempts.push(attempt);
+    }
+
+    /// Returns the recorded attempts.
+    pub fn attempts(&self) -> &[RequestAttempt] {
+        &self.attempts
+    }
+
+    /// Returns the most recent attempt, so that it can be updated once the next attempt is planned.
+    pub fn last_mut(&mut self) -> Option<&mut RequestAttempt> {
+        self.attempts.last_mut()
+    }
+
+    /// Returns the number of attempts made.
+    pub fn len(&self) -> usize {
+        self.attempts.len()
+    }
+
+    /// Returns true if no attempts were made.
+    pub fn is_empty(&self) -> bool {
+        self.attempts.is_empty()
+    }
+}
+
+impl fmt::Display for AttemptHistory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let plural = if self.attempts.len() == 1 { "" } else { "s" };
+        write!(f, "{} attempt{plural}", self.attempts.len())?;
+        for (index, attempt) in self.attempts.iter().enumerate() {
+            let separator = if index == 0 { ": " } else { "; " };
+            write!(f, "{separator}#{} {attempt}", index + 1)?;
+        }
+        Ok(())
+    }
+}
+
+/// Provides the [`AttemptHistory`] of the operation invocation that produced a result.
+pub trait ProvideAttemptHistory {
+    /// Returns the attempt history, if it was recorded.
+    fn attempt_history(&self) -> Option<&AttemptHistory>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::retry::ErrorKind;
+
+    #[test]
+    fn display() {
+        let start = SystemTime::UNIX_EPOCH;
+        let mut history = AttemptHistory::new();
+        assert_eq!("0 attempts", history.to_string());
+
+        history.push(
+            RequestAttempt::new(start, Duration::from_millis(120))
+                .with_endpoint("https://example.com")
+                .with_status(503)
+                .with_request_id("abc")
+                .with_retry_reason(RetryReason::Error(ErrorKind::ThrottlingError))
+                .with_backoff(Duration::from_millis(250)),
+        );
+        history.push(
+            RequestAttempt::new(start, Duration::from_secs(3))
+                .with_retry_reason(RetryReason::Explicit(Duration::from_secs(1))),
+        );
+        history
+            .last_mut()
+            .unwrap()
+            .set_backoff(Some(Duration::from_secs(1)));
+        history.push(RequestAttempt::new(start, Duration::from_millis(5)).with_status(200));
+
+        assert_eq!(
+            "3 attempts: \
+             #1 HTTP 503 after 120ms (request ID: abc), retryable ThrottlingError, backed off 250ms; \
+             #2 no response after 3s, retry requested after 1s, backed off 1s; \
+             #3 HTTP 200 after 5ms",
+            history.to_string()
+        );
+        assert_eq!(
+            Some("https://example.com"),
+            history.attempts()[0].endpoint()
+        );
+    }
+}
@@ -0,0 +1,43 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Serialization helpers for members targeting `@sensitive` shapes.
+
+const REDACTED: &str = "*** Sensitive Data Redacted ***";
+
+/// Serializes a redacted placeholder in place of `_value`.
+///
+/// Generated shapes use this with `#[serde(serialize_with = "...")]` so that sensitive members
+/// don't end up in serialized output, mirroring their redacted `Debug` output.
+pub fn serialize_redacted<T: ?Sized, S>(_value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    serializer.serialize_str(REDACTED)
+}
+
+#[cfg(test)]
+mod test {
+    use serde::Serialize;
+
+    #[derive(Serialize)]
+    struct Login {
+        user: String,
+        #[serde(serialize_with = "super::serialize_redacted")]
+        password: Option<String>,
+    }
+
+    #[test]
+    fn sensitive_values_are_redacted() {
+        let login = Login {
+            user: "alice".into(),
+            password: Some("hunter2".into()),
+        };
+        assert_eq!(
+            r#"{"user":"alice","password":"*** Sensitive Data Redacted ***"}"#,
+            serde_json::to_string(&login).unwrap()
+        );
+    }
+}
@@ -10,6 +10,8 @@ use std::error::Error;
 use std::fmt::{Display, Formatter};
 use xmlparser::{ElementEnd, Token, Tokenizer};
 
+pub mod streaming;
+
 pub type Depth = usize;
 
 // in general, these errors are just for reporting what happened, there isn't
@@ -0,0 +1,461 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Element-at-a-time decoding of XML documents that arrive in chunks.
+//!
+//! [`Document`] requires the complete document as one string. [`StreamingDecoder`] instead
+//! accepts chunks of the document as they arrive, and yields each child element of the root
+//! element as soon as its closing tag has been received. Each [`Element`] is a complete XML
+//! fragment that can be read with the regular [`Document`] and [`ScopedDecoder`](crate::decode::ScopedDecoder)
+//! APIs. Only the element currently being received is buffered, so memory use is bounded by the
+//! size of the largest child element rather than the size of the document.
+//!
+//! ```
+//! use aws_smithy_xml::decode::streaming::{Next, StreamingDecoder};
+//! use aws_smithy_xml::decode::try_data;
+//!
+//! let mut decoder = StreamingDecoder::new();
+//! let mut chunks = vec![
+//!     "<ListPartsResult><Part><PartNumber>1</PartNumber></Part><Part><Part",
+//!     "Number>2</PartNumber></Part></ListPartsResult>",
+//! ]
+//! .into_iter();
+//! let mut part_numbers = Vec::new();
+//! loop {
+//!     match decoder.next_element().unwrap() {
+//!         Next::Element(element) => {
+//!             let mut doc = element.document();
+//!             let mut part = doc.root_element().unwrap();
+//!             let mut part_number = part.next_tag().unwrap();
+//!             part_numbers.push(try_data(&mut part_number).unwrap().into_owned());
+//!         }
+//!         Next::NeedMoreInput => match chunks.next() {
+//!             Some(chunk) => decoder.push(chunk.as_bytes()),
+//!             None => decoder.finish(),
+//!         },
+//!         Next::Done => break,
+//!     }
+//! }
+//! assert_eq!(vec!["1", "2"], part_numbers);
+//! ```
+
+use crate::decode::{Document, StartEl, XmlDecodeError};
+
+/// The result of asking a [`StreamingDecoder`] for the next element.
+#[derive(Debug, PartialEq)]
+pub enum Next<'a> {
+    /// A complete child element of the root element.
+    Element(Element<'a>),
+    /// The buffered input ends partway through an element. Call [`StreamingDecoder::push`] with
+    /// the next chunk, or [`StreamingDecoder::finish`] if there is none.
+    NeedMoreInput,
+    /// The root element has been closed.
+    Done,
+}
+
+/// A complete child element of the root element, including its start and end tags.
+#[derive(Debug, PartialEq)]
+pub struct Element<'a> {
+    xml: &'a str,
+}
+
+impl<'a> Element<'a> {
+    /// Returns the XML of this element.
+    pub fn as_str(&self) -> &'a str {
+        self.xml
+    }
+
+    /// Returns a [`Document`] whose root element is this element.
+    pub fn document(&self) -> Document<'a> {
+        Document::new(self.xml)
+    }
+}
+
+/// The result of scanning a single markup construct.
+enum Scanned {
+    /// The construct ends at the given index (exclusive).
+    Complete(usize),
+    /// The construct continues past the end of the buffer.
+    Incomplete,
+}
+
+/// Decodes the children of an XML document's root element one at a time, as chunks of the
+/// document arrive.
+///
+/// Text and comments directly within the root element are skipped. The XML within each
+/// element is not validated until it's read through [`Element::document`].
+#[derive(Debug, Default)]
+pub struct StreamingDecoder {
+    buffer: Vec<u8>,
+    /// Index of the next byte of `buffer` to scan
+    position: usize,
+    /// Index of the start tag of the child element currently being received
+    element_start: Option<usize>,
+    /// Number of open elements at `position`
+    depth: usize,
+    /// Start tag of the root element
+    root: Option<String>,
+    finished: bool,
+    done: bool,
+}
+
+impl StreamingDecoder {
+    /// Creates a new decoder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends the next chunk of the document.
+    pub fn push(&mut self, chunk: &[u8]) {
+        debug_assert!(!self.finished, "chunks must not be pushed after `finish`");
+        self.discard_consumed();
+        self.buffer.extend_from_slice(chunk);
+    }
+
+    /// Marks the end of the document, after which incomplete elements are errors.
+    pub fn finish(&mut self) {
+        self.finished = true;
+    }
+
+    /// Returns the start element of the root element, once it has been received.
+    pub fn root_start_el(&self) -> Option<StartEl<'_>> {
+        Document::new(self.root.as_deref()?).next_start_element()
+    }
+
+    /// Drops bytes that have been scanned and aren't part of the element being received.
+    fn discard_consumed(&mut self) {
+        let consumed = self.element_start.unwrap_or(self.position);
+        self.buffer.drain(..consumed);
+        self.position -= consumed;
+        self.element_start = self.element_start.map(|start| start - consumed);
+    }
+
+    /// Returns the next child element of the root element, or what the decoder needs to make
+    /// progress.
+    pub fn next_element(&mut self) -> Result<Next<'_>, XmlDecodeError> {
+        self.discard_consumed();
+        loop {
+            if self.done {
+                return trailing(&self.buffer, &mut self.position, self.finished);
+            }
+            let text_end = self.buffer[self.position..]
+                .iter()
+                .position(|b| *b == b'<')
+                .map(|offset| self.position + offset);
+            if self.depth == 0 {
+                // Only whitespace is allowed between markup before the root element
+                check_whitespace(
+                    &self.buffer[self.position..text_end.unwrap_or(self.buffer.len())],
+                )?;
+            }
+            let start = match text_end {
+                Some(start) => start,
+                None => {
+                    self.position = self.buffer.len();
+                    return self.need_more_input();
+                }
+            };
+            let end = match scan_markup(&self.buffer[start..]) {
+                Scanned::Complete(length) => start + length,
+                Scanned::Incomplete => {
+                    self.position = start;
+                    return self.need_more_input();
+                }
+            };
+            let markup = &self.buffer[start..end];
+            self.position = end;
+            match markup_kind(markup) {
+                MarkupKind::Other => {}
+                MarkupKind::Start { empty } => match self.depth {
+                    0 => {
+                        self.root = Some(utf8(markup)?.to_string());
+                        self.done = empty;
+                        self.depth = 1;
+                    }
+                    1 if empty => {
+                        return Ok(Next::Element(Element { xml: utf8(markup)? }));
+                    }
+                    _ if empty => {}
+                    depth => {
+                        if depth == 1 {
+                            self.element_start = Some(start);
+                        }
+                        self.depth += 1;
+                    }
+                },
+                MarkupKind::End => match self.depth {
+                    0 => return Err(XmlDecodeError::custom("unexpected closing tag")),
+                    1 => self.done = true,
+                    2 => {
+                        self.depth = 1;
+                        let element_start = self
+                            .element_start
+                            .take()
+                            .expect("an element is open at depth 2");
+                        return Ok(Next::Element(Element {
+                            xml: utf8(&self.buffer[element_start..end])?,
+                        }));
+                    }
+                    _ => self.depth -= 1,
+                },
+            }
+        }
+    }
+
+    fn need_more_input(&self) -> Result<Next<'static>, XmlDecodeError> {
+        if self.finished {
+            Err(XmlDecodeError::custom("unexpected end of document"))
+        } else {
+            Ok(Next::NeedMoreInput)
+        }
+    }
+}
+
+/// Checks that only whitespace, comments, and processing instructions follow the root element.
+fn trailing(
+    buffer: &[u8],
+    position: &mut usize,
+    finished: bool,
+) -> Result<Next<'static>, XmlDecodeError> {
+    loop {
+        let rest = &buffer[*position..];
+        let start = match rest.iter().position(|b| !b.is_ascii_whitespace()) {
+            Some(start) => *position + start,
+            None => {
+                *position = buffer.len();
+                return Ok(if finished {
+                    Next::Done
+                } else {
+                    Next::NeedMoreInput
+                });
+            }
+        };
+        if buffer[start] != b'<' {
+            return Err(XmlDecodeError::custom(
+                "unexpected content after the root element",
+            ));
+        }
+        match scan_markup(&buffer[start..]) {
+            Scanned::Complete(length) => {
+                if !matches!(
+                    markup_kind(&buffer[start..start + length]),
+                    MarkupKind::Other
+                ) {
+                    return Err(XmlDecodeError::custom(
+                        "unexpected element after the root element",
+                    ));
+                }
+                *position = start + length;
+            }
+            Scanned::Incomplete => {
+                *position = start;
+                return if finished {
+                    Err(XmlDecodeError::custom("unexpected end of document"))
+                } else {
+                    Ok(Next::NeedMoreInput)
+                };
+            }
+        }
+    }
+}
+
+fn utf8(bytes: &[u8]) -> Result<&str, XmlDecodeError> {
+    std::str::from_utf8(bytes).map_err(XmlDecodeError::unhandled)
+}
+
+fn check_whitespace(bytes: &[u8]) -> Result<(), XmlDecodeError> {
+    if bytes.iter().all(u8::is_ascii_whitespace) {
+        Ok(())
+    } else {
+        Err(XmlDecodeError::custom(
+            "unexpected content before the root element",
+        ))
+    }
+}
+
+enum MarkupKind {
+    Start {
+        empty: bool,
+    },
+    End,
+    /// Comments, CDATA sections, processing instructions, and declarations
+    Other,
+}
+
+fn markup_kind(markup: &[u8]) -> MarkupKind {
+    match markup.get(1) {
+        Some(b'/') => MarkupKind::End,
+        Some(b'!' | b'?') => MarkupKind::Other,
+        _ => MarkupKind::Start {
+            empty: markup.ends_with(b"/>"),
+        },
+    }
+}
+
+/// Finds the end of the markup construct at the start of `input`, which begins with `<`.
+fn scan_markup(input: &[u8]) -> Scanned {
+    const COMMENT: &[u8] = b"<!--";
+    const CDATA: &[u8] = b"<![CDATA[";
+
+    let find = |from: usize, terminator: &[u8]| {
+        input
+            .get(from..)
+            .and_then(|rest| {
+                rest.windows(terminator.len())
+                    .position(|window| window == terminator)
+            })
+            .map(|offset| Scanned::Complete(from + offset + terminator.len()))
+            .unwrap_or(Scanned::Incomplete)
+    };
+    // Until enough of the construct has been received, it's unclear which kind it is
+    let is_partial = |prefix: &[u8]| input.len() < prefix.len() && prefix.starts_with(input);
+
+    if input.starts_with(COMMENT) {
+        find(COMMENT.len(), b"-->")
+    } else if input.starts_with(CDATA) {
+        find(CDATA.len(), b"]]>")
+    } else if is_partial(COMMENT) || is_partial(CDATA) || input.len() < 2 {
+        Scanned::Incomplete
+    } else if input.starts_with(b"<?") {
+        find(2, b"?>")
+    } else {
+        // A tag or declaration, which ends at the first `>` outside of a quoted value
+        let mut quote = None;
+        for (index, byte) in input.iter().enumerate().skip(1) {
+            match (quote, byte) {
+                (None, b'"' | b'\'') => quote = Some(*byte),
+                (None, b'>') => return Scanned::Complete(index + 1),
+                (Some(open), byte) if open == *byte => quote = None,
+                _ => {}
+            }
+        }
+        Scanned::Incomplete
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::decode::try_data;
+
+    const DOCUMENT: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<ListVersionsResult xmlns="http://s3.amazonaws.com/doc/2006-03-01/">
+    <Name>bucket</Name>
+    <!-- a comment with <tags> inside -->
+    <IsTruncated>false</IsTruncated>
+    <Version><Key>a&gt;b</Key><ETag>"e1"</ETag><Owner><ID>o</ID></Owner></Version>
+    <DeleteMarker attr="x>y" other='/>'><Key>café</Key></DeleteMarker>
+    <Version><Key><![CDATA[</Version><Version>]]></Key></Version>
+    <Empty/>
+</ListVersionsResult>
+"#;
+
+    const EXPECTED: &[&str] = &[
+        "<Name>bucket</Name>",
+        "<IsTruncated>false</IsTruncated>",
+        r#"<Version><Key>a&gt;b</Key><ETag>"e1"</ETag><Owner><ID>o</ID></Owner></Version>"#,
+        r#"<DeleteMarker attr="x>y" other='/>'><Key>café</Key></DeleteMarker>"#,
+        "<Version><Key><![CDATA[</Version><Version>]]></Key></Version>",
+        "<Empty/>",
+    ];
+
+    fn decode(chunks: &[&[u8]]) -> Result<Vec<String>, XmlDecodeError> {
+        let mut decoder = StreamingDecoder::new();
+        let mut chunks = chunks.iter();
+        let mut elements = Vec::new();
+        loop {
+            match decoder.next_element()? {
+                Next::Element(element) => elements.push(element.as_str().to_string()),
+                Next::NeedMoreInput => match chunks.next() {
+                    Some(chunk) => decoder.push(chunk),
+                    None => decoder.finish(),
+                },
+                Next::Done => return Ok(elements),
+            }
+        }
+    }
+
+    #[test]
+    fn yields_children_at_every_split() {
+        let document = DOCUMENT.as_bytes();
+        for split in 0..=document.len() {
+            let (first, second) = document.split_at(split);
+            assert_eq!(
+                EXPECTED,
+                decode(&[first, second]).unwrap().as_slice(),
+                "split at {}",
+                split
+            );
+        }
+    }
+
+    #[test]
+    fn byte_at_a_time() {
+        let chunks: Vec<&[u8]> = DOCUMENT.as_bytes().chunks(1).collect();
+        assert_eq!(EXPECTED, decode(&chunks).unwrap().as_slice());
+    }
+
+    #[test]
+    fn elements_can_be_decoded() {
+        let mut decoder = StreamingDecoder::new();
+        decoder.push(DOCUMENT.as_bytes());
+        assert!(decoder.root_start_el().is_none());
+        let (mut names, mut keys) = (Vec::new(), Vec::new());
+        while let Next::Element(element) = decoder.next_element().unwrap() {
+            let mut doc = element.document();
+            let mut el = doc.root_element().unwrap();
+            names.push(el.start_el().local().to_string());
+            if el.start_el().matches("Version") && keys.is_empty() {
+                let mut key = el.next_tag().unwrap();
+                keys.push(try_data(&mut key).unwrap().into_owned());
+            }
+        }
+        assert_eq!(
+            vec![
+                "Name",
+                "IsTruncated",
+                "Version",
+                "DeleteMarker",
+                "Version",
+                "Empty"
+            ],
+            names
+        );
+        assert_eq!(vec!["a>b"], keys);
+        let root = decoder.root_start_el().unwrap();
+        assert!(root.matches("ListVersionsResult"));
+        assert_eq!(
+            Some("http://s3.amazonaws.com/doc/2006-03-01/"),
+            root.attr("xmlns")
+        );
+    }
+
+    #[test]
+    fn only_the_current_element_is_buffered() {
+        let mut decoder = StreamingDecoder::new();
+        decoder.push(b"<Root><A>1</A><B>2");
+        assert!(matches!(decoder.next_element(), Ok(Next::Element(_))));
+        assert_eq!(Next::NeedMoreInput, decoder.next_element().unwrap());
+        assert_eq!(b"<B>2", decoder.buffer.as_slice());
+    }
+
+    #[test]
+    fn empty_root() {
+        assert!(decode(&[b"<Root/>"]).unwrap().is_empty());
+        assert!(decode(&[b"<Root></Root>  <!-- done -->"])
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn invalid_documents() {
+        decode(&[b"<Root><A>"]).expect_err("truncated");
+        decode(&[b"<Root><A></A"]).expect_err("truncated");
+        decode(&[b"text<Root/>"]).expect_err("text before the root");
+        decode(&[b"<Root/><Other/>"]).expect_err("two roots");
+        decode(&[b"<Root/>text"]).expect_err("text after the root");
+        decode(&[b""]).expect_err("no root");
+    }
+}